@@ -17,4 +17,7 @@ pub enum Error {
 
     #[error("bitcoin consensus error: {0}")]
     BitcoinConsensusError(#[from] consensus::encode::Error),
+
+    #[error("invalid api key: {0}")]
+    InvalidApiKey(#[from] reqwest::header::InvalidHeaderValue),
 }
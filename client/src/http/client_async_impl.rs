@@ -6,7 +6,10 @@ use titan_types_core::*;
 
 use crate::Error;
 
-use super::TitanApiAsync;
+use super::{
+    address_query, api_key_headers, delivery_query, mempool_package_query, pagination_query,
+    rune_history_query, runes_query, TitanApiAsync,
+};
 
 #[derive(Clone)]
 pub struct AsyncClient {
@@ -25,6 +28,17 @@ impl AsyncClient {
         }
     }
 
+    /// Creates a new `AsyncClient` that sends `api_key` with every request, for indexers
+    /// running with API keys enabled.
+    pub fn new_with_api_key(base_url: &str, api_key: &str) -> Result<Self, Error> {
+        Ok(Self {
+            http_client: AsyncReqwestClient::builder()
+                .default_headers(api_key_headers(api_key)?)
+                .build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
     async fn call_text(&self, path: &str) -> Result<String, Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.get(&url).send().await?;
@@ -55,6 +69,20 @@ impl AsyncClient {
         }
     }
 
+    async fn post_json<T: serde::Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<String, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        let response = self.http_client.post(&url).json(body).send().await?;
+        if response.status().is_success() {
+            Ok(response.text().await?)
+        } else {
+            Err(Error::TitanError(response.status(), response.text().await?))
+        }
+    }
+
     async fn delete(&self, path: &str) -> Result<(), Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.delete(&url).send().await?;
@@ -97,11 +125,63 @@ impl TitanApiAsync for AsyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_address_transactions(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        filter: Option<AddressFilter>,
+    ) -> Result<PaginationResponse<Txid>, Error> {
+        let query = address_query(pagination.as_ref(), filter.as_ref());
+        let text = self
+            .call_text(&format!("/address/{}/txs?{}", address, query))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_address_utxos(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        filter: Option<AddressFilter>,
+    ) -> Result<PaginationResponse<AddressTxOut>, Error> {
+        let query = address_query(pagination.as_ref(), filter.as_ref());
+        let text = self
+            .call_text(&format!("/address/{}/utxos?{}", address, query))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_address_runes(&self, address: &str) -> Result<Vec<AddressRuneBalance>, Error> {
+        let text = self
+            .call_text(&format!("/address/{}/runes", address))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_address_rune(
+        &self,
+        address: &str,
+        rune: &query::Rune,
+    ) -> Result<AddressRuneBalance, Error> {
+        let text = self
+            .call_text(&format!("/address/{}/runes/{}", address, rune))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error> {
         let text = self.call_text(&format!("/tx/{}", txid)).await?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_transactions(
+        &self,
+        txids: &[Txid],
+    ) -> Result<HashMap<Txid, Option<Transaction>>, Error> {
+        let text = self.post_json("/txs", txids).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error> {
         self.call_bytes(&format!("/tx/{}/raw", txid)).await
     }
@@ -115,16 +195,40 @@ impl TitanApiAsync for AsyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_transaction_outspends(&self, txid: &Txid) -> Result<Vec<TxOutspend>, Error> {
+        let text = self.call_text(&format!("/tx/{}/outspends", txid)).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error> {
         let text = self.post_text("/tx/broadcast", tx_hex).await?;
         Txid::from_str(&text).map_err(Error::from)
     }
 
+    async fn decode_transaction(&self, tx_hex: String) -> Result<DecodedTransaction, Error> {
+        let text = self.post_text("/tx/decode", tx_hex).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_output(&self, outpoint: &OutPoint) -> Result<TxOut, Error> {
         let text = self.call_text(&format!("/output/{}", outpoint)).await?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_outputs(&self, outpoints: &[OutPoint]) -> Result<Vec<Option<TxOut>>, Error> {
+        let outpoints: Vec<SerializedOutPoint> = outpoints.iter().map(|o| (*o).into()).collect();
+        let text = self.post_json("/outputs", &outpoints).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn build_rune_transfer_psbt(
+        &self,
+        request: &RuneTransferRequest,
+    ) -> Result<RuneTransferPsbt, Error> {
+        let text = self.post_json("/psbt/rune-transfer", request).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_inscription(
         &self,
         inscription_id: &InscriptionId,
@@ -144,12 +248,10 @@ impl TitanApiAsync for AsyncClient {
     async fn get_runes(
         &self,
         pagination: Option<Pagination>,
+        filter: Option<RuneFilter>,
     ) -> Result<PaginationResponse<RuneResponse>, Error> {
-        let mut path = "/runes".to_string();
-        if let Some(p) = pagination {
-            path = format!("{}?skip={}&limit={}", path, p.skip, p.limit);
-        }
-        let text = self.call_text(&path).await?;
+        let query = runes_query(pagination.as_ref(), filter.as_ref());
+        let text = self.call_text(&format!("/runes?{}", query)).await?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
@@ -158,6 +260,42 @@ impl TitanApiAsync for AsyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn get_runes_by_ids(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, Option<RuneResponse>>, Error> {
+        let text = self.post_json("/runes/batch", rune_ids).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_rune_history(
+        &self,
+        rune: &query::Rune,
+        query: &RuneHistoryQuery,
+    ) -> Result<Vec<RuneHistoryEntry>, Error> {
+        let text = self
+            .call_text(&format!(
+                "/rune/{}/history?{}",
+                rune,
+                rune_history_query(query)
+            ))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_rune_holders(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneHolder>, Error> {
+        let mut path = format!("/rune/{}/holders", rune);
+        if let Some(p) = pagination {
+            path = format!("{}?{}", path, pagination_query(&p));
+        }
+        let text = self.call_text(&path).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_rune_transactions(
         &self,
         rune: &query::Rune,
@@ -165,7 +303,7 @@ impl TitanApiAsync for AsyncClient {
     ) -> Result<PaginationResponse<Txid>, Error> {
         let mut path = format!("/rune/{}/transactions", rune);
         if let Some(p) = pagination {
-            path = format!("{}?skip={}&limit={}", path, p.skip, p.limit);
+            path = format!("{}?{}", path, pagination_query(&p));
         }
         let text = self.call_text(&path).await?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -211,6 +349,36 @@ impl TitanApiAsync for AsyncClient {
         }
     }
 
+    async fn get_mempool_package(
+        &self,
+        txid: &Txid,
+        query: &MempoolPackageQuery,
+    ) -> Result<MempoolPackage, Error> {
+        let text = self
+            .call_text(&format!(
+                "/mempool/tx/{}/package?{}",
+                txid,
+                mempool_package_query(query)
+            ))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_mempool_blocks(&self) -> Result<Vec<MempoolBlock>, Error> {
+        let text = self.call_text("/mempool/blocks").await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_mempool_fee_histogram(&self) -> Result<Vec<FeeHistogramBucket>, Error> {
+        let text = self.call_text("/mempool/fee-histogram").await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn get_recommended_fees(&self) -> Result<RecommendedFees, Error> {
+        let text = self.call_text("/fees/recommended").await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn get_subscription(&self, id: &str) -> Result<Subscription, Error> {
         let text = self.call_text(&format!("/subscription/{}", id)).await?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -228,7 +396,45 @@ impl TitanApiAsync for AsyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    async fn rotate_subscription_secret(&self, id: &str) -> Result<Subscription, Error> {
+        let text = self
+            .post_text(
+                &format!("/subscription/{}/rotate-secret", id),
+                String::new(),
+            )
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     async fn delete_subscription(&self, id: &str) -> Result<(), Error> {
         self.delete(&format!("/subscription/{}", id)).await
     }
+
+    async fn get_subscription_deliveries(
+        &self,
+        id: &str,
+        query: &DeliveryQuery,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        let text = self
+            .call_text(&format!(
+                "/subscription/{}/deliveries?{}",
+                id,
+                delivery_query(query)
+            ))
+            .await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    async fn redeliver_subscription(
+        &self,
+        id: &str,
+        seq: Option<u64>,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        let path = match seq {
+            Some(seq) => format!("/subscription/{}/redeliver?seq={}", id, seq),
+            None => format!("/subscription/{}/redeliver", id),
+        };
+        let text = self.post_text(&path, String::new()).await?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
 }
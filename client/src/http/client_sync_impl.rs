@@ -6,7 +6,10 @@ use titan_types_core::*;
 
 use crate::Error;
 
-use super::TitanApiSync;
+use super::{
+    address_query, api_key_headers, delivery_query, mempool_package_query, pagination_query,
+    rune_history_query, runes_query, TitanApiSync,
+};
 
 #[derive(Clone)]
 pub struct SyncClient {
@@ -25,6 +28,17 @@ impl SyncClient {
         }
     }
 
+    /// Creates a new `SyncClient` that sends `api_key` with every request, for indexers
+    /// running with API keys enabled.
+    pub fn new_with_api_key(base_url: &str, api_key: &str) -> Result<Self, Error> {
+        Ok(Self {
+            http_client: BlockingReqwestClient::builder()
+                .default_headers(api_key_headers(api_key)?)
+                .build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
     fn call_text(&self, path: &str) -> Result<String, Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.get(&url).send()?;
@@ -57,6 +71,20 @@ impl SyncClient {
         }
     }
 
+    fn post_json<T: serde::Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<String, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        let response = self.http_client.post(&url).json(body).send()?;
+        if response.status().is_success() {
+            Ok(response.text()?)
+        } else {
+            Err(Error::TitanError(response.status(), response.text()?))
+        }
+    }
+
     fn delete(&self, path: &str) -> Result<(), Error> {
         let url = format!("{}{}", self.base_url, path);
         let response = self.http_client.delete(&url).send()?;
@@ -98,11 +126,55 @@ impl TitanApiSync for SyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_address_transactions(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        filter: Option<AddressFilter>,
+    ) -> Result<PaginationResponse<Txid>, Error> {
+        let query = address_query(pagination.as_ref(), filter.as_ref());
+        let text = self.call_text(&format!("/address/{}/txs?{}", address, query))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_address_utxos(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        filter: Option<AddressFilter>,
+    ) -> Result<PaginationResponse<AddressTxOut>, Error> {
+        let query = address_query(pagination.as_ref(), filter.as_ref());
+        let text = self.call_text(&format!("/address/{}/utxos?{}", address, query))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_address_runes(&self, address: &str) -> Result<Vec<AddressRuneBalance>, Error> {
+        let text = self.call_text(&format!("/address/{}/runes", address))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_address_rune(
+        &self,
+        address: &str,
+        rune: &query::Rune,
+    ) -> Result<AddressRuneBalance, Error> {
+        let text = self.call_text(&format!("/address/{}/runes/{}", address, rune))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error> {
         let text = self.call_text(&format!("/tx/{}", txid))?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_transactions(
+        &self,
+        txids: &[Txid],
+    ) -> Result<HashMap<Txid, Option<Transaction>>, Error> {
+        let text = self.post_json("/txs", txids)?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error> {
         self.call_bytes(&format!("/tx/{}/raw", txid))
     }
@@ -116,16 +188,40 @@ impl TitanApiSync for SyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_transaction_outspends(&self, txid: &Txid) -> Result<Vec<TxOutspend>, Error> {
+        let text = self.call_text(&format!("/tx/{}/outspends", txid))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error> {
         let text = self.post_text("/tx/broadcast", tx_hex)?;
         Txid::from_str(&text).map_err(Error::from)
     }
 
+    fn decode_transaction(&self, tx_hex: String) -> Result<DecodedTransaction, Error> {
+        let text = self.post_text("/tx/decode", tx_hex)?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn get_output(&self, outpoint: &OutPoint) -> Result<TxOut, Error> {
         let text = self.call_text(&format!("/output/{}", outpoint))?;
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn get_outputs(&self, outpoints: &[OutPoint]) -> Result<Vec<Option<TxOut>>, Error> {
+        let outpoints: Vec<SerializedOutPoint> = outpoints.iter().map(|o| (*o).into()).collect();
+        let text = self.post_json("/outputs", &outpoints)?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn build_rune_transfer_psbt(
+        &self,
+        request: &RuneTransferRequest,
+    ) -> Result<RuneTransferPsbt, Error> {
+        let text = self.post_json("/psbt/rune-transfer", request)?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn get_inscription(
         &self,
         inscription_id: &InscriptionId,
@@ -145,13 +241,11 @@ impl TitanApiSync for SyncClient {
     fn get_runes(
         &self,
         pagination: Option<Pagination>,
+        filter: Option<RuneFilter>,
     ) -> Result<PaginationResponse<RuneResponse>, Error> {
-        let url = format!("{}/runes", self.base_url);
-        let mut req = self.http_client.get(&url);
-        if let Some(ref p) = pagination {
-            req = req.query(&[("skip", p.skip), ("limit", p.limit)]);
-        }
-        let resp = req.send()?;
+        let query = runes_query(pagination.as_ref(), filter.as_ref());
+        let url = format!("{}/runes?{}", self.base_url, query);
+        let resp = self.http_client.get(&url).send()?;
         Ok(resp.json()?)
     }
 
@@ -161,17 +255,52 @@ impl TitanApiSync for SyncClient {
         Ok(resp.json()?)
     }
 
+    fn get_runes_by_ids(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, Option<RuneResponse>>, Error> {
+        let text = self.post_json("/runes/batch", rune_ids)?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_rune_history(
+        &self,
+        rune: &query::Rune,
+        query: &RuneHistoryQuery,
+    ) -> Result<Vec<RuneHistoryEntry>, Error> {
+        let url = format!(
+            "{}/rune/{}/history?{}",
+            self.base_url,
+            rune,
+            rune_history_query(query)
+        );
+        let resp = self.http_client.get(&url).send()?;
+        Ok(resp.json()?)
+    }
+
+    fn get_rune_holders(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneHolder>, Error> {
+        let mut url = format!("{}/rune/{}/holders", self.base_url, rune);
+        if let Some(ref p) = pagination {
+            url = format!("{}?{}", url, pagination_query(p));
+        }
+        let resp = self.http_client.get(&url).send()?;
+        Ok(resp.json()?)
+    }
+
     fn get_rune_transactions(
         &self,
         rune: &query::Rune,
         pagination: Option<Pagination>,
     ) -> Result<PaginationResponse<Txid>, Error> {
-        let url = format!("{}/rune/{}/transactions", self.base_url, rune);
-        let mut req = self.http_client.get(&url);
+        let mut url = format!("{}/rune/{}/transactions", self.base_url, rune);
         if let Some(ref p) = pagination {
-            req = req.query(&[("skip", p.skip), ("limit", p.limit)]);
+            url = format!("{}?{}", url, pagination_query(p));
         }
-        let resp = req.send()?;
+        let resp = self.http_client.get(&url).send()?;
         Ok(resp.json()?)
     }
 
@@ -217,6 +346,34 @@ impl TitanApiSync for SyncClient {
         }
     }
 
+    fn get_mempool_package(
+        &self,
+        txid: &Txid,
+        query: &MempoolPackageQuery,
+    ) -> Result<MempoolPackage, Error> {
+        let text = self.call_text(&format!(
+            "/mempool/tx/{}/package?{}",
+            txid,
+            mempool_package_query(query)
+        ))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_mempool_blocks(&self) -> Result<Vec<MempoolBlock>, Error> {
+        let text = self.call_text("/mempool/blocks")?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_mempool_fee_histogram(&self) -> Result<Vec<FeeHistogramBucket>, Error> {
+        let text = self.call_text("/mempool/fee-histogram")?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn get_recommended_fees(&self) -> Result<RecommendedFees, Error> {
+        let text = self.call_text("/fees/recommended")?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn get_subscription(&self, id: &str) -> Result<Subscription, Error> {
         let text = self.call_text(&format!("/subscription/{}", id))?;
         serde_json::from_str(&text).map_err(Error::from)
@@ -232,7 +389,41 @@ impl TitanApiSync for SyncClient {
         serde_json::from_str(&text).map_err(Error::from)
     }
 
+    fn rotate_subscription_secret(&self, id: &str) -> Result<Subscription, Error> {
+        let text = self.post_text(
+            &format!("/subscription/{}/rotate-secret", id),
+            String::new(),
+        )?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
     fn delete_subscription(&self, id: &str) -> Result<(), Error> {
         self.delete(&format!("/subscription/{}", id))
     }
+
+    fn get_subscription_deliveries(
+        &self,
+        id: &str,
+        query: &DeliveryQuery,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        let text = self.call_text(&format!(
+            "/subscription/{}/deliveries?{}",
+            id,
+            delivery_query(query)
+        ))?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
+
+    fn redeliver_subscription(
+        &self,
+        id: &str,
+        seq: Option<u64>,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        let path = match seq {
+            Some(seq) => format!("/subscription/{}/redeliver?seq={}", id, seq),
+            None => format!("/subscription/{}/redeliver", id),
+        };
+        let text = self.post_text(&path, String::new())?;
+        serde_json::from_str(&text).map_err(Error::from)
+    }
 }
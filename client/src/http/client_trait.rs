@@ -5,10 +5,15 @@ use async_trait::async_trait;
 use bitcoin::{OutPoint, Txid};
 use reqwest::header::HeaderMap;
 use titan_types_api::{
-    query, AddressData, BlockTip, Pagination, PaginationResponse, RuneResponse, Status,
-    Subscription,
+    query, AddressData, AddressFilter, AddressRuneBalance, AddressTxOut, BlockTip,
+    DecodedTransaction, DeliveryQuery, FeeHistogramBucket, MempoolBlock, MempoolPackage,
+    MempoolPackageQuery, Pagination, PaginationResponse, RecommendedFees, RuneFilter,
+    RuneHistoryEntry, RuneHistoryQuery, RuneHolder, RuneResponse, RuneTransferPsbt,
+    RuneTransferRequest, Status, Subscription, TxOutspend, WebhookDelivery,
+};
+use titan_types_core::{
+    Block, InscriptionId, MempoolEntry, RuneId, Transaction, TransactionStatus, TxOut,
 };
-use titan_types_core::{Block, InscriptionId, MempoolEntry, Transaction, TransactionStatus, TxOut};
 
 /// Trait for all **async** methods.
 #[async_trait]
@@ -31,9 +36,43 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     /// Fetches address data (balance, transactions, etc.).
     async fn get_address(&self, address: &str) -> Result<AddressData, Error>;
 
+    /// Returns the transactions that spent from or paid to `address`, newest first,
+    /// with cursor pagination and optional mempool and rune filters.
+    async fn get_address_transactions(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        filter: Option<AddressFilter>,
+    ) -> Result<PaginationResponse<Txid>, Error>;
+
+    /// Returns the unspent outputs of `address`, with cursor pagination and optional
+    /// mempool and rune filters.
+    async fn get_address_utxos(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        filter: Option<AddressFilter>,
+    ) -> Result<PaginationResponse<AddressTxOut>, Error>;
+
+    /// Returns the confirmed, pending and risky balance of every rune held by `address`.
+    async fn get_address_runes(&self, address: &str) -> Result<Vec<AddressRuneBalance>, Error>;
+
+    /// Returns the balance of a single `rune` held by `address`.
+    async fn get_address_rune(
+        &self,
+        address: &str,
+        rune: &query::Rune,
+    ) -> Result<AddressRuneBalance, Error>;
+
     /// Returns a higher-level transaction object (including Runes info) by `txid`.
     async fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error>;
 
+    /// Returns the transactions of up to 100 `txids`, with `None` for the unknown ones.
+    async fn get_transactions(
+        &self,
+        txids: &[Txid],
+    ) -> Result<HashMap<Txid, Option<Transaction>>, Error>;
+
     /// Returns raw transaction bytes (binary).
     async fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error>;
 
@@ -43,28 +82,75 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
     /// Returns the status of a transaction by `txid`.
     async fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error>;
 
+    /// Returns the spending status of every output of `txid`, with the spender's txid,
+    /// input and confirmation status. Spends in the mempool are included.
+    async fn get_transaction_outspends(&self, txid: &Txid) -> Result<Vec<TxOutspend>, Error>;
+
     /// Broadcasts a transaction (raw hex) to the network and returns the resulting `Txid`.
     async fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error>;
 
+    /// Decodes a transaction (raw hex) and returns the rune effects it would have if
+    /// broadcast now, without broadcasting or indexing it.
+    async fn decode_transaction(&self, tx_hex: String) -> Result<DecodedTransaction, Error>;
+
     /// Fetches a specific output by outpoint (`<txid>:<vout>`).
     async fn get_output(&self, outpoint: &OutPoint) -> Result<TxOut, Error>;
 
+    /// Fetches up to 1000 outputs, in the order of `outpoints`, with `None` for the
+    /// unknown ones.
+    async fn get_outputs(&self, outpoints: &[OutPoint]) -> Result<Vec<Option<TxOut>>, Error>;
+
+    /// Builds an unsigned PSBT sending runes from the indexed outputs of
+    /// `request.sources`, with a preview of the runes each output gets.
+    async fn build_rune_transfer_psbt(
+        &self,
+        request: &RuneTransferRequest,
+    ) -> Result<RuneTransferPsbt, Error>;
+
     /// Returns `(HTTP Headers, Bytes)` for an inscription by its `inscription_id`.
     async fn get_inscription(
         &self,
         inscription_id: &InscriptionId,
     ) -> Result<(HeaderMap, Vec<u8>), Error>;
 
-    /// Lists existing runes, supporting pagination.
+    /// Lists existing runes, supporting pagination. Pass the returned `next_cursor`
+    /// back in `Pagination::cursor` to continue from the last page.
+    ///
+    /// `filter` narrows down and orders the listing. Filtered listings have no cursors
+    /// and are paginated with `skip` and `limit`.
     async fn get_runes(
         &self,
         pagination: Option<Pagination>,
+        filter: Option<RuneFilter>,
     ) -> Result<PaginationResponse<RuneResponse>, Error>;
 
     /// Fetches data about a specific rune.
     async fn get_rune(&self, rune: &query::Rune) -> Result<RuneResponse, Error>;
 
+    /// Fetches data about up to 1000 runes by id, with `None` for the unknown ones.
+    async fn get_runes_by_ids(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, Option<RuneResponse>>, Error>;
+
+    /// Returns the confirmed mints, burns and transfers of a given `rune` per block or per
+    /// day, oldest first, between the heights of `query`.
+    async fn get_rune_history(
+        &self,
+        rune: &query::Rune,
+        query: &RuneHistoryQuery,
+    ) -> Result<Vec<RuneHistoryEntry>, Error>;
+
+    /// Returns the holders of a given `rune`, sorted by balance from largest to smallest.
+    /// Only confirmed balances are counted. Pages are selected with `skip` and `limit`.
+    async fn get_rune_holders(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneHolder>, Error>;
+
     /// Returns a paginated list of `Txid` for all transactions involving a given `rune`.
+    /// Supports cursors in the same way as [`TitanApiAsync::get_runes`].
     async fn get_rune_transactions(
         &self,
         rune: &query::Rune,
@@ -92,17 +178,52 @@ pub trait TitanApiAsync: Send + Sync + Clone + 'static {
         txids: &[Txid],
     ) -> Result<HashMap<Txid, MempoolEntry>, Error>;
 
+    /// Returns a mempool transaction with its unconfirmed ancestors and descendants,
+    /// its effective fee rate and the fees to bump it by CPFP or RBF.
+    async fn get_mempool_package(
+        &self,
+        txid: &Txid,
+        query: &MempoolPackageQuery,
+    ) -> Result<MempoolPackage, Error>;
+
+    /// Returns the blocks projected from the mempool, next block first.
+    async fn get_mempool_blocks(&self) -> Result<Vec<MempoolBlock>, Error>;
+
+    /// Returns the mempool vsize bucketed by effective fee rate, highest first.
+    async fn get_mempool_fee_histogram(&self) -> Result<Vec<FeeHistogramBucket>, Error>;
+
+    /// Returns recommended fee rates in sat/vB for common confirmation targets.
+    async fn get_recommended_fees(&self) -> Result<RecommendedFees, Error>;
+
     /// Fetches a single subscription by `id`.
     async fn get_subscription(&self, id: &str) -> Result<Subscription, Error>;
 
     /// Lists all subscriptions currently known.
     async fn list_subscriptions(&self) -> Result<Vec<Subscription>, Error>;
 
-    /// Adds (creates) a subscription.
+    /// Adds (creates) a subscription. The returned subscription carries the secret its
+    /// deliveries are signed with.
     async fn add_subscription(&self, subscription: &Subscription) -> Result<Subscription, Error>;
 
+    /// Replaces the signing secret of subscription `id` and returns it with the new secret.
+    async fn rotate_subscription_secret(&self, id: &str) -> Result<Subscription, Error>;
+
     /// Deletes a subscription by `id`.
     async fn delete_subscription(&self, id: &str) -> Result<(), Error>;
+
+    /// Lists the pending deliveries and dead letters of subscription `id`.
+    async fn get_subscription_deliveries(
+        &self,
+        id: &str,
+        query: &DeliveryQuery,
+    ) -> Result<Vec<WebhookDelivery>, Error>;
+
+    /// Requeues the dead letters of subscription `id`, or only the one at `seq`.
+    async fn redeliver_subscription(
+        &self,
+        id: &str,
+        seq: Option<u64>,
+    ) -> Result<Vec<WebhookDelivery>, Error>;
 }
 
 /// Trait for all **blocking** (synchronous) methods.
@@ -125,9 +246,39 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     /// Returns address data in a **blocking** manner.
     fn get_address(&self, address: &str) -> Result<AddressData, Error>;
 
+    /// Returns the transactions of an address in a **blocking** manner.
+    fn get_address_transactions(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        filter: Option<AddressFilter>,
+    ) -> Result<PaginationResponse<Txid>, Error>;
+
+    /// Returns the unspent outputs of an address in a **blocking** manner.
+    fn get_address_utxos(
+        &self,
+        address: &str,
+        pagination: Option<Pagination>,
+        filter: Option<AddressFilter>,
+    ) -> Result<PaginationResponse<AddressTxOut>, Error>;
+
+    /// Returns the rune balances of an address in a **blocking** manner.
+    fn get_address_runes(&self, address: &str) -> Result<Vec<AddressRuneBalance>, Error>;
+
+    /// Returns the balance of a single rune held by an address in a **blocking** manner.
+    fn get_address_rune(
+        &self,
+        address: &str,
+        rune: &query::Rune,
+    ) -> Result<AddressRuneBalance, Error>;
+
     /// Returns a transaction (with runic info) by `txid` in a **blocking** manner.
     fn get_transaction(&self, txid: &Txid) -> Result<Transaction, Error>;
 
+    /// Returns the transactions of up to 100 `txids` in a **blocking** manner.
+    fn get_transactions(&self, txids: &[Txid])
+        -> Result<HashMap<Txid, Option<Transaction>>, Error>;
+
     /// Returns raw tx bytes in a **blocking** manner.
     fn get_transaction_raw(&self, txid: &Txid) -> Result<Vec<u8>, Error>;
 
@@ -137,27 +288,63 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
     /// Returns the status of a transaction by `txid` in a **blocking** manner.
     fn get_transaction_status(&self, txid: &Txid) -> Result<TransactionStatus, Error>;
 
+    /// Returns the spending status of every output of `txid` in a **blocking** manner.
+    fn get_transaction_outspends(&self, txid: &Txid) -> Result<Vec<TxOutspend>, Error>;
+
     /// Broadcasts a raw-hex transaction in a **blocking** manner.
     fn send_transaction(&self, tx_hex: String) -> Result<Txid, Error>;
 
+    /// Decodes a raw-hex transaction without broadcasting it, in a **blocking** manner.
+    fn decode_transaction(&self, tx_hex: String) -> Result<DecodedTransaction, Error>;
+
     /// Fetches a specific output (outpoint) in a **blocking** manner.
     fn get_output(&self, outpoint: &OutPoint) -> Result<TxOut, Error>;
 
+    /// Fetches up to 1000 outputs, in the order of `outpoints`, in a **blocking** manner.
+    fn get_outputs(&self, outpoints: &[OutPoint]) -> Result<Vec<Option<TxOut>>, Error>;
+
+    /// Builds an unsigned rune transfer PSBT, blocking.
+    fn build_rune_transfer_psbt(
+        &self,
+        request: &RuneTransferRequest,
+    ) -> Result<RuneTransferPsbt, Error>;
+
     /// Fetches an inscription (headers + bytes) by `inscription_id`, blocking.
     fn get_inscription(
         &self,
         inscription_id: &InscriptionId,
     ) -> Result<(HeaderMap, Vec<u8>), Error>;
 
-    /// Returns paginated runes in a **blocking** manner.
+    /// Returns paginated runes, optionally filtered, in a **blocking** manner.
     fn get_runes(
         &self,
         pagination: Option<Pagination>,
+        filter: Option<RuneFilter>,
     ) -> Result<PaginationResponse<RuneResponse>, Error>;
 
     /// Fetches data for a specific rune in a **blocking** manner.
     fn get_rune(&self, rune: &query::Rune) -> Result<RuneResponse, Error>;
 
+    /// Fetches data for up to 1000 runes by id in a **blocking** manner.
+    fn get_runes_by_ids(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, Option<RuneResponse>>, Error>;
+
+    /// Returns the history of a given rune in a **blocking** manner.
+    fn get_rune_history(
+        &self,
+        rune: &query::Rune,
+        query: &RuneHistoryQuery,
+    ) -> Result<Vec<RuneHistoryEntry>, Error>;
+
+    /// Returns the holders of a given rune in a **blocking** manner.
+    fn get_rune_holders(
+        &self,
+        rune: &query::Rune,
+        pagination: Option<Pagination>,
+    ) -> Result<PaginationResponse<RuneHolder>, Error>;
+
     /// Returns transactions for a given rune in a **blocking** manner.
     fn get_rune_transactions(
         &self,
@@ -186,15 +373,50 @@ pub trait TitanApiSync: Send + Sync + Clone + 'static {
         txids: &[Txid],
     ) -> Result<HashMap<Txid, MempoolEntry>, Error>;
 
+    /// Returns a mempool transaction with its unconfirmed ancestors and descendants,
+    /// its effective fee rate and the fees to bump it by CPFP or RBF.
+    fn get_mempool_package(
+        &self,
+        txid: &Txid,
+        query: &MempoolPackageQuery,
+    ) -> Result<MempoolPackage, Error>;
+
+    /// Returns the blocks projected from the mempool, next block first.
+    fn get_mempool_blocks(&self) -> Result<Vec<MempoolBlock>, Error>;
+
+    /// Returns the mempool vsize bucketed by effective fee rate, highest first.
+    fn get_mempool_fee_histogram(&self) -> Result<Vec<FeeHistogramBucket>, Error>;
+
+    /// Returns recommended fee rates in sat/vB for common confirmation targets.
+    fn get_recommended_fees(&self) -> Result<RecommendedFees, Error>;
+
     /// Fetches a single subscription by `id`, blocking.
     fn get_subscription(&self, id: &str) -> Result<Subscription, Error>;
 
     /// Lists all subscriptions, blocking.
     fn list_subscriptions(&self) -> Result<Vec<Subscription>, Error>;
 
-    /// Adds a new subscription, blocking.
+    /// Adds a new subscription, blocking. The returned subscription carries the secret
+    /// its deliveries are signed with.
     fn add_subscription(&self, subscription: &Subscription) -> Result<Subscription, Error>;
 
+    /// Replaces the signing secret of subscription `id`, blocking.
+    fn rotate_subscription_secret(&self, id: &str) -> Result<Subscription, Error>;
+
     /// Deletes a subscription by `id`, blocking.
     fn delete_subscription(&self, id: &str) -> Result<(), Error>;
+
+    /// Lists the pending deliveries and dead letters of subscription `id`, blocking.
+    fn get_subscription_deliveries(
+        &self,
+        id: &str,
+        query: &DeliveryQuery,
+    ) -> Result<Vec<WebhookDelivery>, Error>;
+
+    /// Requeues the dead letters of subscription `id`, or only the one at `seq`, blocking.
+    fn redeliver_subscription(
+        &self,
+        id: &str,
+        seq: Option<u64>,
+    ) -> Result<Vec<WebhookDelivery>, Error>;
 }
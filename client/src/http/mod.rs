@@ -5,3 +5,140 @@ mod client_trait;
 pub use client_async_impl::*;
 pub use client_sync_impl::*;
 pub use client_trait::*;
+
+use {
+    crate::Error,
+    reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION},
+    titan_types_api::{
+        AddressFilter, DeliveryQuery, MempoolPackageQuery, Pagination, RuneFilter, RuneHistoryQuery,
+    },
+};
+
+/// Default headers of a client that authenticates with `api_key`.
+fn api_key_headers(api_key: &str) -> Result<HeaderMap, Error> {
+    let mut value = HeaderValue::from_str(&format!("Bearer {api_key}"))?;
+    value.set_sensitive(true);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, value);
+    Ok(headers)
+}
+
+/// Query string for a paginated request. The cursor is only sent when set, and then
+/// takes precedence over `skip` on the server.
+fn pagination_query(pagination: &Pagination) -> String {
+    let mut query = format!("skip={}&limit={}", pagination.skip, pagination.limit);
+    if let Some(cursor) = &pagination.cursor {
+        query.push_str(&format!(
+            "&cursor={}&direction={}",
+            cursor, pagination.direction
+        ));
+    }
+    query
+}
+
+/// Query string for the paginated address endpoints.
+fn address_query(pagination: Option<&Pagination>, filter: Option<&AddressFilter>) -> String {
+    let mut params = Vec::new();
+    if let Some(pagination) = pagination {
+        params.push(pagination_query(pagination));
+    }
+    if let Some(filter) = filter {
+        if let Some(mempool) = filter.mempool {
+            params.push(format!("mempool={}", mempool));
+        }
+        if let Some(rune) = &filter.rune {
+            params.push(format!("rune={}", rune));
+        }
+    }
+    params.join("&")
+}
+
+/// Query string for the rune listing.
+fn runes_query(pagination: Option<&Pagination>, filter: Option<&RuneFilter>) -> String {
+    let mut params = Vec::new();
+    if let Some(pagination) = pagination {
+        params.push(pagination_query(pagination));
+    }
+    if let Some(filter) = filter {
+        if let Some(prefix) = &filter.prefix {
+            params.push(format!("prefix={}", encode_query_value(prefix)));
+        }
+        if let Some(search) = &filter.search {
+            params.push(format!("search={}", encode_query_value(search)));
+        }
+        if let Some(mintable) = filter.mintable {
+            params.push(format!("mintable={}", mintable));
+        }
+        if let Some(turbo) = filter.turbo {
+            params.push(format!("turbo={}", turbo));
+        }
+        if let Some(etched_from) = filter.etched_from {
+            params.push(format!("etched_from={}", etched_from));
+        }
+        if let Some(etched_to) = filter.etched_to {
+            params.push(format!("etched_to={}", etched_to));
+        }
+        if let Some(symbol) = filter.symbol {
+            params.push(format!(
+                "symbol={}",
+                encode_query_value(symbol.encode_utf8(&mut [0; 4]))
+            ));
+        }
+        params.push(format!("sort={}", filter.sort));
+    }
+    params.join("&")
+}
+
+/// Query string for the rune history.
+fn rune_history_query(query: &RuneHistoryQuery) -> String {
+    let mut params = Vec::new();
+    if let Some(from) = query.from {
+        params.push(format!("from={}", from));
+    }
+    if let Some(to) = query.to {
+        params.push(format!("to={}", to));
+    }
+    params.push(format!("bucket={}", query.bucket));
+    params.join("&")
+}
+
+/// Query string for the bump fees of a mempool package.
+fn mempool_package_query(query: &MempoolPackageQuery) -> String {
+    let mut params = Vec::new();
+    if let Some(fee_rate) = query.fee_rate {
+        params.push(format!("fee_rate={}", fee_rate));
+    }
+    if let Some(child_vsize) = query.child_vsize {
+        params.push(format!("child_vsize={}", child_vsize));
+    }
+    params.join("&")
+}
+
+/// Query string for the deliveries of a webhook subscription.
+fn delivery_query(query: &DeliveryQuery) -> String {
+    let mut params = Vec::new();
+    if let Some(status) = query.status {
+        params.push(format!("status={}", status));
+    }
+    if let Some(from_seq) = query.from_seq {
+        params.push(format!("from_seq={}", from_seq));
+    }
+    if let Some(limit) = query.limit {
+        params.push(format!("limit={}", limit));
+    }
+    params.join("&")
+}
+
+/// Percent-encodes everything but unreserved characters.
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -50,6 +50,9 @@ mod tests {
                 EventType::TransactionsReplaced,
                 EventType::NewBlock,
             ],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         // Connect to the TCP server and subscribe.
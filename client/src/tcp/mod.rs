@@ -1,4 +1,5 @@
 mod connection_status;
+mod received_event;
 mod reconnection;
 mod tcp_client;
 mod tcp_client_blocking;
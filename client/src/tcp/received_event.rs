@@ -0,0 +1,10 @@
+use {serde::Deserialize, titan_types_core::Event};
+
+/// An event line sent by the server. Servers that predate the event log send
+/// events without a sequence number.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ReceivedEvent {
+    pub seq: Option<u64>,
+    #[serde(flatten)]
+    pub event: Event,
+}
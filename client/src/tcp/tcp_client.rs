@@ -17,6 +17,7 @@ use tracing::{debug, error, info, warn};
 
 use crate::tcp::{
     connection_status::ConnectionStatus,
+    received_event::ReceivedEvent,
     reconnection::{self, ReconnectionManager},
 };
 
@@ -225,6 +226,10 @@ impl AsyncTcpClient {
             ping_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
             let mut awaiting_pong = false;
 
+            // Sequence number of the next event we expect, so that a reconnect resumes
+            // where the previous connection stopped.
+            let mut next_seq = subscription_request.from_seq;
+
             loop {
                 // Before each connection attempt, check for a shutdown signal.
                 if shutdown_rx.has_changed().unwrap_or(false) {
@@ -255,7 +260,11 @@ impl AsyncTcpClient {
                         let mut awaiting_pong = false;
 
                         // Serialize and send the subscription request.
-                        match serde_json::to_string(&subscription_request) {
+                        let request = TcpSubscriptionRequest {
+                            from_seq: next_seq,
+                            ..subscription_request.clone()
+                        };
+                        match serde_json::to_string(&request) {
                             Ok(req_json) => {
                                 if let Err(e) = writer.write_all(req_json.as_bytes()).await {
                                     error!("Error sending subscription request: {}", e);
@@ -327,8 +336,11 @@ impl AsyncTcpClient {
                                                         // Note: We don't break here, just clear the buffer and continue reading the next message.
                                                     } else {
                                                         // Try to parse as an event from the byte slice
-                                                        match serde_json::from_slice::<Event>(message_bytes) {
-                                                            Ok(event) => {
+                                                        match serde_json::from_slice::<ReceivedEvent>(message_bytes) {
+                                                            Ok(ReceivedEvent { seq, event }) => {
+                                                                if let Some(seq) = seq {
+                                                                    next_seq = Some(seq + 1);
+                                                                }
                                                                 // Every successful message resets pong timer
                                                                 last_pong_time = std::time::Instant::now();
                                                                 awaiting_pong = false; // Also reset awaiting_pong if we received a valid event
@@ -707,7 +719,12 @@ mod tests {
         });
 
         // Subscribe to a non-existent server - this will keep retrying
-        let subscription_request = TcpSubscriptionRequest { subscribe: vec![] };
+        let subscription_request = TcpSubscriptionRequest {
+            subscribe: vec![],
+            from_seq: None,
+            filter: None,
+            api_key: None,
+        };
         info!("Subscribing to non-existent server to test shutdown");
 
         // We know this will fail to connect, but it starts the background task
@@ -752,7 +769,12 @@ mod tests {
         });
 
         // First subscription
-        let subscription_request1 = TcpSubscriptionRequest { subscribe: vec![] };
+        let subscription_request1 = TcpSubscriptionRequest {
+            subscribe: vec![],
+            from_seq: None,
+            filter: None,
+            api_key: None,
+        };
         info!("Creating first subscription");
         let result1 = client.subscribe("127.0.0.1:1", subscription_request1).await;
         assert!(result1.is_ok());
@@ -765,7 +787,12 @@ mod tests {
         sleep(Duration::from_millis(200)).await;
 
         // Second subscription - should replace the first one
-        let subscription_request2 = TcpSubscriptionRequest { subscribe: vec![] };
+        let subscription_request2 = TcpSubscriptionRequest {
+            subscribe: vec![],
+            from_seq: None,
+            filter: None,
+            api_key: None,
+        };
         info!("Creating second subscription (should replace the first)");
         let result2 = client.subscribe("127.0.0.1:2", subscription_request2).await;
         assert!(result2.is_ok());
@@ -811,6 +838,9 @@ mod tests {
         // Subscribe to the server
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         info!("Subscribing to test server at {}", server_addr);
@@ -871,6 +901,9 @@ mod tests {
         // Subscribe to the server
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         info!("Subscribing to test server at {}", server_addr);
@@ -962,6 +995,9 @@ mod tests {
         // Try to connect to a non-existent server
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         info!("Subscribing to non-existent server to test error handling");
@@ -1014,6 +1050,9 @@ mod tests {
         // Subscribe to a non-existent server to trigger reconnection attempts
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         info!("Subscribing to non-existent server to trigger reconnection");
@@ -1138,6 +1177,9 @@ mod tests {
         // Subscribe to the server
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         info!("Subscribing to server with buffer size limit test");
@@ -19,6 +19,7 @@ use crate::tcp::reconnection::ReconnectionManager;
 
 use super::{
     connection_status::{ConnectionStatus, ConnectionStatusTracker},
+    received_event::ReceivedEvent,
     reconnection,
 };
 
@@ -290,6 +291,10 @@ fn subscribe(
         // Create the reconnection manager
         let mut reconnection_manager = ReconnectionManager::new(reconnection_config);
 
+        // Sequence number of the next event we expect, so that a reconnect resumes
+        // where the previous connection stopped.
+        let mut next_seq = subscription_request.from_seq;
+
         loop {
             if shutdown_flag.load(Ordering::SeqCst) {
                 info!("Shutdown flag set. Exiting subscription thread.");
@@ -337,7 +342,11 @@ fn subscribe(
                     let mut reader = BufReader::new(reader_stream);
 
                     // Serialize and send the subscription request.
-                    match serde_json::to_string(&subscription_request) {
+                    let request = TcpSubscriptionRequest {
+                        from_seq: next_seq,
+                        ..subscription_request.clone()
+                    };
+                    match serde_json::to_string(&request) {
                         Ok(req_json) => {
                             if let Err(e) = stream.write_all(req_json.as_bytes()) {
                                 error!("Failed to send subscription request: {}", e);
@@ -447,8 +456,13 @@ fn subscribe(
                                             // Don't break, just clear buffer and continue reading the next message.
                                         } else {
                                             // Try to parse as an event from the byte slice
-                                            match serde_json::from_slice::<Event>(message_bytes) {
-                                                Ok(event) => {
+                                            match serde_json::from_slice::<ReceivedEvent>(
+                                                message_bytes,
+                                            ) {
+                                                Ok(ReceivedEvent { seq, event }) => {
+                                                    if let Some(seq) = seq {
+                                                        next_seq = Some(seq + 1);
+                                                    }
                                                     // Any successful message means the connection is alive
                                                     last_pong_time = std::time::Instant::now();
                                                     awaiting_pong = false; // Reset awaiting_pong if we received a valid event
@@ -652,6 +666,9 @@ mod tests {
         // Subscribe - this should connect
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         let rx = client
@@ -697,6 +714,9 @@ mod tests {
         // Subscribe to receive events
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         let rx = client
@@ -745,6 +765,9 @@ mod tests {
         // Try to connect to a non-existent server
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         let _rx = client
@@ -777,6 +800,9 @@ mod tests {
         // Subscribe to a non-existent server
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         let rx = client
@@ -817,6 +843,9 @@ mod tests {
                 "127.0.0.1:1".to_string(),
                 TcpSubscriptionRequest {
                     subscribe: vec![EventType::TransactionsAdded],
+                    from_seq: None,
+                    filter: None,
+                    api_key: None,
                 },
             )
             .unwrap();
@@ -835,6 +864,9 @@ mod tests {
                 "127.0.0.1:1".to_string(),
                 TcpSubscriptionRequest {
                     subscribe: vec![EventType::TransactionsAdded],
+                    from_seq: None,
+                    filter: None,
+                    api_key: None,
                 },
             )
             .unwrap();
@@ -997,6 +1029,9 @@ mod tests {
         // Subscribe to receive events
         let subscription_request = TcpSubscriptionRequest {
             subscribe: vec![EventType::TransactionsAdded],
+            from_seq: None,
+            filter: None,
+            api_key: None,
         };
 
         let _rx = client
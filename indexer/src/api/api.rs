@@ -1,30 +1,39 @@
 use {
     super::{
         content::{content_response, AcceptEncoding, ContentError},
+        psbt::{build_rune_transfer, Destination, PsbtError, Utxo},
         query::{to_hash, to_rune_id},
     },
     crate::{
         bitcoin_rpc::PooledClient,
-        index::{Index, IndexError},
+        index::{Index, IndexError, StoreError},
         subscription::{self, WebhookSubscriptionManager},
     },
-    bitcoin::{consensus, Address, BlockHash},
+    base64::{engine::general_purpose::STANDARD as BASE64, Engine},
+    bitcoin::{consensus, Address, BlockHash, Network, Psbt},
     bitcoincore_rpc::RpcApi,
     http::HeaderMap,
-    rustc_hash::FxHashMap as HashMap,
+    rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet},
     std::sync::Arc,
     titan_types_api::{
-        query, AddressData, BlockTip, Pagination, PaginationResponse, RuneResponse, Status,
-        Subscription,
+        query, AddressData, AddressFilter, AddressRuneBalance, AddressTxOut, BlockTip,
+        DecodedTransaction, DeliveryQuery, FeeHistogramBucket, MempoolBlock, MempoolPackage,
+        MempoolPackageQuery, Pagination, PaginationResponse, RecommendedFees, RuneFilter,
+        RuneHistoryEntry, RuneHistoryQuery, RuneHolder, RuneResponse, RuneTransferInput,
+        RuneTransferOutput, RuneTransferPsbt, RuneTransferRequest, Status, Subscription,
+        TxOutspend, WebhookDelivery,
     },
     titan_types_core::{
-        Block, InscriptionId, MempoolEntry, SerializedOutPoint, SerializedTxid, Transaction,
-        TransactionStatus, TxOut,
+        Block, InscriptionId, MempoolEntry, RuneAmount, RuneId, SerializedOutPoint, SerializedTxid,
+        SpentStatus, Transaction, TransactionStatus, TxOut,
     },
     tracing::error,
     uuid::Uuid,
 };
 
+/// Outputs of a source read by a rune transfer for each rune it sends, and for the fee.
+const RUNE_TRANSFER_OUTPUTS_PER_PAGE: u64 = 200;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
     #[error("not found: {0}")]
@@ -39,6 +48,10 @@ pub enum ApiError {
     HexError(#[from] hex::FromHexError),
     #[error("consensus error: {0}")]
     ConsensusError(#[from] consensus::encode::Error),
+    #[error("psbt error: {0}")]
+    PsbtError(#[from] PsbtError),
+    #[error("too many unspent transaction outputs (>{0})")]
+    TooManyUtxos(u64),
 }
 
 pub type Result<T> = std::result::Result<T, ApiError>;
@@ -54,6 +67,10 @@ pub fn tip(index: Arc<Index>) -> Result<BlockTip> {
     })
 }
 
+pub fn metrics(index: Arc<Index>) -> Result<String> {
+    Ok(index.encode_metrics()?)
+}
+
 pub fn status(index: Arc<Index>) -> Result<Status> {
     let block_count = index.get_block_count()?;
     let block_hash = index.get_block_hash(block_count - 1)?;
@@ -92,6 +109,15 @@ pub fn output(index: Arc<Index>, outpoint: &SerializedOutPoint) -> Result<TxOut>
     Ok(index.get_tx_out(outpoint)?)
 }
 
+/// Outputs in the order of `outpoints`, with `None` for the ones that aren't known.
+pub fn outputs(index: Arc<Index>, outpoints: &[SerializedOutPoint]) -> Result<Vec<Option<TxOut>>> {
+    let tx_outs = index.get_tx_outs(outpoints)?;
+    Ok(outpoints
+        .iter()
+        .map(|outpoint| tx_outs.get(outpoint).cloned())
+        .collect())
+}
+
 pub fn inscription_content(
     index: Arc<Index>,
     inscription_id: &InscriptionId,
@@ -107,30 +133,85 @@ pub fn inscription_content(
 pub fn rune(index: Arc<Index>, rune_query: &query::Rune) -> Result<RuneResponse> {
     let rune_id = to_rune_id(rune_query, &index)?;
     let block_count = index.get_block_count()?;
-    let rune_response = index
-        .get_rune(&rune_id)?
-        .to_rune_response(rune_id, block_count - 1);
+    let holders = index
+        .get_rune_holder_counts(&[rune_id])?
+        .remove(&rune_id)
+        .unwrap_or_default();
+    let rune_response =
+        index
+            .get_rune(&rune_id)?
+            .to_rune_response(rune_id, block_count - 1, holders);
     Ok(rune_response)
 }
 
+pub fn runes_batch(
+    index: Arc<Index>,
+    rune_ids: &Vec<RuneId>,
+) -> Result<HashMap<RuneId, Option<RuneResponse>>> {
+    let rune_entries = index.get_runes_by_ids(rune_ids)?;
+    let block_count = index.get_block_count()?;
+    let holders = index.get_rune_holder_counts(rune_ids)?;
+
+    Ok(rune_ids
+        .iter()
+        .map(|rune_id| {
+            let rune_response = rune_entries.get(rune_id).map(|rune_entry| {
+                let holders = holders.get(rune_id).copied().unwrap_or_default();
+                rune_entry.to_rune_response(*rune_id, block_count - 1, holders)
+            });
+            (*rune_id, rune_response)
+        })
+        .collect())
+}
+
 pub fn runes(
     index: Arc<Index>,
     pagination: Pagination,
+    filter: &RuneFilter,
 ) -> Result<PaginationResponse<RuneResponse>> {
-    let rune_entries = index.get_runes(pagination)?;
+    let rune_entries = index.get_runes(pagination, filter)?;
     let block_count = index.get_block_count()?;
+    let rune_ids = rune_entries
+        .items
+        .iter()
+        .map(|(rune_id, _)| *rune_id)
+        .collect::<Vec<_>>();
+    let holders = index.get_rune_holder_counts(&rune_ids)?;
     let rune_responses: Vec<RuneResponse> = rune_entries
         .items
         .into_iter()
-        .map(|(rune_id, rune_entry)| rune_entry.to_rune_response(rune_id, block_count))
+        .map(|(rune_id, rune_entry)| {
+            let holders = holders.get(&rune_id).copied().unwrap_or_default();
+            rune_entry.to_rune_response(rune_id, block_count, holders)
+        })
         .collect();
 
     Ok(PaginationResponse {
         items: rune_responses,
         offset: rune_entries.offset,
+        next_cursor: rune_entries.next_cursor,
+        prev_cursor: rune_entries.prev_cursor,
     })
 }
 
+pub fn rune_holders(
+    index: Arc<Index>,
+    rune_query: &query::Rune,
+    pagination: Pagination,
+) -> Result<PaginationResponse<RuneHolder>> {
+    let rune_id = to_rune_id(rune_query, &index)?;
+    Ok(index.get_rune_holders(&rune_id, pagination)?)
+}
+
+pub fn rune_history(
+    index: Arc<Index>,
+    rune_query: &query::Rune,
+    history_query: &RuneHistoryQuery,
+) -> Result<Vec<RuneHistoryEntry>> {
+    let rune_id = to_rune_id(rune_query, &index)?;
+    Ok(index.get_rune_history(&rune_id, history_query)?)
+}
+
 pub fn last_rune_transactions(
     index: Arc<Index>,
     rune_query: &query::Rune,
@@ -173,6 +254,11 @@ pub fn broadcast_transaction(
     Ok(serialized_txid)
 }
 
+pub fn decode_transaction(index: Arc<Index>, hex: &str) -> Result<DecodedTransaction> {
+    let transaction: bitcoin::Transaction = consensus::deserialize(&hex::decode(hex.trim())?)?;
+    Ok(index.decode_transaction(&transaction)?)
+}
+
 pub fn bitcoin_transaction_raw(
     index: Arc<Index>,
     client: PooledClient,
@@ -201,18 +287,64 @@ pub fn transaction(
     client: PooledClient,
     txid: &SerializedTxid,
 ) -> Result<Transaction> {
-    let transaction = if index.is_indexing_bitcoin_transactions() {
-        index.get_transaction(txid)?
+    if index.is_indexing_bitcoin_transactions() {
+        Ok(index.get_transaction(txid)?)
     } else {
-        let status = index.get_transaction_status(txid)?;
-        let transaction = client.get_raw_transaction(&txid.into(), None)?;
-        let (inputs, outputs) = index.get_inputs_outputs_from_transaction(&transaction, txid)?;
-        let transaction = Transaction::from((transaction, status, inputs, outputs));
+        rpc_transaction(&index, &client, txid)
+    }
+}
 
-        transaction
-    };
+pub fn transactions(
+    index: Arc<Index>,
+    client: PooledClient,
+    txids: &Vec<SerializedTxid>,
+) -> Result<HashMap<SerializedTxid, Option<Transaction>>> {
+    let indexed = index.is_indexing_bitcoin_transactions();
+
+    txids
+        .iter()
+        .map(|txid| {
+            let transaction = if indexed {
+                index.get_transaction(txid).map_err(ApiError::from)
+            } else {
+                rpc_transaction(&index, &client, txid)
+            };
+
+            match transaction {
+                Ok(transaction) => Ok((*txid, Some(transaction))),
+                Err(ApiError::IndexError(IndexError::StoreError(StoreError::NotFound(_)))) => {
+                    Ok((*txid, None))
+                }
+                Err(e) => Err(e),
+            }
+        })
+        .collect()
+}
+
+/// Build a transaction from the node, for indexes that don't store bitcoin transactions.
+fn rpc_transaction(
+    index: &Index,
+    client: &PooledClient,
+    txid: &SerializedTxid,
+) -> Result<Transaction> {
+    let status = index.get_transaction_status(txid)?;
+    let transaction = client.get_raw_transaction(&txid.into(), None)?;
+    let (inputs, outputs) = index.get_inputs_outputs_from_transaction(&transaction, txid)?;
+    let artifact = index.get_transaction_artifact(txid)?;
+
+    Ok(Transaction {
+        artifact,
+        ..Transaction::from((transaction, status, inputs, outputs))
+    })
+}
 
-    Ok(transaction)
+pub fn transaction_outspends(
+    index: Arc<Index>,
+    client: PooledClient,
+    txid: &SerializedTxid,
+) -> Result<Vec<TxOutspend>> {
+    let transaction = transaction(index.clone(), client, txid)?;
+    Ok(index.get_outspends(txid, transaction.output.len() as u32)?)
 }
 
 pub fn transaction_status(index: Arc<Index>, txid: &SerializedTxid) -> Result<TransactionStatus> {
@@ -262,21 +394,266 @@ pub fn mempool_entries_with_ancestors(
     Ok(index.get_mempool_entries_with_ancestors(txids)?)
 }
 
+pub fn mempool_package(
+    index: Arc<Index>,
+    txid: &SerializedTxid,
+    query: &MempoolPackageQuery,
+) -> Result<MempoolPackage> {
+    Ok(index.get_mempool_package(txid, query)?)
+}
+
+pub fn recommended_fees(index: Arc<Index>) -> Result<RecommendedFees> {
+    Ok(index.get_mempool_fees()?.recommended)
+}
+
+pub fn mempool_blocks(index: Arc<Index>) -> Result<Vec<MempoolBlock>> {
+    Ok(index.get_mempool_fees()?.blocks)
+}
+
+pub fn mempool_fee_histogram(index: Arc<Index>) -> Result<Vec<FeeHistogramBucket>> {
+    Ok(index.get_mempool_fees()?.histogram)
+}
+
 pub fn address(index: Arc<Index>, address: &Address) -> Result<AddressData> {
     let outpoints = index.get_script_pubkey_outpoints(&address)?;
     Ok(outpoints)
 }
 
+pub fn address_transactions(
+    index: Arc<Index>,
+    address: &Address,
+    pagination: Pagination,
+    filter: &AddressFilter,
+) -> Result<PaginationResponse<SerializedTxid>> {
+    let rune_id = match &filter.rune {
+        Some(rune) => Some(to_rune_id(rune, &index)?),
+        None => None,
+    };
+
+    Ok(index.get_address_transactions(address, pagination, filter.mempool, rune_id.as_ref())?)
+}
+
+pub fn address_utxos(
+    index: Arc<Index>,
+    address: &Address,
+    pagination: Pagination,
+    filter: &AddressFilter,
+) -> Result<PaginationResponse<AddressTxOut>> {
+    let rune_id = match &filter.rune {
+        Some(rune) => Some(to_rune_id(rune, &index)?),
+        None => None,
+    };
+
+    Ok(index.get_address_utxos(address, pagination, filter.mempool, rune_id.as_ref())?)
+}
+
+pub fn address_runes(index: Arc<Index>, address: &Address) -> Result<Vec<AddressRuneBalance>> {
+    Ok(index.get_address_rune_balances(address, None)?)
+}
+
+pub fn address_rune(
+    index: Arc<Index>,
+    address: &Address,
+    rune_query: &query::Rune,
+) -> Result<AddressRuneBalance> {
+    let rune_id = to_rune_id(rune_query, &index)?;
+    let balance = index
+        .get_address_rune_balances(address, Some(&rune_id))?
+        .into_iter()
+        .next()
+        .unwrap_or(AddressRuneBalance {
+            rune_id,
+            confirmed: 0,
+            confirmed_risky: 0,
+            pending: 0,
+            risky: 0,
+            pending_spent: 0,
+        });
+
+    Ok(balance)
+}
+
+/// Builds an unsigned rune transfer spending the indexed outputs of the request's
+/// sources.
+pub fn rune_transfer_psbt(
+    index: Arc<Index>,
+    client: PooledClient,
+    network: Network,
+    request: &RuneTransferRequest,
+) -> Result<RuneTransferPsbt> {
+    let sources = request
+        .sources
+        .iter()
+        .map(|address| address.clone().require_network(network))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(PsbtError::from)?;
+    let change = request
+        .change_address
+        .clone()
+        .require_network(network)
+        .map_err(PsbtError::from)?;
+
+    let mut destinations = Vec::with_capacity(request.destinations.len());
+    for destination in &request.destinations {
+        destinations.push(Destination {
+            script_pubkey: destination
+                .address
+                .clone()
+                .require_network(network)
+                .map_err(PsbtError::from)?
+                .script_pubkey(),
+            rune_id: to_rune_id(&destination.rune, &index)?,
+            amount: destination.amount,
+            value: destination.value,
+        });
+    }
+
+    let mut rune_ids: Vec<RuneId> = destinations
+        .iter()
+        .map(|destination| destination.rune_id)
+        .collect();
+    rune_ids.sort();
+    rune_ids.dedup();
+
+    let mut seen = HashSet::default();
+    let mut utxos = Vec::new();
+    let mut source_addresses = HashMap::default();
+    for address in sources {
+        utxos.extend(rune_transfer_utxos(
+            &index, &address, &rune_ids, request, &mut seen,
+        )?);
+        source_addresses.insert(address.script_pubkey(), address);
+    }
+
+    let transfer = build_rune_transfer(
+        &utxos,
+        &destinations,
+        change.script_pubkey(),
+        request.fee_rate,
+    )?;
+
+    let mut psbt = Psbt::from_unsigned_tx(transfer.transaction.clone()).map_err(PsbtError::from)?;
+    for (input, utxo) in psbt.inputs.iter_mut().zip(&transfer.inputs) {
+        if utxo.script_pubkey.is_p2pkh() {
+            // Legacy inputs are signed over the whole previous transaction.
+            input.non_witness_utxo =
+                Some(client.get_raw_transaction(&utxo.outpoint.to_txid(), None)?);
+        } else {
+            input.witness_utxo = Some(bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(utxo.value),
+                script_pubkey: utxo.script_pubkey.clone(),
+            });
+        }
+    }
+
+    Ok(RuneTransferPsbt {
+        psbt: BASE64.encode(psbt.serialize()),
+        fee: transfer.fee,
+        vsize: transfer.vsize,
+        inputs: transfer
+            .inputs
+            .into_iter()
+            .map(|utxo| RuneTransferInput {
+                txid: utxo.outpoint.to_txid(),
+                vout: utxo.outpoint.vout(),
+                address: source_addresses[&utxo.script_pubkey].as_unchecked().clone(),
+                value: utxo.value,
+                runes: utxo.runes,
+            })
+            .collect(),
+        outputs: transfer
+            .transaction
+            .output
+            .iter()
+            .zip(transfer.allocations)
+            .zip(0..)
+            .map(|((output, runes), vout)| RuneTransferOutput {
+                vout,
+                address: Address::from_script(&output.script_pubkey, network)
+                    .ok()
+                    .map(Address::into_unchecked),
+                value: output.value.to_sat(),
+                runes,
+            })
+            .collect(),
+    })
+}
+
+/// Unspent outputs of `address` a rune transfer can spend: a page of the ones holding
+/// each rune in `rune_ids`, then a page of any of them to pay the fee. Outputs already
+/// in `seen` are skipped.
+fn rune_transfer_utxos(
+    index: &Index,
+    address: &Address,
+    rune_ids: &[RuneId],
+    request: &RuneTransferRequest,
+    seen: &mut HashSet<SerializedOutPoint>,
+) -> Result<Vec<Utxo>> {
+    // Outputs spent in the mempool are marked spent either way.
+    let mempool = (!request.allow_pending).then_some(false);
+
+    let mut utxos = Vec::new();
+    for rune_id in rune_ids.iter().map(Some).chain([None]) {
+        let pagination = Pagination {
+            limit: RUNE_TRANSFER_OUTPUTS_PER_PAGE,
+            ..Default::default()
+        };
+
+        for output in index
+            .get_address_utxos(address, pagination, mempool, rune_id)?
+            .items
+        {
+            let outpoint = SerializedOutPoint::from_txid_vout(&output.txid.into(), output.vout);
+            if output.spent != SpentStatus::Unspent
+                || (!request.allow_risky && !output.risky_runes.is_empty())
+                || !seen.insert(outpoint)
+            {
+                continue;
+            }
+
+            let mut runes: Vec<RuneAmount> = Vec::new();
+            for rune in output.runes.into_iter().chain(output.risky_runes) {
+                match runes.iter_mut().find(|held| held.id == rune.id) {
+                    Some(held) => {
+                        held.amount = held
+                            .amount
+                            .checked_add(rune.amount)
+                            .ok_or(PsbtError::Overflow)?;
+                    }
+                    None => runes.push(rune),
+                }
+            }
+
+            utxos.push(Utxo {
+                outpoint,
+                value: output.value,
+                script_pubkey: address.script_pubkey(),
+                runes,
+            });
+        }
+    }
+
+    Ok(utxos)
+}
+
+/// Secrets are only returned when a subscription is added or its secret rotated.
 pub fn subscriptions(
     subscription_manager: Arc<WebhookSubscriptionManager>,
 ) -> Result<Vec<Subscription>> {
-    Ok(subscription_manager.get_subscriptions()?)
+    Ok(subscription_manager
+        .get_subscriptions()?
+        .into_iter()
+        .map(|subscription| Subscription {
+            secret: None,
+            ..subscription
+        })
+        .collect())
 }
 
 pub fn add_subscription(
     subscription_manager: Arc<WebhookSubscriptionManager>,
     subscription: Subscription,
-) -> Result<()> {
+) -> Result<Subscription> {
     Ok(subscription_manager.add_subscription(&subscription)?)
 }
 
@@ -291,5 +668,443 @@ pub fn get_subscription(
     subscription_manager: Arc<WebhookSubscriptionManager>,
     id: Uuid,
 ) -> Result<Subscription> {
-    Ok(subscription_manager.get_subscription(&id)?)
+    Ok(Subscription {
+        secret: None,
+        ..subscription_manager.get_subscription(&id)?
+    })
+}
+
+pub fn subscription_deliveries(
+    subscription_manager: Arc<WebhookSubscriptionManager>,
+    id: Uuid,
+    query: &DeliveryQuery,
+) -> Result<Vec<WebhookDelivery>> {
+    Ok(subscription_manager.get_deliveries(&id, query)?)
+}
+
+pub fn redeliver_subscription(
+    subscription_manager: Arc<WebhookSubscriptionManager>,
+    id: Uuid,
+    seq: Option<u64>,
+) -> Result<Vec<WebhookDelivery>> {
+    Ok(subscription_manager.redeliver(&id, seq)?)
+}
+
+pub fn rotate_subscription_secret(
+    subscription_manager: Arc<WebhookSubscriptionManager>,
+    id: Uuid,
+) -> Result<Subscription> {
+    Ok(subscription_manager.rotate_secret(&id)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            bitcoin_rpc::RpcClientPool,
+            db::TempDb,
+            index::tests::{index, mempool_entry, settings, transaction},
+            models::{BatchUpdate, BlockId, RuneEntry, TransactionStateChange},
+        },
+        bitcoin::{hashes::Hash, OutPoint, ScriptBuf, Sequence},
+        titan_types_core::{ArtifactKind, Rune, SpacedRune, SpenderReference},
+    };
+
+    fn tx_out(script_pubkey: &ScriptBuf, amount: u128) -> TxOut {
+        TxOut {
+            runes: vec![RuneAmount {
+                id: RuneId::new(840000, 1),
+                amount,
+            }],
+            risky_runes: vec![],
+            spent: SpentStatus::Unspent,
+            value: 546,
+            script_pubkey: script_pubkey.clone(),
+        }
+    }
+
+    #[test]
+    fn batches_outputs_and_transactions() {
+        let db = TempDb::open();
+        let index = Arc::new(index(&db));
+        let scripts = [
+            ScriptBuf::from_bytes(vec![0x51]),
+            ScriptBuf::from_bytes(vec![0x52]),
+        ];
+
+        let tx = transaction(&[OutPoint::null()], scripts.to_vec());
+        let txid = SerializedTxid::from(tx.compute_txid());
+        let outpoint = |vout| SerializedOutPoint::from_txid_vout(&txid, vout);
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.transactions.insert(txid, tx.clone());
+        // The artifact comes from what was found when indexing it.
+        update.tx_state_changes.insert(
+            txid,
+            TransactionStateChange {
+                inputs: vec![],
+                outputs: vec![],
+                etched: None,
+                minted: None,
+                burned: HashMap::default(),
+                is_coinbase: false,
+                artifact: Some(ArtifactKind::Cenotaph { flaw: None }),
+            },
+        );
+        update.txouts.insert(outpoint(0), tx_out(&scripts[0], 100));
+        update.txouts.insert(outpoint(1), tx_out(&scripts[1], 200));
+        db.batch_update(&update, true).unwrap();
+
+        // Outputs come back in the order they were asked for.
+        assert_eq!(
+            outputs(index.clone(), &[outpoint(1), outpoint(2), outpoint(0)]).unwrap(),
+            vec![
+                Some(tx_out(&scripts[1], 200)),
+                None,
+                Some(tx_out(&scripts[0], 100)),
+            ]
+        );
+
+        let unknown = SerializedTxid::from([9; 32]);
+        let client = RpcClientPool::new(Arc::new(settings()), 1).get().unwrap();
+        let mut transactions = transactions(index, client, &vec![txid, unknown]).unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert!(matches!(transactions.remove(&unknown), Some(None)));
+
+        let transaction = transactions.remove(&txid).unwrap().unwrap();
+        assert_eq!(transaction.txid, tx.compute_txid());
+        assert_eq!(transaction.status, TransactionStatus::unconfirmed());
+        assert_eq!(
+            transaction.artifact,
+            Some(ArtifactKind::Cenotaph { flaw: None })
+        );
+        assert_eq!(
+            transaction
+                .output
+                .iter()
+                .map(|output| output.runes.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                tx_out(&scripts[0], 100).runes,
+                tx_out(&scripts[1], 200).runes,
+            ]
+        );
+    }
+
+    #[test]
+    fn batches_runes() {
+        let db = TempDb::open();
+        let index = Arc::new(index(&db));
+        let rune_id = RuneId::new(840000, 1);
+        let entry = RuneEntry {
+            block: 840000,
+            burned: 0,
+            divisibility: 2,
+            etching: SerializedTxid::from([1; 32]),
+            mints: 0,
+            number: 0,
+            premine: 1000,
+            spaced_rune: SpacedRune::new(Rune(99246114928149462), 0),
+            symbol: Some('R'),
+            terms: None,
+            pending_burns: 0,
+            pending_mints: 0,
+            inscription_id: None,
+            timestamp: 0,
+            turbo: false,
+        };
+
+        let mut update = BatchUpdate::new(1, 840001, 0);
+        update.runes.insert(rune_id, entry.clone());
+        db.batch_update(&update, false).unwrap();
+
+        let unknown = RuneId::new(840000, 2);
+        let mut runes = runes_batch(index, &vec![rune_id, unknown]).unwrap();
+        assert_eq!(runes.len(), 2);
+        assert_eq!(runes.remove(&unknown), Some(None));
+        assert_eq!(
+            runes.remove(&rune_id),
+            Some(Some(entry.to_rune_response(rune_id, 840000, 0)))
+        );
+    }
+
+    #[test]
+    fn lists_outspends_with_mempool_spends() {
+        let db = TempDb::open();
+        let index = Arc::new(index(&db));
+        let script_pubkey = ScriptBuf::from_bytes(vec![0x51]);
+        let client = || RpcClientPool::new(Arc::new(settings()), 1).get().unwrap();
+
+        let tx = transaction(&[OutPoint::null()], vec![script_pubkey.clone(); 4]);
+        let txid = SerializedTxid::from(tx.compute_txid());
+        let outpoint = |vout| OutPoint::new(tx.compute_txid(), vout);
+
+        let confirmed_spender = transaction(&[outpoint(0)], vec![script_pubkey.clone()]);
+        let confirmed_spender_txid = SerializedTxid::from(confirmed_spender.compute_txid());
+        let mempool_spender = transaction(&[outpoint(1)], vec![script_pubkey.clone()]);
+        let mempool_spender_txid = SerializedTxid::from(mempool_spender.compute_txid());
+
+        let block = BlockId {
+            hash: BlockHash::from_byte_array([7; 32]),
+            height: 100,
+        };
+        let unspent = tx_out(&script_pubkey, 100);
+
+        // The first output is spent in a block, the second in the mempool, the third
+        // is unspent and the last one was pruned.
+        let mut update = BatchUpdate::new(0, 101, 0);
+        for spender_txid in [txid, confirmed_spender_txid] {
+            update
+                .transaction_confirming_block
+                .insert(spender_txid, block);
+        }
+        update.transactions.insert(txid, tx.clone());
+        update
+            .transactions
+            .insert(confirmed_spender_txid, confirmed_spender);
+        update.txouts.insert(
+            outpoint(0).into(),
+            TxOut {
+                spent: SpentStatus::Spent(SpenderReference {
+                    txid: confirmed_spender_txid,
+                    vin: 0,
+                }),
+                ..unspent.clone()
+            },
+        );
+        update.txouts.insert(outpoint(1).into(), unspent.clone());
+        update.txouts.insert(outpoint(2).into(), unspent);
+        db.batch_update(&update, false).unwrap();
+
+        let mut update = BatchUpdate::new(0, 101, 0);
+        update
+            .mempool_txs
+            .insert(mempool_spender_txid, mempool_entry(100, 1000, vec![]));
+        update
+            .transactions
+            .insert(mempool_spender_txid, mempool_spender);
+        update.spent_outpoints_in_mempool.insert(
+            outpoint(1).into(),
+            SpenderReference {
+                txid: mempool_spender_txid,
+                vin: 0,
+            },
+        );
+        db.batch_update(&update, true).unwrap();
+
+        assert_eq!(
+            transaction_outspends(index.clone(), client(), &txid).unwrap(),
+            vec![
+                TxOutspend {
+                    vout: 0,
+                    spent: true,
+                    spender: Some(SpenderReference {
+                        txid: confirmed_spender_txid,
+                        vin: 0,
+                    }),
+                    status: Some(block.into_transaction_status()),
+                },
+                TxOutspend {
+                    vout: 1,
+                    spent: true,
+                    spender: Some(SpenderReference {
+                        txid: mempool_spender_txid,
+                        vin: 0,
+                    }),
+                    status: Some(TransactionStatus::unconfirmed()),
+                },
+                TxOutspend {
+                    vout: 2,
+                    spent: false,
+                    spender: None,
+                    status: None,
+                },
+                TxOutspend {
+                    vout: 3,
+                    spent: true,
+                    spender: None,
+                    status: None,
+                },
+            ]
+        );
+
+        assert!(transaction_outspends(index, client(), &SerializedTxid::from([9; 32])).is_err());
+    }
+
+    #[test]
+    fn reads_rune_transfer_utxos_a_page_per_rune() {
+        let db = TempDb::open();
+        let index = Arc::new(index(&db));
+        let script_pubkey =
+            ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let address = Address::from_script(&script_pubkey, Network::Regtest).unwrap();
+        let rune_id = RuneId::new(840000, 1);
+
+        // A rune output, a funding output and a risky output holding the rune twice.
+        let tx = transaction(&[OutPoint::null()], vec![script_pubkey.clone(); 3]);
+        let txid = SerializedTxid::from(tx.compute_txid());
+        let outpoint = |vout| SerializedOutPoint::from_txid_vout(&txid, vout);
+        let risky = TxOut {
+            risky_runes: vec![RuneAmount {
+                id: rune_id,
+                amount: u128::MAX,
+            }],
+            ..tx_out(&script_pubkey, 1)
+        };
+
+        let mut update = BatchUpdate::new(0, 101, 0);
+        update.transaction_confirming_block.insert(
+            txid,
+            BlockId {
+                hash: BlockHash::from_byte_array([7; 32]),
+                height: 100,
+            },
+        );
+        update.transactions.insert(txid, tx);
+        update
+            .txouts
+            .insert(outpoint(0), tx_out(&script_pubkey, 100));
+        update.txouts.insert(
+            outpoint(1),
+            TxOut {
+                runes: vec![],
+                value: 10_000,
+                ..tx_out(&script_pubkey, 0)
+            },
+        );
+        update.txouts.insert(outpoint(2), risky);
+        update.script_pubkeys.insert(
+            script_pubkey.clone(),
+            ((0..3).map(outpoint).collect(), vec![]),
+        );
+        db.batch_update(&update, false).unwrap();
+
+        let mut request = RuneTransferRequest {
+            sources: vec![address.as_unchecked().clone()],
+            destinations: vec![],
+            fee_rate: 1.0,
+            change_address: address.as_unchecked().clone(),
+            allow_pending: false,
+            allow_risky: false,
+        };
+
+        // The rune's page first, then the rest for the fee, each output once.
+        let utxos = rune_transfer_utxos(
+            &index,
+            &address,
+            &[rune_id],
+            &request,
+            &mut HashSet::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            utxos
+                .iter()
+                .map(|utxo| (utxo.outpoint, utxo.value, utxo.runes.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (outpoint(0), 546, tx_out(&script_pubkey, 100).runes),
+                (outpoint(1), 10_000, vec![]),
+            ]
+        );
+
+        // Adding up the risky output's amounts overflows.
+        request.allow_risky = true;
+        assert!(matches!(
+            rune_transfer_utxos(
+                &index,
+                &address,
+                &[rune_id],
+                &request,
+                &mut HashSet::default()
+            ),
+            Err(ApiError::PsbtError(PsbtError::Overflow))
+        ));
+    }
+
+    #[test]
+    fn computes_package_bump_fees() {
+        let db = TempDb::open();
+        let index = Arc::new(index(&db));
+        let script_pubkey = ScriptBuf::from_bytes(vec![0x51]);
+
+        // A 200 vB parent paying 1 sat/vB, signaling replaceability, bumped by a
+        // 100 vB child paying 300 sats.
+        let mut parent = transaction(&[OutPoint::null()], vec![script_pubkey.clone()]);
+        parent.input[0].sequence = Sequence::ENABLE_RBF_NO_LOCKTIME;
+        let parent_txid = SerializedTxid::from(parent.compute_txid());
+        let child = transaction(
+            &[OutPoint::new(parent.compute_txid(), 0)],
+            vec![script_pubkey.clone()],
+        );
+        let child_txid = SerializedTxid::from(child.compute_txid());
+        // An unrelated transaction that doesn't signal.
+        let other = transaction(&[OutPoint::null()], vec![script_pubkey.clone(); 2]);
+        let other_txid = SerializedTxid::from(other.compute_txid());
+
+        let mut parent_entry = mempool_entry(200, 200, vec![]);
+        parent_entry.descendant_count = 2;
+        parent_entry.descendant_size = 300;
+        parent_entry.fees.descendant = 500;
+        parent_entry.spent_by = vec![child_txid];
+        let mut child_entry = mempool_entry(100, 300, vec![parent_txid]);
+        child_entry.ancestor_count = 2;
+        child_entry.ancestor_size = 300;
+        child_entry.fees.ancestor = 500;
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.mempool_txs.insert(parent_txid, parent_entry);
+        update.mempool_txs.insert(child_txid, child_entry);
+        update
+            .mempool_txs
+            .insert(other_txid, mempool_entry(200, 2000, vec![]));
+        update.transactions.insert(parent_txid, parent);
+        update.transactions.insert(child_txid, child);
+        update.transactions.insert(other_txid, other);
+        db.batch_update(&update, true).unwrap();
+
+        let query = MempoolPackageQuery {
+            fee_rate: Some(10.0),
+            child_vsize: Some(100),
+        };
+        let package = mempool_package(index.clone(), &parent_txid, &query).unwrap();
+        assert!(package.ancestors.is_empty());
+        assert_eq!(package.descendants, vec![child_txid]);
+        assert_eq!(package.descendant_vsize, 300);
+        assert_eq!(package.descendant_fees, 500);
+        // Both are mined together, at the rate of the package.
+        assert_eq!(package.effective_fee_rate, 500.0 / 300.0);
+        assert!(package.bip125_replaceable);
+        assert_eq!(package.target_fee_rate, 10.0);
+        // 10 sat/vB for the parent and the child, less what the parent pays.
+        assert_eq!(package.cpfp.child_vsize, 100);
+        assert_eq!(package.cpfp.child_fee, 2_800);
+        // A replacement evicts the child too, so it pays for both plus its relay.
+        assert_eq!(package.rbf.min_fee, 700);
+        assert_eq!(package.rbf.target_fee, 2_000);
+
+        // The child inherits replaceability and is already above the recommended
+        // rate, so another child only pays for its own relay.
+        let package =
+            mempool_package(index.clone(), &child_txid, &MempoolPackageQuery::default()).unwrap();
+        assert_eq!(package.ancestors, vec![parent_txid]);
+        assert!(package.descendants.is_empty());
+        assert_eq!(package.ancestor_vsize, 300);
+        assert_eq!(package.ancestor_fees, 500);
+        assert_eq!(package.effective_fee_rate, 500.0 / 300.0);
+        assert!(package.bip125_replaceable);
+        assert_eq!(package.target_fee_rate, 1.0);
+        // One P2TR input and output.
+        assert_eq!(package.cpfp.child_vsize, 111);
+        assert_eq!(package.cpfp.child_fee, 111);
+        assert_eq!(package.rbf.min_fee, 400);
+        assert_eq!(package.rbf.target_fee, 400);
+
+        let package = mempool_package(index.clone(), &other_txid, &query).unwrap();
+        assert_eq!(package.effective_fee_rate, 10.0);
+        assert!(!package.bip125_replaceable);
+
+        assert!(mempool_package(index, &SerializedTxid::from([9; 32]), &query).is_err());
+    }
 }
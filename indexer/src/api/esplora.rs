@@ -0,0 +1,790 @@
+//! Esplora-compatible views of the index, with the JSON shapes of the Blockstream
+//! Esplora REST API.
+
+use {
+    super::{transactions, ApiError, Result},
+    crate::{
+        bitcoin_rpc::PooledClient,
+        index::{Index, IndexError, StoreError},
+    },
+    bitcoin::{consensus, Address, BlockHash, Network, Script, Txid},
+    bitcoincore_rpc::RpcApi,
+    rustc_hash::FxHashMap as HashMap,
+    serde::Serialize,
+    std::{collections::BTreeMap, sync::Arc},
+    titan_types_api::{Cursor, Pagination, PaginationResponse},
+    titan_types_core::{SerializedTxid, SpentStatus, TransactionStatus},
+};
+
+/// Confirmed transactions per page of `/address/{address}/txs/chain`.
+const CHAIN_TXS_PER_PAGE: u64 = 25;
+/// Most mempool transactions listed by `/address/{address}/txs`.
+const MEMPOOL_TXS_LIMIT: u64 = 50;
+/// Most outputs listed by `/address/{address}/utxo`, Esplora's default `--utxos-limit`.
+const MAX_UTXOS: u64 = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TxStatus {
+    pub confirmed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<BlockHash>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_time: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Tx {
+    pub txid: Txid,
+    pub version: i32,
+    pub locktime: u32,
+    pub vin: Vec<Vin>,
+    pub vout: Vec<Vout>,
+    pub size: u64,
+    pub weight: u64,
+    pub fee: u64,
+    pub status: TxStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Vin {
+    pub txid: Txid,
+    pub vout: u32,
+    pub prevout: Option<Vout>,
+    pub scriptsig: String,
+    pub scriptsig_asm: String,
+    pub witness: Vec<String>,
+    pub is_coinbase: bool,
+    pub sequence: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Vout {
+    pub scriptpubkey: String,
+    pub scriptpubkey_asm: String,
+    pub scriptpubkey_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scriptpubkey_address: Option<String>,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Outspend {
+    pub spent: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txid: Option<Txid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vin: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<TxStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Utxo {
+    pub txid: Txid,
+    pub vout: u32,
+    pub status: TxStatus,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Block {
+    pub id: BlockHash,
+    pub height: u64,
+    pub version: i32,
+    pub timestamp: u32,
+    pub tx_count: u64,
+    pub size: u64,
+    pub weight: u64,
+    pub merkle_root: String,
+    pub previousblockhash: Option<BlockHash>,
+    pub mediantime: Option<u64>,
+    pub nonce: u32,
+    pub bits: u32,
+    pub difficulty: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockStatus {
+    pub in_best_chain: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_best: Option<BlockHash>,
+}
+
+pub fn tip_height(index: Arc<Index>) -> Result<u64> {
+    Ok(index.get_block_count()?.saturating_sub(1))
+}
+
+pub fn tip_hash(index: Arc<Index>) -> Result<BlockHash> {
+    let height = tip_height(index.clone())?;
+    Ok(index.get_block_hash(height)?)
+}
+
+pub fn block_hash(index: Arc<Index>, height: u64) -> Result<BlockHash> {
+    Ok(index.get_block_hash(height)?)
+}
+
+pub fn block(index: Arc<Index>, client: PooledClient, hash: &BlockHash) -> Result<Block> {
+    let block = index.get_block_by_hash(hash)?;
+    // Sizes and the median time aren't indexed.
+    let info = client.get_block_info(hash)?;
+
+    Ok(Block {
+        id: *hash,
+        height: block.height,
+        version: block.header.version.to_consensus(),
+        timestamp: block.header.time,
+        tx_count: block.tx_ids.len() as u64,
+        size: info.size as u64,
+        weight: info.weight as u64,
+        merkle_root: block.header.merkle_root.to_string(),
+        previousblockhash: (block.height > 0).then_some(block.header.prev_blockhash),
+        mediantime: info.mediantime.map(|time| time as u64),
+        nonce: block.header.nonce,
+        bits: block.header.bits.to_consensus(),
+        difficulty: info.difficulty,
+    })
+}
+
+pub fn block_status(index: Arc<Index>, hash: &BlockHash) -> Result<BlockStatus> {
+    let Ok(block) = index.get_block_by_hash(hash) else {
+        return Ok(BlockStatus {
+            in_best_chain: false,
+            height: None,
+            next_best: None,
+        });
+    };
+
+    let in_best_chain = index.get_block_hash(block.height)? == *hash;
+
+    Ok(BlockStatus {
+        in_best_chain,
+        height: Some(block.height),
+        next_best: in_best_chain
+            .then(|| index.get_block_hash(block.height + 1).ok())
+            .flatten(),
+    })
+}
+
+pub fn block_header(index: Arc<Index>, hash: &BlockHash) -> Result<String> {
+    let header = index.get_block_header(hash)?;
+    Ok(consensus::encode::serialize_hex(&header))
+}
+
+pub fn block_txids(index: Arc<Index>, hash: &BlockHash) -> Result<Vec<Txid>> {
+    let block = index.get_block_by_hash(hash)?;
+    Ok(block.tx_ids.iter().map(Txid::from).collect())
+}
+
+pub fn tx(
+    index: Arc<Index>,
+    client: PooledClient,
+    network: Network,
+    txid: &SerializedTxid,
+) -> Result<Tx> {
+    let transaction = super::transaction(index.clone(), client, txid)?;
+    to_tx(&mut BlockTimes::new(&index), network, transaction)
+}
+
+pub fn tx_status(index: Arc<Index>, txid: &SerializedTxid) -> Result<TxStatus> {
+    let status = index.get_transaction_status(txid)?;
+
+    // Esplora has no replaced state, replaced transactions are just gone.
+    if status.replaced.is_some() {
+        return Err(IndexError::StoreError(StoreError::NotFound(txid.to_string())).into());
+    }
+
+    BlockTimes::new(&index).status(status)
+}
+
+/// Spending status of every output of a transaction, mempool spends included.
+pub fn tx_outspends(
+    index: Arc<Index>,
+    client: PooledClient,
+    txid: &SerializedTxid,
+) -> Result<Vec<Outspend>> {
+    let mut block_times = BlockTimes::new(&index);
+
+    super::transaction_outspends(index.clone(), client, txid)?
+        .into_iter()
+        .map(|outspend| {
+            Ok(Outspend {
+                spent: outspend.spent,
+                txid: outspend.spender.as_ref().map(|spender| spender.txid.into()),
+                vin: outspend.spender.as_ref().map(|spender| spender.vin),
+                status: match outspend.status {
+                    Some(status) => Some(block_times.status(status)?),
+                    None => None,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Every unspent output of `address`, mempool included, without the ones spent in the
+/// mempool. Like Esplora, addresses with more than `MAX_UTXOS` of them are refused.
+pub fn address_utxos(index: Arc<Index>, address: &Address) -> Result<Vec<Utxo>> {
+    let mut outputs = Vec::new();
+    let mut pagination = Pagination {
+        limit: MAX_UTXOS + 1,
+        ..Default::default()
+    };
+
+    loop {
+        let page = index.get_address_utxos(address, pagination.clone(), None, None)?;
+        outputs.extend(
+            page.items
+                .into_iter()
+                .filter(|output| output.spent == SpentStatus::Unspent),
+        );
+
+        if outputs.len() as u64 > MAX_UTXOS {
+            return Err(ApiError::TooManyUtxos(MAX_UTXOS));
+        }
+
+        match page.next_cursor {
+            Some(cursor) => {
+                pagination.cursor = Some(cursor);
+                pagination.limit = MAX_UTXOS + 1 - outputs.len() as u64;
+            }
+            None => break,
+        }
+    }
+
+    let mut block_times = BlockTimes::new(&index);
+    outputs
+        .into_iter()
+        .map(|output| {
+            Ok(Utxo {
+                txid: output.txid,
+                vout: output.vout,
+                status: block_times.status(output.status)?,
+                value: output.value,
+            })
+        })
+        .collect()
+}
+
+/// Mempool transactions of `address`, followed by its newest confirmed ones.
+pub fn address_txs(
+    index: Arc<Index>,
+    client: PooledClient,
+    network: Network,
+    address: &Address,
+) -> Result<Vec<Tx>> {
+    let mut txids = address_txids(&index, address, true, None, MEMPOOL_TXS_LIMIT)?.items;
+    txids.extend(address_txids(&index, address, false, None, CHAIN_TXS_PER_PAGE)?.items);
+
+    to_txs(&index, client, network, &txids)
+}
+
+pub fn address_txs_mempool(
+    index: Arc<Index>,
+    client: PooledClient,
+    network: Network,
+    address: &Address,
+) -> Result<Vec<Tx>> {
+    let txids = address_txids(&index, address, true, None, MEMPOOL_TXS_LIMIT)?.items;
+    to_txs(&index, client, network, &txids)
+}
+
+/// Confirmed transactions of `address`, newest first, after `last_seen` if set.
+pub fn address_txs_chain(
+    index: Arc<Index>,
+    client: PooledClient,
+    network: Network,
+    address: &Address,
+    last_seen: Option<&SerializedTxid>,
+) -> Result<Vec<Tx>> {
+    // Esplora pages by txid and the index by position, so start from the position of
+    // `last_seen`. Like Esplora, nothing follows a transaction outside the history.
+    let cursor = match last_seen {
+        Some(last_seen) => match index.get_address_transaction_index(address, last_seen)? {
+            Some(position) => Some(Cursor::new(false, position)),
+            None => return Ok(vec![]),
+        },
+        None => None,
+    };
+
+    let txids = address_txids(&index, address, false, cursor, CHAIN_TXS_PER_PAGE)?.items;
+    to_txs(&index, client, network, &txids)
+}
+
+/// Fee rates, in sat/vB, to confirm within each target number of blocks, as estimated
+/// by the node for the current block.
+pub fn fee_estimates(index: Arc<Index>) -> Result<BTreeMap<u16, f64>> {
+    Ok(index.get_fee_estimates()?.as_ref().clone())
+}
+
+fn address_txids(
+    index: &Index,
+    address: &Address,
+    mempool: bool,
+    cursor: Option<Cursor>,
+    limit: u64,
+) -> Result<PaginationResponse<SerializedTxid>> {
+    let pagination = Pagination {
+        limit,
+        cursor,
+        ..Default::default()
+    };
+
+    Ok(index.get_address_transactions(address, pagination, Some(mempool), None)?)
+}
+
+fn to_txs(
+    index: &Arc<Index>,
+    client: PooledClient,
+    network: Network,
+    txids: &[SerializedTxid],
+) -> Result<Vec<Tx>> {
+    let mut transactions = transactions(index.clone(), client, &txids.to_vec())?;
+    let mut block_times = BlockTimes::new(index);
+
+    txids
+        .iter()
+        .filter_map(|txid| transactions.remove(txid).flatten())
+        .map(|transaction| to_tx(&mut block_times, network, transaction))
+        .collect()
+}
+
+fn to_tx(
+    block_times: &mut BlockTimes,
+    network: Network,
+    transaction: titan_types_core::Transaction,
+) -> Result<Tx> {
+    let is_coinbase = transaction.is_coinbase();
+    let fee = transaction.fee_paid_sat().unwrap_or_default();
+
+    let vin = transaction
+        .input
+        .into_iter()
+        .map(|input| Vin {
+            txid: input.previous_output.to_txid(),
+            vout: input.previous_output.vout(),
+            prevout: input
+                .previous_output_data
+                .map(|prevout| to_vout(network, &prevout.script_pubkey, prevout.value)),
+            scriptsig: input.script_sig.to_hex_string(),
+            scriptsig_asm: input.script_sig.to_asm_string(),
+            witness: input.witness.iter().map(hex::encode).collect(),
+            is_coinbase,
+            sequence: input.sequence.0,
+        })
+        .collect();
+
+    let vout = transaction
+        .output
+        .iter()
+        .map(|output| to_vout(network, &output.script_pubkey, output.value))
+        .collect();
+
+    Ok(Tx {
+        txid: transaction.txid,
+        version: transaction.version,
+        locktime: transaction.lock_time,
+        vin,
+        vout,
+        size: transaction.size,
+        weight: transaction.weight,
+        fee,
+        status: block_times.status(transaction.status)?,
+    })
+}
+
+fn to_vout(network: Network, script_pubkey: &Script, value: u64) -> Vout {
+    Vout {
+        scriptpubkey: script_pubkey.to_hex_string(),
+        scriptpubkey_asm: script_pubkey.to_asm_string(),
+        scriptpubkey_type: script_type(script_pubkey),
+        scriptpubkey_address: Address::from_script(script_pubkey, network)
+            .ok()
+            .map(|address| address.to_string()),
+        value,
+    }
+}
+
+/// Times of the blocks seen while serving a request, so that each header is read once.
+struct BlockTimes<'a> {
+    index: &'a Index,
+    times: HashMap<BlockHash, u32>,
+}
+
+impl<'a> BlockTimes<'a> {
+    fn new(index: &'a Index) -> Self {
+        Self {
+            index,
+            times: HashMap::default(),
+        }
+    }
+
+    fn status(&mut self, status: TransactionStatus) -> Result<TxStatus> {
+        let block_time = match status.block_hash {
+            Some(block_hash) => Some(match self.times.get(&block_hash) {
+                Some(time) => *time,
+                None => {
+                    let time = self.index.get_block_header(&block_hash)?.time;
+                    self.times.insert(block_hash, time);
+                    time
+                }
+            }),
+            None => None,
+        };
+
+        Ok(TxStatus {
+            confirmed: status.confirmed,
+            block_height: status.block_height,
+            block_hash: status.block_hash,
+            block_time,
+        })
+    }
+}
+
+fn script_type(script: &Script) -> &'static str {
+    if script.is_empty() {
+        "empty"
+    } else if script.is_op_return() {
+        "op_return"
+    } else if script.is_p2pk() {
+        "p2pk"
+    } else if script.is_p2pkh() {
+        "p2pkh"
+    } else if script.is_p2sh() {
+        "p2sh"
+    } else if script.is_p2wpkh() {
+        "v0_p2wpkh"
+    } else if script.is_p2wsh() {
+        "v0_p2wsh"
+    } else if script.is_p2tr() {
+        "v1_p2tr"
+    } else if script.is_multisig() {
+        "multisig"
+    } else if script.is_provably_unspendable() {
+        "provably_unspendable"
+    } else {
+        "unknown"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            bitcoin_rpc::RpcClientPool,
+            db::TempDb,
+            index::tests::{index, settings, transaction},
+            models::{BatchUpdate, BlockId},
+        },
+        bitcoin::{
+            block::{Header, Version},
+            hashes::Hash,
+            CompactTarget, OutPoint, ScriptBuf, Transaction, TxMerkleNode,
+        },
+        serde_json::{json, Value},
+        titan_types_core::{Block as IndexedBlock, SerializedOutPoint, SpenderReference, TxOut},
+    };
+
+    const BLOCK_TIME: u32 = 1_700_000_000;
+
+    fn script_pubkey() -> ScriptBuf {
+        ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap()
+    }
+
+    fn address() -> Address {
+        Address::from_script(&script_pubkey(), Network::Regtest).unwrap()
+    }
+
+    fn client() -> PooledClient {
+        RpcClientPool::new(Arc::new(settings()), 1).get().unwrap()
+    }
+
+    /// Confirms `transactions` in block 100, with their outputs unspent.
+    fn confirm(update: &mut BatchUpdate, transactions: &[&Transaction]) -> BlockId {
+        let header = Header {
+            version: Version::TWO,
+            prev_blockhash: BlockHash::all_zeros(),
+            merkle_root: TxMerkleNode::all_zeros(),
+            time: BLOCK_TIME,
+            bits: CompactTarget::from_consensus(0x207fffff),
+            nonce: 0,
+        };
+        let block = BlockId {
+            hash: header.block_hash(),
+            height: 100,
+        };
+        let txids: Vec<SerializedTxid> = transactions
+            .iter()
+            .map(|transaction| transaction.compute_txid().into())
+            .collect();
+
+        update.blocks.insert(
+            block.hash,
+            IndexedBlock {
+                height: block.height,
+                header,
+                tx_ids: txids.clone(),
+                etched_runes: vec![],
+            },
+        );
+        update.block_hashes.insert(block.height, block.hash);
+
+        for (transaction, txid) in transactions.iter().zip(txids) {
+            update.transactions.insert(txid, (*transaction).clone());
+            update.transaction_confirming_block.insert(txid, block);
+
+            for (output, vout) in transaction.output.iter().zip(0..) {
+                update.txouts.insert(
+                    SerializedOutPoint::from_txid_vout(&txid, vout),
+                    TxOut {
+                        runes: vec![],
+                        risky_runes: vec![],
+                        spent: SpentStatus::Unspent,
+                        value: output.value.to_sat(),
+                        script_pubkey: output.script_pubkey.clone(),
+                    },
+                );
+            }
+        }
+
+        block
+    }
+
+    fn confirmed_status(block: BlockId) -> Value {
+        json!({
+            "confirmed": true,
+            "block_height": block.height,
+            "block_hash": block.hash,
+            "block_time": BLOCK_TIME,
+        })
+    }
+
+    fn to_json(value: impl Serialize) -> Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    fn vout() -> Value {
+        json!({
+            "scriptpubkey": "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+            "scriptpubkey_asm": "OP_0 OP_PUSHBYTES_20 751e76e8199196d454941c45d1b3a323f1433bd6",
+            "scriptpubkey_type": "v0_p2wpkh",
+            "scriptpubkey_address": address().to_string(),
+            "value": 546,
+        })
+    }
+
+    #[test]
+    fn serves_transactions_like_esplora() {
+        let db = TempDb::open();
+        let index = Arc::new(index(&db));
+
+        // A coinbase paying the address twice, and a transaction spending its first
+        // output.
+        let coinbase = transaction(&[OutPoint::null()], vec![script_pubkey(); 2]);
+        let coinbase_txid = SerializedTxid::from(coinbase.compute_txid());
+        let spender = transaction(
+            &[OutPoint::new(coinbase.compute_txid(), 0)],
+            vec![script_pubkey()],
+        );
+        let spender_txid = SerializedTxid::from(spender.compute_txid());
+
+        let mut update = BatchUpdate::new(0, 101, 0);
+        let block = confirm(&mut update, &[&coinbase, &spender]);
+        update
+            .txouts
+            .get_mut(&SerializedOutPoint::from_txid_vout(&coinbase_txid, 0))
+            .unwrap()
+            .spent = SpentStatus::Spent(SpenderReference {
+            txid: spender_txid,
+            vin: 0,
+        });
+        db.batch_update(&update, false).unwrap();
+
+        assert_eq!(
+            to_json(tx(index.clone(), client(), Network::Regtest, &spender_txid).unwrap()),
+            json!({
+                "txid": spender.compute_txid(),
+                "version": 2,
+                "locktime": 0,
+                "vin": [{
+                    "txid": coinbase.compute_txid(),
+                    "vout": 0,
+                    "prevout": vout(),
+                    "scriptsig": "",
+                    "scriptsig_asm": "",
+                    "witness": [],
+                    "is_coinbase": false,
+                    "sequence": 4294967295u32,
+                }],
+                "vout": [vout()],
+                "size": spender.total_size(),
+                "weight": spender.weight().to_wu(),
+                "fee": 0,
+                "status": confirmed_status(block),
+            })
+        );
+
+        let coinbase_tx =
+            to_json(tx(index.clone(), client(), Network::Regtest, &coinbase_txid).unwrap());
+        assert_eq!(coinbase_tx["vin"][0]["is_coinbase"], json!(true));
+        assert_eq!(coinbase_tx["vin"][0]["prevout"], Value::Null);
+        assert_eq!(coinbase_tx["vout"], json!([vout(), vout()]));
+
+        assert_eq!(
+            to_json(tx_status(index.clone(), &spender_txid).unwrap()),
+            confirmed_status(block)
+        );
+
+        // Unspent outputs only say so.
+        assert_eq!(
+            to_json(tx_outspends(index.clone(), client(), &coinbase_txid).unwrap()),
+            json!([
+                {
+                    "spent": true,
+                    "txid": spender.compute_txid(),
+                    "vin": 0,
+                    "status": confirmed_status(block),
+                },
+                { "spent": false },
+            ])
+        );
+
+        assert!(tx_status(index, &SerializedTxid::from([9; 32])).is_err());
+    }
+
+    #[test]
+    fn lists_utxos_like_esplora() {
+        let db = TempDb::open();
+        let index = Arc::new(index(&db));
+        let crowded = Address::from_script(
+            &ScriptBuf::from_hex("00140000000000000000000000000000000000000001").unwrap(),
+            Network::Regtest,
+        )
+        .unwrap();
+
+        let funding = transaction(&[OutPoint::null()], vec![script_pubkey(); 2]);
+        let funding_txid = SerializedTxid::from(funding.compute_txid());
+        let crowding = transaction(
+            &[OutPoint::new(Txid::from_byte_array([1; 32]), 0)],
+            vec![crowded.script_pubkey(); MAX_UTXOS as usize + 1],
+        );
+        let crowding_txid = SerializedTxid::from(crowding.compute_txid());
+
+        let mut update = BatchUpdate::new(0, 101, 0);
+        let block = confirm(&mut update, &[&funding, &crowding]);
+        update.script_pubkeys.insert(
+            script_pubkey(),
+            (
+                (0..2)
+                    .map(|vout| SerializedOutPoint::from_txid_vout(&funding_txid, vout))
+                    .collect(),
+                vec![],
+            ),
+        );
+        update.script_pubkeys.insert(
+            crowded.script_pubkey(),
+            (
+                (0..=MAX_UTXOS as u32)
+                    .map(|vout| SerializedOutPoint::from_txid_vout(&crowding_txid, vout))
+                    .collect(),
+                vec![],
+            ),
+        );
+        db.batch_update(&update, false).unwrap();
+
+        let mut utxos = to_json(address_utxos(index.clone(), &address()).unwrap())
+            .as_array()
+            .unwrap()
+            .clone();
+        utxos.sort_by_key(|utxo| utxo["vout"].as_u64());
+        assert_eq!(
+            utxos,
+            (0..2)
+                .map(|vout| json!({
+                    "txid": funding.compute_txid(),
+                    "vout": vout,
+                    "status": confirmed_status(block),
+                    "value": 546,
+                }))
+                .collect::<Vec<_>>()
+        );
+
+        assert!(matches!(
+            address_utxos(index, &crowded),
+            Err(ApiError::TooManyUtxos(MAX_UTXOS))
+        ));
+    }
+
+    #[test]
+    fn pages_chain_transactions_like_esplora() {
+        let db = TempDb::open();
+        let index = Arc::new(index(&db));
+
+        // A history of 30 transactions, oldest first.
+        let history: Vec<Transaction> = (0..30)
+            .map(|n| {
+                transaction(
+                    &[OutPoint::new(Txid::from_byte_array([n; 32]), 0)],
+                    vec![script_pubkey()],
+                )
+            })
+            .collect();
+        let txids: Vec<Txid> = history.iter().map(Transaction::compute_txid).collect();
+
+        let mut update = BatchUpdate::new(0, 101, 0);
+        confirm(&mut update, &history.iter().collect::<Vec<_>>());
+        update.address_transactions.insert(
+            script_pubkey(),
+            txids.iter().copied().map(SerializedTxid::from).collect(),
+        );
+        db.batch_update(&update, false).unwrap();
+
+        let page = |last_seen: Option<Txid>| {
+            address_txs_chain(
+                index.clone(),
+                client(),
+                Network::Regtest,
+                &address(),
+                last_seen.map(SerializedTxid::from).as_ref(),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|tx| tx.txid)
+            .collect::<Vec<_>>()
+        };
+
+        // Newest first, 25 at a time, each page after the last txid of the previous one.
+        let newest: Vec<Txid> = txids.iter().rev().copied().collect();
+        assert_eq!(page(None), newest[..25]);
+        assert_eq!(page(Some(newest[24])), newest[25..]);
+        assert_eq!(page(Some(newest[29])), vec![]);
+        assert_eq!(page(Some(Txid::from_byte_array([9; 32]))), vec![]);
+    }
+
+    #[test]
+    fn names_script_types_like_esplora() {
+        let script = |hex: &str| ScriptBuf::from_hex(hex).unwrap();
+
+        assert_eq!(script_type(&ScriptBuf::new()), "empty");
+        assert_eq!(script_type(&script("6a0461626364")), "op_return");
+        assert_eq!(
+            script_type(&script(
+                "76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac"
+            )),
+            "p2pkh"
+        );
+        assert_eq!(
+            script_type(&script("0014751e76e8199196d454941c45d1b3a323f1433bd6")),
+            "v0_p2wpkh"
+        );
+        assert_eq!(
+            script_type(&script(
+                "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+            )),
+            "v1_p2tr"
+        );
+    }
+}
@@ -2,5 +2,7 @@ pub use api::*;
 
 mod api;
 pub mod content;
+pub mod esplora;
+pub mod psbt;
 pub mod query;
 mod re;
@@ -0,0 +1,485 @@
+//! Rune-aware coin selection for `POST /psbt/rune-transfer`.
+
+use {
+    bitcoin::{
+        absolute::LockTime, transaction::Version, Amount, OutPoint, Script, ScriptBuf, Sequence,
+        Transaction, TxIn, TxOut, Witness,
+    },
+    ordinals::{Artifact, Edict, Runestone},
+    rustc_hash::FxHashMap as HashMap,
+    std::{cmp::Reverse, collections::BTreeMap},
+    titan_types_core::{RuneAmount, RuneId, SerializedOutPoint},
+};
+
+/// The runestone is output 0 and the change output 1, so the runes no edict
+/// allocates go to the change without a pointer. Destinations follow in order.
+const CHANGE_VOUT: usize = 1;
+const FIRST_DESTINATION_VOUT: usize = 2;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PsbtError {
+    #[error("insufficient {rune_id} balance: {needed} needed, {available} available")]
+    InsufficientRunes {
+        rune_id: RuneId,
+        needed: u128,
+        available: u128,
+    },
+    #[error("insufficient funds: {needed} sats needed, {available} available")]
+    InsufficientFunds { needed: u64, available: u64 },
+    #[error("output value {value} is below the dust limit of {dust}")]
+    Dust { value: u64, dust: u64 },
+    #[error("amounts overflow")]
+    Overflow,
+    #[error("the transfer would be a cenotaph")]
+    Cenotaph,
+    #[error("invalid address: {0}")]
+    Address(#[from] bitcoin::address::ParseError),
+    #[error("psbt error: {0}")]
+    Psbt(#[from] bitcoin::psbt::Error),
+}
+
+/// An output the transfer can spend.
+#[derive(Debug, Clone)]
+pub struct Utxo {
+    pub outpoint: SerializedOutPoint,
+    pub value: u64,
+    pub script_pubkey: ScriptBuf,
+    pub runes: Vec<RuneAmount>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Destination {
+    pub script_pubkey: ScriptBuf,
+    pub rune_id: RuneId,
+    pub amount: u128,
+    pub value: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct RuneTransfer {
+    pub transaction: Transaction,
+    /// The spent outputs, in input order.
+    pub inputs: Vec<Utxo>,
+    /// The runes of every output, in output order.
+    pub allocations: Vec<Vec<RuneAmount>>,
+    pub fee: u64,
+    pub vsize: u64,
+}
+
+/// Builds an unsigned transaction sending runes from `utxos` to `destinations`.
+///
+/// Outputs holding a rune are selected largest first until every destination
+/// of that rune is covered. The fee is then paid from outputs without runes,
+/// also largest first, and the runes and bitcoin left over go to `change`.
+/// Outputs with a script whose signature size isn't known are never spent.
+pub fn build_rune_transfer(
+    utxos: &[Utxo],
+    destinations: &[Destination],
+    change: ScriptBuf,
+    fee_rate: f64,
+) -> Result<RuneTransfer, PsbtError> {
+    let input_weights: Vec<Option<(u64, bool)>> = utxos
+        .iter()
+        .map(|utxo| input_weight(&utxo.script_pubkey))
+        .collect();
+
+    let mut sent_runes: BTreeMap<RuneId, u128> = BTreeMap::new();
+    for destination in destinations {
+        let amount = sent_runes.entry(destination.rune_id).or_default();
+        *amount = amount
+            .checked_add(destination.amount)
+            .ok_or(PsbtError::Overflow)?;
+    }
+
+    let mut selection = Selection::default();
+    for (rune_id, needed) in &sent_runes {
+        let mut candidates: Vec<(usize, u128)> = utxos
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| input_weights[*i].is_some() && !selection.inputs.contains(i))
+            .filter_map(|(i, utxo)| {
+                utxo.runes
+                    .iter()
+                    .find(|rune| rune.id == *rune_id)
+                    .map(|rune| (i, rune.amount))
+            })
+            .collect();
+        candidates.sort_by_key(|(_, amount)| Reverse(*amount));
+
+        let mut candidates = candidates.into_iter();
+        while selection.rune_amount(rune_id) < *needed {
+            let Some((i, _)) = candidates.next() else {
+                return Err(PsbtError::InsufficientRunes {
+                    rune_id: *rune_id,
+                    needed: *needed,
+                    available: selection.rune_amount(rune_id),
+                });
+            };
+
+            selection.add(i, &utxos[i])?;
+        }
+    }
+
+    let runestone = Runestone {
+        edicts: destinations
+            .iter()
+            .zip(FIRST_DESTINATION_VOUT as u32..)
+            .map(|(destination, output)| Edict {
+                id: ordinals::RuneId {
+                    block: destination.rune_id.block,
+                    tx: destination.rune_id.tx,
+                },
+                amount: destination.amount,
+                output,
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let change_dust = change.minimal_non_dust().to_sat();
+    let mut output = vec![
+        TxOut {
+            value: Amount::ZERO,
+            script_pubkey: runestone.encipher(),
+        },
+        TxOut {
+            value: Amount::from_sat(change_dust),
+            script_pubkey: change,
+        },
+    ];
+
+    let mut sent: u64 = 0;
+    for destination in destinations {
+        let dust = destination.script_pubkey.minimal_non_dust().to_sat();
+        let value = destination.value.unwrap_or(dust);
+        if value < dust {
+            return Err(PsbtError::Dust { value, dust });
+        }
+
+        sent = sent.checked_add(value).ok_or(PsbtError::Overflow)?;
+        output.push(TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: destination.script_pubkey.clone(),
+        });
+    }
+
+    let mut funding: Vec<usize> = (0..utxos.len())
+        .filter(|i| {
+            input_weights[*i].is_some()
+                && !selection.inputs.contains(i)
+                && utxos[*i].runes.is_empty()
+        })
+        .collect();
+    funding.sort_by_key(|i| Reverse(utxos[*i].value));
+    let mut funding = funding.into_iter();
+
+    let (mut transaction, fee, vsize) = loop {
+        let transaction = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: selection
+                .inputs
+                .iter()
+                .map(|i| TxIn {
+                    previous_output: OutPoint::from(utxos[*i].outpoint),
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::new(),
+                })
+                .collect(),
+            output: output.clone(),
+        };
+
+        let vsize = estimate_vsize(
+            &transaction,
+            selection.inputs.iter().filter_map(|i| input_weights[*i]),
+        );
+        let fee = (vsize as f64 * fee_rate).ceil() as u64;
+        let needed = sent + change_dust + fee;
+
+        if selection.value >= needed {
+            break (transaction, fee, vsize);
+        }
+
+        let Some(i) = funding.next() else {
+            return Err(PsbtError::InsufficientFunds {
+                needed,
+                available: selection.value,
+            });
+        };
+
+        selection.add(i, &utxos[i])?;
+    };
+
+    transaction.output[CHANGE_VOUT].value = Amount::from_sat(selection.value - sent - fee);
+
+    // Edicts only ever point at existing outputs, but a cenotaph would burn
+    // every rune in the inputs.
+    if !matches!(
+        Runestone::decipher(&transaction),
+        Some(Artifact::Runestone(_))
+    ) {
+        return Err(PsbtError::Cenotaph);
+    }
+
+    let mut allocations = vec![Vec::new(); transaction.output.len()];
+    for (destination, allocation) in destinations
+        .iter()
+        .zip(allocations.iter_mut().skip(FIRST_DESTINATION_VOUT))
+    {
+        allocation.push(RuneAmount::from((destination.rune_id, destination.amount)));
+    }
+
+    let mut change_runes: Vec<RuneAmount> = selection
+        .runes
+        .into_iter()
+        .map(|(rune_id, amount)| {
+            let sent = sent_runes.get(&rune_id).copied().unwrap_or(0);
+            RuneAmount::from((rune_id, amount - sent))
+        })
+        .filter(|rune| rune.amount > 0)
+        .collect();
+    change_runes.sort_by_key(|rune| rune.id);
+    allocations[CHANGE_VOUT] = change_runes;
+
+    Ok(RuneTransfer {
+        transaction,
+        inputs: selection.inputs.iter().map(|i| utxos[*i].clone()).collect(),
+        allocations,
+        fee,
+        vsize,
+    })
+}
+
+#[derive(Default)]
+struct Selection {
+    inputs: Vec<usize>,
+    value: u64,
+    runes: HashMap<RuneId, u128>,
+}
+
+impl Selection {
+    fn add(&mut self, i: usize, utxo: &Utxo) -> Result<(), PsbtError> {
+        for rune in &utxo.runes {
+            let amount = self.runes.entry(rune.id).or_default();
+            *amount = amount.checked_add(rune.amount).ok_or(PsbtError::Overflow)?;
+        }
+
+        self.value = self
+            .value
+            .checked_add(utxo.value)
+            .ok_or(PsbtError::Overflow)?;
+        self.inputs.push(i);
+        Ok(())
+    }
+
+    fn rune_amount(&self, rune_id: &RuneId) -> u128 {
+        self.runes.get(rune_id).copied().unwrap_or(0)
+    }
+}
+
+/// Weight that signing adds to an input spending `script_pubkey`, and whether
+/// it has a witness. P2SH outputs are assumed to wrap P2WPKH.
+fn input_weight(script_pubkey: &Script) -> Option<(u64, bool)> {
+    if script_pubkey.is_p2tr() {
+        // Key path: item count and a 64 byte signature.
+        Some((1 + 1 + 64, true))
+    } else if script_pubkey.is_p2wpkh() {
+        // Item count, a signature of up to 72 bytes and a compressed key.
+        Some((1 + 1 + 72 + 1 + 33, true))
+    } else if script_pubkey.is_p2sh() {
+        // The redeem script push in the script sig, and the P2WPKH witness.
+        Some((23 * 4 + 1 + 1 + 72 + 1 + 33, true))
+    } else if script_pubkey.is_p2pkh() {
+        Some(((1 + 72 + 1 + 33) * 4, false))
+    } else {
+        None
+    }
+}
+
+fn estimate_vsize(
+    transaction: &Transaction,
+    input_weights: impl Iterator<Item = (u64, bool)>,
+) -> u64 {
+    let mut weight = transaction.weight().to_wu();
+    let mut segwit = false;
+    for (input_weight, witness) in input_weights {
+        weight += input_weight;
+        segwit |= witness;
+    }
+
+    // Segwit marker and flag.
+    if segwit {
+        weight += 2;
+    }
+
+    weight.div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        bitcoin::{hashes::Hash, WPubkeyHash, WScriptHash},
+    };
+
+    fn utxo(n: u8, value: u64, runes: &[(RuneId, u128)]) -> Utxo {
+        Utxo {
+            outpoint: SerializedOutPoint::new(&[n; 32], 0),
+            value,
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20])),
+            runes: runes.iter().map(|rune| RuneAmount::from(*rune)).collect(),
+        }
+    }
+
+    #[test]
+    fn sends_runes_and_returns_change() {
+        let rune_id = RuneId::new(840000, 1);
+        let other_rune_id = RuneId::new(840000, 2);
+        let utxos = vec![
+            utxo(1, 330, &[(rune_id, 300)]),
+            utxo(2, 330, &[(rune_id, 800), (other_rune_id, 5)]),
+            utxo(3, 330, &[(rune_id, 100)]),
+            utxo(4, 1_000, &[]),
+            utxo(5, 50_000, &[]),
+        ];
+        let destination = ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([2; 20]));
+        let change = ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([3; 20]));
+
+        let transfer = build_rune_transfer(
+            &utxos,
+            &[Destination {
+                script_pubkey: destination,
+                rune_id,
+                amount: 1_000,
+                value: None,
+            }],
+            change,
+            10.0,
+        )
+        .unwrap();
+
+        // The two largest rune outputs, then the largest output without runes.
+        let inputs: Vec<SerializedOutPoint> =
+            transfer.inputs.iter().map(|utxo| utxo.outpoint).collect();
+        assert_eq!(
+            inputs,
+            vec![utxos[1].outpoint, utxos[0].outpoint, utxos[4].outpoint]
+        );
+
+        let Some(Artifact::Runestone(runestone)) = Runestone::decipher(&transfer.transaction)
+        else {
+            panic!("expected a runestone");
+        };
+        assert_eq!(runestone.edicts.len(), 1);
+        assert_eq!(runestone.edicts[0].amount, 1_000);
+        assert_eq!(runestone.edicts[0].output, 2);
+
+        assert_eq!(
+            transfer.allocations,
+            vec![
+                vec![],
+                vec![
+                    RuneAmount::from((rune_id, 100)),
+                    RuneAmount::from((other_rune_id, 5))
+                ],
+                vec![RuneAmount::from((rune_id, 1_000))],
+            ]
+        );
+
+        let output_value: u64 = transfer
+            .transaction
+            .output
+            .iter()
+            .map(|output| output.value.to_sat())
+            .sum();
+        assert_eq!(330 * 2 + 50_000 - output_value, transfer.fee);
+        assert_eq!(transfer.fee, transfer.vsize * 10);
+    }
+
+    #[test]
+    fn skips_unsupported_scripts() {
+        let rune_id = RuneId::new(840000, 1);
+        let change = ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([3; 20]));
+        let p2wsh = ScriptBuf::new_p2wsh(&WScriptHash::from_byte_array([4; 32]));
+        let utxos = vec![
+            utxo(1, 330, &[(rune_id, 10)]),
+            Utxo {
+                script_pubkey: p2wsh.clone(),
+                ..utxo(2, 100_000, &[])
+            },
+            Utxo {
+                script_pubkey: p2wsh,
+                ..utxo(3, 330, &[(rune_id, 50)])
+            },
+            utxo(4, 10_000, &[]),
+        ];
+
+        let transfer = build_rune_transfer(
+            &utxos,
+            &[Destination {
+                script_pubkey: change.clone(),
+                rune_id,
+                amount: 10,
+                value: None,
+            }],
+            change.clone(),
+            1.0,
+        )
+        .unwrap();
+
+        let inputs: Vec<SerializedOutPoint> =
+            transfer.inputs.iter().map(|utxo| utxo.outpoint).collect();
+        assert_eq!(inputs, vec![utxos[0].outpoint, utxos[3].outpoint]);
+
+        let error = build_rune_transfer(
+            &utxos,
+            &[Destination {
+                script_pubkey: change.clone(),
+                rune_id,
+                amount: 11,
+                value: None,
+            }],
+            change,
+            1.0,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            PsbtError::InsufficientRunes {
+                needed: 11,
+                available: 10,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn reports_missing_runes() {
+        let rune_id = RuneId::new(840000, 1);
+        let change = ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([3; 20]));
+
+        let error = build_rune_transfer(
+            &[utxo(1, 10_000, &[(rune_id, 10)])],
+            &[Destination {
+                script_pubkey: change.clone(),
+                rune_id,
+                amount: 11,
+                value: None,
+            }],
+            change,
+            1.0,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            PsbtError::InsufficientRunes {
+                needed: 11,
+                available: 10,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,162 @@
+use {
+    super::store::{Store, StoreError},
+    crate::{
+        models::{ApiKeyEntry, ApiKeyScope},
+        util::generate_secret,
+    },
+    bitcoin::hashes::{sha256, Hash},
+    std::{
+        sync::Arc,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+    thiserror::Error,
+    uuid::Uuid,
+};
+
+/// Prefix of API keys.
+const KEY_PREFIX: &str = "titan";
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("missing API key")]
+    Missing,
+    #[error("invalid API key")]
+    Invalid,
+    #[error("API key lacks the `{0}` scope")]
+    MissingScope(ApiKeyScope),
+    #[error("store error: {0}")]
+    Store(#[from] StoreError),
+}
+
+/// The API keys accepted by the HTTP server and the TCP subscription server.
+pub struct ApiKeys {
+    store: Arc<dyn Store>,
+    enabled: bool,
+}
+
+impl ApiKeys {
+    /// When `enabled` is false every request is allowed, with or without a key.
+    pub fn new(store: Arc<dyn Store>, enabled: bool) -> Self {
+        Self { store, enabled }
+    }
+
+    /// Create a key with the given scopes. The key is returned only here, the
+    /// database keeps its hash.
+    pub fn create(
+        &self,
+        name: String,
+        scopes: Vec<ApiKeyScope>,
+    ) -> Result<(String, ApiKeyEntry), StoreError> {
+        let key = generate_secret(KEY_PREFIX);
+        let entry = ApiKeyEntry {
+            id: Uuid::new_v4(),
+            name,
+            scopes,
+            created_at_epoch_secs: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+
+        self.store.set_api_key(&hash_key(&key), &entry)?;
+        Ok((key, entry))
+    }
+
+    /// Lists the keys, oldest first.
+    pub fn list(&self) -> Result<Vec<ApiKeyEntry>, StoreError> {
+        let mut keys = self.store.get_api_keys()?;
+        keys.sort_by_key(|entry| entry.created_at_epoch_secs);
+        Ok(keys)
+    }
+
+    pub fn revoke(&self, id: &Uuid) -> Result<(), StoreError> {
+        self.store.delete_api_key(id)
+    }
+
+    /// Check that `key` grants `scope` and return the key's entry, or `None` when API
+    /// keys are disabled.
+    pub fn authorize(
+        &self,
+        key: Option<&str>,
+        scope: ApiKeyScope,
+    ) -> Result<Option<ApiKeyEntry>, AuthError> {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        let key = key.ok_or(AuthError::Missing)?;
+        let entry = self
+            .store
+            .get_api_key(&hash_key(key))?
+            .ok_or(AuthError::Invalid)?;
+
+        if !entry.has_scope(scope) {
+            return Err(AuthError::MissingScope(scope));
+        }
+
+        Ok(Some(entry))
+    }
+}
+
+fn hash_key(key: &str) -> [u8; 32] {
+    sha256::Hash::hash(key.as_bytes()).to_byte_array()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use {super::*, std::collections::HashMap, std::sync::Mutex};
+
+    #[derive(Default)]
+    pub(crate) struct MemoryStore(Mutex<HashMap<[u8; 32], ApiKeyEntry>>);
+
+    impl Store for MemoryStore {
+        fn set_api_key(&self, key_hash: &[u8; 32], entry: &ApiKeyEntry) -> Result<(), StoreError> {
+            self.0.lock().unwrap().insert(*key_hash, entry.clone());
+            Ok(())
+        }
+
+        fn get_api_key(&self, key_hash: &[u8; 32]) -> Result<Option<ApiKeyEntry>, StoreError> {
+            Ok(self.0.lock().unwrap().get(key_hash).cloned())
+        }
+
+        fn get_api_keys(&self) -> Result<Vec<ApiKeyEntry>, StoreError> {
+            Ok(self.0.lock().unwrap().values().cloned().collect())
+        }
+
+        fn delete_api_key(&self, id: &Uuid) -> Result<(), StoreError> {
+            self.0.lock().unwrap().retain(|_, entry| entry.id != *id);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn authorizes_keys_by_scope() {
+        let api_keys = ApiKeys::new(Arc::new(MemoryStore::default()), true);
+        let (key, entry) = api_keys
+            .create("explorer".to_string(), vec![ApiKeyScope::Read])
+            .unwrap();
+
+        assert_eq!(
+            api_keys.authorize(Some(&key), ApiKeyScope::Read).unwrap(),
+            Some(entry.clone())
+        );
+        assert!(matches!(
+            api_keys.authorize(Some(&key), ApiKeyScope::Broadcast),
+            Err(AuthError::MissingScope(ApiKeyScope::Broadcast))
+        ));
+        assert!(matches!(
+            api_keys.authorize(Some("titan_unknown"), ApiKeyScope::Read),
+            Err(AuthError::Invalid)
+        ));
+        assert!(matches!(
+            api_keys.authorize(None, ApiKeyScope::Read),
+            Err(AuthError::Missing)
+        ));
+
+        api_keys.revoke(&entry.id).unwrap();
+        assert!(matches!(
+            api_keys.authorize(Some(&key), ApiKeyScope::Read),
+            Err(AuthError::Invalid)
+        ));
+    }
+}
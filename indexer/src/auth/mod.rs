@@ -0,0 +1,10 @@
+mod api_keys;
+mod store;
+
+pub use {
+    api_keys::{ApiKeys, AuthError},
+    store::StoreError,
+};
+
+#[cfg(test)]
+pub(crate) use api_keys::tests::MemoryStore;
@@ -0,0 +1,50 @@
+use {
+    crate::{
+        db::{RocksDB, RocksDBError},
+        models::ApiKeyEntry,
+    },
+    thiserror::Error,
+    uuid::Uuid,
+};
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("db error {0}")]
+    DB(RocksDBError),
+    #[error("not found: {0}")]
+    NotFound(String),
+}
+
+impl From<RocksDBError> for StoreError {
+    fn from(error: RocksDBError) -> Self {
+        match error {
+            RocksDBError::NotFound(msg) => StoreError::NotFound(msg),
+            other => StoreError::DB(other),
+        }
+    }
+}
+
+pub trait Store: Send + Sync {
+    fn set_api_key(&self, key_hash: &[u8; 32], entry: &ApiKeyEntry) -> Result<(), StoreError>;
+    fn get_api_key(&self, key_hash: &[u8; 32]) -> Result<Option<ApiKeyEntry>, StoreError>;
+    fn get_api_keys(&self) -> Result<Vec<ApiKeyEntry>, StoreError>;
+    fn delete_api_key(&self, id: &Uuid) -> Result<(), StoreError>;
+}
+
+impl Store for RocksDB {
+    fn set_api_key(&self, key_hash: &[u8; 32], entry: &ApiKeyEntry) -> Result<(), StoreError> {
+        Ok(self.set_api_key(key_hash, entry)?)
+    }
+
+    fn get_api_key(&self, key_hash: &[u8; 32]) -> Result<Option<ApiKeyEntry>, StoreError> {
+        Ok(self.get_api_key(key_hash)?)
+    }
+
+    fn get_api_keys(&self) -> Result<Vec<ApiKeyEntry>, StoreError> {
+        Ok(self.get_api_keys()?)
+    }
+
+    fn delete_api_key(&self, id: &Uuid) -> Result<(), StoreError> {
+        Ok(self.delete_api_key(id)?)
+    }
+}
@@ -1,7 +1,10 @@
 use std::{
     collections::VecDeque,
     ops::{Deref, DerefMut},
-    sync::{Arc, RwLock},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
 };
 
 use bitcoincore_rpc::Client;
@@ -32,6 +35,8 @@ impl Drop for PooledClient {
     fn drop(&mut self) {
         // Take the client out to avoid double-free
         if let Some(client) = self.client.take() {
+            self.pool.in_use.fetch_sub(1, Ordering::Relaxed);
+
             // Try to return it to the pool, ignoring errors
             let _ = self.pool.release(client);
         }
@@ -50,6 +55,7 @@ pub enum RpcClientPoolError {
 #[derive(Clone)]
 pub struct RpcClientPool {
     clients: Arc<RwLock<VecDeque<Client>>>,
+    in_use: Arc<AtomicUsize>,
     max_size: usize,
     provider: Arc<dyn RpcClientProvider>,
 }
@@ -58,6 +64,7 @@ impl RpcClientPool {
     pub fn new(provider: Arc<dyn RpcClientProvider>, max_size: usize) -> Self {
         Self {
             clients: Arc::new(RwLock::new(VecDeque::new())),
+            in_use: Arc::new(AtomicUsize::new(0)),
             max_size,
             provider,
         }
@@ -76,12 +83,27 @@ impl RpcClientPool {
             self.provider.get_new_rpc_client()?
         };
 
+        self.in_use.fetch_add(1, Ordering::Relaxed);
+
         Ok(PooledClient {
             client: Some(client),
             pool: Arc::new(self.clone()),
         })
     }
 
+    /// Number of clients currently checked out of the pool.
+    pub fn in_use(&self) -> usize {
+        self.in_use.load(Ordering::Relaxed)
+    }
+
+    /// Number of idle clients waiting in the pool.
+    pub fn idle(&self) -> usize {
+        self.clients
+            .read()
+            .map(|clients| clients.len())
+            .unwrap_or_default()
+    }
+
     fn release(&self, client: Client) -> Result<(), RpcClientPoolError> {
         let mut clients = self
             .clients
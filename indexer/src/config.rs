@@ -0,0 +1,143 @@
+use {
+    crate::{index::Chain, server::RouteCost},
+    serde::Deserialize,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse config file {path}: {source}")]
+    Toml {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("failed to parse config file {path}: {source}")]
+    Yaml {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+    #[error("unsupported config file format {0}, expected .toml, .yaml or .yml")]
+    UnsupportedFormat(PathBuf),
+    #[error("invalid option: {0}")]
+    Invalid(String),
+    #[error("{0}")]
+    Clap(#[from] clap::Error),
+}
+
+/// Contents of the file passed with `--config`.
+///
+/// Keys mirror the long command line flags in snake_case. Every key is optional and
+/// unknown keys are rejected so that typos don't silently fall back to defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub bitcoin_rpc_password: Option<String>,
+    pub bitcoin_rpc_url: Option<String>,
+    pub bitcoin_rpc_username: Option<String>,
+    pub bitcoin_rpc_limit: Option<u32>,
+    pub bitcoin_rpc_pool_size: Option<u32>,
+    pub chain: Option<Chain>,
+    pub cookie_file: Option<PathBuf>,
+    pub data_dir: Option<PathBuf>,
+    pub no_index_inscriptions: Option<bool>,
+    pub index_bitcoin_transactions: Option<bool>,
+    pub index_spent_outputs: Option<bool>,
+    pub index_addresses: Option<bool>,
+    pub commit_interval: Option<u64>,
+    pub enable_zmq_listener: Option<bool>,
+    pub zmq_endpoint: Option<String>,
+    pub http_listen: Option<String>,
+    pub csp_origin: Option<String>,
+    pub decompress: Option<bool>,
+    pub main_loop_interval: Option<u64>,
+    pub enable_webhook_subscriptions: Option<bool>,
+    pub enable_tcp_subscriptions: Option<bool>,
+    pub enable_event_streams: Option<bool>,
+    pub tcp_address: Option<String>,
+    pub enable_file_logging: Option<bool>,
+    pub event_log_max_events: Option<u64>,
+    pub event_log_max_block_depth: Option<u64>,
+    pub enable_api_keys: Option<bool>,
+    pub rate_limit_per_second: Option<u32>,
+    pub rate_limit_burst: Option<u32>,
+    pub rate_limit_route_costs: Option<Vec<RouteCost>>,
+    pub rate_limit_forwarded_for: Option<bool>,
+    pub enable_esplora: Option<bool>,
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => toml::from_str(&contents).map_err(|source| ConfigError::Toml {
+                path: path.to_path_buf(),
+                source,
+            }),
+            Some("yaml") | Some("yml") => {
+                serde_yaml::from_str(&contents).map_err(|source| ConfigError::Yaml {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+            _ => Err(ConfigError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_toml() {
+        let config: ConfigFile = toml::from_str(
+            r#"
+            bitcoin_rpc_url = "http://localhost:18443"
+            chain = "regtest"
+            index_addresses = true
+            commit_interval = 5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.bitcoin_rpc_url.as_deref(),
+            Some("http://localhost:18443")
+        );
+        assert_eq!(config.chain, Some(Chain::Regtest));
+        assert_eq!(config.index_addresses, Some(true));
+        assert_eq!(config.commit_interval, Some(5));
+        assert_eq!(config.http_listen, None);
+    }
+
+    #[test]
+    fn parses_yaml() {
+        let config: ConfigFile = serde_yaml::from_str(
+            "chain: testnet4\nenable_tcp_subscriptions: true\ntcp_address: 0.0.0.0:8080\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.chain, Some(Chain::Testnet4));
+        assert_eq!(config.enable_tcp_subscriptions, Some(true));
+        assert_eq!(config.tcp_address.as_deref(), Some("0.0.0.0:8080"));
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(toml::from_str::<ConfigFile>("index_adresses = true").is_err());
+        assert!(serde_yaml::from_str::<ConfigFile>("config: other.toml").is_err());
+    }
+}
@@ -1,8 +1,13 @@
 use {
-    crate::models::{BlockId, Inscription, RuneEntry, TransactionStateChange, TxRuneIndexRef},
+    crate::models::{
+        ApiKeyEntry, BlockId, EventLogEntry, Inscription, RuneBalance, RuneEntry, RuneHistory,
+        TransactionStateChange, TxAddressIndexRef, TxRuneIndexRef, WebhookDeliveryEntry,
+    },
     borsh::{BorshDeserialize, BorshSerialize},
     titan_types_api::Subscription,
-    titan_types_core::{Block, MempoolEntry, RuneId, SpenderReference, TxOut},
+    titan_types_core::{
+        Block, MempoolEntry, RuneId, SpenderReference, TransactionReplacement, TxOut,
+    },
 };
 
 pub trait Entry: Sized + BorshDeserialize + BorshSerialize {
@@ -24,15 +29,22 @@ pub trait Entry: Sized + BorshDeserialize + BorshSerialize {
     }
 }
 
+impl Entry for ApiKeyEntry {}
 impl Entry for Block {}
 impl Entry for BlockId {}
+impl Entry for EventLogEntry {}
 impl Entry for Inscription {}
 impl Entry for RuneEntry {}
+impl Entry for RuneBalance {}
+impl Entry for RuneHistory {}
 impl Entry for TxRuneIndexRef {}
 impl Entry for Vec<TxRuneIndexRef> {}
+impl Entry for Vec<TxAddressIndexRef> {}
 impl Entry for TransactionStateChange {}
 impl Entry for TxOut {}
 impl Entry for Subscription {}
+impl Entry for WebhookDeliveryEntry {}
 impl Entry for SpenderReference {}
 impl Entry for MempoolEntry {}
 impl Entry for RuneId {}
+impl Entry for TransactionReplacement {}
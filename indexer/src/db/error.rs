@@ -12,6 +12,8 @@ pub enum RocksDBError {
     InvalidString,
     #[error("invalid block hash")]
     InvalidBlockHash,
+    #[error("invalid block")]
+    InvalidBlock,
     #[error("invalid rune id")]
     InvalidRuneId,
     #[error("invalid txid")]
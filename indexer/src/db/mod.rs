@@ -1,7 +1,13 @@
-pub use {error::RocksDBError, rocks::RocksDB};
+pub use {
+    error::RocksDBError,
+    rocks::{RocksDB, MAX_SCANNED_ADDRESS_ENTRIES},
+};
 
 mod entry;
 mod error;
 mod mapper;
 mod rocks;
 pub mod util;
+
+#[cfg(test)]
+pub(crate) use rocks::tests::TempDb;
@@ -2,17 +2,21 @@ use {
     super::{
         entry::Entry,
         util::{
-            parse_outpoint_from_script_pubkey_key, rune_index_key, rune_transaction_key,
-            rune_transaction_key_from_bytes, script_pubkey_outpoint_to_bytes,
-            script_pubkey_search_key,
+            address_rune_balance_key, address_transaction_key, address_transaction_prefix,
+            outpoint_from_bytes, parse_event_log_key, parse_outpoint_from_script_pubkey_key,
+            parse_rune_history_key, parse_rune_holder_key, parse_webhook_delivery_key,
+            rune_history_key, rune_holder_key, rune_holders_upper_bound, rune_id_from_bytes,
+            rune_index_key, rune_transaction_key, rune_transaction_key_from_bytes,
+            script_pubkey_outpoint_to_bytes, script_pubkey_search_key, webhook_delivery_key,
         },
         *,
     },
     crate::models::{
-        BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, RuneEntry,
-        TransactionStateChange, TxRuneIndexRef,
+        ApiKeyEntry, BatchDelete, BatchRollback, BatchUpdate, BlockId, EventLogEntry, Inscription,
+        RuneBalance, RuneBalanceChanges, RuneEntry, RuneHistory, TransactionStateChange,
+        TxAddressIndexRef, TxRuneIndexRef, WebhookDeliveryEntry,
     },
-    bitcoin::{consensus, hashes::Hash, BlockHash, ScriptBuf, Transaction},
+    bitcoin::{block::Header, consensus, hashes::Hash, BlockHash, ScriptBuf, Transaction},
     borsh::BorshDeserialize,
     mapper::DBResultMapper,
     rocksdb::{
@@ -22,13 +26,16 @@ use {
     rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet},
     std::{
         collections::VecDeque,
-        sync::{Arc, RwLock},
+        sync::{Arc, Mutex, RwLock},
+    },
+    titan_types_api::{
+        Cursor, DeliveryStatus, Pagination, PaginationDirection, PaginationResponse, Subscription,
     },
-    titan_types_api::{Pagination, PaginationResponse, Subscription},
     titan_types_core::{
-        Block, InscriptionId, MempoolEntry, RuneId, SerializedOutPoint, SerializedTxid,
-        SpenderReference, TxOut,
+        Block, Event, InscriptionId, MempoolEntry, Rune, RuneId, SerializedOutPoint,
+        SerializedTxid, SpenderReference, TransactionReplacement, TxOut,
     },
+    tracing::info,
     uuid::Uuid,
 };
 
@@ -36,6 +43,9 @@ pub struct RocksDB {
     db: DBWithThreadMode<MultiThreaded>,
     mempool_cache: RwLock<HashMap<SerializedTxid, MempoolEntry>>,
     write_opts: WriteOptions,
+    /// Serializes the read-modify-write updates and the deletion of subscriptions,
+    /// which run concurrently with every delivery.
+    subscriptions_lock: Mutex<()>,
 }
 
 pub type DBResult<T> = Result<T, RocksDBError>;
@@ -59,18 +69,32 @@ const RUNES_COUNT_KEY: &str = "runes_count";
 const RUNES_CF: &str = "runes";
 const RUNE_IDS_CF: &str = "rune_ids";
 const RUNE_NUMBER_CF: &str = "rune_number";
+const RUNE_NAMES_CF: &str = "rune_names";
 
 const INSCRIPTIONS_CF: &str = "inscriptions";
 
 const SCRIPT_PUBKEYS_CF: &str = "script_pubkeys";
 const SCRIPT_PUBKEYS_MEMPOOL_CF: &str = "script_pubkeys_mempool";
 
+const ADDRESS_TRANSACTIONS_CF: &str = "address_transactions";
+const ADDRESS_TRANSACTIONS_MEMPOOL_CF: &str = "address_transactions_mempool";
+
+const TRANSACTION_ADDRESS_INDEX_CF: &str = "transaction_address_index";
+const TRANSACTION_ADDRESS_INDEX_MEMPOOL_CF: &str = "transaction_address_index_mempool";
+
+const ADDRESS_RUNE_BALANCES_CF: &str = "address_rune_balances";
+const ADDRESS_RUNE_BALANCES_MEMPOOL_CF: &str = "address_rune_balances_mempool";
+
+const RUNE_HOLDERS_CF: &str = "rune_holders";
+const RUNE_HISTORY_CF: &str = "rune_history";
+
 const OUTPOINT_TO_SCRIPT_PUBKEY_CF: &str = "outpoint_to_script_pubkey";
 const OUTPOINT_TO_SCRIPT_PUBKEY_MEMPOOL_CF: &str = "outpoint_to_script_pubkey_mempool";
 const SPENT_OUTPOINTS_MEMPOOL_CF: &str = "spent_outpoints_mempool";
 
 const TRANSACTIONS_CF: &str = "transactions";
 const TRANSACTIONS_MEMPOOL_CF: &str = "transactions_mempool";
+const REPLACEMENTS_CF: &str = "replacements";
 const TRANSACTION_CONFIRMING_BLOCK_CF: &str = "transaction_confirming_block";
 
 const MEMPOOL_CF: &str = "mempool";
@@ -80,6 +104,13 @@ const STATS_CF: &str = "stats";
 const SETTINGS_CF: &str = "settings";
 
 const SUBSCRIPTIONS_CF: &str = "subscriptions";
+const EVENT_LOG_CF: &str = "event_log";
+const EVENT_LOG_HEIGHTS_CF: &str = "event_log_heights";
+const WEBHOOK_OUTBOX_CF: &str = "webhook_outbox";
+const WEBHOOK_DEAD_LETTERS_CF: &str = "webhook_dead_letters";
+const WEBHOOK_DELIVERY_SEQS_CF: &str = "webhook_delivery_seqs";
+
+const API_KEYS_CF: &str = "api_keys";
 
 const INDEX_ADDRESSES_KEY: &str = "index_addresses";
 const INDEX_BITCOIN_TRANSACTIONS_KEY: &str = "index_bitcoin_transactions";
@@ -89,12 +120,29 @@ const BLOCK_COUNT_KEY: &str = "block_count";
 const PURGED_BLOCKS_COUNT_KEY: &str = "purged_blocks_count";
 const DB_SCHEMA_VERSION_KEY: &str = "db_schema_version";
 const IS_AT_TIP_KEY: &str = "is_at_tip";
+const RUNE_HOLDERS_KEY: &str = "rune_holders";
+const RUNE_NAMES_KEY: &str = "rune_names";
+
+/// Most entries of an address' history or outputs a page filtered by rune looks at,
+/// so that a rune that is rare in a long history doesn't walk all of it.
+pub const MAX_SCANNED_ADDRESS_ENTRIES: u64 = 10_000;
 
 /// Increment this when the on-disk schema changes in a backward-incompatible way.
-const EXPECTED_DB_SCHEMA_VERSION: u64 = 1;
+/// Version 2 added the address transaction history, version 3 the address rune
+/// balances and version 4 the event log heights.
+const EXPECTED_DB_SCHEMA_VERSION: u64 = 4;
+
+/// `(amount, script_pubkey)` position of a rune holder and the holder itself.
+type RuneHolderEntry = ((u128, ScriptBuf), (ScriptBuf, u128));
 
 impl RocksDB {
     pub fn open(file_path: &str) -> DBResult<Self> {
+        Self::open_with_direct_io(file_path, true)
+    }
+
+    /// Opens the database with direct I/O switched on or off. Tests turn it off, their
+    /// temporary directories are often on tmpfs where it isn't supported.
+    fn open_with_direct_io(file_path: &str, direct_io: bool) -> DBResult<Self> {
         // Create descriptors
         let mut cf_opts = Options::default();
         cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
@@ -117,6 +165,8 @@ impl RocksDB {
             ColumnFamilyDescriptor::new(RUNE_IDS_CF, cf_opts.clone());
         let rune_number_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(RUNE_NUMBER_CF, cf_opts.clone());
+        let rune_names_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_NAMES_CF, cf_opts.clone());
         let inscriptions_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(INSCRIPTIONS_CF, cf_opts.clone());
         let mempool_cfd: ColumnFamilyDescriptor =
@@ -135,6 +185,22 @@ impl RocksDB {
             ColumnFamilyDescriptor::new(SCRIPT_PUBKEYS_CF, cf_opts.clone());
         let script_pubkeys_mempool_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(SCRIPT_PUBKEYS_MEMPOOL_CF, cf_opts.clone());
+        let address_transactions_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(ADDRESS_TRANSACTIONS_CF, cf_opts.clone());
+        let address_transactions_mempool_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(ADDRESS_TRANSACTIONS_MEMPOOL_CF, cf_opts.clone());
+        let transaction_address_index_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(TRANSACTION_ADDRESS_INDEX_CF, cf_opts.clone());
+        let transaction_address_index_mempool_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(TRANSACTION_ADDRESS_INDEX_MEMPOOL_CF, cf_opts.clone());
+        let address_rune_balances_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(ADDRESS_RUNE_BALANCES_CF, cf_opts.clone());
+        let address_rune_balances_mempool_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(ADDRESS_RUNE_BALANCES_MEMPOOL_CF, cf_opts.clone());
+        let rune_holders_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_HOLDERS_CF, cf_opts.clone());
+        let rune_history_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(RUNE_HISTORY_CF, cf_opts.clone());
         let outpoint_to_script_pubkey_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(OUTPOINT_TO_SCRIPT_PUBKEY_CF, cf_opts.clone());
         let outpoint_to_script_pubkey_mempool_cfd: ColumnFamilyDescriptor =
@@ -151,6 +217,20 @@ impl RocksDB {
             ColumnFamilyDescriptor::new(SETTINGS_CF, cf_opts.clone());
         let subscriptions_cfd: ColumnFamilyDescriptor =
             ColumnFamilyDescriptor::new(SUBSCRIPTIONS_CF, cf_opts.clone());
+        let event_log_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(EVENT_LOG_CF, cf_opts.clone());
+        let event_log_heights_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(EVENT_LOG_HEIGHTS_CF, cf_opts.clone());
+        let webhook_outbox_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(WEBHOOK_OUTBOX_CF, cf_opts.clone());
+        let webhook_dead_letters_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(WEBHOOK_DEAD_LETTERS_CF, cf_opts.clone());
+        let webhook_delivery_seqs_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(WEBHOOK_DELIVERY_SEQS_CF, cf_opts.clone());
+        let api_keys_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(API_KEYS_CF, cf_opts.clone());
+        let replacements_cfd: ColumnFamilyDescriptor =
+            ColumnFamilyDescriptor::new(REPLACEMENTS_CF, cf_opts.clone());
 
         let mut db_opts = Options::default();
         db_opts.create_if_missing(true);
@@ -183,8 +263,8 @@ impl RocksDB {
         db_opts.set_enable_pipelined_write(true);
 
         // Direct I/O to avoid double buffering
-        db_opts.set_use_direct_reads(true);
-        db_opts.set_use_direct_io_for_flush_and_compaction(true);
+        db_opts.set_use_direct_reads(direct_io);
+        db_opts.set_use_direct_io_for_flush_and_compaction(direct_io);
 
         // Skipping checksum verification during compaction is not available
         // in the current rocksdb crate version. The default checksum behaviour
@@ -209,6 +289,7 @@ impl RocksDB {
                 runes_cfd,
                 rune_ids_cfd,
                 rune_number_cfd,
+                rune_names_cfd,
                 inscriptions_cfd,
                 mempool_cfd,
                 stats_cfd,
@@ -218,6 +299,14 @@ impl RocksDB {
                 transaction_rune_index_mempool_cfd,
                 script_pubkeys_cfd,
                 script_pubkeys_mempool_cfd,
+                address_transactions_cfd,
+                address_transactions_mempool_cfd,
+                transaction_address_index_cfd,
+                transaction_address_index_mempool_cfd,
+                address_rune_balances_cfd,
+                address_rune_balances_mempool_cfd,
+                rune_holders_cfd,
+                rune_history_cfd,
                 outpoint_to_script_pubkey_cfd,
                 outpoint_to_script_pubkey_mempool_cfd,
                 spent_outpoints_mempool_cfd,
@@ -226,6 +315,13 @@ impl RocksDB {
                 transaction_confirming_block_cfd,
                 settings_cfd,
                 subscriptions_cfd,
+                event_log_cfd,
+                event_log_heights_cfd,
+                webhook_outbox_cfd,
+                webhook_dead_letters_cfd,
+                webhook_delivery_seqs_cfd,
+                api_keys_cfd,
+                replacements_cfd,
             ],
         )?;
 
@@ -240,10 +336,13 @@ impl RocksDB {
             db: descriptors,
             mempool_cache: RwLock::new(mempool_cache),
             write_opts,
+            subscriptions_lock: Mutex::new(()),
         };
 
         // Verify that the on-disk schema is compatible with the running binary.
         rocks_db.verify_schema_version()?;
+        rocks_db.backfill_rune_holders()?;
+        rocks_db.backfill_rune_names()?;
 
         Ok(rocks_db)
     }
@@ -291,7 +390,8 @@ impl RocksDB {
 
     /// Ensures the database schema version on disk matches the one compiled into the
     /// binary. If the key is missing (fresh database) the current version is written.
-    /// If a mismatch is detected we return `RocksDBError::SchemaMismatch`.
+    /// Older versions are migrated in place when possible, otherwise we return
+    /// `RocksDBError::SchemaMismatch`.
     fn verify_schema_version(&self) -> DBResult<()> {
         let cf_handle = self.cf_handle(SETTINGS_CF)?;
 
@@ -300,11 +400,40 @@ impl RocksDB {
             .get_option_vec_data(&cf_handle, DB_SCHEMA_VERSION_KEY)
             .mapped()?;
 
+        let mismatch = |v: u64| {
+            RocksDBError::SchemaMismatch(format!(
+                "found version {v}, expected {EXPECTED_DB_SCHEMA_VERSION}. Please wipe or migrate your RocksDB data directory."
+            ))
+        };
+
         match stored_version {
             Some(v) if v == EXPECTED_DB_SCHEMA_VERSION => Ok(()),
-            Some(v) => Err(RocksDBError::SchemaMismatch(format!(
-                "found version {v}, expected {EXPECTED_DB_SCHEMA_VERSION}. Please wipe or migrate your RocksDB data directory."
-            ))),
+            Some(v) if v == 0 || v > EXPECTED_DB_SCHEMA_VERSION => Err(mismatch(v)),
+            Some(v) => {
+                let index_addresses = self.is_index_addresses()? == Some(true);
+
+                // The address transaction history can only be built by indexing every
+                // block again.
+                if v < 2 && index_addresses {
+                    return Err(mismatch(v));
+                }
+
+                if v < 3 && index_addresses {
+                    info!("Migrating database to version 3, building address rune balances");
+                    self.backfill_address_rune_balances()?;
+                }
+
+                if v < 4 {
+                    self.backfill_event_log_heights()?;
+                }
+
+                self.db.put_cf(
+                    &cf_handle,
+                    DB_SCHEMA_VERSION_KEY,
+                    EXPECTED_DB_SCHEMA_VERSION.to_le_bytes(),
+                )?;
+                Ok(())
+            }
             None => {
                 // Fresh DB – store the expected version for future runs.
                 self.db.put_cf(
@@ -317,6 +446,195 @@ impl RocksDB {
         }
     }
 
+    /// Builds the address rune balances of a version 2 database from the address
+    /// outputs that are already indexed. The confirmed balances also build the rune
+    /// holders.
+    fn backfill_address_rune_balances(&self) -> DBResult<()> {
+        const CHUNK_SIZE: usize = 10_000;
+
+        for mempool in [false, true] {
+            let cf_handle = if mempool {
+                self.cf_handle(SCRIPT_PUBKEYS_MEMPOOL_CF)?
+            } else {
+                self.cf_handle(SCRIPT_PUBKEYS_CF)?
+            };
+
+            let mut outpoints = Vec::with_capacity(CHUNK_SIZE);
+            for item in self.db.iterator_cf(&cf_handle, IteratorMode::Start) {
+                let (key, _) = item?;
+                outpoints.push(
+                    parse_outpoint_from_script_pubkey_key(&key)
+                        .map_err(|_| RocksDBError::InvalidOutpoint)?,
+                );
+
+                if outpoints.len() >= CHUNK_SIZE {
+                    self.backfill_rune_balances_chunk(&outpoints, mempool, false)?;
+                    outpoints.clear();
+                }
+            }
+            self.backfill_rune_balances_chunk(&outpoints, mempool, false)?;
+        }
+
+        let cf_handle = self.cf_handle(SPENT_OUTPOINTS_MEMPOOL_CF)?;
+        let mut outpoints = Vec::with_capacity(CHUNK_SIZE);
+        for item in self.db.iterator_cf(&cf_handle, IteratorMode::Start) {
+            let (key, _) = item?;
+            outpoints.push(outpoint_from_bytes(&key).map_err(|_| RocksDBError::InvalidOutpoint)?);
+
+            if outpoints.len() >= CHUNK_SIZE {
+                self.backfill_rune_balances_chunk(&outpoints, true, true)?;
+                outpoints.clear();
+            }
+        }
+        self.backfill_rune_balances_chunk(&outpoints, true, true)?;
+
+        // The balances are written without WAL, make sure they are on disk first.
+        self.db
+            .flush_cf(&self.cf_handle(ADDRESS_RUNE_BALANCES_CF)?)?;
+        self.db
+            .flush_cf(&self.cf_handle(ADDRESS_RUNE_BALANCES_MEMPOOL_CF)?)?;
+
+        Ok(())
+    }
+
+    /// Marks the events of a version 3 event log where the height changes.
+    fn backfill_event_log_heights(&self) -> DBResult<()> {
+        let event_log_cf = self.cf_handle(EVENT_LOG_CF)?;
+        let heights_cf = self.cf_handle(EVENT_LOG_HEIGHTS_CF)?;
+
+        let mut batch = WriteBatch::default();
+        let mut last_height = None;
+        for item in self.db.iterator_cf(&event_log_cf, IteratorMode::Start) {
+            let (key, value) = item?;
+            let height = EventLogEntry::load(value.into_vec()).height;
+            if last_height != Some(height) {
+                batch.put_cf(&heights_cf, key, height.to_le_bytes());
+                last_height = Some(height);
+            }
+        }
+        self.db.write(batch)?;
+
+        Ok(())
+    }
+
+    /// Builds the rune name index from the rune ids when it predates it.
+    fn backfill_rune_names(&self) -> DBResult<()> {
+        let settings_cf = self.cf_handle(SETTINGS_CF)?;
+        if self
+            .get_option_vec_data(&settings_cf, RUNE_NAMES_KEY)?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let rune_ids_cf = self.cf_handle(RUNE_IDS_CF)?;
+        let rune_names_cf = self.cf_handle(RUNE_NAMES_CF)?;
+
+        let mut batch = WriteBatch::default();
+        for item in self.db.iterator_cf(&rune_ids_cf, IteratorMode::Start) {
+            let (key, value) = item?;
+            let rune = Rune(u128::from_le_bytes(
+                key.as_ref()
+                    .try_into()
+                    .map_err(|_| RocksDBError::InvalidRuneId)?,
+            ));
+            batch.put_cf(&rune_names_cf, rune.to_string(), value);
+
+            if batch.len() >= 10_000 {
+                self.db
+                    .write_opt(std::mem::take(&mut batch), &self.write_opts)?;
+            }
+        }
+        self.db.write_opt(batch, &self.write_opts)?;
+
+        self.db.flush_cf(&rune_names_cf)?;
+        self.db
+            .put_cf(&settings_cf, RUNE_NAMES_KEY, 1u64.to_le_bytes())?;
+
+        Ok(())
+    }
+
+    /// Builds the rune holders from the confirmed balances when they predate them.
+    fn backfill_rune_holders(&self) -> DBResult<()> {
+        let settings_cf = self.cf_handle(SETTINGS_CF)?;
+        if self
+            .get_option_vec_data(&settings_cf, RUNE_HOLDERS_KEY)?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let balances_cf = self.cf_handle(ADDRESS_RUNE_BALANCES_CF)?;
+        let holders_cf = self.cf_handle(RUNE_HOLDERS_CF)?;
+
+        let mut holder_counts: HashMap<RuneId, u64> = HashMap::default();
+        let mut batch = WriteBatch::default();
+        for item in self.db.iterator_cf(&balances_cf, IteratorMode::Start) {
+            let (key, value) = item?;
+
+            // "<len><script_pubkey><rune_id>"
+            let script_len = u32::from_be_bytes(key[..4].try_into().unwrap()) as usize;
+            let script_pubkey = &key[4..4 + script_len];
+            let rune_id = rune_id_from_bytes(&key[4 + script_len..])
+                .map_err(|_| RocksDBError::InvalidRuneId)?;
+            let balance = RuneBalance::load(value.into_vec());
+
+            if balance.amount > 0 {
+                batch.put_cf(
+                    &holders_cf,
+                    rune_holder_key(&rune_id, balance.amount, script_pubkey),
+                    vec![1],
+                );
+                *holder_counts.entry(rune_id).or_default() += 1;
+            }
+
+            if batch.len() >= 10_000 {
+                self.db
+                    .write_opt(std::mem::take(&mut batch), &self.write_opts)?;
+            }
+        }
+
+        for (rune_id, count) in holder_counts {
+            batch.put_cf(&holders_cf, rune_id.to_bytes(), count.to_le_bytes());
+        }
+        self.db.write_opt(batch, &self.write_opts)?;
+
+        self.db.flush_cf(&holders_cf)?;
+        self.db
+            .put_cf(&settings_cf, RUNE_HOLDERS_KEY, 1u64.to_le_bytes())?;
+
+        Ok(())
+    }
+
+    fn backfill_rune_balances_chunk(
+        &self,
+        outpoints: &[SerializedOutPoint],
+        mempool: bool,
+        spent: bool,
+    ) -> DBResult<()> {
+        if outpoints.is_empty() {
+            return Ok(());
+        }
+
+        // Spent outputs may be confirmed or in the mempool.
+        let tx_outs = self.get_tx_outs(outpoints, (!spent).then_some(mempool))?;
+
+        let mut changes = RuneBalanceChanges::default();
+        for tx_out in tx_outs.values() {
+            if spent {
+                changes.add_spend(tx_out);
+            } else {
+                changes.add_output(tx_out);
+            }
+        }
+
+        let mut batch = WriteBatch::default();
+        self.apply_rune_balance_changes(&mut batch, &changes, mempool)?;
+        self.db.write_opt(batch, &self.write_opts)?;
+
+        Ok(())
+    }
+
     pub fn is_index_addresses(&self) -> DBResult<Option<bool>> {
         let cf_handle = self.cf_handle(SETTINGS_CF)?;
         let val: Option<u64> = self
@@ -462,6 +780,19 @@ impl RocksDB {
         Ok(result)
     }
 
+    /// Header of the block `hash`, read without decoding the txids stored after it.
+    pub fn get_block_header(&self, hash: &BlockHash) -> DBResult<Header> {
+        let cf_handle = self.cf_handle(BLOCKS_CF)?;
+        let value = self
+            .db
+            .get_pinned_cf(&cf_handle, hash.as_raw_hash().to_byte_array())?
+            .ok_or(RocksDBError::NotFound(format!("block not found: {}", hash)))?;
+
+        let (_, header) = Block::deserialize_header_reader(&mut &value[..])
+            .map_err(|_| RocksDBError::InvalidBlock)?;
+        Ok(header)
+    }
+
     pub fn get_blocks_by_hashes(
         &self,
         hashes: &Vec<BlockHash>,
@@ -704,6 +1035,109 @@ impl RocksDB {
         Ok(rune_id)
     }
 
+    /// Returns the ids of the runes whose name starts with `prefix` and, if set, contains
+    /// `search`. Names are unspaced.
+    pub fn get_rune_ids_by_name(
+        &self,
+        prefix: &str,
+        search: Option<&str>,
+    ) -> DBResult<Vec<RuneId>> {
+        let cf_handle = self.cf_handle(RUNE_NAMES_CF)?;
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+        );
+
+        let mut rune_ids = Vec::new();
+        for item in iter {
+            let (key, value) = item?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+
+            if let Some(search) = search {
+                let name = std::str::from_utf8(&key).map_err(|_| RocksDBError::InvalidString)?;
+                if !name.contains(search) {
+                    continue;
+                }
+            }
+
+            rune_ids.push(RuneId::load(value.into_vec()));
+        }
+
+        Ok(rune_ids)
+    }
+
+    pub fn get_all_runes(&self) -> DBResult<Vec<(RuneId, RuneEntry)>> {
+        let cf_handle = self.cf_handle(RUNES_CF)?;
+
+        let mut runes = Vec::new();
+        for item in self.db.iterator_cf(&cf_handle, IteratorMode::Start) {
+            let (key, value) = item?;
+            let rune_id = rune_id_from_bytes(&key).map_err(|_| RocksDBError::InvalidRuneId)?;
+            runes.push((rune_id, RuneEntry::load(value.into_vec())));
+        }
+
+        Ok(runes)
+    }
+
+    /// Returns the height of the block that confirmed the last transaction of each rune.
+    /// Runes without confirmed transactions are left out.
+    pub fn get_runes_last_activity(&self, rune_ids: &[RuneId]) -> DBResult<HashMap<RuneId, u64>> {
+        let cf_handle = self.cf_handle(RUNE_TRANSACTIONS_CF)?;
+
+        // 1. Get the last index of every rune
+        let last_indexes = self.db.multi_get_cf(
+            rune_ids
+                .iter()
+                .map(|rune_id| (&cf_handle, rune_index_key(rune_id))),
+        );
+
+        let mut keys = Vec::new();
+        for (rune_id, last_index) in rune_ids.iter().zip(last_indexes) {
+            if let Some(bytes) = last_index? {
+                let last_index = u64::from_le_bytes(
+                    bytes[..8]
+                        .try_into()
+                        .map_err(|_| RocksDBError::InvalidU64)?,
+                );
+                if last_index > 0 {
+                    keys.push((*rune_id, rune_transaction_key(rune_id, last_index)));
+                }
+            }
+        }
+
+        // 2. Get the last transaction of every rune
+        let txids = self
+            .db
+            .multi_get_cf(keys.iter().map(|(_, key)| (&cf_handle, key.as_slice())));
+
+        let mut last_txids = Vec::with_capacity(keys.len());
+        for ((rune_id, _), txid) in keys.iter().zip(txids) {
+            if let Some(bytes) = txid? {
+                let txid = SerializedTxid::try_from(bytes.into_boxed_slice())
+                    .map_err(|_| RocksDBError::InvalidTxid)?;
+                last_txids.push((*rune_id, txid));
+            }
+        }
+
+        // 3. Get the block that confirmed it
+        let confirming_blocks = self.get_transaction_confirming_blocks(
+            &last_txids
+                .iter()
+                .map(|(_, txid)| txid.clone())
+                .collect::<Vec<_>>(),
+        )?;
+
+        Ok(last_txids
+            .into_iter()
+            .filter_map(|(rune_id, txid)| {
+                let block_id = confirming_blocks.get(&txid)?.as_ref()?;
+                Some((rune_id, block_id.height))
+            })
+            .collect())
+    }
+
     pub fn get_inscription(&self, id: &InscriptionId) -> DBResult<Inscription> {
         let cf_handle = self.cf_handle(INSCRIPTIONS_CF)?;
         let inscription: Inscription = self
@@ -741,6 +1175,8 @@ impl RocksDB {
             return Ok(PaginationResponse {
                 items: vec![],
                 offset: 0,
+                next_cursor: None,
+                prev_cursor: None,
             });
         }
 
@@ -754,6 +1190,8 @@ impl RocksDB {
             return Ok(PaginationResponse {
                 items: vec![],
                 offset,
+                next_cursor: None,
+                prev_cursor: None,
             });
         }
 
@@ -811,7 +1249,7 @@ impl RocksDB {
             // Convert valuecf to SerializedTxid
             let txid = SerializedTxid::try_from(value_bytes).unwrap();
 
-            results.push(txid);
+            results.push((idx, txid));
 
             // Stop if we have limit items
             if results.len() as u64 >= limit {
@@ -821,12 +1259,151 @@ impl RocksDB {
 
         let offset = skip + results.len() as u64;
 
+        // 5. Cursors so the caller can continue from here without skip
+        let next_cursor = match results.last() {
+            Some((idx, _)) if results.len() as u64 >= limit && *idx > 1 => {
+                Some(Cursor::new(mempool, *idx))
+            }
+            _ => None,
+        };
+        let prev_cursor = match results.first() {
+            Some((idx, _)) if skip > 0 => Some(Cursor::new(mempool, *idx)),
+            _ => None,
+        };
+
         Ok(PaginationResponse {
-            items: results,
+            items: results.into_iter().map(|(_, txid)| txid).collect(),
             offset,
+            next_cursor,
+            prev_cursor,
         })
     }
 
+    /// Returns up to `limit` `(index, txid)` entries of `rune_id`, starting right after
+    /// `from` (exclusive) and walking in `direction`. `Forward` walks from newer to older
+    /// entries. Entries are returned in walk order.
+    ///
+    /// Confirmed entries are only removed on reorgs, so they are read by index. Mempool
+    /// entries are removed as soon as they get mined and leave large gaps in the index
+    /// range, so the (small) mempool list is scanned instead.
+    pub fn get_rune_transactions_from(
+        &self,
+        rune_id: &RuneId,
+        from: Option<u64>,
+        direction: PaginationDirection,
+        limit: u64,
+        mempool: bool,
+    ) -> DBResult<Vec<(u64, SerializedTxid)>> {
+        if mempool {
+            return self.scan_mempool_rune_transactions_from(rune_id, from, direction, limit);
+        }
+
+        let cf_handle = self.cf_handle(RUNE_TRANSACTIONS_CF)?;
+
+        let last_index: u64 = self
+            .get_option_vec_data(&cf_handle, &rune_index_key(rune_id))
+            .mapped()?
+            .unwrap_or(0);
+
+        let mut next = match direction {
+            PaginationDirection::Forward => from
+                .map_or(last_index, |index| index.saturating_sub(1))
+                .min(last_index),
+            PaginationDirection::Backward => from.map_or(1, |index| index.saturating_add(1)),
+        };
+
+        let mut results = Vec::new();
+        while (results.len() as u64) < limit && (1..=last_index).contains(&next) {
+            let chunk = limit - results.len() as u64;
+            let indexes: Vec<u64> = match direction {
+                PaginationDirection::Forward => (next.saturating_sub(chunk - 1).max(1)..=next)
+                    .rev()
+                    .collect(),
+                PaginationDirection::Backward => {
+                    (next..=next.saturating_add(chunk - 1).min(last_index)).collect()
+                }
+            };
+
+            let keys: Vec<_> = indexes
+                .iter()
+                .map(|index| (&cf_handle, rune_transaction_key(rune_id, *index)))
+                .collect();
+
+            for (index, value) in indexes.iter().zip(self.db.multi_get_cf(keys)) {
+                if let Some(value) = value? {
+                    let txid = SerializedTxid::try_from(value.into_boxed_slice())
+                        .map_err(|_| RocksDBError::InvalidTxid)?;
+                    results.push((*index, txid));
+                }
+            }
+
+            let last = *indexes.last().expect("chunk is never empty");
+            next = match direction {
+                PaginationDirection::Forward => last - 1,
+                PaginationDirection::Backward => last.saturating_add(1),
+            };
+        }
+
+        Ok(results)
+    }
+
+    fn scan_mempool_rune_transactions_from(
+        &self,
+        rune_id: &RuneId,
+        from: Option<u64>,
+        direction: PaginationDirection,
+        limit: u64,
+    ) -> DBResult<Vec<(u64, SerializedTxid)>> {
+        let cf_handle = self.cf_handle(RUNE_TRANSACTIONS_MEMPOOL_CF)?;
+
+        let prefix_bytes = {
+            let rune_id_bytes = rune_id.to_bytes();
+            let mut p = Vec::with_capacity(5 + rune_id_bytes.len() + 1);
+            p.extend_from_slice(b"rune:");
+            p.extend_from_slice(&rune_id_bytes);
+            p.push(b':');
+            p
+        };
+
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(&prefix_bytes, Direction::Forward),
+        );
+
+        let mut results = Vec::new();
+        for item in iter {
+            let (key_bytes, value_bytes) = item?;
+            if !key_bytes.starts_with(&prefix_bytes) {
+                break;
+            }
+
+            if key_bytes.len() != prefix_bytes.len() + 8 {
+                continue;
+            }
+
+            let idx = u64::from_le_bytes(key_bytes[prefix_bytes.len()..].try_into().unwrap());
+            let in_range = match (direction, from) {
+                (_, None) => true,
+                (PaginationDirection::Forward, Some(from)) => idx < from,
+                (PaginationDirection::Backward, Some(from)) => idx > from,
+            };
+
+            if in_range {
+                let txid =
+                    SerializedTxid::try_from(value_bytes).map_err(|_| RocksDBError::InvalidTxid)?;
+                results.push((idx, txid));
+            }
+        }
+
+        match direction {
+            PaginationDirection::Forward => results.sort_unstable_by(|a, b| b.0.cmp(&a.0)),
+            PaginationDirection::Backward => results.sort_unstable_by(|a, b| a.0.cmp(&b.0)),
+        }
+        results.truncate(limit.try_into().unwrap_or(usize::MAX));
+
+        Ok(results)
+    }
+
     /// Batch-add multiple rune transactions.
     ///
     /// # Arguments
@@ -957,35 +1534,613 @@ impl RocksDB {
             return Ok(());
         }
 
-        let primary_cf = if mempool {
-            self.cf_handle(RUNE_TRANSACTIONS_MEMPOOL_CF)?
+        let primary_cf = if mempool {
+            self.cf_handle(RUNE_TRANSACTIONS_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(RUNE_TRANSACTIONS_CF)?
+        };
+
+        let secondary_cf = if mempool {
+            self.cf_handle(TRANSACTION_RUNE_INDEX_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(TRANSACTION_RUNE_INDEX_CF)?
+        };
+
+        let mut batch = WriteBatch::default();
+
+        for (txid, idx_refs) in idx_refs {
+            for TxRuneIndexRef { rune_id, index } in &idx_refs {
+                // Validate length but use bytes directly without reconstructing RuneId
+                if rune_id.len() != 12 {
+                    return Err(RocksDBError::InvalidRuneId);
+                }
+                let key = rune_transaction_key_from_bytes(rune_id, *index);
+                batch.delete_cf(&primary_cf, key);
+            }
+
+            batch.delete_cf(&secondary_cf, txid.as_ref());
+        }
+
+        self.db.write_opt(batch, &self.write_opts)?;
+        Ok(())
+    }
+
+    /// Appends `txids` to the history of each script pubkey, the same way
+    /// `add_rune_transactions_batch` does for runes, and replaces the secondary
+    /// txid -> history index used to remove them again on rollback.
+    pub fn add_address_transactions_batch(
+        &self,
+        address_tx_map: &HashMap<ScriptBuf, Vec<SerializedTxid>>,
+        mempool: bool,
+    ) -> DBResult<()> {
+        let primary_cf = if mempool {
+            self.cf_handle(ADDRESS_TRANSACTIONS_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(ADDRESS_TRANSACTIONS_CF)?
+        };
+
+        let secondary_cf = if mempool {
+            self.cf_handle(TRANSACTION_ADDRESS_INDEX_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(TRANSACTION_ADDRESS_INDEX_CF)?
+        };
+
+        let mut batch = WriteBatch::default();
+        let mut sec_index_acc: HashMap<SerializedTxid, Vec<TxAddressIndexRef>> = HashMap::default();
+
+        for (script_pubkey, txids) in address_tx_map {
+            // The bare prefix holds the last index used by this script pubkey.
+            let last_index_key = address_transaction_prefix(script_pubkey.as_bytes());
+
+            let last_index = match self.db.get_cf(&primary_cf, &last_index_key)? {
+                Some(bytes) if bytes.len() >= 8 => {
+                    u64::from_be_bytes(bytes[..8].try_into().expect("Expected 8 bytes"))
+                }
+                _ => 0,
+            };
+
+            let mut current_index = last_index;
+
+            for txid in txids {
+                current_index = current_index
+                    .checked_add(1)
+                    .ok_or_else(|| RocksDBError::Overflow)?;
+
+                let tx_key = address_transaction_key(script_pubkey.as_bytes(), current_index);
+                batch.put_cf(&primary_cf, tx_key, txid.as_ref());
+
+                sec_index_acc
+                    .entry(*txid)
+                    .or_default()
+                    .push(TxAddressIndexRef {
+                        script_pubkey: script_pubkey.to_bytes(),
+                        index: current_index,
+                    });
+            }
+
+            batch.put_cf(&primary_cf, last_index_key, current_index.to_be_bytes());
+        }
+
+        for (txid, new_refs) in sec_index_acc.into_iter() {
+            batch.put_cf(&secondary_cf, txid.as_ref(), new_refs.store());
+        }
+
+        self.db.write_opt(batch, &self.write_opts)?;
+        Ok(())
+    }
+
+    /// Remove `txid` from the history of every script pubkey it touched.
+    pub fn delete_address_transactions(
+        &self,
+        txids: &[SerializedTxid],
+        mempool: bool,
+    ) -> DBResult<()> {
+        let primary_cf = if mempool {
+            self.cf_handle(ADDRESS_TRANSACTIONS_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(ADDRESS_TRANSACTIONS_CF)?
+        };
+
+        let secondary_cf = if mempool {
+            self.cf_handle(TRANSACTION_ADDRESS_INDEX_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(TRANSACTION_ADDRESS_INDEX_CF)?
+        };
+
+        let keys: Vec<_> = txids
+            .iter()
+            .map(|txid| (&secondary_cf, txid.as_ref()))
+            .collect();
+
+        let mut batch = WriteBatch::default();
+        for (i, value) in self.db.multi_get_cf(keys).into_iter().enumerate() {
+            if let Some(value) = value? {
+                let idx_refs: Vec<TxAddressIndexRef> = Entry::load(value);
+                for TxAddressIndexRef {
+                    script_pubkey,
+                    index,
+                } in &idx_refs
+                {
+                    batch.delete_cf(&primary_cf, address_transaction_key(script_pubkey, *index));
+                }
+
+                batch.delete_cf(&secondary_cf, txids[i].as_ref());
+            }
+        }
+
+        self.db.write_opt(batch, &self.write_opts)?;
+        Ok(())
+    }
+
+    /// Position of `txid` in the history of `script_pubkey`, read from the secondary
+    /// txid -> history index.
+    pub fn get_address_transaction_index(
+        &self,
+        script_pubkey: &ScriptBuf,
+        txid: &SerializedTxid,
+        mempool: bool,
+    ) -> DBResult<Option<u64>> {
+        let cf_handle = if mempool {
+            self.cf_handle(TRANSACTION_ADDRESS_INDEX_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(TRANSACTION_ADDRESS_INDEX_CF)?
+        };
+
+        let Some(value) = self.db.get_cf(&cf_handle, txid.as_ref())? else {
+            return Ok(None);
+        };
+
+        let idx_refs: Vec<TxAddressIndexRef> = Entry::load(value);
+        Ok(idx_refs
+            .into_iter()
+            .find(|idx_ref| idx_ref.script_pubkey == script_pubkey.as_bytes())
+            .map(|idx_ref| idx_ref.index))
+    }
+
+    /// Returns up to `limit` `(index, txid)` entries from the history of `script_pubkey`,
+    /// starting right after `from` (exclusive) and walking in `direction`. `Forward` walks
+    /// from newer to older entries. When `rune_id` is set, only transactions that touched
+    /// that rune are returned, and the walk stops after `MAX_SCANNED_ADDRESS_ENTRIES`
+    /// entries, returning the index of the last one it looked at.
+    pub fn get_address_transactions_from(
+        &self,
+        script_pubkey: &ScriptBuf,
+        from: Option<u64>,
+        direction: PaginationDirection,
+        limit: u64,
+        mempool: bool,
+        rune_id: Option<&RuneId>,
+    ) -> DBResult<(Vec<(u64, SerializedTxid)>, Option<u64>)> {
+        let cf_handle = if mempool {
+            self.cf_handle(ADDRESS_TRANSACTIONS_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(ADDRESS_TRANSACTIONS_CF)?
+        };
+
+        let rune_index_cf = if mempool {
+            self.cf_handle(TRANSACTION_RUNE_INDEX_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(TRANSACTION_RUNE_INDEX_CF)?
+        };
+
+        let prefix = address_transaction_prefix(script_pubkey.as_bytes());
+
+        let (seek_index, iterator_direction) = match direction {
+            PaginationDirection::Forward => match from {
+                Some(from) => match from.checked_sub(1) {
+                    Some(index) => (index, Direction::Reverse),
+                    None => return Ok((vec![], None)),
+                },
+                None => (u64::MAX, Direction::Reverse),
+            },
+            PaginationDirection::Backward => match from {
+                Some(from) => match from.checked_add(1) {
+                    Some(index) => (index, Direction::Forward),
+                    None => return Ok((vec![], None)),
+                },
+                None => (0, Direction::Forward),
+            },
+        };
+
+        let seek_key = address_transaction_key(script_pubkey.as_bytes(), seek_index);
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(&seek_key, iterator_direction),
+        );
+
+        let rune_id_bytes = rune_id.map(|rune_id| rune_id.to_bytes());
+
+        let mut results = Vec::new();
+        let mut scanned = 0;
+        let mut last_scanned = None;
+        for item in iter {
+            if results.len() as u64 >= limit {
+                break;
+            }
+
+            let (key_bytes, value_bytes) = item?;
+            if !key_bytes.starts_with(&prefix) {
+                break;
+            }
+
+            // Skip the last index entry, which is the bare prefix.
+            if key_bytes.len() != prefix.len() + 8 {
+                continue;
+            }
+
+            let index = u64::from_be_bytes(key_bytes[prefix.len()..].try_into().unwrap());
+
+            if rune_id_bytes.is_some() {
+                if scanned >= MAX_SCANNED_ADDRESS_ENTRIES {
+                    return Ok((results, last_scanned));
+                }
+                scanned += 1;
+                last_scanned = Some(index);
+            }
+
+            let txid =
+                SerializedTxid::try_from(value_bytes).map_err(|_| RocksDBError::InvalidTxid)?;
+
+            if let Some(rune_id_bytes) = &rune_id_bytes {
+                let touches_rune = match self.db.get_cf(&rune_index_cf, txid.as_ref())? {
+                    Some(value) => {
+                        let idx_refs: Vec<TxRuneIndexRef> = Entry::load(value);
+                        idx_refs
+                            .iter()
+                            .any(|idx_ref| idx_ref.rune_id == rune_id_bytes.as_slice())
+                    }
+                    None => false,
+                };
+
+                if !touches_rune {
+                    continue;
+                }
+            }
+
+            results.push((index, txid));
+        }
+
+        Ok((results, None))
+    }
+
+    /// Adds the balance changes to `batch`, on top of the balances currently stored.
+    fn apply_rune_balance_changes(
+        &self,
+        batch: &mut WriteBatch,
+        changes: &RuneBalanceChanges,
+        mempool: bool,
+    ) -> DBResult<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let cf_handle = if mempool {
+            self.cf_handle(ADDRESS_RUNE_BALANCES_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(ADDRESS_RUNE_BALANCES_CF)?
+        };
+
+        let changes = changes
+            .iter()
+            .map(|((script_pubkey, rune_id), change)| {
+                (
+                    address_rune_balance_key(script_pubkey.as_bytes(), rune_id),
+                    script_pubkey,
+                    rune_id,
+                    change,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let values = self
+            .db
+            .multi_get_cf(changes.iter().map(|(key, ..)| (&cf_handle, key.as_slice())));
+
+        // Confirmed balances also keep the rune holders up to date.
+        let holders_cf = self.cf_handle(RUNE_HOLDERS_CF)?;
+        let mut holder_count_changes: HashMap<RuneId, i64> = HashMap::default();
+
+        for ((key, script_pubkey, rune_id, change), value) in changes.iter().zip(values) {
+            let previous = match value? {
+                Some(value) => RuneBalance::load(value),
+                None => RuneBalance::default(),
+            };
+            let balance = previous.apply(change);
+
+            if balance.is_zero() {
+                batch.delete_cf(&cf_handle, key);
+            } else {
+                batch.put_cf(&cf_handle, key, balance.store());
+            }
+
+            if mempool || previous.amount == balance.amount {
+                continue;
+            }
+
+            if previous.amount > 0 {
+                batch.delete_cf(
+                    &holders_cf,
+                    rune_holder_key(rune_id, previous.amount, script_pubkey.as_bytes()),
+                );
+            }
+
+            if balance.amount > 0 {
+                batch.put_cf(
+                    &holders_cf,
+                    rune_holder_key(rune_id, balance.amount, script_pubkey.as_bytes()),
+                    vec![1],
+                );
+            }
+
+            match (previous.amount > 0, balance.amount > 0) {
+                (false, true) => *holder_count_changes.entry(**rune_id).or_default() += 1,
+                (true, false) => *holder_count_changes.entry(**rune_id).or_default() -= 1,
+                _ => {}
+            }
+        }
+
+        let holder_count_changes = holder_count_changes
+            .into_iter()
+            .filter(|(_, change)| *change != 0)
+            .collect::<Vec<_>>();
+
+        let counts = self.db.multi_get_cf(
+            holder_count_changes
+                .iter()
+                .map(|(rune_id, _)| (&holders_cf, rune_id.to_bytes())),
+        );
+
+        for ((rune_id, change), count) in holder_count_changes.iter().zip(counts) {
+            let count = match count? {
+                Some(bytes) => u64::from_le_bytes(
+                    bytes[..8]
+                        .try_into()
+                        .map_err(|_| RocksDBError::InvalidU64)?,
+                ),
+                None => 0,
+            };
+
+            let count = count.saturating_add_signed(*change);
+            if count == 0 {
+                batch.delete_cf(&holders_cf, rune_id.to_bytes());
+            } else {
+                batch.put_cf(&holders_cf, rune_id.to_bytes(), count.to_le_bytes());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the history of `rune_id` between the `from` and `to` heights, inclusive.
+    pub fn get_rune_history(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> DBResult<Vec<(u64, RuneHistory)>> {
+        let cf_handle = self.cf_handle(RUNE_HISTORY_CF)?;
+
+        let prefix = rune_id.to_bytes();
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(&rune_history_key(rune_id, from), Direction::Forward),
+        );
+
+        let mut history = Vec::new();
+        for item in iter {
+            let (key, value) = item?;
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            let height = parse_rune_history_key(&key).map_err(|_| RocksDBError::InvalidU64)?;
+            if height > to {
+                break;
+            }
+
+            history.push((height, RuneHistory::load(value.into_vec())));
+        }
+
+        Ok(history)
+    }
+
+    /// Returns up to `limit` `((amount, script_pubkey), holder)` entries of the holders of
+    /// `rune_id`, starting right after `from` (exclusive) and walking in `direction`.
+    /// `Forward` walks from the largest balance to the smallest.
+    pub fn get_rune_holders_from(
+        &self,
+        rune_id: &RuneId,
+        from: Option<(u128, &[u8])>,
+        direction: PaginationDirection,
+        limit: u64,
+    ) -> DBResult<Vec<RuneHolderEntry>> {
+        let cf_handle = self.cf_handle(RUNE_HOLDERS_CF)?;
+
+        let prefix = rune_id.to_bytes();
+        let from_key =
+            from.map(|(amount, script_pubkey)| rune_holder_key(rune_id, amount, script_pubkey));
+
+        let iter = match (&from_key, direction) {
+            (Some(from_key), PaginationDirection::Forward) => self
+                .db
+                .iterator_cf(&cf_handle, IteratorMode::From(from_key, Direction::Reverse)),
+            (Some(from_key), PaginationDirection::Backward) => self
+                .db
+                .iterator_cf(&cf_handle, IteratorMode::From(from_key, Direction::Forward)),
+            (None, PaginationDirection::Forward) => match rune_holders_upper_bound(&prefix) {
+                Some(upper_bound) => self.db.iterator_cf(
+                    &cf_handle,
+                    IteratorMode::From(&upper_bound, Direction::Reverse),
+                ),
+                None => self.db.iterator_cf(&cf_handle, IteratorMode::End),
+            },
+            (None, PaginationDirection::Backward) => self
+                .db
+                .iterator_cf(&cf_handle, IteratorMode::From(&prefix, Direction::Forward)),
+        };
+
+        let mut holders = Vec::new();
+        for item in iter {
+            if holders.len() as u64 >= limit {
+                break;
+            }
+
+            let (key, _) = item?;
+            if !key.starts_with(&prefix) {
+                // The seek can land on the holder count of the next rune.
+                if direction == PaginationDirection::Forward && key.as_ref() > prefix.as_slice() {
+                    continue;
+                }
+
+                break;
+            }
+
+            // The holder count is stored under the bare prefix, and `from` is exclusive.
+            if key.len() == prefix.len() || from_key.as_deref() == Some(key.as_ref()) {
+                continue;
+            }
+
+            let (amount, script_pubkey) =
+                parse_rune_holder_key(&key).map_err(|_| RocksDBError::InvalidRuneId)?;
+            let script_pubkey = ScriptBuf::from_bytes(script_pubkey.to_vec());
+            holders.push(((amount, script_pubkey.clone()), (script_pubkey, amount)));
+        }
+
+        Ok(holders)
+    }
+
+    pub fn get_rune_holder_counts(&self, rune_ids: &[RuneId]) -> DBResult<HashMap<RuneId, u64>> {
+        let cf_handle = self.cf_handle(RUNE_HOLDERS_CF)?;
+
+        let counts = self.db.multi_get_cf(
+            rune_ids
+                .iter()
+                .map(|rune_id| (&cf_handle, rune_id.to_bytes())),
+        );
+
+        let mut result = HashMap::default();
+        for (rune_id, count) in rune_ids.iter().zip(counts) {
+            if let Some(bytes) = count? {
+                let count = u64::from_le_bytes(
+                    bytes[..8]
+                        .try_into()
+                        .map_err(|_| RocksDBError::InvalidU64)?,
+                );
+                result.insert(*rune_id, count);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the rune balances of a script pubkey, or only the one of `rune_id`.
+    pub fn get_address_rune_balances(
+        &self,
+        script_pubkey: &ScriptBuf,
+        rune_id: Option<&RuneId>,
+        mempool: bool,
+    ) -> DBResult<HashMap<RuneId, RuneBalance>> {
+        let cf_handle = if mempool {
+            self.cf_handle(ADDRESS_RUNE_BALANCES_MEMPOOL_CF)?
+        } else {
+            self.cf_handle(ADDRESS_RUNE_BALANCES_CF)?
+        };
+
+        let mut balances = HashMap::default();
+
+        if let Some(rune_id) = rune_id {
+            let key = address_rune_balance_key(script_pubkey.as_bytes(), rune_id);
+            if let Some(value) = self.db.get_cf(&cf_handle, key)? {
+                balances.insert(*rune_id, RuneBalance::load(value));
+            }
+
+            return Ok(balances);
+        }
+
+        let prefix = address_transaction_prefix(script_pubkey.as_bytes());
+        let iter = self
+            .db
+            .iterator_cf(&cf_handle, IteratorMode::From(&prefix, Direction::Forward));
+
+        for item in iter {
+            let (key, value) = item?;
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            let rune_id = rune_id_from_bytes(&key[prefix.len()..])
+                .map_err(|_| RocksDBError::InvalidRuneId)?;
+            balances.insert(rune_id, RuneBalance::load(value.into_vec()));
+        }
+
+        Ok(balances)
+    }
+
+    /// Returns up to `limit` outpoints owned by `script_pubkey`, starting right after
+    /// `from` (exclusive). `Forward` walks the outpoints in ascending key order.
+    pub fn get_script_pubkey_outpoints_from(
+        &self,
+        script_pubkey: &ScriptBuf,
+        from: Option<SerializedOutPoint>,
+        direction: PaginationDirection,
+        limit: u64,
+        mempool: bool,
+    ) -> DBResult<Vec<SerializedOutPoint>> {
+        let cf_handle = if mempool {
+            self.cf_handle(SCRIPT_PUBKEYS_MEMPOOL_CF)?
         } else {
-            self.cf_handle(RUNE_TRANSACTIONS_CF)?
+            self.cf_handle(SCRIPT_PUBKEYS_CF)?
         };
 
-        let secondary_cf = if mempool {
-            self.cf_handle(TRANSACTION_RUNE_INDEX_MEMPOOL_CF)?
-        } else {
-            self.cf_handle(TRANSACTION_RUNE_INDEX_CF)?
+        let search_key = script_pubkey_search_key(script_pubkey);
+        let seek_key = match from {
+            Some(from) => script_pubkey_outpoint_to_bytes(script_pubkey, &from),
+            None => match direction {
+                PaginationDirection::Forward => search_key.clone(),
+                PaginationDirection::Backward => {
+                    let mut key = search_key.clone();
+                    key.extend_from_slice(&[0xff; 37]);
+                    key
+                }
+            },
         };
 
-        let mut batch = WriteBatch::default();
+        let iterator_direction = match direction {
+            PaginationDirection::Forward => Direction::Forward,
+            PaginationDirection::Backward => Direction::Reverse,
+        };
 
-        for (txid, idx_refs) in idx_refs {
-            for TxRuneIndexRef { rune_id, index } in &idx_refs {
-                // Validate length but use bytes directly without reconstructing RuneId
-                if rune_id.len() != 12 {
-                    return Err(RocksDBError::InvalidRuneId);
-                }
-                let key = rune_transaction_key_from_bytes(rune_id, *index);
-                batch.delete_cf(&primary_cf, key);
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(&seek_key, iterator_direction),
+        );
+
+        let mut outpoints = Vec::new();
+        for item in iter {
+            if outpoints.len() as u64 >= limit {
+                break;
             }
 
-            batch.delete_cf(&secondary_cf, txid.as_ref());
+            let (key, _) = item?;
+            if !key.starts_with(&search_key) {
+                break;
+            }
+
+            if key.len() != search_key.len() + 36 {
+                continue;
+            }
+
+            let outpoint = parse_outpoint_from_script_pubkey_key(&key)
+                .map_err(|_| RocksDBError::InvalidOutpoint)?;
+
+            if Some(outpoint) == from {
+                continue;
+            }
+
+            outpoints.push(outpoint);
         }
 
-        self.db.write_opt(batch, &self.write_opts)?;
-        Ok(())
+        Ok(outpoints)
     }
 
     pub fn get_mempool_txids(&self) -> DBResult<HashMap<SerializedTxid, MempoolEntry>> {
@@ -1324,6 +2479,42 @@ impl RocksDB {
             )))?)
     }
 
+    pub fn set_replacements(&self, replacements: &[TransactionReplacement]) -> DBResult<()> {
+        let cf_handle = self.cf_handle(REPLACEMENTS_CF)?;
+        let mut batch = WriteBatch::default();
+        for replacement in replacements {
+            batch.put_cf(
+                &cf_handle,
+                replacement.txid.as_ref(),
+                replacement.store_ref(),
+            );
+        }
+
+        self.db.write_opt(batch, &self.write_opts)?;
+        Ok(())
+    }
+
+    pub fn get_replacements(
+        &self,
+        txids: &[SerializedTxid],
+    ) -> DBResult<HashMap<SerializedTxid, TransactionReplacement>> {
+        let cf_handle = self.cf_handle(REPLACEMENTS_CF)?;
+        let keys: Vec<_> = txids
+            .iter()
+            .map(|txid| (&cf_handle, txid.as_ref()))
+            .collect();
+        let results = self.db.multi_get_cf(keys);
+
+        let mut replacements = HashMap::default();
+        for (i, result) in results.into_iter().enumerate() {
+            if let Some(bytes) = result? {
+                replacements.insert(txids[i], TransactionReplacement::load(bytes));
+            }
+        }
+
+        Ok(replacements)
+    }
+
     pub fn get_transaction_confirming_blocks(
         &self,
         txids: &[SerializedTxid],
@@ -1417,8 +2608,15 @@ impl RocksDB {
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_IDS_CF)?;
 
+            let names_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_NAMES_CF)?;
+
             for (rune, rune_id) in update.rune_ids.iter() {
                 batch.put_cf(&cf_handle, rune.to_le_bytes(), rune_id.store_ref());
+                batch.put_cf(
+                    &names_cf_handle,
+                    Rune(*rune).to_string(),
+                    rune_id.store_ref(),
+                );
             }
         }
 
@@ -1559,6 +2757,33 @@ impl RocksDB {
             }
         }
 
+        // 18. Update address rune balances
+        self.apply_rune_balance_changes(&mut batch, &update.rune_balances, mempool)?;
+
+        // 19. Update rune history
+        if !mempool && !update.rune_history.is_empty() {
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_HISTORY_CF)?;
+
+            let entries = update
+                .rune_history
+                .iter()
+                .map(|((rune_id, height), history)| (rune_history_key(rune_id, *height), history))
+                .collect::<Vec<_>>();
+            let values = self
+                .db
+                .multi_get_cf(entries.iter().map(|(key, _)| (&cf_handle, key.as_slice())));
+
+            // A block can be split across batches, so merge with what is stored.
+            for ((key, history), value) in entries.iter().zip(values) {
+                let mut history = **history;
+                if let Some(value) = value? {
+                    history.merge(&RuneHistory::load(value));
+                }
+
+                batch.put_cf(&cf_handle, key, history.store());
+            }
+        }
+
         // Proceed with the actual write
         self.db.write_opt(batch, &self.write_opts)?;
 
@@ -1567,6 +2792,11 @@ impl RocksDB {
             self.add_rune_transactions_batch(&update.rune_transactions, mempool)?;
         }
 
+        // 13. Update address_transactions. This is batched on its own.
+        if !update.address_transactions.is_empty() {
+            self.add_address_transactions_batch(&update.address_transactions, mempool)?;
+        }
+
         Ok(())
     }
 
@@ -1744,8 +2974,10 @@ impl RocksDB {
         // 9. Update runes_ids_to_delete
         {
             let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_IDS_CF)?;
+            let names_cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_NAMES_CF)?;
             for rune in rollback.runes_ids_to_delete.iter() {
                 batch.delete_cf(&cf_handle, rune.0.to_le_bytes());
+                batch.delete_cf(&names_cf_handle, rune.to_string());
             }
         }
 
@@ -1833,9 +3065,21 @@ impl RocksDB {
             }
         }
 
+        // 18. Revert address rune balances
+        self.apply_rune_balance_changes(&mut batch, &rollback.rune_balances, mempool)?;
+
+        // 19. Delete rune history
+        if !mempool {
+            let cf_handle: Arc<BoundColumnFamily<'_>> = self.cf_handle(RUNE_HISTORY_CF)?;
+            for (rune_id, height) in rollback.rune_history_to_delete.iter() {
+                batch.delete_cf(&cf_handle, rune_history_key(rune_id, *height));
+            }
+        }
+
         self.db.write_opt(batch, &self.write_opts)?;
 
         self.delete_rune_transactions(&rollback.txs_to_delete, mempool)?;
+        self.delete_address_transactions(&rollback.txs_to_delete, mempool)?;
 
         // Update runen numbers after revert.
         let total_runes_before_delete =
@@ -1907,7 +3151,20 @@ impl RocksDB {
         Ok(())
     }
 
-    pub fn set_subscription(&self, sub: &Subscription) -> DBResult<()> {
+    /// Stores a subscription, replacing the one with the same id. Returns whether
+    /// it is a new subscription.
+    pub fn set_subscription(&self, sub: &Subscription) -> DBResult<bool> {
+        let _lock = self
+            .subscriptions_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let cf_handle = self.cf_handle(SUBSCRIPTIONS_CF)?;
+        let is_new = self.db.get_cf(&cf_handle, sub.id.as_bytes())?.is_none();
+        self.put_subscription(sub)?;
+        Ok(is_new)
+    }
+
+    fn put_subscription(&self, sub: &Subscription) -> DBResult<()> {
         let cf_handle = self.cf_handle(SUBSCRIPTIONS_CF)?;
         self.db
             .put_cf(&cf_handle, sub.id.as_bytes(), sub.store_ref())?;
@@ -1939,11 +3196,36 @@ impl RocksDB {
         Ok(subs)
     }
 
-    pub fn delete_subscription(&self, id: &Uuid) -> DBResult<()> {
+    /// Deletes a subscription along with its outbox and dead letters. Returns
+    /// whether the subscription existed.
+    pub fn delete_subscription(&self, id: &Uuid) -> DBResult<bool> {
+        let _lock = self
+            .subscriptions_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
         let cf_handle = self.cf_handle(SUBSCRIPTIONS_CF)?;
-        let key = id.as_bytes();
-        self.db.delete_cf(&cf_handle, key)?;
-        Ok(())
+        let existed = self.db.get_cf(&cf_handle, id.as_bytes())?.is_some();
+        let mut batch = WriteBatch::default();
+        batch.delete_cf(&cf_handle, id.as_bytes());
+        batch.delete_cf(&self.cf_handle(WEBHOOK_DELIVERY_SEQS_CF)?, id.as_bytes());
+
+        for cf in [WEBHOOK_OUTBOX_CF, WEBHOOK_DEAD_LETTERS_CF] {
+            let cf_handle = self.cf_handle(cf)?;
+            let iter = self.db.iterator_cf(
+                &cf_handle,
+                IteratorMode::From(id.as_bytes(), Direction::Forward),
+            );
+            for item in iter {
+                let (key, _) = item?;
+                if !key.starts_with(id.as_bytes()) {
+                    break;
+                }
+                batch.delete_cf(&cf_handle, key);
+            }
+        }
+
+        self.db.write_opt(batch, &self.write_opts)?;
+        Ok(existed)
     }
 
     pub fn update_subscription_last_success(
@@ -1951,9 +3233,363 @@ impl RocksDB {
         subscription_id: &Uuid,
         new_time_secs: u64,
     ) -> DBResult<()> {
+        let _lock = self
+            .subscriptions_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
         let mut sub = self.get_subscription(subscription_id)?;
         sub.last_success_epoch_secs = new_time_secs;
-        self.set_subscription(&sub)
+        self.put_subscription(&sub)
+    }
+
+    pub fn update_subscription_secret(
+        &self,
+        subscription_id: &Uuid,
+        secret: String,
+    ) -> DBResult<Subscription> {
+        let _lock = self
+            .subscriptions_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let mut sub = self.get_subscription(subscription_id)?;
+        sub.secret = Some(secret);
+        self.put_subscription(&sub)?;
+        Ok(sub)
+    }
+
+    /// Sequence number of the newest event in the event log.
+    pub fn get_last_event_seq(&self) -> DBResult<Option<u64>> {
+        let cf_handle = self.cf_handle(EVENT_LOG_CF)?;
+        match self.db.iterator_cf(&cf_handle, IteratorMode::End).next() {
+            Some(item) => {
+                let (key, _) = item?;
+                Ok(Some(
+                    parse_event_log_key(&key).map_err(|_| RocksDBError::InvalidU64)?,
+                ))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Sequence number of the oldest event still in the event log.
+    pub fn get_first_event_seq(&self) -> DBResult<Option<u64>> {
+        let cf_handle = self.cf_handle(EVENT_LOG_CF)?;
+        match self.db.iterator_cf(&cf_handle, IteratorMode::Start).next() {
+            Some(item) => {
+                let (key, _) = item?;
+                Ok(Some(
+                    parse_event_log_key(&key).map_err(|_| RocksDBError::InvalidU64)?,
+                ))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Sequence number of the oldest event emitted at `height` or above.
+    ///
+    /// Events only change height when they move the tip, so besides the oldest event
+    /// only the heights marked for those are read.
+    pub fn get_first_event_seq_from_height(&self, height: u64) -> DBResult<Option<u64>> {
+        let cf_handle = self.cf_handle(EVENT_LOG_CF)?;
+        if let Some(item) = self.db.iterator_cf(&cf_handle, IteratorMode::Start).next() {
+            let (key, value) = item?;
+            if EventLogEntry::load(value.into_vec()).height >= height {
+                return Ok(Some(
+                    parse_event_log_key(&key).map_err(|_| RocksDBError::InvalidU64)?,
+                ));
+            }
+        }
+
+        let cf_handle = self.cf_handle(EVENT_LOG_HEIGHTS_CF)?;
+        for item in self.db.iterator_cf(&cf_handle, IteratorMode::Start) {
+            let (key, value) = item?;
+            let marked_height = u64::from_le_bytes(
+                value
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| RocksDBError::InvalidU64)?,
+            );
+
+            if marked_height >= height {
+                return Ok(Some(
+                    parse_event_log_key(&key).map_err(|_| RocksDBError::InvalidU64)?,
+                ));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Appends `entry` to the event log. Events that move the tip also mark their
+    /// height, to find where the events of a height start without reading the log.
+    pub fn append_event(&self, seq: u64, entry: &EventLogEntry) -> DBResult<()> {
+        let mut batch = WriteBatch::default();
+        batch.put_cf(
+            &self.cf_handle(EVENT_LOG_CF)?,
+            seq.to_be_bytes(),
+            entry.store_ref(),
+        );
+
+        if matches!(entry.event, Event::NewBlock { .. } | Event::Reorg { .. }) {
+            batch.put_cf(
+                &self.cf_handle(EVENT_LOG_HEIGHTS_CF)?,
+                seq.to_be_bytes(),
+                entry.height.to_le_bytes(),
+            );
+        }
+
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` events from the event log, starting at `from_seq`.
+    pub fn get_events(&self, from_seq: u64, limit: usize) -> DBResult<Vec<(u64, EventLogEntry)>> {
+        let cf_handle = self.cf_handle(EVENT_LOG_CF)?;
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(&from_seq.to_be_bytes(), Direction::Forward),
+        );
+
+        let mut events = Vec::new();
+        for item in iter.take(limit) {
+            let (key, value) = item?;
+            events.push((
+                parse_event_log_key(&key).map_err(|_| RocksDBError::InvalidU64)?,
+                EventLogEntry::load(value.into_vec()),
+            ));
+        }
+
+        Ok(events)
+    }
+
+    /// Deletes every event in the event log older than `seq`.
+    pub fn delete_events_before(&self, seq: u64) -> DBResult<()> {
+        for cf in [EVENT_LOG_CF, EVENT_LOG_HEIGHTS_CF] {
+            let cf_handle = self.cf_handle(cf)?;
+            self.db
+                .delete_range_cf(&cf_handle, 0u64.to_be_bytes(), seq.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn webhook_deliveries_cf(status: DeliveryStatus) -> &'static str {
+        match status {
+            DeliveryStatus::Pending => WEBHOOK_OUTBOX_CF,
+            DeliveryStatus::DeadLetter => WEBHOOK_DEAD_LETTERS_CF,
+        }
+    }
+
+    /// Fails with `NotFound` if the subscription doesn't exist. Meant to be called with
+    /// `subscriptions_lock` held, so that it can't be deleted before the caller writes.
+    fn ensure_subscription(&self, id: &Uuid) -> DBResult<()> {
+        let cf_handle = self.cf_handle(SUBSCRIPTIONS_CF)?;
+        match self.db.get_cf(&cf_handle, id.as_bytes())? {
+            Some(_) => Ok(()),
+            None => Err(RocksDBError::NotFound(format!(
+                "Subscription not found: {}",
+                id
+            ))),
+        }
+    }
+
+    /// Queues a delivery in the outbox of a subscription and returns its sequence
+    /// number. Sequence numbers are never reused, even once the outbox is drained.
+    pub fn queue_webhook_delivery(
+        &self,
+        subscription_id: &Uuid,
+        entry: &WebhookDeliveryEntry,
+    ) -> DBResult<u64> {
+        let _lock = self
+            .subscriptions_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        self.ensure_subscription(subscription_id)?;
+
+        let seqs_cf = self.cf_handle(WEBHOOK_DELIVERY_SEQS_CF)?;
+        let next_seq: Option<u64> = self
+            .get_option_vec_data(&seqs_cf, subscription_id.as_bytes())
+            .mapped()?;
+        // Subscriptions that predate the counter continue after their last delivery.
+        let seq = match next_seq {
+            Some(seq) => seq,
+            None => self
+                .get_last_webhook_delivery_seq(subscription_id)?
+                .map_or(0, |seq| seq + 1),
+        };
+
+        let mut batch = WriteBatch::default();
+        batch.put_cf(
+            &self.cf_handle(WEBHOOK_OUTBOX_CF)?,
+            webhook_delivery_key(subscription_id, seq),
+            entry.store_ref(),
+        );
+        batch.put_cf(
+            &seqs_cf,
+            subscription_id.as_bytes(),
+            (seq + 1).to_le_bytes(),
+        );
+
+        self.db.write_opt(batch, &self.write_opts)?;
+        Ok(seq)
+    }
+
+    /// Highest sequence number used by the outbox or the dead letters of a subscription.
+    fn get_last_webhook_delivery_seq(&self, subscription_id: &Uuid) -> DBResult<Option<u64>> {
+        let mut last_seq = None;
+        for cf in [WEBHOOK_OUTBOX_CF, WEBHOOK_DEAD_LETTERS_CF] {
+            let cf_handle = self.cf_handle(cf)?;
+            let mut iter = self.db.iterator_cf(
+                &cf_handle,
+                IteratorMode::From(
+                    &webhook_delivery_key(subscription_id, u64::MAX),
+                    Direction::Reverse,
+                ),
+            );
+
+            if let Some(item) = iter.next() {
+                let (key, _) = item?;
+                if key.starts_with(subscription_id.as_bytes()) {
+                    let seq =
+                        parse_webhook_delivery_key(&key).map_err(|_| RocksDBError::InvalidU64)?;
+                    last_seq = last_seq.max(Some(seq));
+                }
+            }
+        }
+
+        Ok(last_seq)
+    }
+
+    /// Returns up to `limit` deliveries of a subscription with the given status,
+    /// starting at `from_seq`.
+    pub fn get_webhook_deliveries(
+        &self,
+        subscription_id: &Uuid,
+        status: DeliveryStatus,
+        from_seq: u64,
+        limit: usize,
+    ) -> DBResult<Vec<(u64, WebhookDeliveryEntry)>> {
+        let cf_handle = self.cf_handle(Self::webhook_deliveries_cf(status))?;
+        let iter = self.db.iterator_cf(
+            &cf_handle,
+            IteratorMode::From(
+                &webhook_delivery_key(subscription_id, from_seq),
+                Direction::Forward,
+            ),
+        );
+
+        let mut deliveries = Vec::new();
+        for item in iter.take(limit) {
+            let (key, value) = item?;
+            if !key.starts_with(subscription_id.as_bytes()) {
+                break;
+            }
+
+            let seq = parse_webhook_delivery_key(&key).map_err(|_| RocksDBError::InvalidU64)?;
+            deliveries.push((seq, WebhookDeliveryEntry::load(value.into_vec())));
+        }
+
+        Ok(deliveries)
+    }
+
+    pub fn get_webhook_delivery(
+        &self,
+        subscription_id: &Uuid,
+        status: DeliveryStatus,
+        seq: u64,
+    ) -> DBResult<Option<WebhookDeliveryEntry>> {
+        let cf_handle = self.cf_handle(Self::webhook_deliveries_cf(status))?;
+        self.get_option_vec_data(&cf_handle, webhook_delivery_key(subscription_id, seq))
+            .mapped()
+    }
+
+    /// Stores a delivery in the outbox or the dead letters, depending on its status,
+    /// and removes it from the other one. Fails with `NotFound` once the subscription
+    /// is deleted.
+    pub fn set_webhook_delivery(
+        &self,
+        subscription_id: &Uuid,
+        seq: u64,
+        entry: &WebhookDeliveryEntry,
+    ) -> DBResult<()> {
+        let _lock = self
+            .subscriptions_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        self.ensure_subscription(subscription_id)?;
+
+        let key = webhook_delivery_key(subscription_id, seq);
+        let outbox_cf = self.cf_handle(WEBHOOK_OUTBOX_CF)?;
+        let dead_letters_cf = self.cf_handle(WEBHOOK_DEAD_LETTERS_CF)?;
+
+        let mut batch = WriteBatch::default();
+        match entry.status {
+            DeliveryStatus::Pending => {
+                batch.delete_cf(&dead_letters_cf, &key);
+                batch.put_cf(&outbox_cf, &key, entry.store_ref());
+            }
+            DeliveryStatus::DeadLetter => {
+                batch.delete_cf(&outbox_cf, &key);
+                batch.put_cf(&dead_letters_cf, &key, entry.store_ref());
+            }
+        }
+
+        self.db.write_opt(batch, &self.write_opts)?;
+        Ok(())
+    }
+
+    /// Fails with `NotFound` once the subscription is deleted.
+    pub fn delete_webhook_delivery(&self, subscription_id: &Uuid, seq: u64) -> DBResult<()> {
+        let _lock = self
+            .subscriptions_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        self.ensure_subscription(subscription_id)?;
+
+        let key = webhook_delivery_key(subscription_id, seq);
+        let mut batch = WriteBatch::default();
+        batch.delete_cf(&self.cf_handle(WEBHOOK_OUTBOX_CF)?, &key);
+        batch.delete_cf(&self.cf_handle(WEBHOOK_DEAD_LETTERS_CF)?, &key);
+        self.db.write_opt(batch, &self.write_opts)?;
+        Ok(())
+    }
+
+    pub fn set_api_key(&self, key_hash: &[u8; 32], entry: &ApiKeyEntry) -> DBResult<()> {
+        let cf_handle = self.cf_handle(API_KEYS_CF)?;
+        self.db.put_cf(&cf_handle, key_hash, entry.store_ref())?;
+        Ok(())
+    }
+
+    pub fn get_api_key(&self, key_hash: &[u8; 32]) -> DBResult<Option<ApiKeyEntry>> {
+        let cf_handle = self.cf_handle(API_KEYS_CF)?;
+        self.get_option_vec_data(&cf_handle, key_hash).mapped()
+    }
+
+    pub fn get_api_keys(&self) -> DBResult<Vec<ApiKeyEntry>> {
+        let cf_handle = self.cf_handle(API_KEYS_CF)?;
+        let iter = self.db.iterator_cf(&cf_handle, IteratorMode::Start);
+        let mut keys = Vec::new();
+        for item in iter {
+            let (_key, value) = item?;
+            keys.push(ApiKeyEntry::load(value.to_vec()));
+        }
+
+        Ok(keys)
+    }
+
+    /// Deletes the API key with the given id. Keys are stored by hash, so this scans
+    /// every key, of which there are only a few.
+    pub fn delete_api_key(&self, id: &Uuid) -> DBResult<()> {
+        let cf_handle = self.cf_handle(API_KEYS_CF)?;
+        let iter = self.db.iterator_cf(&cf_handle, IteratorMode::Start);
+        for item in iter {
+            let (key, value) = item?;
+            if ApiKeyEntry::load(value.to_vec()).id == *id {
+                self.db.delete_cf(&cf_handle, key)?;
+                return Ok(());
+            }
+        }
+
+        Err(RocksDBError::NotFound(format!("API key not found: {}", id)))
     }
 
     pub fn flush(&self) -> DBResult<()> {
@@ -1971,3 +3607,116 @@ impl RocksDB {
         Ok(())
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use {super::*, std::ops::Deref, tempfile::TempDir};
+
+    /// A database in a temporary directory that is removed on drop.
+    pub(crate) struct TempDb {
+        db: Arc<RocksDB>,
+        _dir: TempDir,
+    }
+
+    impl TempDb {
+        pub(crate) fn open() -> Self {
+            let dir = tempfile::tempdir().unwrap();
+            let db = RocksDB::open_with_direct_io(dir.path().to_str().unwrap(), false).unwrap();
+            Self {
+                db: Arc::new(db),
+                _dir: dir,
+            }
+        }
+
+        /// The database, for the components that share it.
+        pub(crate) fn shared(&self) -> Arc<RocksDB> {
+            self.db.clone()
+        }
+    }
+
+    impl Deref for TempDb {
+        type Target = RocksDB;
+
+        fn deref(&self) -> &RocksDB {
+            &self.db
+        }
+    }
+
+    #[test]
+    fn finds_the_first_event_of_a_height() {
+        let db = TempDb::open();
+        let new_block = |block_height| Event::NewBlock {
+            block_hash: BlockHash::all_zeros(),
+            block_height,
+        };
+
+        let events = [
+            (5, Event::TransactionsAdded { txids: vec![] }),
+            (6, new_block(6)),
+            (6, Event::TransactionsAdded { txids: vec![] }),
+            (7, new_block(7)),
+            (
+                5,
+                Event::Reorg {
+                    height: 7,
+                    depth: 1,
+                },
+            ),
+            (6, new_block(6)),
+        ];
+        for (seq, (height, event)) in events.into_iter().enumerate() {
+            db.append_event(seq as u64, &EventLogEntry { height, event })
+                .unwrap();
+        }
+
+        let first_from = |height| db.get_first_event_seq_from_height(height).unwrap();
+        assert_eq!(first_from(5), Some(0));
+        assert_eq!(first_from(6), Some(1));
+        assert_eq!(first_from(7), Some(3));
+        assert_eq!(first_from(8), None);
+
+        db.delete_events_before(2).unwrap();
+        assert_eq!(first_from(6), Some(2));
+        assert_eq!(first_from(7), Some(3));
+
+        // The reorg went back below the remaining events of height 7.
+        db.delete_events_before(4).unwrap();
+        assert_eq!(first_from(6), Some(5));
+        assert_eq!(first_from(7), None);
+    }
+
+    #[test]
+    fn webhook_delivery_seqs_outlive_the_outbox() {
+        let db = TempDb::open();
+        let subscription = Subscription {
+            id: Uuid::new_v4(),
+            endpoint: "http://127.0.0.1:1".to_string(),
+            event_types: vec![],
+            last_success_epoch_secs: 0,
+            filter: None,
+            secret: None,
+            delivery_policy: None,
+        };
+        let id = subscription.id;
+        let entry = WebhookDeliveryEntry::new(Event::TransactionsAdded { txids: vec![] }, 0);
+
+        db.set_subscription(&subscription).unwrap();
+        assert_eq!(db.queue_webhook_delivery(&id, &entry).unwrap(), 0);
+        db.delete_webhook_delivery(&id, 0).unwrap();
+        assert_eq!(db.queue_webhook_delivery(&id, &entry).unwrap(), 1);
+
+        db.delete_subscription(&id).unwrap();
+        assert!(matches!(
+            db.queue_webhook_delivery(&id, &entry),
+            Err(RocksDBError::NotFound(_))
+        ));
+        assert!(matches!(
+            db.set_webhook_delivery(&id, 1, &entry),
+            Err(RocksDBError::NotFound(_))
+        ));
+        assert!(db
+            .get_webhook_deliveries(&id, DeliveryStatus::Pending, 0, usize::MAX)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -2,6 +2,7 @@ use bitcoin::ScriptBuf;
 use std::convert::TryInto;
 
 use titan_types_core::{RuneId, SerializedOutPoint};
+use uuid::Uuid;
 
 /// Creates an `OutPoint` from a 36-byte slice.
 /// Returns an error if the slice is not exactly 36 bytes long.
@@ -74,3 +75,118 @@ pub fn rune_transaction_key_from_bytes(rune_id_bytes: &[u8], index: u64) -> Vec<
     v.extend_from_slice(&index.to_le_bytes());
     v
 }
+
+/// Prefix of every address history key, and key of the address' last history index.
+/// The script length goes first so that a script is never a prefix of another one.
+pub fn address_transaction_prefix(script_pubkey: &[u8]) -> Vec<u8> {
+    let mut v = Vec::with_capacity(4 + script_pubkey.len());
+    v.extend_from_slice(&(script_pubkey.len() as u32).to_be_bytes());
+    v.extend_from_slice(script_pubkey);
+    v
+}
+
+/// Builds "<len><script_pubkey><index>". The index is big-endian so that keys of the
+/// same address iterate in index order.
+pub fn address_transaction_key(script_pubkey: &[u8], index: u64) -> Vec<u8> {
+    let mut v = address_transaction_prefix(script_pubkey);
+    v.extend_from_slice(&index.to_be_bytes());
+    v
+}
+
+/// Builds "<len><script_pubkey><rune_id>". All the balances of an address share the
+/// `address_transaction_prefix` of its script.
+pub fn address_rune_balance_key(script_pubkey: &[u8], rune_id: &RuneId) -> Vec<u8> {
+    let mut v = address_transaction_prefix(script_pubkey);
+    v.extend_from_slice(&rune_id.to_bytes());
+    v
+}
+
+/// Parses a `RuneId` from the 12 bytes produced by `RuneId::to_bytes`.
+pub fn rune_id_from_bytes(bytes: &[u8]) -> Result<RuneId, &'static str> {
+    if bytes.len() != 12 {
+        return Err("Invalid length for RuneId, expected 12 bytes");
+    }
+
+    let block = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+    let tx = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+
+    Ok(RuneId::new(block, tx))
+}
+
+/// Builds "<rune_id><height>". The height is big-endian so that the history of a rune
+/// iterates by height.
+pub fn rune_history_key(rune_id: &RuneId, height: u64) -> Vec<u8> {
+    let mut v = Vec::with_capacity(12 + 8);
+    v.extend_from_slice(&rune_id.to_bytes());
+    v.extend_from_slice(&height.to_be_bytes());
+    v
+}
+
+/// Returns the height of a `rune_history_key`.
+pub fn parse_rune_history_key(key: &[u8]) -> Result<u64, &'static str> {
+    if key.len() != 20 {
+        return Err("Invalid length for rune history key");
+    }
+
+    Ok(u64::from_be_bytes(key[12..20].try_into().unwrap()))
+}
+
+/// Returns the sequence number of an event log key. Keys are big-endian so that the
+/// log iterates in emission order.
+pub fn parse_event_log_key(key: &[u8]) -> Result<u64, &'static str> {
+    let bytes = key
+        .try_into()
+        .map_err(|_| "Invalid length for event log key")?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Builds "<subscription_id><seq>". The sequence number is big-endian so that the
+/// deliveries of a subscription iterate in order.
+pub fn webhook_delivery_key(subscription_id: &Uuid, seq: u64) -> Vec<u8> {
+    let mut v = Vec::with_capacity(16 + 8);
+    v.extend_from_slice(subscription_id.as_bytes());
+    v.extend_from_slice(&seq.to_be_bytes());
+    v
+}
+
+/// Returns the sequence number of a webhook delivery key.
+pub fn parse_webhook_delivery_key(key: &[u8]) -> Result<u64, &'static str> {
+    if key.len() != 24 {
+        return Err("Invalid length for webhook delivery key");
+    }
+
+    Ok(u64::from_be_bytes(key[16..].try_into().unwrap()))
+}
+
+/// Builds "<rune_id><amount><script_pubkey>". The amount is big-endian so that the
+/// holders of a rune iterate by balance. The bare rune id holds the holder count.
+pub fn rune_holder_key(rune_id: &RuneId, amount: u128, script_pubkey: &[u8]) -> Vec<u8> {
+    let mut v = Vec::with_capacity(12 + 16 + script_pubkey.len());
+    v.extend_from_slice(&rune_id.to_bytes());
+    v.extend_from_slice(&amount.to_be_bytes());
+    v.extend_from_slice(script_pubkey);
+    v
+}
+
+/// Returns the amount and script pubkey of a `rune_holder_key`.
+pub fn parse_rune_holder_key(key: &[u8]) -> Result<(u128, &[u8]), &'static str> {
+    if key.len() < 28 {
+        return Err("Invalid length for rune holder key");
+    }
+
+    let amount = u128::from_be_bytes(key[12..28].try_into().unwrap());
+    Ok((amount, &key[28..]))
+}
+
+/// Smallest key that sorts after every key starting with `prefix`, if any.
+pub fn rune_holders_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper_bound = prefix.to_vec();
+    while let Some(last) = upper_bound.pop() {
+        if last < u8::MAX {
+            upper_bound.push(last + 1);
+            return Some(upper_bound);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,521 @@
+use {
+    rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet},
+    std::{cmp::Ordering, collections::BinaryHeap},
+    titan_types_api::{FeeHistogramBucket, MempoolBlock, RecommendedFees},
+    titan_types_core::{MempoolEntry, SerializedTxid},
+};
+
+const MAX_BLOCK_VSIZE: u64 = 1_000_000;
+const MAX_PROJECTED_BLOCKS: usize = 8;
+// Like Bitcoin Core's block assembler: once a block is this close to full, or
+// this many packages failed to fit, the block is closed.
+const BLOCK_FULL_THRESHOLD: u64 = 4_000;
+const MAX_CONSECUTIVE_FAILURES: usize = 1_000;
+
+const MINIMUM_FEE_RATE: f64 = 1.0;
+// Bitcoin Core's default `-incrementalrelayfee`, the least a replacement has to
+// pay for its own relay on top of the fees of the transactions it evicts.
+const INCREMENTAL_RELAY_FEE_RATE: f64 = 1.0;
+
+/// CPFP child assumed when none is given: one P2TR input and one P2TR output.
+pub const DEFAULT_CHILD_VSIZE: u64 = 111;
+
+const HISTOGRAM_FEE_RATES: [f64; 38] = [
+    1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 8.0, 10.0, 12.0, 15.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0,
+    90.0, 100.0, 125.0, 150.0, 175.0, 200.0, 250.0, 300.0, 350.0, 400.0, 500.0, 600.0, 700.0,
+    800.0, 900.0, 1000.0, 1200.0, 1400.0, 1600.0, 1800.0, 2000.0,
+];
+
+/// Projected blocks, fee histogram and recommended fees computed from a
+/// snapshot of the mempool.
+#[derive(Debug, Clone)]
+pub struct MempoolFees {
+    pub blocks: Vec<MempoolBlock>,
+    pub histogram: Vec<FeeHistogramBucket>,
+    pub recommended: RecommendedFees,
+    /// Rate of the package each transaction was selected in.
+    pub effective_fee_rates: HashMap<SerializedTxid, f64>,
+}
+
+impl MempoolFees {
+    pub fn new(entries: &HashMap<SerializedTxid, MempoolEntry>) -> Self {
+        let (blocks, effective_fee_rates) = project_blocks(entries);
+
+        let recommended = recommended_fees(&blocks);
+        let histogram = histogram(&blocks);
+
+        Self {
+            blocks: blocks.iter().map(ProjectedBlock::summary).collect(),
+            histogram,
+            recommended,
+            effective_fee_rates,
+        }
+    }
+}
+
+/// Fee a child of `child_vsize` has to pay for the transaction, its unconfirmed
+/// ancestors and itself to be mined at `fee_rate`.
+pub fn cpfp_child_fee(
+    ancestor_fees: u64,
+    ancestor_vsize: u64,
+    child_vsize: u64,
+    fee_rate: f64,
+) -> u64 {
+    let package_fee = (fee_rate * (ancestor_vsize + child_vsize) as f64).ceil() as u64;
+    let relay_fee = (MINIMUM_FEE_RATE * child_vsize as f64).ceil() as u64;
+
+    package_fee.saturating_sub(ancestor_fees).max(relay_fee)
+}
+
+/// Least fee a replacement of `vsize` can pay under BIP125 rules 3 and 4: the
+/// fees of every transaction it evicts plus its own relay fee.
+pub fn rbf_min_fee(evicted_fees: u64, vsize: u64) -> u64 {
+    evicted_fees + (INCREMENTAL_RELAY_FEE_RATE * vsize as f64).ceil() as u64
+}
+
+#[derive(Default)]
+struct ProjectedBlock {
+    vsize: u64,
+    fees: u64,
+    // (effective fee rate, vsize) of every transaction in the block.
+    txs: Vec<(f64, u64)>,
+}
+
+impl ProjectedBlock {
+    fn add(&mut self, rate: f64, fee: u64, vsize: u64) {
+        self.vsize += vsize;
+        self.fees += fee;
+        self.txs.push((rate, vsize));
+    }
+
+    fn summary(&self) -> MempoolBlock {
+        let mut rates = self.txs.clone();
+        rates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let fee_range = if rates.is_empty() {
+            Vec::new()
+        } else {
+            [0.0, 0.1, 0.25, 0.5, 0.75, 0.9, 1.0]
+                .iter()
+                .map(|p| weighted_percentile(&rates, *p))
+                .collect()
+        };
+
+        MempoolBlock {
+            block_vsize: self.vsize,
+            n_tx: self.txs.len() as u64,
+            total_fees: self.fees,
+            median_fee: if rates.is_empty() {
+                0.0
+            } else {
+                weighted_percentile(&rates, 0.5)
+            },
+            fee_range,
+        }
+    }
+}
+
+/// Fee rate paid by the vbyte at `p` of the block, with `rates` sorted ascending.
+fn weighted_percentile(rates: &[(f64, u64)], p: f64) -> f64 {
+    let total: u64 = rates.iter().map(|(_, vsize)| vsize).sum();
+    let target = (total as f64 * p).ceil() as u64;
+
+    let mut seen = 0;
+    for (rate, vsize) in rates {
+        seen += vsize;
+        if seen >= target {
+            return *rate;
+        }
+    }
+
+    rates.last().map(|(rate, _)| *rate).unwrap_or_default()
+}
+
+struct Package {
+    fee: u64,
+    vsize: u64,
+}
+
+struct Candidate {
+    tx: usize,
+    fee: u64,
+    vsize: u64,
+}
+
+impl Candidate {
+    fn rate(&self) -> f64 {
+        self.fee as f64 / self.vsize.max(1) as f64
+    }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.fee as u128 * other.vsize as u128)
+            .cmp(&(other.fee as u128 * self.vsize as u128))
+            .then_with(|| other.vsize.cmp(&self.vsize))
+    }
+}
+
+/// Fills blocks by repeatedly selecting the package with the highest ancestor
+/// fee rate, the same ordering miners use to build block templates. Like Bitcoin
+/// Core's block assembler, the package of every transaction is computed once and
+/// updated as its ancestors are selected.
+fn project_blocks(
+    entries: &HashMap<SerializedTxid, MempoolEntry>,
+) -> (Vec<ProjectedBlock>, HashMap<SerializedTxid, f64>) {
+    let (txids, entries): (Vec<&SerializedTxid>, Vec<&MempoolEntry>) = entries.iter().unzip();
+    let (ancestors, descendants) = relatives(&txids, &entries);
+
+    let mut packages: Vec<Package> = entries
+        .iter()
+        .zip(&ancestors)
+        .map(|(entry, ancestors)| Package {
+            fee: entry.fees.base + ancestors.iter().map(|a| entries[*a].fees.base).sum::<u64>(),
+            vsize: entry.vsize + ancestors.iter().map(|a| entries[*a].vsize).sum::<u64>(),
+        })
+        .collect();
+
+    let mut heap: BinaryHeap<Candidate> = packages
+        .iter()
+        .enumerate()
+        .map(|(tx, package)| Candidate {
+            tx,
+            fee: package.fee,
+            vsize: package.vsize,
+        })
+        .collect();
+
+    let mut selected = vec![false; entries.len()];
+    let mut rates: HashMap<SerializedTxid, f64> = HashMap::default();
+    let mut blocks = Vec::new();
+    let mut block = ProjectedBlock::default();
+    let mut deferred = Vec::new();
+
+    loop {
+        let Some(candidate) = heap.pop() else {
+            if deferred.is_empty() {
+                break;
+            }
+
+            blocks.push(std::mem::take(&mut block));
+            heap.extend(deferred.drain(..));
+            continue;
+        };
+
+        if selected[candidate.tx] {
+            continue;
+        }
+
+        let package = &packages[candidate.tx];
+        if package.fee != candidate.fee || package.vsize != candidate.vsize {
+            // Stale entry, the package shrank since it was pushed.
+            continue;
+        }
+
+        let is_last_block = blocks.len() + 1 >= MAX_PROJECTED_BLOCKS;
+        if !is_last_block && !block.txs.is_empty() && block.vsize + package.vsize > MAX_BLOCK_VSIZE
+        {
+            deferred.push(candidate);
+
+            if deferred.len() >= MAX_CONSECUTIVE_FAILURES
+                || block.vsize + BLOCK_FULL_THRESHOLD > MAX_BLOCK_VSIZE
+            {
+                blocks.push(std::mem::take(&mut block));
+                heap.extend(deferred.drain(..));
+            }
+
+            continue;
+        }
+
+        // Every transaction of the package pays the package's fee rate.
+        let rate = candidate.rate();
+        let members: Vec<usize> = ancestors[candidate.tx]
+            .iter()
+            .copied()
+            .filter(|ancestor| !selected[*ancestor])
+            .chain([candidate.tx])
+            .collect();
+
+        for member in &members {
+            let entry = entries[*member];
+            block.add(rate, entry.fees.base, entry.vsize);
+            selected[*member] = true;
+            rates.insert(*txids[*member], rate);
+        }
+
+        // Selected transactions leave the packages of their descendants.
+        let mut updated = HashSet::default();
+        for member in &members {
+            let entry = entries[*member];
+            for descendant in &descendants[*member] {
+                if selected[*descendant] {
+                    continue;
+                }
+
+                let package = &mut packages[*descendant];
+                package.fee -= entry.fees.base;
+                package.vsize -= entry.vsize;
+                updated.insert(*descendant);
+            }
+        }
+
+        for tx in updated {
+            let package = &packages[tx];
+            heap.push(Candidate {
+                tx,
+                fee: package.fee,
+                vsize: package.vsize,
+            });
+        }
+    }
+
+    if !block.txs.is_empty() {
+        blocks.push(block);
+    }
+
+    (blocks, rates)
+}
+
+/// In-mempool ancestors and descendants of every transaction, by position in
+/// `txids`. Ancestors are built parents first, so long chains don't recurse.
+fn relatives(
+    txids: &[&SerializedTxid],
+    entries: &[&MempoolEntry],
+) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let positions: HashMap<SerializedTxid, usize> = txids
+        .iter()
+        .enumerate()
+        .map(|(position, txid)| (**txid, position))
+        .collect();
+
+    let parents: Vec<Vec<usize>> = entries
+        .iter()
+        .map(|entry| {
+            let mut parents: Vec<usize> = entry
+                .depends
+                .iter()
+                .filter_map(|parent| positions.get(parent).copied())
+                .collect();
+            parents.sort_unstable();
+            parents.dedup();
+            parents
+        })
+        .collect();
+
+    let mut children = vec![Vec::new(); entries.len()];
+    for (tx, parents) in parents.iter().enumerate() {
+        for parent in parents {
+            children[*parent].push(tx);
+        }
+    }
+
+    // Transactions ordered after all of their parents.
+    let mut unordered_parents: Vec<usize> = parents.iter().map(Vec::len).collect();
+    let mut order: Vec<usize> = (0..entries.len())
+        .filter(|tx| unordered_parents[*tx] == 0)
+        .collect();
+    let mut next = 0;
+    while let Some(tx) = order.get(next).copied() {
+        next += 1;
+        for child in &children[tx] {
+            unordered_parents[*child] -= 1;
+            if unordered_parents[*child] == 0 {
+                order.push(*child);
+            }
+        }
+    }
+
+    let mut ancestors: Vec<Vec<usize>> = vec![Vec::new(); entries.len()];
+    for tx in order {
+        let mut tx_ancestors = parents[tx].clone();
+        for parent in &parents[tx] {
+            tx_ancestors.extend_from_slice(&ancestors[*parent]);
+        }
+        tx_ancestors.sort_unstable();
+        tx_ancestors.dedup();
+        ancestors[tx] = tx_ancestors;
+    }
+
+    let mut descendants = vec![Vec::new(); entries.len()];
+    for (tx, tx_ancestors) in ancestors.iter().enumerate() {
+        for ancestor in tx_ancestors {
+            descendants[*ancestor].push(tx);
+        }
+    }
+
+    (ancestors, descendants)
+}
+
+/// The rate needed to confirm within `blocks` blocks. Anything above the
+/// minimum confirms if the target block is less than half full.
+fn target_fee_rate(blocks: &[ProjectedBlock], target: usize) -> f64 {
+    blocks
+        .get(target - 1)
+        .filter(|block| block.vsize * 2 > MAX_BLOCK_VSIZE)
+        .map(|block| block.summary().median_fee)
+        .unwrap_or(MINIMUM_FEE_RATE)
+        .max(MINIMUM_FEE_RATE)
+}
+
+fn recommended_fees(blocks: &[ProjectedBlock]) -> RecommendedFees {
+    let fastest_fee = target_fee_rate(blocks, 1);
+    let half_hour_fee = target_fee_rate(blocks, 3).min(fastest_fee);
+    let hour_fee = target_fee_rate(blocks, 6).min(half_hour_fee);
+    let economy_fee = (MINIMUM_FEE_RATE * 2.0).min(hour_fee);
+
+    RecommendedFees {
+        fastest_fee,
+        half_hour_fee,
+        hour_fee,
+        economy_fee,
+        minimum_fee: MINIMUM_FEE_RATE,
+    }
+}
+
+/// Buckets every projected transaction by its effective fee rate, highest
+/// rates first. Rates below the first bucket are counted in it.
+fn histogram(blocks: &[ProjectedBlock]) -> Vec<FeeHistogramBucket> {
+    let mut buckets: Vec<FeeHistogramBucket> = HISTOGRAM_FEE_RATES
+        .iter()
+        .map(|fee_rate| FeeHistogramBucket {
+            fee_rate: *fee_rate,
+            count: 0,
+            vsize: 0,
+        })
+        .collect();
+
+    for (rate, vsize) in blocks.iter().flat_map(|block| &block.txs) {
+        let position = HISTOGRAM_FEE_RATES
+            .iter()
+            .rposition(|fee_rate| rate >= fee_rate)
+            .unwrap_or(0);
+
+        buckets[position].count += 1;
+        buckets[position].vsize += vsize;
+    }
+
+    buckets.retain(|bucket| bucket.count > 0);
+    buckets.reverse();
+    buckets
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use {super::*, titan_types_core::MempoolEntryFee};
+
+    pub(crate) fn entry(vsize: u64, fee: u64, depends: Vec<SerializedTxid>) -> MempoolEntry {
+        MempoolEntry {
+            vsize,
+            weight: Some(vsize * 4),
+            descendant_count: 1,
+            descendant_size: vsize,
+            ancestor_count: 1,
+            ancestor_size: vsize,
+            fees: MempoolEntryFee {
+                base: fee,
+                descendant: fee,
+                ancestor: fee,
+            },
+            depends,
+            spent_by: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn selects_child_pays_for_parent_packages_first() {
+        let parent = SerializedTxid([1; 32]);
+        let child = SerializedTxid([2; 32]);
+        let other = SerializedTxid([3; 32]);
+
+        let mut entries = HashMap::default();
+        // 400_000 vB at 1 sat/vB, bumped by a child paying 20 sat/vB overall.
+        entries.insert(parent, entry(400_000, 400_000, vec![]));
+        entries.insert(child, entry(100_000, 9_600_000, vec![parent]));
+        // 700_000 vB at 10 sat/vB.
+        entries.insert(other, entry(700_000, 7_000_000, vec![]));
+
+        let fees = MempoolFees::new(&entries);
+
+        assert_eq!(fees.blocks.len(), 2);
+        assert_eq!(fees.blocks[0].n_tx, 2);
+        assert_eq!(fees.blocks[0].block_vsize, 500_000);
+        assert_eq!(fees.blocks[0].median_fee, 20.0);
+        assert_eq!(fees.effective_fee_rates[&parent], 20.0);
+        assert_eq!(fees.blocks[1].n_tx, 1);
+        assert_eq!(fees.blocks[1].median_fee, 10.0);
+
+        // The first block is half full, so anything confirms next block.
+        assert_eq!(fees.recommended.fastest_fee, MINIMUM_FEE_RATE);
+        assert_eq!(fees.histogram[0].fee_rate, 20.0);
+        assert_eq!(fees.histogram[0].vsize, 500_000);
+    }
+
+    #[test]
+    fn counts_shared_ancestors_once() {
+        let grandparent = SerializedTxid([1; 32]);
+        let left = SerializedTxid([2; 32]);
+        let right = SerializedTxid([3; 32]);
+        let child = SerializedTxid([4; 32]);
+
+        let mut entries = HashMap::default();
+        entries.insert(grandparent, entry(100, 100, vec![]));
+        entries.insert(left, entry(100, 100, vec![grandparent]));
+        entries.insert(right, entry(100, 100, vec![grandparent]));
+        entries.insert(child, entry(100, 3_700, vec![left, right]));
+
+        let fees = MempoolFees::new(&entries);
+
+        assert_eq!(fees.blocks[0].n_tx, 4);
+        assert_eq!(fees.blocks[0].block_vsize, 400);
+        assert_eq!(fees.effective_fee_rates[&grandparent], 10.0);
+        assert_eq!(fees.effective_fee_rates[&child], 10.0);
+    }
+
+    #[test]
+    fn projects_long_chains() {
+        let txid = |n: u32| {
+            let mut txid = [0; 32];
+            txid[..4].copy_from_slice(&n.to_le_bytes());
+            SerializedTxid(txid)
+        };
+
+        // Each transaction spends the previous one, and the last one pays for all.
+        let mut entries = HashMap::default();
+        entries.insert(txid(0), entry(100, 100, vec![]));
+        for n in 1..2_000 {
+            let fee = if n == 1_999 { 1_800_100 } else { 100 };
+            entries.insert(txid(n), entry(100, fee, vec![txid(n - 1)]));
+        }
+
+        let fees = MempoolFees::new(&entries);
+
+        assert_eq!(fees.blocks.len(), 1);
+        assert_eq!(fees.blocks[0].n_tx, 2_000);
+        assert_eq!(fees.effective_fee_rates[&txid(0)], 10.0);
+    }
+
+    #[test]
+    fn computes_bump_fees() {
+        // 200 vB paying 200 sats, bumped to 10 sat/vB by a 100 vB child.
+        assert_eq!(cpfp_child_fee(200, 200, 100, 10.0), 2_800);
+        // Already above the target, the child only pays for its own relay.
+        assert_eq!(cpfp_child_fee(5_000, 200, 100, 10.0), 100);
+
+        // Evicting 200 + 300 sats of fees with a 200 vB replacement.
+        assert_eq!(rbf_min_fee(500, 200), 700);
+    }
+}
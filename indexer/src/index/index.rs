@@ -1,6 +1,7 @@
 use {
     super::{
-        metrics::Metrics,
+        fees::{self, MempoolFees},
+        metrics::{Gauge, Metrics},
         settings::Settings,
         store::{Store, StoreError},
         updater::Updater,
@@ -9,27 +10,53 @@ use {
     crate::{
         bitcoin_rpc::{RpcClientError, RpcClientPool},
         index::updater::{ReorgError, UpdaterError},
-        models::{block_id_to_transaction_status, Inscription, RuneEntry},
+        models::{block_id_to_transaction_status, cenotaph_flaw, Inscription, RuneEntry},
     },
-    bitcoin::{Address, BlockHash, Transaction as BitcoinTransaction},
-    rustc_hash::FxHashMap as HashMap,
+    bitcoin::{block::Header, Address, BlockHash, Transaction as BitcoinTransaction},
+    bitcoincore_rpc::RpcApi,
+    ordinals::{Artifact, Runestone},
+    rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet},
     std::{
+        cmp::Reverse,
+        collections::BTreeMap,
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc,
+            Arc, RwLock,
         },
         thread::{self},
-        time::Duration,
+        time::{Duration, Instant},
+    },
+    titan_types_api::{
+        AddressData, AddressRuneBalance, AddressTxOut, CpfpFee, DecodedCenotaph, DecodedEdict,
+        DecodedEtching, DecodedInput, DecodedRunestone, DecodedTerms, DecodedTransaction,
+        HistoryBucket, MempoolPackage, MempoolPackageQuery, Pagination, PaginationResponse, RbfFee,
+        RuneArtifact, RuneFilter, RuneHistoryEntry, RuneHistoryQuery, RuneHolder, RuneSort,
+        TxOutspend,
     },
-    titan_types_api::{AddressData, AddressTxOut, Pagination, PaginationResponse},
     titan_types_core::{
-        Block, Event, InscriptionId, MempoolEntry, Rune, RuneAmount, RuneId, SerializedOutPoint,
-        SerializedTxid, Transaction, TransactionStatus, TxOut,
+        ArtifactKind, Block, Event, InscriptionId, MempoolEntry, Rune, RuneAmount, RuneId,
+        SerializedOutPoint, SerializedTxid, SpacedRune, SpentStatus, Transaction,
+        TransactionStatus, TxOut,
     },
     tokio::{runtime::Runtime, sync::mpsc::Sender},
     tracing::{error, info, warn},
 };
 
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Runes looked up at a time while listing the runes that match a filter.
+const RUNES_SCAN_CHUNK: u64 = 500;
+
+/// Least time between two projections of the mempool fees caused by mempool changes
+/// alone. New blocks are projected right away.
+const MEMPOOL_FEES_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Confirmation targets of the node's fee estimates, those of Esplora's `/fee-estimates`.
+const FEE_ESTIMATE_TARGETS: &[u16] = &[
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 144,
+    504, 1008,
+];
+
 #[derive(Debug, thiserror::Error)]
 pub enum IndexError {
     #[error("store error: {0}")]
@@ -44,6 +71,10 @@ pub enum IndexError {
     RpcApiError(#[from] bitcoincore_rpc::Error),
     #[error("updater error: {0}")]
     UpdaterError(#[from] UpdaterError),
+    #[error("metrics error: {0}")]
+    MetricsError(#[from] prometheus::Error),
+    #[error("lock poisoned")]
+    LockPoisoned,
 }
 
 type Result<T> = std::result::Result<T, IndexError>;
@@ -56,6 +87,18 @@ pub struct Index {
     shutdown_flag: Arc<AtomicBool>,
 
     zmq_manager: Arc<ZmqManager>,
+
+    mempool_fees: RwLock<Option<MempoolFees>>,
+    /// Ids of every rune by sort order, with the block count they were ranked at.
+    rune_rankings: RwLock<HashMap<RuneSort, (u64, Arc<Vec<RuneId>>)>>,
+    /// Node fee estimates by target, with the block count they were made at.
+    fee_estimates: RwLock<Option<(u64, Arc<BTreeMap<u16, f64>>)>>,
+
+    // monitoring
+    metrics: Metrics,
+    bitcoin_rpc_pool: RpcClientPool,
+    rpc_pool_active_clients: Gauge,
+    rpc_pool_idle_clients: Gauge,
 }
 
 impl Index {
@@ -63,10 +106,10 @@ impl Index {
         db: Arc<dyn Store + Send + Sync>,
         bitcoin_rpc_pool: RpcClientPool,
         settings: Settings,
+        metrics: Metrics,
         sender: Option<Sender<Event>>,
     ) -> Self {
         let shutdown_flag = Arc::new(AtomicBool::new(false));
-        let metrics = Metrics::new();
         metrics.start(shutdown_flag.clone());
 
         let zmq_manager = ZmqManager::new(settings.zmq_endpoint.clone());
@@ -76,7 +119,7 @@ impl Index {
             settings: settings.clone(),
             updater: Arc::new(Updater::new(
                 db.clone(),
-                bitcoin_rpc_pool,
+                bitcoin_rpc_pool.clone(),
                 settings.clone(),
                 &metrics,
                 shutdown_flag.clone(),
@@ -84,6 +127,19 @@ impl Index {
             )),
             shutdown_flag,
             zmq_manager: Arc::new(zmq_manager),
+            mempool_fees: RwLock::new(None),
+            rune_rankings: RwLock::new(HashMap::default()),
+            fee_estimates: RwLock::new(None),
+            rpc_pool_active_clients: metrics.gauge(prometheus::Opts::new(
+                "bitcoin_rpc_pool_active_clients",
+                "Bitcoin RPC clients currently checked out of the pool",
+            )),
+            rpc_pool_idle_clients: metrics.gauge(prometheus::Opts::new(
+                "bitcoin_rpc_pool_idle_clients",
+                "Bitcoin RPC clients idle in the pool",
+            )),
+            bitcoin_rpc_pool,
+            metrics,
         }
     }
 
@@ -147,6 +203,12 @@ impl Index {
     }
 
     pub fn index(&self) {
+        // Block count the mempool fees were last computed at. Confirmed transactions
+        // leave the mempool while indexing the block, not in `index_mempool`.
+        let mut fees_block_count = None;
+        let mut fees_refreshed_at: Option<Instant> = None;
+        let mut fees_stale = false;
+
         loop {
             if self.shutdown_flag.load(Ordering::SeqCst) {
                 info!("Indexer received shutdown signal, stopping...");
@@ -188,7 +250,23 @@ impl Index {
             }
 
             match self.updater.index_mempool() {
-                Ok(_) => (),
+                Ok(mempool_changed) => {
+                    fees_stale |= mempool_changed;
+
+                    let block_count = self.db.get_block_count().ok();
+                    let refresh_due = fees_refreshed_at
+                        .map_or(true, |at| at.elapsed() >= MEMPOOL_FEES_REFRESH_INTERVAL);
+                    if block_count != fees_block_count || (fees_stale && refresh_due) {
+                        match self.refresh_mempool_fees() {
+                            Ok(_) => {
+                                fees_block_count = block_count;
+                                fees_refreshed_at = Some(Instant::now());
+                                fees_stale = false;
+                            }
+                            Err(e) => warn!("Failed to refresh mempool fees: {}", e),
+                        }
+                    }
+                }
                 Err(UpdaterError::BitcoinRpc(e)) => {
                     warn!(
                         "We're getting network connection issues, retrying... {}",
@@ -229,6 +307,16 @@ impl Index {
             .await;
     }
 
+    pub fn encode_metrics(&self) -> Result<String> {
+        // Pool usage is sampled on scrape rather than on every checkout.
+        self.rpc_pool_active_clients
+            .set(self.bitcoin_rpc_pool.in_use() as i64);
+        self.rpc_pool_idle_clients
+            .set(self.bitcoin_rpc_pool.idle() as i64);
+
+        Ok(self.metrics.encode()?)
+    }
+
     pub fn get_block_count(&self) -> Result<u64> {
         Ok(self.db.get_block_count()?)
     }
@@ -245,6 +333,10 @@ impl Index {
         Ok(self.db.get_block_by_hash(hash)?)
     }
 
+    pub fn get_block_header(&self, hash: &BlockHash) -> Result<Header> {
+        Ok(self.db.get_block_header(hash)?)
+    }
+
     pub fn get_mempool_txids(&self) -> Result<Vec<SerializedTxid>> {
         Ok(self.db.get_mempool_txids()?.keys().cloned().collect())
     }
@@ -264,6 +356,67 @@ impl Index {
         Ok(self.db.get_mempool_txids()?)
     }
 
+    /// Recomputes the projected mempool blocks and fee estimates.
+    pub fn refresh_mempool_fees(&self) -> Result<MempoolFees> {
+        let fees = MempoolFees::new(&self.db.get_mempool_txids()?);
+
+        *self
+            .mempool_fees
+            .write()
+            .map_err(|_| IndexError::LockPoisoned)? = Some(fees.clone());
+
+        Ok(fees)
+    }
+
+    pub fn get_mempool_fees(&self) -> Result<MempoolFees> {
+        let fees = self
+            .mempool_fees
+            .read()
+            .map_err(|_| IndexError::LockPoisoned)?
+            .clone();
+
+        match fees {
+            Some(fees) => Ok(fees),
+            None => self.refresh_mempool_fees(),
+        }
+    }
+
+    /// Fee rates, in sat/vB, to confirm within each of `FEE_ESTIMATE_TARGETS` blocks, as
+    /// estimated by the node, asked again once a block is indexed. Targets it has no
+    /// estimate for are left out.
+    pub fn get_fee_estimates(&self) -> Result<Arc<BTreeMap<u16, f64>>> {
+        let block_count = self.db.get_block_count()?;
+
+        if let Some((estimated_at, estimates)) = self
+            .fee_estimates
+            .read()
+            .map_err(|_| IndexError::LockPoisoned)?
+            .as_ref()
+        {
+            if *estimated_at == block_count {
+                return Ok(estimates.clone());
+            }
+        }
+
+        let client = self.bitcoin_rpc_pool.get()?;
+        let mut estimates = BTreeMap::new();
+        for target in FEE_ESTIMATE_TARGETS {
+            let estimate = client.estimate_smart_fee(*target, None)?;
+            if let Some(fee_rate) = estimate.fee_rate {
+                // The node estimates in BTC/kvB.
+                estimates.insert(*target, fee_rate.to_sat() as f64 / 1000.0);
+            }
+        }
+        let estimates = Arc::new(estimates);
+
+        *self
+            .fee_estimates
+            .write()
+            .map_err(|_| IndexError::LockPoisoned)? = Some((block_count, estimates.clone()));
+
+        Ok(estimates)
+    }
+
     pub fn get_mempool_entries_with_ancestors(
         &self,
         txids: &[SerializedTxid],
@@ -271,6 +424,125 @@ impl Index {
         Ok(self.db.get_mempool_entries_with_ancestors(txids)?)
     }
 
+    /// Returns a mempool transaction with its unconfirmed ancestors and descendants,
+    /// and the fees to bump it to the target rate by CPFP or by replacing it.
+    pub fn get_mempool_package(
+        &self,
+        txid: &SerializedTxid,
+        query: &MempoolPackageQuery,
+    ) -> Result<MempoolPackage> {
+        let entry = self.db.get_mempool_entry(txid)?;
+
+        let ancestors: Vec<SerializedTxid> = self
+            .db
+            .get_mempool_entries_with_ancestors(&[*txid])?
+            .into_keys()
+            .filter(|ancestor| ancestor != txid)
+            .collect();
+
+        let mut descendants = Vec::new();
+        let mut visited: HashSet<SerializedTxid> = entry.spent_by.iter().copied().collect();
+        let mut queue = entry.spent_by.clone();
+        while !queue.is_empty() {
+            let mut children = Vec::new();
+            for (descendant, descendant_entry) in self.db.get_mempool_entries(&queue)? {
+                // Descendants that left the mempool since the entry was stored are skipped.
+                let Some(descendant_entry) = descendant_entry else {
+                    continue;
+                };
+
+                descendants.push(descendant);
+                children.extend(
+                    descendant_entry
+                        .spent_by
+                        .into_iter()
+                        .filter(|child| visited.insert(*child)),
+                );
+            }
+
+            queue = children;
+        }
+
+        let mempool_fees = self.get_mempool_fees()?;
+        let target_fee_rate = query
+            .fee_rate
+            .unwrap_or(mempool_fees.recommended.fastest_fee);
+        let child_vsize = query.child_vsize.unwrap_or(fees::DEFAULT_CHILD_VSIZE);
+
+        // Transactions newer than the last projection are mined at their ancestor rate.
+        let effective_fee_rate = mempool_fees
+            .effective_fee_rates
+            .get(txid)
+            .copied()
+            .unwrap_or(entry.fees.ancestor as f64 / entry.ancestor_size.max(1) as f64);
+
+        let rbf_min_fee = fees::rbf_min_fee(entry.fees.descendant, entry.vsize);
+
+        // Replaceability is inherited from unconfirmed ancestors.
+        let mut package_txids: Vec<SerializedTxid> = ancestors.clone();
+        package_txids.push(*txid);
+        let bip125_replaceable = self.signals_replaceability(&package_txids)?;
+
+        Ok(MempoolPackage {
+            txid: *txid,
+            vsize: entry.vsize,
+            fee: entry.fees.base,
+            ancestors,
+            descendants,
+            ancestor_vsize: entry.ancestor_size,
+            ancestor_fees: entry.fees.ancestor,
+            descendant_vsize: entry.descendant_size,
+            descendant_fees: entry.fees.descendant,
+            effective_fee_rate,
+            bip125_replaceable,
+            target_fee_rate,
+            cpfp: CpfpFee {
+                child_vsize,
+                child_fee: fees::cpfp_child_fee(
+                    entry.fees.ancestor,
+                    entry.ancestor_size,
+                    child_vsize,
+                    target_fee_rate,
+                ),
+            },
+            rbf: RbfFee {
+                min_fee: rbf_min_fee,
+                target_fee: rbf_min_fee.max((target_fee_rate * entry.vsize as f64).ceil() as u64),
+            },
+        })
+    }
+
+    /// Whether one of `txids` signals BIP125 replaceability in its inputs. Transactions
+    /// that left the mempool are skipped, and indexes that don't store transactions
+    /// read them from the node.
+    fn signals_replaceability(&self, txids: &[SerializedTxid]) -> Result<bool> {
+        for txid in txids {
+            let signals = if self.settings.index_bitcoin_transactions {
+                match self.db.get_transaction(txid, Some(true)) {
+                    Ok(transaction) => transaction
+                        .input
+                        .iter()
+                        .any(|input| input.sequence.is_rbf()),
+                    Err(StoreError::NotFound(_)) => false,
+                    Err(e) => return Err(e.into()),
+                }
+            } else {
+                self.bitcoin_rpc_pool
+                    .get()?
+                    .get_raw_transaction(&txid.into(), None)?
+                    .input
+                    .iter()
+                    .any(|input| input.sequence.is_rbf())
+            };
+
+            if signals {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
     pub fn get_tx_out(&self, outpoint: &SerializedOutPoint) -> Result<TxOut> {
         Ok(self
             .db
@@ -290,11 +562,204 @@ impl Index {
         Ok(self.db.get_rune(rune_id)?)
     }
 
+    pub fn get_runes_by_ids(&self, rune_ids: &Vec<RuneId>) -> Result<HashMap<RuneId, RuneEntry>> {
+        Ok(self.db.get_runes_by_ids(rune_ids)?)
+    }
+
+    /// Lists the runes that match `filter`. Filtered listings are paginated with `skip`
+    /// and `limit` only.
     pub fn get_runes(
         &self,
         pagination: Pagination,
+        filter: &RuneFilter,
     ) -> Result<PaginationResponse<(RuneId, RuneEntry)>> {
-        Ok(self.db.get_runes(pagination)?)
+        if filter.is_empty() {
+            return Ok(self.db.get_runes(pagination)?);
+        }
+
+        let prefix = filter
+            .prefix
+            .as_deref()
+            .map(normalize_rune_name)
+            .unwrap_or_default();
+        let search = filter
+            .search
+            .as_deref()
+            .map(normalize_rune_name)
+            .filter(|search| !search.is_empty());
+
+        // Mintable in the next block.
+        let height = self.db.get_block_count()?;
+        let (skip, limit) = pagination.into();
+
+        let runes = if prefix.is_empty() && search.is_none() {
+            self.scan_runes(filter, height, skip.saturating_add(limit))?
+        } else {
+            let rune_ids = self.db.get_rune_ids_by_name(&prefix, search.as_deref())?;
+            let mut runes: Vec<(RuneId, RuneEntry)> = self
+                .db
+                .get_runes_by_ids(&rune_ids)?
+                .into_iter()
+                .filter(|(_, entry)| matches_rune_filter(filter, height, entry))
+                .collect();
+            self.sort_runes(&mut runes, filter.sort)?;
+            runes
+        };
+
+        let items = runes
+            .into_iter()
+            .skip(skip as usize)
+            .take(limit as usize)
+            .collect::<Vec<_>>();
+
+        Ok(PaginationResponse {
+            offset: skip + items.len() as u64,
+            items,
+            next_cursor: None,
+            prev_cursor: None,
+        })
+    }
+
+    /// Returns the first `wanted` runes that match `filter`, in the order of
+    /// `filter.sort`, looking runes up a chunk at a time until there are enough.
+    fn scan_runes(
+        &self,
+        filter: &RuneFilter,
+        height: u64,
+        wanted: u64,
+    ) -> Result<Vec<(RuneId, RuneEntry)>> {
+        let ranking = match filter.sort {
+            RuneSort::Number => None,
+            sort => Some(self.get_rune_ranking(sort)?),
+        };
+
+        // Listed by number, runes etched in the range are a range of numbers.
+        let (first_number, end_number) = match ranking {
+            Some(_) => (0, 0),
+            None => self.rune_numbers_etched(filter.etched_from, filter.etched_to)?,
+        };
+        let len = match &ranking {
+            Some(ranking) => ranking.len() as u64,
+            None => end_number - first_number,
+        };
+
+        let mut runes = Vec::new();
+        let mut position = 0;
+        while position < len && (runes.len() as u64) < wanted {
+            let chunk_end = position.saturating_add(RUNES_SCAN_CHUNK).min(len);
+
+            let rune_ids: Vec<RuneId> = match &ranking {
+                Some(ranking) => ranking[position as usize..chunk_end as usize].to_vec(),
+                None => {
+                    let numbers: Vec<u64> = (position..chunk_end)
+                        .map(|position| end_number - 1 - position)
+                        .collect();
+                    let mut rune_ids = self.db.get_rune_ids_by_numbers(&numbers)?;
+                    numbers
+                        .iter()
+                        .filter_map(|number| rune_ids.remove(number))
+                        .collect()
+                }
+            };
+
+            let mut entries = self.db.get_runes_by_ids(&rune_ids)?;
+            for rune_id in rune_ids {
+                if let Some(entry) = entries.remove(&rune_id) {
+                    if matches_rune_filter(filter, height, &entry) {
+                        runes.push((rune_id, entry));
+                    }
+                }
+            }
+
+            position = chunk_end;
+        }
+
+        runes.truncate(wanted.try_into().unwrap_or(usize::MAX));
+        Ok(runes)
+    }
+
+    /// Numbers of the runes etched between `from` and `to`, as a range. Rune numbers
+    /// are given in etching order, so the range is found by bisection.
+    fn rune_numbers_etched(&self, from: Option<u64>, to: Option<u64>) -> Result<(u64, u64)> {
+        let runes_count = self.db.get_runes_count()?;
+
+        // First number whose etching block is past `height`.
+        let first_etched_after = |height: u64| -> Result<u64> {
+            let (mut low, mut high) = (0, runes_count);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                if self.db.get_rune_id_by_number(mid)?.block <= height {
+                    low = mid + 1;
+                } else {
+                    high = mid;
+                }
+            }
+            Ok(low)
+        };
+
+        let first = match from {
+            Some(0) | None => 0,
+            Some(from) => first_etched_after(from - 1)?,
+        };
+        let end = match to {
+            Some(to) => first_etched_after(to)?,
+            None => runes_count,
+        };
+
+        Ok((first, end.max(first)))
+    }
+
+    /// Ids of every rune in the order of `sort`, ranked again once a block is indexed.
+    fn get_rune_ranking(&self, sort: RuneSort) -> Result<Arc<Vec<RuneId>>> {
+        let block_count = self.db.get_block_count()?;
+
+        if let Some((ranked_at, ranking)) = self
+            .rune_rankings
+            .read()
+            .map_err(|_| IndexError::LockPoisoned)?
+            .get(&sort)
+        {
+            if *ranked_at == block_count {
+                return Ok(ranking.clone());
+            }
+        }
+
+        let mut runes = self.db.get_all_runes()?;
+        self.sort_runes(&mut runes, sort)?;
+        let ranking: Arc<Vec<RuneId>> =
+            Arc::new(runes.into_iter().map(|(rune_id, _)| rune_id).collect());
+
+        self.rune_rankings
+            .write()
+            .map_err(|_| IndexError::LockPoisoned)?
+            .insert(sort, (block_count, Arc::clone(&ranking)));
+
+        Ok(ranking)
+    }
+
+    fn sort_runes(&self, runes: &mut [(RuneId, RuneEntry)], sort: RuneSort) -> Result<()> {
+        match sort {
+            RuneSort::Number => runes.sort_by_key(|(_, entry)| Reverse(entry.number)),
+            RuneSort::Supply => {
+                runes.sort_by_key(|(_, entry)| Reverse((entry.supply(), entry.number)))
+            }
+            RuneSort::Mints => runes.sort_by_key(|(_, entry)| Reverse((entry.mints, entry.number))),
+            RuneSort::Activity => {
+                let rune_ids = runes
+                    .iter()
+                    .map(|(rune_id, _)| *rune_id)
+                    .collect::<Vec<_>>();
+                let last_activity = self.db.get_runes_last_activity(&rune_ids)?;
+
+                // Runes without confirmed transactions fall back to their etching block.
+                runes.sort_by_key(|(rune_id, entry)| {
+                    let height = last_activity.get(rune_id).copied().unwrap_or(entry.block);
+                    Reverse((height, entry.number))
+                });
+            }
+        }
+
+        Ok(())
     }
 
     pub fn get_rune_id(&self, rune: &Rune) -> Result<RuneId> {
@@ -309,6 +774,90 @@ impl Index {
         Ok(self.db.get_inscription(inscription_id)?)
     }
 
+    pub fn get_rune_holders(
+        &self,
+        rune_id: &RuneId,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<RuneHolder>> {
+        let holders = self.db.get_rune_holders(rune_id, pagination)?;
+
+        Ok(PaginationResponse {
+            items: holders
+                .items
+                .into_iter()
+                .map(|(script_pubkey, amount)| RuneHolder {
+                    address: self
+                        .settings
+                        .chain
+                        .address_from_script(&script_pubkey)
+                        .ok()
+                        .map(|address| address.to_string()),
+                    script_pubkey,
+                    amount,
+                })
+                .collect(),
+            offset: holders.offset,
+            next_cursor: holders.next_cursor,
+            prev_cursor: holders.prev_cursor,
+        })
+    }
+
+    /// Returns the confirmed activity of a rune, oldest first, grouped by `query.bucket`.
+    pub fn get_rune_history(
+        &self,
+        rune_id: &RuneId,
+        query: &RuneHistoryQuery,
+    ) -> Result<Vec<RuneHistoryEntry>> {
+        let from = query.from.unwrap_or_default();
+        let to = query.to.unwrap_or(u64::MAX);
+        if from > to {
+            return Ok(vec![]);
+        }
+
+        let mut entries: Vec<RuneHistoryEntry> = Vec::new();
+        for (height, history) in self.db.get_rune_history(rune_id, from, to)? {
+            let timestamp = match query.bucket {
+                HistoryBucket::Block => history.block_time,
+                // Block times aren't monotonic, keep days in height order.
+                HistoryBucket::Day => {
+                    let day = history.block_time - history.block_time % SECONDS_PER_DAY;
+                    entries.last().map_or(day, |entry| entry.timestamp.max(day))
+                }
+            };
+
+            match entries.last_mut() {
+                Some(entry)
+                    if entry.timestamp == timestamp && query.bucket == HistoryBucket::Day =>
+                {
+                    entry.end_height = height;
+                    entry.mints = entry.mints.saturating_add(history.mints);
+                    entry.minted = entry.minted.saturating_add(history.minted);
+                    entry.burns = entry.burns.saturating_add(history.burns);
+                    entry.burned = entry.burned.saturating_add(history.burned);
+                    entry.transfers = entry.transfers.saturating_add(history.transfers);
+                    entry.transferred = entry.transferred.saturating_add(history.transferred);
+                }
+                _ => entries.push(RuneHistoryEntry {
+                    start_height: height,
+                    end_height: height,
+                    timestamp,
+                    mints: history.mints,
+                    minted: history.minted,
+                    burns: history.burns,
+                    burned: history.burned,
+                    transfers: history.transfers,
+                    transferred: history.transferred,
+                }),
+            }
+        }
+
+        Ok(entries)
+    }
+
+    pub fn get_rune_holder_counts(&self, rune_ids: &[RuneId]) -> Result<HashMap<RuneId, u64>> {
+        Ok(self.db.get_rune_holder_counts(rune_ids)?)
+    }
+
     pub fn get_last_rune_transactions(
         &self,
         rune_id: &RuneId,
@@ -323,18 +872,93 @@ impl Index {
     pub fn get_script_pubkey_outpoints(&self, address: &Address) -> Result<AddressData> {
         let script_pubkey = address.script_pubkey();
         let outpoints = self.db.get_script_pubkey_outpoints(&script_pubkey, None)?;
-        let outpoints_to_tx_out = self
+        let outputs = self.get_address_tx_outs(&outpoints)?;
+
+        let mut runes = HashMap::default();
+        let mut value = 0;
+        for output in outputs.iter() {
+            for rune in output.runes.iter().chain(output.risky_runes.iter()) {
+                runes
+                    .entry(rune.id)
+                    .and_modify(|amount| *amount += rune.amount)
+                    .or_insert(rune.amount);
+            }
+
+            value += output.value;
+        }
+
+        Ok(AddressData {
+            value,
+            runes: runes
+                .into_iter()
+                .map(|(rune_id, amount)| RuneAmount::from((rune_id, amount)))
+                .collect(),
+            outputs,
+        })
+    }
+
+    pub fn get_address_transactions(
+        &self,
+        address: &Address,
+        pagination: Pagination,
+        mempool: Option<bool>,
+        rune_id: Option<&RuneId>,
+    ) -> Result<PaginationResponse<SerializedTxid>> {
+        Ok(self.db.get_address_transactions(
+            &address.script_pubkey(),
+            pagination,
+            mempool,
+            rune_id,
+        )?)
+    }
+
+    /// Position of the confirmed transaction `txid` in the history of `address`.
+    pub fn get_address_transaction_index(
+        &self,
+        address: &Address,
+        txid: &SerializedTxid,
+    ) -> Result<Option<u64>> {
+        Ok(self
             .db
-            .get_tx_outs_with_mempool_spent_update(&outpoints, None)?;
+            .get_address_transaction_index(&address.script_pubkey(), txid, false)?)
+    }
+
+    pub fn get_address_rune_balances(
+        &self,
+        address: &Address,
+        rune_id: Option<&RuneId>,
+    ) -> Result<Vec<AddressRuneBalance>> {
+        Ok(self
+            .db
+            .get_address_rune_balances(&address.script_pubkey(), rune_id)?)
+    }
 
-        // if outpoints.len() != outpoints_to_tx_out.len() {
-        //     error!(
-        //         "Address {} has {} outpoints but {} txouts",
-        //         address,
-        //         outpoints.len(),
-        //         outpoints_to_tx_out.len()
-        //     );
-        // }
+    pub fn get_address_utxos(
+        &self,
+        address: &Address,
+        pagination: Pagination,
+        mempool: Option<bool>,
+        rune_id: Option<&RuneId>,
+    ) -> Result<PaginationResponse<AddressTxOut>> {
+        let outpoints = self.db.get_address_outpoints(
+            &address.script_pubkey(),
+            pagination,
+            mempool,
+            rune_id,
+        )?;
+
+        Ok(PaginationResponse {
+            items: self.get_address_tx_outs(&outpoints.items)?,
+            offset: outpoints.offset,
+            next_cursor: outpoints.next_cursor,
+            prev_cursor: outpoints.prev_cursor,
+        })
+    }
+
+    fn get_address_tx_outs(&self, outpoints: &[SerializedOutPoint]) -> Result<Vec<AddressTxOut>> {
+        let outpoints_to_tx_out = self
+            .db
+            .get_tx_outs_with_mempool_spent_update(outpoints, None)?;
 
         let outpoint_txns: Vec<SerializedTxid> = outpoints
             .iter()
@@ -343,26 +967,13 @@ impl Index {
 
         let txns_confirming_block = self.db.get_transaction_confirming_blocks(&outpoint_txns)?;
 
-        let mut runes = HashMap::default();
-        let mut value = 0;
         let mut outputs = Vec::new();
         let mut tx_sizes: HashMap<SerializedTxid, (u64, u64)> = HashMap::default();
-        for (outpoint, tx_out) in outpoints_to_tx_out {
-            for rune in tx_out.runes.iter() {
-                runes
-                    .entry(rune.id)
-                    .and_modify(|amount| *amount += rune.amount)
-                    .or_insert(rune.amount);
-            }
-
-            for rune in tx_out.risky_runes.iter() {
-                runes
-                    .entry(rune.id)
-                    .and_modify(|amount| *amount += rune.amount)
-                    .or_insert(rune.amount);
-            }
-
-            value += tx_out.value;
+        // Keep the order of `outpoints`, which matters for paginated listings.
+        for outpoint in outpoints {
+            let Some(tx_out) = outpoints_to_tx_out.get(outpoint) else {
+                continue;
+            };
 
             // Populate transaction size and vsize
             let txid_ser = outpoint.to_serialized_txid();
@@ -384,9 +995,9 @@ impl Index {
                 txid: outpoint.to_txid(),
                 vout: outpoint.vout(),
                 value: tx_out.value,
-                runes: tx_out.runes,
-                risky_runes: tx_out.risky_runes,
-                spent: tx_out.spent,
+                runes: tx_out.runes.clone(),
+                risky_runes: tx_out.risky_runes.clone(),
+                spent: tx_out.spent.clone(),
                 status,
                 size,
                 weight,
@@ -394,14 +1005,7 @@ impl Index {
             outputs.push(output);
         }
 
-        Ok(AddressData {
-            value,
-            runes: runes
-                .into_iter()
-                .map(|(rune_id, amount)| RuneAmount::from((rune_id, amount)))
-                .collect(),
-            outputs,
-        })
+        Ok(outputs)
     }
 
     pub fn is_indexing_bitcoin_transactions(&self) -> bool {
@@ -426,27 +1030,95 @@ impl Index {
             .get_inputs_outputs_from_transaction(transaction, txid)?)
     }
 
+    pub fn get_transaction_artifact(&self, txid: &SerializedTxid) -> Result<Option<ArtifactKind>> {
+        Ok(self.db.get_transaction_artifact(txid, None)?)
+    }
+
     pub fn get_transaction_status(&self, txid: &SerializedTxid) -> Result<TransactionStatus> {
         let result = self.db.get_transaction_confirming_block(txid);
         match result {
             Ok(block_id) => Ok(block_id.into_transaction_status()),
-            Err(StoreError::NotFound(_)) => {
-                // If the transaction is not found, we will return a not found error.
-                self.db.get_transaction_raw(txid, None)?;
-
+            Err(StoreError::NotFound(_)) => match self.db.get_transaction_raw(txid, None) {
                 // If it's found, then it's unconfirmed.
-                Ok(TransactionStatus::unconfirmed())
-            }
+                Ok(_) => Ok(TransactionStatus::unconfirmed()),
+                // Otherwise it was replaced, or we never saw it.
+                Err(StoreError::NotFound(e)) => {
+                    match self.db.get_replacements(&[*txid])?.remove(txid) {
+                        Some(replacement) => Ok(TransactionStatus::replaced(replacement)),
+                        None => Err(IndexError::StoreError(StoreError::NotFound(e))),
+                    }
+                }
+                Err(e) => Err(IndexError::StoreError(e)),
+            },
             Err(e) => Err(IndexError::StoreError(e)),
         }
     }
 
+    /// Spending status of the first `output_count` outputs of `txid`, with the spends in
+    /// the mempool.
+    pub fn get_outspends(
+        &self,
+        txid: &SerializedTxid,
+        output_count: u32,
+    ) -> Result<Vec<TxOutspend>> {
+        let outpoints: Vec<SerializedOutPoint> = (0..output_count)
+            .map(|vout| SerializedOutPoint::from_txid_vout(txid, vout))
+            .collect();
+        let tx_outs = self
+            .db
+            .get_tx_outs_with_mempool_spent_update(&outpoints, None)?;
+
+        let spent: Vec<SpentStatus> = outpoints
+            .iter()
+            .map(|outpoint| match tx_outs.get(outpoint) {
+                Some(tx_out) => tx_out.spent.clone(),
+                // Every output is indexed, so a missing one was spent and pruned.
+                None => SpentStatus::SpentUnknown,
+            })
+            .collect();
+
+        let spenders: Vec<SerializedTxid> = spent
+            .iter()
+            .filter_map(|spent| match spent {
+                SpentStatus::Spent(spender) => Some(spender.txid),
+                _ => None,
+            })
+            .collect();
+        let statuses = self.get_transactions_statuses(&spenders)?;
+
+        Ok(spent
+            .into_iter()
+            .zip(0..)
+            .map(|(spent, vout)| match spent {
+                SpentStatus::Unspent => TxOutspend {
+                    vout,
+                    spent: false,
+                    spender: None,
+                    status: None,
+                },
+                SpentStatus::Spent(spender) => TxOutspend {
+                    vout,
+                    spent: true,
+                    status: statuses.get(&spender.txid).cloned().flatten(),
+                    spender: Some(spender),
+                },
+                SpentStatus::SpentUnknown => TxOutspend {
+                    vout,
+                    spent: true,
+                    spender: None,
+                    status: None,
+                },
+            })
+            .collect())
+    }
+
     pub fn get_transactions_statuses(
         &self,
         txids: &Vec<SerializedTxid>,
     ) -> Result<HashMap<SerializedTxid, Option<TransactionStatus>>> {
-        let (exist, _) = self.db.partition_transactions_by_existence(txids)?;
+        let (exist, not_exist) = self.db.partition_transactions_by_existence(txids)?;
         let result = self.db.get_transaction_confirming_blocks(&exist)?;
+        let mut replacements = self.db.get_replacements(&not_exist)?;
 
         Ok(txids
             .into_iter()
@@ -455,12 +1127,48 @@ impl Index {
                 match block_id {
                     Some(Some(block_id)) => (*txid, Some(block_id.into_transaction_status())),
                     Some(None) => (*txid, Some(TransactionStatus::unconfirmed())),
-                    None => (*txid, None),
+                    None => (
+                        *txid,
+                        replacements.remove(txid).map(TransactionStatus::replaced),
+                    ),
                 }
             })
             .collect())
     }
 
+    pub fn decode_transaction(&self, tx: &BitcoinTransaction) -> Result<DecodedTransaction> {
+        let (result, mut prev_outputs) = self.updater.decode_tx(tx)?;
+
+        let inputs = result
+            .inputs
+            .into_iter()
+            .map(|input| DecodedInput {
+                tx_out: prev_outputs.remove(&input.previous_outpoint),
+                previous_outpoint: input.previous_outpoint,
+            })
+            .collect();
+
+        let mut burned = result
+            .burned
+            .into_iter()
+            .map(|(id, amount)| RuneAmount {
+                id,
+                amount: amount.0,
+            })
+            .collect::<Vec<_>>();
+
+        burned.sort_by_key(|rune_amount| rune_amount.id);
+
+        Ok(DecodedTransaction {
+            txid: tx.compute_txid().into(),
+            artifact: Runestone::decipher(tx).map(to_rune_artifact),
+            inputs,
+            outputs: result.outputs,
+            minted: result.minted,
+            burned,
+        })
+    }
+
     pub fn pre_index_new_submitted_transaction(&self, txid: &SerializedTxid) -> Result<()> {
         Ok(self.updater.pre_index_new_submitted_transaction(txid)?)
     }
@@ -485,3 +1193,359 @@ impl Index {
         }
     }
 }
+
+/// Whether `entry` passes the criteria of `filter` other than its name.
+fn matches_rune_filter(filter: &RuneFilter, height: u64, entry: &RuneEntry) -> bool {
+    filter
+        .mintable
+        .map_or(true, |mintable| entry.mintable(height).is_ok() == mintable)
+        && filter.turbo.map_or(true, |turbo| entry.turbo == turbo)
+        && filter.etched_from.map_or(true, |from| entry.block >= from)
+        && filter.etched_to.map_or(true, |to| entry.block <= to)
+        && filter
+            .symbol
+            .map_or(true, |symbol| entry.symbol == Some(symbol))
+}
+
+/// Unspaced, uppercase form of a rune name, as stored in the name index.
+fn normalize_rune_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '•' | '.'))
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn to_rune_artifact(artifact: Artifact) -> RuneArtifact {
+    let to_rune_id = |id: ordinals::RuneId| RuneId::new(id.block, id.tx);
+
+    match artifact {
+        Artifact::Runestone(runestone) => RuneArtifact::Runestone(DecodedRunestone {
+            edicts: runestone
+                .edicts
+                .into_iter()
+                .map(|edict| DecodedEdict {
+                    id: to_rune_id(edict.id),
+                    amount: edict.amount,
+                    output: edict.output,
+                })
+                .collect(),
+            etching: runestone.etching.map(|etching| DecodedEtching {
+                rune: etching.rune.map(|rune| {
+                    SpacedRune::new(Rune(rune.n()), etching.spacers.unwrap_or_default())
+                }),
+                divisibility: etching.divisibility.unwrap_or_default(),
+                premine: etching.premine.unwrap_or_default(),
+                symbol: etching.symbol,
+                terms: etching.terms.map(|terms| DecodedTerms {
+                    amount: terms.amount.unwrap_or_default(),
+                    cap: terms.cap.unwrap_or_default(),
+                    height_start: terms.height.0,
+                    height_end: terms.height.1,
+                    offset_start: terms.offset.0,
+                    offset_end: terms.offset.1,
+                }),
+                turbo: etching.turbo,
+            }),
+            mint: runestone.mint.map(to_rune_id),
+            pointer: runestone.pointer,
+        }),
+        Artifact::Cenotaph(cenotaph) => RuneArtifact::Cenotaph(DecodedCenotaph {
+            flaw: cenotaph.flaw.map(cenotaph_flaw),
+            etching: cenotaph
+                .etching
+                .map(|rune| SpacedRune::new(Rune(rune.n()), 0)),
+            mint: cenotaph.mint.map(to_rune_id),
+        }),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use {
+        super::*,
+        crate::{db::TempDb, index::Chain, models::BatchUpdate},
+        bitcoin::{
+            absolute::LockTime, hashes::Hash, transaction::Version, Amount, OutPoint, ScriptBuf,
+            Sequence, TxIn, TxOut as BitcoinTxOut, Witness,
+        },
+        bitcoincore_rpc::Auth,
+        ordinals::Edict,
+        std::path::PathBuf,
+    };
+
+    /// Settings of a regtest index whose node can't be reached, for the calls that
+    /// don't need it.
+    pub(crate) fn settings() -> Settings {
+        Settings {
+            data_dir: PathBuf::new(),
+            zmq_endpoint: String::new(),
+            bitcoin_rpc_limit: 1,
+            bitcoin_rpc_url: "http://127.0.0.1:1".to_string(),
+            bitcoin_rpc_auth: Auth::None,
+            chain: Chain::Regtest,
+            no_index_inscriptions: true,
+            index_bitcoin_transactions: true,
+            index_spent_outputs: true,
+            index_addresses: false,
+            commit_interval: 1,
+            main_loop_interval: 1,
+        }
+    }
+
+    pub(crate) fn index(db: &TempDb) -> Index {
+        let bitcoin_rpc_pool = RpcClientPool::new(Arc::new(settings()), 1);
+
+        Index::new(
+            db.shared(),
+            bitcoin_rpc_pool,
+            settings(),
+            Metrics::new(),
+            None,
+        )
+    }
+
+    pub(crate) fn transaction(inputs: &[OutPoint], outputs: Vec<ScriptBuf>) -> BitcoinTransaction {
+        BitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: inputs
+                .iter()
+                .map(|previous_output| TxIn {
+                    previous_output: *previous_output,
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::MAX,
+                    witness: Witness::new(),
+                })
+                .collect(),
+            output: outputs
+                .into_iter()
+                .map(|script_pubkey| BitcoinTxOut {
+                    value: Amount::from_sat(if script_pubkey.is_op_return() { 0 } else { 546 }),
+                    script_pubkey,
+                })
+                .collect(),
+        }
+    }
+
+    fn runestone(edicts: Vec<Edict>) -> ScriptBuf {
+        Runestone {
+            edicts,
+            ..Default::default()
+        }
+        .encipher()
+    }
+
+    #[test]
+    fn filters_runes_while_scanning() {
+        let db = TempDb::open();
+        let index = index(&db);
+
+        // Runes etched at blocks 100 to 500, every other one turbo, with growing supply
+        // but for the last one.
+        let mut update = BatchUpdate::new(5, 600, 0);
+        for number in 0..5u64 {
+            let rune_id = RuneId::new(100 * (number + 1), 1);
+            update.rune_numbers.insert(number, rune_id);
+            update.runes.insert(
+                rune_id,
+                RuneEntry {
+                    block: rune_id.block,
+                    burned: 0,
+                    divisibility: 0,
+                    etching: SerializedTxid::from([number as u8; 32]),
+                    mints: 0,
+                    number,
+                    premine: if number == 4 {
+                        0
+                    } else {
+                        1000 * (number as u128 + 1)
+                    },
+                    spaced_rune: SpacedRune::new(Rune(number as u128), 0),
+                    symbol: None,
+                    terms: None,
+                    pending_burns: 0,
+                    pending_mints: 0,
+                    inscription_id: None,
+                    timestamp: 0,
+                    turbo: number % 2 == 1,
+                },
+            );
+        }
+        db.batch_update(&update, false).unwrap();
+
+        let numbers = |pagination: (u64, u64), filter: RuneFilter| {
+            index
+                .get_runes(pagination.into(), &filter)
+                .unwrap()
+                .items
+                .into_iter()
+                .map(|(_, entry)| entry.number)
+                .collect::<Vec<_>>()
+        };
+
+        let etched = RuneFilter {
+            etched_from: Some(200),
+            etched_to: Some(450),
+            ..Default::default()
+        };
+        assert_eq!(numbers((0, 10), etched.clone()), vec![3, 2, 1]);
+        assert_eq!(numbers((1, 1), etched.clone()), vec![2]);
+
+        let turbo = RuneFilter {
+            turbo: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(numbers((0, 10), turbo.clone()), vec![3, 1]);
+
+        let by_supply = RuneFilter {
+            sort: RuneSort::Supply,
+            ..etched
+        };
+        assert_eq!(numbers((0, 10), by_supply.clone()), vec![3, 2, 1]);
+        assert_eq!(
+            numbers(
+                (0, 10),
+                RuneFilter {
+                    etched_from: None,
+                    etched_to: None,
+                    ..by_supply
+                }
+            ),
+            vec![3, 2, 1, 0, 4]
+        );
+        assert_eq!(
+            numbers(
+                (0, 10),
+                RuneFilter {
+                    etched_to: Some(99),
+                    ..turbo
+                }
+            ),
+            Vec::<u64>::new()
+        );
+    }
+
+    #[test]
+    fn decodes_without_writing() {
+        let db = TempDb::open();
+        let index = index(&db);
+        let rune_id = RuneId::new(840000, 1);
+        let edict = |amount, output| Edict {
+            id: ordinals::RuneId {
+                block: 840000,
+                tx: 1,
+            },
+            amount,
+            output,
+        };
+
+        let previous_outpoint = OutPoint::new(bitcoin::Txid::from_byte_array([1; 32]), 0);
+        let previous_output = TxOut {
+            runes: vec![RuneAmount {
+                id: rune_id,
+                amount: 1000,
+            }],
+            risky_runes: vec![],
+            spent: SpentStatus::Unspent,
+            value: 546,
+            script_pubkey: ScriptBuf::from_bytes(vec![0x51]),
+        };
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update
+            .txouts
+            .insert(previous_outpoint.into(), previous_output.clone());
+        db.batch_update(&update, false).unwrap();
+
+        let owner = ScriptBuf::from_bytes(vec![0x52]);
+        let recipient = ScriptBuf::from_bytes(vec![0x53]);
+        let tx = transaction(
+            &[previous_outpoint],
+            vec![
+                owner.clone(),
+                recipient.clone(),
+                runestone(vec![edict(400, 1)]),
+            ],
+        );
+
+        let decoded = index.decode_transaction(&tx).unwrap();
+        assert_eq!(decoded.txid, tx.compute_txid().into());
+        assert_eq!(
+            decoded.inputs,
+            vec![DecodedInput {
+                previous_outpoint: previous_outpoint.into(),
+                tx_out: Some(previous_output),
+            }]
+        );
+        assert_eq!(
+            decoded.artifact,
+            Some(RuneArtifact::Runestone(DecodedRunestone {
+                edicts: vec![DecodedEdict {
+                    id: rune_id,
+                    amount: 400,
+                    output: 1,
+                }],
+                etching: None,
+                mint: None,
+                pointer: None,
+            }))
+        );
+        // What isn't allocated goes to the first output that isn't an OP_RETURN.
+        let amounts = decoded
+            .outputs
+            .iter()
+            .map(|output| (output.runes.clone(), output.risky_runes.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            amounts,
+            vec![
+                (
+                    vec![RuneAmount {
+                        id: rune_id,
+                        amount: 600,
+                    }],
+                    vec![]
+                ),
+                (
+                    vec![RuneAmount {
+                        id: rune_id,
+                        amount: 400,
+                    }],
+                    vec![]
+                ),
+                (vec![], vec![]),
+            ]
+        );
+        assert_eq!(decoded.minted, None);
+        assert!(decoded.burned.is_empty());
+
+        // Nothing was indexed.
+        let txid = SerializedTxid::from(tx.compute_txid());
+        assert!(Store::get_tx_state_changes(&*db, &txid, None).is_err());
+        assert!(!Store::is_tx_in_mempool(&*db, &txid).unwrap());
+
+        // An edict to an output that doesn't exist makes a cenotaph, which burns the
+        // inputs.
+        let tx = transaction(
+            &[previous_outpoint],
+            vec![owner, runestone(vec![edict(400, 5)])],
+        );
+
+        let decoded = index.decode_transaction(&tx).unwrap();
+        assert_eq!(
+            decoded.artifact,
+            Some(RuneArtifact::Cenotaph(DecodedCenotaph {
+                flaw: Some(titan_types_core::CenotaphFlaw::EdictOutput),
+                etching: None,
+                mint: None,
+            }))
+        );
+        assert!(decoded.outputs.iter().all(|output| output.runes.is_empty()));
+        assert_eq!(
+            decoded.burned,
+            vec![RuneAmount {
+                id: rune_id,
+                amount: 1000,
+            }]
+        );
+    }
+}
@@ -33,6 +33,7 @@ impl std::fmt::Display for MetricsError {
 
 impl std::error::Error for MetricsError {}
 
+#[derive(Clone)]
 pub struct Metrics {
     reg: prometheus::Registry,
 }
@@ -80,6 +81,14 @@ impl Metrics {
         h
     }
 
+    /// Encode every registered metric family using the Prometheus text exposition format.
+    pub fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        let encoder = prometheus::TextEncoder::new();
+        encoder.encode(&self.reg.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+
     pub fn start(&self, shutdown_flag: Arc<AtomicBool>) -> thread::JoinHandle<()> {
         let registry = self.reg.clone();
         let handle = thread::spawn(move || loop {
@@ -1,11 +1,13 @@
 pub use {
     chain::Chain,
     index::{Index, IndexError},
+    metrics::{CounterVec, Gauge, GaugeVec, Metrics},
     settings::Settings,
     store::StoreError,
 };
 
 mod chain;
+mod fees;
 mod index;
 mod inscription;
 mod metrics;
@@ -13,3 +15,11 @@ mod settings;
 mod store;
 mod updater;
 mod zmq;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    pub(crate) use super::{
+        fees::tests::entry as mempool_entry,
+        index::tests::{index, settings, transaction},
+    };
+}
@@ -1,19 +1,22 @@
 use {
     crate::{
-        db::{RocksDB, RocksDBError},
+        db::{RocksDB, RocksDBError, MAX_SCANNED_ADDRESS_ENTRIES},
         models::{
-            BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, RuneEntry,
+            BatchDelete, BatchRollback, BatchUpdate, BlockId, Inscription, RuneEntry, RuneHistory,
             TransactionStateChange,
         },
     },
-    bitcoin::{consensus, hex::HexToArrayError, BlockHash, ScriptBuf},
-    rustc_hash::FxHashMap as HashMap,
+    bitcoin::{block::Header, consensus, hex::HexToArrayError, BlockHash, ScriptBuf},
+    rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet},
     thiserror::Error,
-    titan_types_api::{Pagination, PaginationResponse},
+    titan_types_api::{
+        AddressRuneBalance, Cursor, Pagination, PaginationDirection, PaginationResponse,
+    },
     titan_types_core::RuneId,
     titan_types_core::{
-        Block, InscriptionId, MempoolEntry, Rune, SerializedOutPoint, SerializedTxid,
-        SpenderReference, SpentStatus, Transaction, TransactionStatus, TxOut,
+        ArtifactKind, Block, InscriptionId, MempoolEntry, Rune, SerializedOutPoint, SerializedTxid,
+        SpenderReference, SpentStatus, Transaction, TransactionReplacement, TransactionStatus,
+        TxOut,
     },
 };
 
@@ -72,6 +75,7 @@ pub trait Store {
     fn delete_block_hash(&self, height: u64) -> Result<(), StoreError>;
 
     fn get_block_by_hash(&self, hash: &BlockHash) -> Result<Block, StoreError>;
+    fn get_block_header(&self, hash: &BlockHash) -> Result<Header, StoreError>;
     fn get_blocks_by_hashes(
         &self,
         hashes: &Vec<BlockHash>,
@@ -134,6 +138,12 @@ pub trait Store {
         txids: &[SerializedTxid],
         mempool: bool,
     ) -> Result<HashMap<SerializedTxid, TransactionStateChange>, StoreError>;
+    /// The runestone or cenotaph found when the transaction was indexed.
+    fn get_transaction_artifact(
+        &self,
+        txid: &SerializedTxid,
+        mempool: Option<bool>,
+    ) -> Result<Option<ArtifactKind>, StoreError>;
 
     // bitcoin transactions
     fn get_transaction_raw(
@@ -164,6 +174,13 @@ pub trait Store {
         txids: &Vec<SerializedTxid>,
     ) -> Result<(Vec<SerializedTxid>, Vec<SerializedTxid>), StoreError>;
 
+    // replacements
+    fn set_replacements(&self, replacements: &[TransactionReplacement]) -> Result<(), StoreError>;
+    fn get_replacements(
+        &self,
+        txids: &[SerializedTxid],
+    ) -> Result<HashMap<SerializedTxid, TransactionReplacement>, StoreError>;
+
     // rune transactions
     fn get_last_rune_transactions(
         &self,
@@ -184,6 +201,36 @@ pub trait Store {
         &self,
         pagination: Pagination,
     ) -> Result<PaginationResponse<(RuneId, RuneEntry)>, StoreError>;
+    fn get_all_runes(&self) -> Result<Vec<(RuneId, RuneEntry)>, StoreError>;
+    fn get_rune_id_by_number(&self, number: u64) -> Result<RuneId, StoreError>;
+    fn get_rune_ids_by_numbers(
+        &self,
+        numbers: &Vec<u64>,
+    ) -> Result<HashMap<u64, RuneId>, StoreError>;
+    fn get_rune_ids_by_name(
+        &self,
+        prefix: &str,
+        search: Option<&str>,
+    ) -> Result<Vec<RuneId>, StoreError>;
+    fn get_runes_last_activity(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, u64>, StoreError>;
+    fn get_rune_holders(
+        &self,
+        rune_id: &RuneId,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<(ScriptBuf, u128)>, StoreError>;
+    fn get_rune_history(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(u64, RuneHistory)>, StoreError>;
+    fn get_rune_holder_counts(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, u64>, StoreError>;
 
     // inscription
     fn get_inscription(&self, inscription_id: &InscriptionId) -> Result<Inscription, StoreError>;
@@ -200,6 +247,31 @@ pub trait Store {
         mempool: Option<bool>,
         optimistic: bool,
     ) -> Result<HashMap<SerializedOutPoint, ScriptBuf>, StoreError>;
+    fn get_address_transactions(
+        &self,
+        script_pubkey: &ScriptBuf,
+        pagination: Pagination,
+        mempool: Option<bool>,
+        rune_id: Option<&RuneId>,
+    ) -> Result<PaginationResponse<SerializedTxid>, StoreError>;
+    fn get_address_transaction_index(
+        &self,
+        script_pubkey: &ScriptBuf,
+        txid: &SerializedTxid,
+        mempool: bool,
+    ) -> Result<Option<u64>, StoreError>;
+    fn get_address_outpoints(
+        &self,
+        script_pubkey: &ScriptBuf,
+        pagination: Pagination,
+        mempool: Option<bool>,
+        rune_id: Option<&RuneId>,
+    ) -> Result<PaginationResponse<SerializedOutPoint>, StoreError>;
+    fn get_address_rune_balances(
+        &self,
+        script_pubkey: &ScriptBuf,
+        rune_id: Option<&RuneId>,
+    ) -> Result<Vec<AddressRuneBalance>, StoreError>;
 
     // batch
     fn batch_update(&self, update: &BatchUpdate, mempool: bool) -> Result<(), StoreError>;
@@ -277,6 +349,10 @@ impl Store for RocksDB {
         Ok(self.get_block_by_hash(&hash)?)
     }
 
+    fn get_block_header(&self, hash: &BlockHash) -> Result<Header, StoreError> {
+        Ok(self.get_block_header(hash)?)
+    }
+
     fn get_blocks_by_hashes(
         &self,
         hashes: &Vec<BlockHash>,
@@ -316,10 +392,25 @@ impl Store for RocksDB {
         pagination: Pagination,
     ) -> Result<PaginationResponse<(RuneId, RuneEntry)>, StoreError> {
         let runes_count = self.get_runes_count()?;
-        let (skip, limit) = pagination.into();
-
-        let start = runes_count.saturating_sub(skip);
-        let end = runes_count.saturating_sub(skip).saturating_sub(limit);
+        let (skip, limit) = (pagination.skip, pagination.limit);
+
+        // Runes are listed from the highest rune number down, so the cursor is the
+        // number of the rune at the edge of the page.
+        let cursor = pagination.cursor.as_ref().and_then(Cursor::index);
+        let (end, start) = match (cursor, pagination.direction) {
+            (None, _) => {
+                let start = runes_count.saturating_sub(skip);
+                (start.saturating_sub(limit), start)
+            }
+            (Some(cursor), PaginationDirection::Forward) => {
+                let start = cursor.min(runes_count);
+                (start.saturating_sub(limit), start)
+            }
+            (Some(cursor), PaginationDirection::Backward) => {
+                let end = cursor.saturating_add(1).min(runes_count);
+                (end, end.saturating_add(limit).min(runes_count))
+            }
+        };
 
         let mut runes = Vec::new();
         for i in (end..start).rev() {
@@ -328,13 +419,103 @@ impl Store for RocksDB {
             runes.push((rune_id, rune_entry));
         }
 
-        let offset = skip + runes.len() as u64;
+        let (next_cursor, prev_cursor) = if runes.is_empty() {
+            (None, None)
+        } else {
+            (
+                (end > 0).then(|| Cursor::new(false, end)),
+                (start < runes_count).then(|| Cursor::new(false, start - 1)),
+            )
+        };
+
+        let offset = match cursor {
+            Some(_) => runes.len() as u64,
+            None => skip + runes.len() as u64,
+        };
+
         Ok(PaginationResponse {
             items: runes,
             offset,
+            next_cursor,
+            prev_cursor,
         })
     }
 
+    fn get_all_runes(&self) -> Result<Vec<(RuneId, RuneEntry)>, StoreError> {
+        Ok(self.get_all_runes()?)
+    }
+
+    fn get_rune_id_by_number(&self, number: u64) -> Result<RuneId, StoreError> {
+        Ok(self.get_rune_id_by_number(number)?)
+    }
+
+    fn get_rune_ids_by_numbers(
+        &self,
+        numbers: &Vec<u64>,
+    ) -> Result<HashMap<u64, RuneId>, StoreError> {
+        Ok(self.get_rune_ids_by_numbers(numbers)?)
+    }
+
+    fn get_rune_ids_by_name(
+        &self,
+        prefix: &str,
+        search: Option<&str>,
+    ) -> Result<Vec<RuneId>, StoreError> {
+        Ok(self.get_rune_ids_by_name(prefix, search)?)
+    }
+
+    fn get_runes_last_activity(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, u64>, StoreError> {
+        Ok(self.get_runes_last_activity(rune_ids)?)
+    }
+
+    fn get_rune_holders(
+        &self,
+        rune_id: &RuneId,
+        pagination: Pagination,
+    ) -> Result<PaginationResponse<(ScriptBuf, u128)>, StoreError> {
+        // Holders are confirmed only, so they are read as a single list.
+        paginate_sources(
+            pagination,
+            Some(false),
+            |cursor| {
+                cursor
+                    .holder()
+                    .map(|(amount, script_pubkey)| (amount, script_pubkey.clone()))
+            },
+            |_, (amount, script_pubkey)| Cursor::from_holder(amount, script_pubkey),
+            |from, direction, limit, _| {
+                Ok(self
+                    .get_rune_holders_from(
+                        rune_id,
+                        from.as_ref()
+                            .map(|(amount, script_pubkey)| (*amount, script_pubkey.as_bytes())),
+                        direction,
+                        limit,
+                    )?
+                    .into())
+            },
+        )
+    }
+
+    fn get_rune_history(
+        &self,
+        rune_id: &RuneId,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(u64, RuneHistory)>, StoreError> {
+        Ok(self.get_rune_history(rune_id, from, to)?)
+    }
+
+    fn get_rune_holder_counts(
+        &self,
+        rune_ids: &[RuneId],
+    ) -> Result<HashMap<RuneId, u64>, StoreError> {
+        Ok(self.get_rune_holder_counts(rune_ids)?)
+    }
+
     fn get_mempool_txids(&self) -> Result<HashMap<SerializedTxid, MempoolEntry>, StoreError> {
         Ok(self.get_mempool_txids()?)
     }
@@ -475,6 +656,18 @@ impl Store for RocksDB {
         Ok(self.get_txs_state_changes(txids, mempool)?)
     }
 
+    fn get_transaction_artifact(
+        &self,
+        txid: &SerializedTxid,
+        mempool: Option<bool>,
+    ) -> Result<Option<ArtifactKind>, StoreError> {
+        match Store::get_tx_state_changes(self, txid, mempool) {
+            Ok(tx_state_change) => Ok(tx_state_change.artifact),
+            Err(StoreError::NotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
     fn get_transaction(
         &self,
         txid: &SerializedTxid,
@@ -485,11 +678,7 @@ impl Store for RocksDB {
                 self.get_transaction_confirming_block(txid)?
                     .into_transaction_status()
             } else {
-                TransactionStatus {
-                    confirmed: false,
-                    block_height: None,
-                    block_hash: None,
-                }
+                TransactionStatus::unconfirmed()
             };
 
             (self.get_transaction(txid, mempool)?, status, mempool)
@@ -504,11 +693,7 @@ impl Store for RocksDB {
                 }
                 Err(err) => match err {
                     RocksDBError::NotFound(_) => {
-                        let status = TransactionStatus {
-                            confirmed: false,
-                            block_height: None,
-                            block_hash: None,
-                        };
+                        let status = TransactionStatus::unconfirmed();
 
                         (self.get_transaction(txid, true)?, status, true)
                     }
@@ -518,8 +703,12 @@ impl Store for RocksDB {
         };
 
         let (inputs, outputs) = self.get_inputs_outputs_from_transaction(&transaction, txid)?;
+        let artifact = self.get_transaction_artifact(txid, Some(mempool))?;
 
-        Ok(Transaction::from((transaction, status, inputs, outputs)))
+        Ok(Transaction {
+            artifact,
+            ..Transaction::from((transaction, status, inputs, outputs))
+        })
     }
 
     fn get_inputs_outputs_from_transaction(
@@ -586,6 +775,17 @@ impl Store for RocksDB {
         Ok(self.partition_transactions_by_existence(txids)?)
     }
 
+    fn set_replacements(&self, replacements: &[TransactionReplacement]) -> Result<(), StoreError> {
+        Ok(self.set_replacements(replacements)?)
+    }
+
+    fn get_replacements(
+        &self,
+        txids: &[SerializedTxid],
+    ) -> Result<HashMap<SerializedTxid, TransactionReplacement>, StoreError> {
+        Ok(self.get_replacements(txids)?)
+    }
+
     fn get_transaction_confirming_block(
         &self,
         txid: &SerializedTxid,
@@ -622,31 +822,35 @@ impl Store for RocksDB {
         pagination: Option<Pagination>,
         mempool: Option<bool>,
     ) -> Result<PaginationResponse<SerializedTxid>, StoreError> {
-        if let Some(mempool) = mempool {
+        // Pages of both lists, and pages after a cursor, are read past their end to
+        // know whether another page follows.
+        if let Some(pagination) = pagination
+            .clone()
+            .filter(|pagination| pagination.cursor.is_some() || mempool.is_none())
+        {
+            paginate_sources(
+                pagination,
+                mempool,
+                Cursor::index,
+                Cursor::new,
+                |from, direction, limit, mempool| {
+                    Ok(self
+                        .get_rune_transactions_from(rune_id, from, direction, limit, mempool)?
+                        .into())
+                },
+            )
+        } else if let Some(mempool) = mempool {
             Ok(self.get_last_rune_transactions(rune_id, pagination, mempool)?)
         } else {
-            // First get mempool transactions
-            let mempool_txids = self.get_last_rune_transactions(rune_id, pagination, true)?;
-
-            // Then get non-mempool transactions
-            // Adapt pagination to offset
-            let non_mempool_pagination = match pagination {
-                Some(pagination) => Pagination {
-                    skip: pagination.skip.saturating_sub(mempool_txids.offset),
-                    limit: pagination
-                        .limit
-                        .saturating_sub(mempool_txids.items.len() as u64),
-                },
-                None => Pagination {
-                    skip: 0,
-                    limit: u64::MAX,
-                },
+            // Everything, mempool transactions first.
+            let all = Pagination {
+                skip: 0,
+                limit: u64::MAX,
+                ..Default::default()
             };
-
-            let non_mempool_txids =
-                self.get_last_rune_transactions(rune_id, Some(non_mempool_pagination), false)?;
-
-            let new_offset = mempool_txids.offset + non_mempool_txids.offset;
+            let mempool_txids =
+                self.get_last_rune_transactions(rune_id, Some(all.clone()), true)?;
+            let non_mempool_txids = self.get_last_rune_transactions(rune_id, Some(all), false)?;
 
             Ok(PaginationResponse {
                 items: mempool_txids
@@ -654,7 +858,9 @@ impl Store for RocksDB {
                     .into_iter()
                     .chain(non_mempool_txids.items)
                     .collect(),
-                offset: new_offset,
+                offset: mempool_txids.offset + non_mempool_txids.offset,
+                next_cursor: None,
+                prev_cursor: None,
             })
         }
     }
@@ -687,6 +893,155 @@ impl Store for RocksDB {
         }
     }
 
+    fn get_address_transactions(
+        &self,
+        script_pubkey: &ScriptBuf,
+        pagination: Pagination,
+        mempool: Option<bool>,
+        rune_id: Option<&RuneId>,
+    ) -> Result<PaginationResponse<SerializedTxid>, StoreError> {
+        paginate_sources(
+            pagination,
+            mempool,
+            Cursor::index,
+            Cursor::new,
+            |from, direction, limit, mempool| {
+                let (entries, stopped_at) = self.get_address_transactions_from(
+                    script_pubkey,
+                    from,
+                    direction,
+                    limit,
+                    mempool,
+                    rune_id,
+                )?;
+
+                Ok(Fetched {
+                    entries,
+                    stopped_at,
+                })
+            },
+        )
+    }
+
+    fn get_address_transaction_index(
+        &self,
+        script_pubkey: &ScriptBuf,
+        txid: &SerializedTxid,
+        mempool: bool,
+    ) -> Result<Option<u64>, StoreError> {
+        Ok(self.get_address_transaction_index(script_pubkey, txid, mempool)?)
+    }
+
+    fn get_address_outpoints(
+        &self,
+        script_pubkey: &ScriptBuf,
+        pagination: Pagination,
+        mempool: Option<bool>,
+        rune_id: Option<&RuneId>,
+    ) -> Result<PaginationResponse<SerializedOutPoint>, StoreError> {
+        // When both lists are read, outputs already spent in the mempool are left out,
+        // like in `get_script_pubkey_outpoints`.
+        let exclude_mempool_spent = mempool.is_none();
+
+        paginate_sources(
+            pagination,
+            mempool,
+            |cursor| cursor.outpoint(),
+            Cursor::from_outpoint,
+            |mut from, direction, limit, mempool| {
+                let mut outpoints = Vec::new();
+                let mut scanned = 0;
+                while (outpoints.len() as u64) < limit {
+                    // Like address transactions, a page filtered by rune gives up after
+                    // a bounded number of outputs.
+                    if rune_id.is_some() && scanned >= MAX_SCANNED_ADDRESS_ENTRIES {
+                        return Ok(Fetched {
+                            entries: outpoints,
+                            stopped_at: from,
+                        });
+                    }
+
+                    let requested = limit - outpoints.len() as u64;
+                    let mut chunk = self.get_script_pubkey_outpoints_from(
+                        script_pubkey,
+                        from,
+                        direction,
+                        requested,
+                        mempool,
+                    )?;
+
+                    let exhausted = (chunk.len() as u64) < requested;
+                    scanned += chunk.len() as u64;
+                    from = chunk.last().copied();
+
+                    if exclude_mempool_spent {
+                        let spent_outpoints = self.get_spent_outpoints_in_mempool(&chunk)?;
+                        chunk.retain(|outpoint| {
+                            !matches!(spent_outpoints.get(outpoint), Some(Some(_)))
+                        });
+                    }
+
+                    if let Some(rune_id) = rune_id {
+                        let tx_outs = self.get_tx_outs(&chunk, Some(mempool))?;
+                        chunk.retain(|outpoint| {
+                            tx_outs.get(outpoint).is_some_and(|tx_out| {
+                                tx_out
+                                    .runes
+                                    .iter()
+                                    .chain(tx_out.risky_runes.iter())
+                                    .any(|rune| rune.id == *rune_id)
+                            })
+                        });
+                    }
+
+                    outpoints.extend(chunk.into_iter().map(|outpoint| (outpoint, outpoint)));
+
+                    if exhausted {
+                        break;
+                    }
+                }
+
+                Ok(outpoints.into())
+            },
+        )
+    }
+
+    fn get_address_rune_balances(
+        &self,
+        script_pubkey: &ScriptBuf,
+        rune_id: Option<&RuneId>,
+    ) -> Result<Vec<AddressRuneBalance>, StoreError> {
+        let mut confirmed = self.get_address_rune_balances(script_pubkey, rune_id, false)?;
+        let mut mempool = self.get_address_rune_balances(script_pubkey, rune_id, true)?;
+
+        let rune_ids = confirmed
+            .keys()
+            .chain(mempool.keys())
+            .copied()
+            .collect::<HashSet<_>>();
+
+        let mut balances = rune_ids
+            .into_iter()
+            .map(|rune_id| {
+                let confirmed = confirmed.remove(&rune_id).unwrap_or_default();
+                let pending = mempool.remove(&rune_id).unwrap_or_default();
+
+                AddressRuneBalance {
+                    rune_id,
+                    confirmed: confirmed.amount,
+                    confirmed_risky: confirmed.risky,
+                    pending: pending.amount,
+                    risky: pending.risky,
+                    pending_spent: pending.spent,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        balances.sort_by_key(|balance| balance.rune_id);
+
+        Ok(balances)
+    }
+
     fn get_outpoints_to_script_pubkey(
         &self,
         outpoints: &[SerializedOutPoint],
@@ -733,3 +1088,531 @@ impl Store for RocksDB {
         self.switch_to_online_mode().map_err(StoreError::DB)
     }
 }
+
+/// Entries `paginate_sources` reads from one of its lists.
+struct Fetched<P, T> {
+    entries: Vec<(P, T)>,
+    /// Position of the last entry looked at, when the read gave up before finding
+    /// `limit` entries or reaching the end of the list.
+    stopped_at: Option<P>,
+}
+
+impl<P, T> From<Vec<(P, T)>> for Fetched<P, T> {
+    fn from(entries: Vec<(P, T)>) -> Self {
+        Self {
+            entries,
+            stopped_at: None,
+        }
+    }
+}
+
+/// Reads one page of a listing made of the mempool entries followed by the confirmed
+/// ones, each of them newest first, and fills in the cursors of the response.
+///
+/// `fetch` reads up to `limit` entries from one of the two lists, starting right after
+/// the given position (or at the edge of the list when `None`) and walking in the given
+/// direction. `position` and `to_cursor` convert between cursors and positions. When a
+/// read stops early, the page ends there and its cursor points where the read stopped.
+fn paginate_sources<P, T>(
+    pagination: Pagination,
+    mempool: Option<bool>,
+    position: impl Fn(&Cursor) -> Option<P>,
+    to_cursor: impl Fn(bool, P) -> Cursor,
+    mut fetch: impl FnMut(
+        Option<P>,
+        PaginationDirection,
+        u64,
+        bool,
+    ) -> Result<Fetched<P, T>, StoreError>,
+) -> Result<PaginationResponse<T>, StoreError> {
+    let Pagination {
+        skip,
+        limit,
+        cursor,
+        direction,
+    } = pagination;
+
+    let mut sources = match mempool {
+        Some(mempool) => vec![mempool],
+        None => vec![true, false],
+    };
+    if direction == PaginationDirection::Backward {
+        sources.reverse();
+    }
+
+    // Start from the list the cursor points into, or from the edge of the first one
+    // when there is no cursor or it belongs to a list that is not being read.
+    let (start, mut from) = match cursor.as_ref().and_then(|cursor| {
+        sources
+            .iter()
+            .position(|m| *m == cursor.mempool())
+            .map(|start| (start, position(cursor)))
+    }) {
+        Some(start) => start,
+        None => (0, None),
+    };
+
+    // Skip only applies when there is no cursor. Read one extra entry to know whether
+    // there is a page after this one.
+    let skip = if cursor.is_some() { 0 } else { skip };
+    let wanted = skip.saturating_add(limit).saturating_add(1);
+
+    let mut entries = Vec::new();
+    let mut stopped_at = None;
+    for source in &sources[start..] {
+        let remaining = wanted - entries.len() as u64;
+        let fetched = fetch(from, direction, remaining, *source)?;
+        for (position, item) in fetched.entries {
+            entries.push((to_cursor(*source, position), item));
+        }
+
+        if let Some(position) = fetched.stopped_at {
+            stopped_at = Some(to_cursor(*source, position));
+            break;
+        }
+
+        if entries.len() as u64 >= wanted {
+            break;
+        }
+        from = None;
+    }
+
+    entries.drain(..entries.len().min(skip.try_into().unwrap_or(usize::MAX)));
+
+    let has_more = stopped_at.is_some() || entries.len() as u64 > limit;
+    let has_before = cursor.is_some() || skip > 0;
+    entries.truncate(limit.try_into().unwrap_or(usize::MAX));
+    if direction == PaginationDirection::Backward {
+        entries.reverse();
+    }
+
+    let first = entries.first().map(|(cursor, _)| cursor.clone());
+    let last = entries.last().map(|(cursor, _)| cursor.clone());
+    // A read that stopped early is continued from where it stopped, past its last item.
+    let more = |edge: Option<Cursor>| stopped_at.clone().or(edge).filter(|_| has_more);
+    let (next_cursor, prev_cursor) = match direction {
+        PaginationDirection::Forward => (more(last), first.filter(|_| has_before)),
+        PaginationDirection::Backward => (last.filter(|_| has_before), more(first)),
+    };
+
+    Ok(PaginationResponse {
+        offset: skip + entries.len() as u64,
+        items: entries.into_iter().map(|(_, item)| item).collect(),
+        next_cursor,
+        prev_cursor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::db::TempDb};
+
+    fn txid(n: u8) -> SerializedTxid {
+        SerializedTxid::from([n; 32])
+    }
+
+    fn page(cursor: Option<Cursor>, direction: PaginationDirection) -> Pagination {
+        Pagination {
+            skip: 0,
+            limit: 2,
+            cursor,
+            direction,
+        }
+    }
+
+    /// Two mempool transactions on top of five confirmed ones.
+    fn rune_transactions(db: &RocksDB, rune_id: RuneId) {
+        let confirmed = (1..=5).map(txid).collect();
+        let mempool = (11..=12).map(txid).collect();
+        db.add_rune_transactions_batch(&HashMap::from_iter([(rune_id, confirmed)]), false)
+            .unwrap();
+        db.add_rune_transactions_batch(&HashMap::from_iter([(rune_id, mempool)]), true)
+            .unwrap();
+    }
+
+    #[test]
+    fn pages_rune_transactions_across_mempool_and_confirmed() {
+        let db = TempDb::open();
+        let rune_id = RuneId::new(840000, 1);
+        rune_transactions(&db, rune_id);
+
+        let first = Store::get_last_rune_transactions(
+            &*db,
+            &rune_id,
+            Some(page(None, PaginationDirection::Forward)),
+            None,
+        )
+        .unwrap();
+        assert_eq!(first.items, vec![txid(12), txid(11)]);
+        assert!(first.prev_cursor.is_none());
+
+        // The mempool page ends at index 1, but confirmed transactions follow.
+        let mut cursor = first.next_cursor.clone();
+        let mut pages = Vec::new();
+        while let Some(next) = cursor {
+            let response = Store::get_last_rune_transactions(
+                &*db,
+                &rune_id,
+                Some(page(Some(next), PaginationDirection::Forward)),
+                None,
+            )
+            .unwrap();
+            cursor = response.next_cursor.clone();
+            pages.push(response);
+        }
+
+        assert_eq!(
+            pages
+                .iter()
+                .map(|page| page.items.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                vec![txid(5), txid(4)],
+                vec![txid(3), txid(2)],
+                vec![txid(1)],
+            ]
+        );
+
+        let back = Store::get_last_rune_transactions(
+            &*db,
+            &rune_id,
+            Some(page(
+                pages[0].prev_cursor.clone(),
+                PaginationDirection::Backward,
+            )),
+            None,
+        )
+        .unwrap();
+        assert_eq!(back.items, vec![txid(12), txid(11)]);
+        assert!(back.prev_cursor.is_none());
+        assert_eq!(back.next_cursor, first.next_cursor);
+    }
+
+    /// Pages over two mempool and four confirmed entries, indexed from 1 and
+    /// listed newest first.
+    fn paginate(pagination: Pagination, mempool: Option<bool>) -> PaginationResponse<(bool, u64)> {
+        paginate_sources(
+            pagination,
+            mempool,
+            Cursor::index,
+            Cursor::new,
+            |from, direction, limit, mempool| {
+                let len = if mempool { 2 } else { 4 };
+                let indexes: Vec<u64> = match direction {
+                    PaginationDirection::Forward => (1..from.unwrap_or(len + 1)).rev().collect(),
+                    PaginationDirection::Backward => (from.unwrap_or(0) + 1..=len).collect(),
+                };
+                Ok(indexes
+                    .into_iter()
+                    .take(limit as usize)
+                    .map(|index| (index, (mempool, index)))
+                    .collect::<Vec<_>>()
+                    .into())
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn paginates_sources_with_cursors() {
+        let pagination = Pagination {
+            limit: 3,
+            ..page(None, PaginationDirection::Forward)
+        };
+
+        let first = paginate(pagination.clone(), None);
+        assert_eq!(first.items, vec![(true, 2), (true, 1), (false, 4)]);
+        assert_eq!(first.next_cursor, Some(Cursor::new(false, 4)));
+        assert_eq!(first.prev_cursor, None);
+
+        let second = paginate(
+            Pagination {
+                cursor: first.next_cursor.clone(),
+                ..pagination.clone()
+            },
+            None,
+        );
+        assert_eq!(second.items, vec![(false, 3), (false, 2), (false, 1)]);
+        assert_eq!(second.next_cursor, None);
+        assert_eq!(second.prev_cursor, Some(Cursor::new(false, 3)));
+
+        let back = paginate(
+            Pagination {
+                cursor: second.prev_cursor,
+                direction: PaginationDirection::Backward,
+                ..pagination
+            },
+            None,
+        );
+        assert_eq!(back.items, first.items);
+        assert_eq!(back.next_cursor, first.next_cursor);
+        assert_eq!(back.prev_cursor, None);
+    }
+
+    #[test]
+    fn paginates_sources_with_skip() {
+        let response = paginate(
+            Pagination {
+                skip: 1,
+                limit: 3,
+                ..page(None, PaginationDirection::Forward)
+            },
+            None,
+        );
+        assert_eq!(response.items, vec![(true, 1), (false, 4), (false, 3)]);
+        assert_eq!(response.offset, 4);
+        assert_eq!(response.next_cursor, Some(Cursor::new(false, 3)));
+        assert_eq!(response.prev_cursor, Some(Cursor::new(true, 1)));
+    }
+
+    #[test]
+    fn paginates_a_single_source() {
+        let pagination = Pagination {
+            limit: 3,
+            ..page(None, PaginationDirection::Forward)
+        };
+
+        let mempool = paginate(pagination.clone(), Some(true));
+        assert_eq!(mempool.items, vec![(true, 2), (true, 1)]);
+        assert_eq!(mempool.next_cursor, None);
+
+        let confirmed = paginate(pagination, Some(false));
+        assert_eq!(confirmed.items, vec![(false, 4), (false, 3), (false, 2)]);
+        assert_eq!(confirmed.next_cursor, Some(Cursor::new(false, 2)));
+    }
+
+    #[test]
+    fn continues_pages_where_a_read_stopped() {
+        // Four confirmed entries, of which only even ones match, read two at a time.
+        let read = |cursor| {
+            paginate_sources(
+                page(cursor, PaginationDirection::Forward),
+                Some(false),
+                Cursor::index,
+                Cursor::new,
+                |from, _, _, _| {
+                    let scanned: Vec<u64> = (1..from.unwrap_or(5)).rev().take(2).collect();
+                    let stopped_at = scanned.last().copied().filter(|index| *index > 1);
+
+                    Ok(Fetched {
+                        entries: scanned
+                            .into_iter()
+                            .filter(|index| index % 2 == 0)
+                            .map(|index| (index, index))
+                            .collect(),
+                        stopped_at,
+                    })
+                },
+            )
+            .unwrap()
+        };
+
+        let first = read(None);
+        assert_eq!(first.items, vec![4]);
+        assert_eq!(first.next_cursor, Some(Cursor::new(false, 3)));
+
+        let second = read(first.next_cursor);
+        assert_eq!(second.items, vec![2]);
+        assert_eq!(second.next_cursor, None);
+        assert_eq!(second.prev_cursor, Some(Cursor::new(false, 2)));
+    }
+
+    #[test]
+    fn rolls_back_address_transactions() {
+        let db = TempDb::open();
+        let a = ScriptBuf::from_bytes(vec![0x51]);
+        let b = ScriptBuf::from_bytes(vec![0x52]);
+
+        db.add_address_transactions_batch(
+            &HashMap::from_iter([(a.clone(), vec![txid(1), txid(2)])]),
+            false,
+        )
+        .unwrap();
+        db.add_address_transactions_batch(
+            &HashMap::from_iter([(a.clone(), vec![txid(3)]), (b.clone(), vec![txid(3)])]),
+            false,
+        )
+        .unwrap();
+
+        let history = |script_pubkey: &ScriptBuf| {
+            Store::get_address_transactions(
+                &*db,
+                script_pubkey,
+                Pagination {
+                    limit: 10,
+                    ..page(None, PaginationDirection::Forward)
+                },
+                Some(false),
+                None,
+            )
+            .unwrap()
+            .items
+        };
+        assert_eq!(history(&a), vec![txid(3), txid(2), txid(1)]);
+        assert_eq!(history(&b), vec![txid(3)]);
+
+        db.delete_address_transactions(&[txid(3)], false).unwrap();
+        assert_eq!(history(&a), vec![txid(2), txid(1)]);
+        assert!(history(&b).is_empty());
+
+        db.add_address_transactions_batch(&HashMap::from_iter([(a.clone(), vec![txid(4)])]), false)
+            .unwrap();
+        assert_eq!(history(&a), vec![txid(4), txid(2), txid(1)]);
+    }
+
+    #[test]
+    fn skips_into_confirmed_rune_transactions() {
+        let db = TempDb::open();
+        let rune_id = RuneId::new(840000, 1);
+        rune_transactions(&db, rune_id);
+
+        let response = Store::get_last_rune_transactions(
+            &*db,
+            &rune_id,
+            Some(Pagination {
+                skip: 1,
+                ..page(None, PaginationDirection::Forward)
+            }),
+            None,
+        )
+        .unwrap();
+        assert_eq!(response.items, vec![txid(11), txid(5)]);
+        assert_eq!(response.offset, 3);
+        assert!(response.next_cursor.is_some());
+        assert!(response.prev_cursor.is_some());
+    }
+
+    fn tx_out(script_pubkey: &ScriptBuf, amount: u128, risky: u128) -> TxOut {
+        let rune = |amount| titan_types_core::RuneAmount {
+            id: RuneId::new(840000, 1),
+            amount,
+        };
+
+        TxOut {
+            runes: (amount > 0).then(|| rune(amount)).into_iter().collect(),
+            risky_runes: (risky > 0).then(|| rune(risky)).into_iter().collect(),
+            value: 546,
+            spent: SpentStatus::Unspent,
+            script_pubkey: script_pubkey.clone(),
+        }
+    }
+
+    #[test]
+    fn applies_and_rolls_back_rune_balances() {
+        let db = TempDb::open();
+        let script_pubkey = ScriptBuf::from_bytes(vec![0x51]);
+        let balances = || Store::get_address_rune_balances(&*db, &script_pubkey, None).unwrap();
+
+        let a = tx_out(&script_pubkey, 100, 5);
+        let b = tx_out(&script_pubkey, 50, 0);
+        let c = tx_out(&script_pubkey, 30, 0);
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.rune_balances.add_output(&a);
+        update.rune_balances.add_output(&b);
+        db.batch_update(&update, false).unwrap();
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update.rune_balances.add_spend(&a);
+        update.rune_balances.add_output(&c);
+        db.batch_update(&update, true).unwrap();
+
+        assert_eq!(
+            balances(),
+            vec![AddressRuneBalance {
+                rune_id: RuneId::new(840000, 1),
+                confirmed: 150,
+                confirmed_risky: 5,
+                pending: 30,
+                risky: 0,
+                pending_spent: 105,
+            }]
+        );
+
+        let mut rollback = BatchRollback::new(0);
+        rollback.rune_balances.remove_spend(&a);
+        rollback.rune_balances.remove_output(&c);
+        db.batch_rollback(&rollback, true).unwrap();
+        assert!(db
+            .get_address_rune_balances(&script_pubkey, None, true)
+            .unwrap()
+            .is_empty());
+
+        let mut rollback = BatchRollback::new(0);
+        rollback.rune_balances.remove_output(&b);
+        db.batch_rollback(&rollback, false).unwrap();
+        let balance = &balances()[0];
+        assert_eq!((balance.confirmed, balance.confirmed_risky), (100, 5));
+
+        let mut rollback = BatchRollback::new(0);
+        rollback.rune_balances.remove_output(&a);
+        db.batch_rollback(&rollback, false).unwrap();
+        assert!(balances().is_empty());
+    }
+
+    #[test]
+    fn tracks_and_pages_rune_holders() {
+        let db = TempDb::open();
+        let rune_id = RuneId::new(840000, 1);
+        let scripts = (1..=3)
+            .map(|n| ScriptBuf::from_bytes(vec![0x50 + n]))
+            .collect::<Vec<_>>();
+        let outputs = [100, 300, 200]
+            .iter()
+            .zip(&scripts)
+            .map(|(amount, script_pubkey)| tx_out(script_pubkey, *amount, 0))
+            .collect::<Vec<_>>();
+
+        let holder_count = || db.get_rune_holder_counts(&[rune_id]).unwrap()[&rune_id];
+        let holders = |cursor: Option<Cursor>, direction| {
+            Store::get_rune_holders(&*db, &rune_id, page(cursor, direction)).unwrap()
+        };
+
+        let mut update = BatchUpdate::new(0, 0, 0);
+        for output in &outputs {
+            update.rune_balances.add_output(output);
+        }
+        db.batch_update(&update, false).unwrap();
+
+        // Mempool balances don't make holders.
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update
+            .rune_balances
+            .add_output(&tx_out(&ScriptBuf::from_bytes(vec![0x60]), 1, 0));
+        db.batch_update(&update, true).unwrap();
+
+        assert_eq!(holder_count(), 3);
+
+        let first = holders(None, PaginationDirection::Forward);
+        assert_eq!(
+            first.items,
+            vec![(scripts[1].clone(), 300), (scripts[2].clone(), 200)]
+        );
+        assert!(first.prev_cursor.is_none());
+
+        let second = holders(first.next_cursor.clone(), PaginationDirection::Forward);
+        assert_eq!(second.items, vec![(scripts[0].clone(), 100)]);
+        assert!(second.next_cursor.is_none());
+
+        let back = holders(second.prev_cursor, PaginationDirection::Backward);
+        assert_eq!(back.items, first.items);
+        assert_eq!(back.next_cursor, first.next_cursor);
+
+        let mut rollback = BatchRollback::new(0);
+        rollback.rune_balances.remove_output(&outputs[2]);
+        db.batch_rollback(&rollback, false).unwrap();
+        assert_eq!(holder_count(), 2);
+
+        // A larger balance moves the holder to the front.
+        let mut update = BatchUpdate::new(0, 0, 0);
+        update
+            .rune_balances
+            .add_output(&tx_out(&scripts[0], 250, 0));
+        db.batch_update(&update, false).unwrap();
+        assert_eq!(holder_count(), 2);
+        assert_eq!(
+            holders(None, PaginationDirection::Forward).items,
+            vec![(scripts[0].clone(), 350), (scripts[1].clone(), 300)]
+        );
+    }
+}
@@ -4,7 +4,7 @@ use crossbeam_channel::{bounded, Sender};
 use tracing::{debug, error, info};
 
 use crate::{
-    index::updater::store_lock::StoreWithLock,
+    index::{updater::store_lock::StoreWithLock, Gauge},
     models::{BatchDelete, BatchUpdate},
 };
 
@@ -15,12 +15,14 @@ pub struct BatchDB {
 
 pub struct BgWriterSettings {
     pub max_async_batches: usize,
+    pub queue_depth: Gauge,
 }
 
 pub struct BgWriter {
     handle: Option<std::thread::JoinHandle<()>>,
     sender: Option<Sender<BgMessage>>,
     pending_batches: Vec<Arc<BatchDB>>,
+    queue_depth: Gauge,
 }
 
 struct BgMessage {
@@ -31,6 +33,7 @@ struct BgMessage {
 impl BgWriter {
     pub fn start(db: Arc<StoreWithLock>, settings: BgWriterSettings) -> Self {
         let (tx, rx) = bounded::<BgMessage>(settings.max_async_batches);
+        let queue_depth = settings.queue_depth.clone();
 
         let handle = thread::Builder::new()
             .name("rocksdb-writer".into())
@@ -54,6 +57,8 @@ impl BgWriter {
 
                     debug!("Flushed delete: {} in {:?}", batch.delete, start.elapsed());
 
+                    queue_depth.dec();
+
                     // Signal completion to any waiter.
                     if let Some(done_tx) = notify {
                         info!("BgWriter: sending notify");
@@ -70,6 +75,7 @@ impl BgWriter {
             handle: Some(handle),
             sender: Some(tx),
             pending_batches: Vec::new(),
+            queue_depth: settings.queue_depth,
         }
     }
 
@@ -97,10 +103,12 @@ impl BgWriter {
         self.pending_batches.push(batch.clone());
 
         if let Some(sender) = &self.sender {
+            self.queue_depth.inc();
             if let Err(e) = sender.send(BgMessage {
                 batch,
                 notify: None,
             }) {
+                self.queue_depth.dec();
                 error!("Failed to send batch to background writer: {:?}", e);
             }
         }
@@ -114,10 +122,12 @@ impl BgWriter {
         let (tx, rx) = bounded::<()>(1);
 
         if let Some(sender) = &self.sender {
+            self.queue_depth.inc();
             if let Err(e) = sender.send(BgMessage {
                 batch,
                 notify: Some(tx),
             }) {
+                self.queue_depth.dec();
                 error!("Failed to send batch to background writer: {:?}", e);
             }
         }
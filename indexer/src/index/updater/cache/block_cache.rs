@@ -18,10 +18,10 @@ use crate::{
             store_lock::StoreWithLock,
             transaction::TransactionStore,
         },
-        Chain, Settings, StoreError,
+        Chain, Gauge, Settings, StoreError,
     },
     models::{
-        BatchDelete, BatchUpdate, BlockId, RuneEntry, TransactionStateChange,
+        BatchDelete, BatchUpdate, BlockId, RuneEntry, RuneHistory, TransactionStateChange,
         TransactionStateChangeInput,
     },
 };
@@ -36,10 +36,11 @@ pub struct BlockCacheSettings {
     pub index_spent_outputs: bool,
     pub rune_cache_size: usize,
     pub outpoint_cache_size: usize,
+    pub bg_writer_queue_depth: Gauge,
 }
 
 impl BlockCacheSettings {
-    pub fn new(settings: &Settings) -> Self {
+    pub fn new(settings: &Settings, bg_writer_queue_depth: Gauge) -> Self {
         Self {
             max_recoverable_reorg_depth: settings.max_recoverable_reorg_depth(),
             chain: settings.chain,
@@ -48,6 +49,7 @@ impl BlockCacheSettings {
             max_async_batches: 8,
             rune_cache_size: 1000,
             outpoint_cache_size: 10_000_000,
+            bg_writer_queue_depth,
         }
     }
 }
@@ -101,6 +103,7 @@ impl BlockCache {
 
         let rune_cache_size = NonZeroUsize::new(settings.rune_cache_size).unwrap();
         let max_async_batches = settings.max_async_batches;
+        let bg_writer_queue_depth = settings.bg_writer_queue_depth.clone();
         let outpoint_cache_size = NonZeroUsize::new(settings.outpoint_cache_size).unwrap();
 
         Ok(Self {
@@ -119,7 +122,13 @@ impl BlockCache {
             runes: CLruCache::new(rune_cache_size),
             rune_ids: HashSet::default(),
 
-            bg_writer: BgWriter::start(db, BgWriterSettings { max_async_batches }),
+            bg_writer: BgWriter::start(
+                db,
+                BgWriterSettings {
+                    max_async_batches,
+                    queue_depth: bg_writer_queue_depth,
+                },
+            ),
         })
     }
 
@@ -466,6 +475,7 @@ impl TransactionStore for BlockCache {
             self.update
                 .script_pubkeys_outpoints
                 .insert(outpoint.clone(), script_pubkey);
+            self.update.rune_balances.add_output(&tx_out);
         }
 
         self.outpoints.put(outpoint, tx_out.clone());
@@ -493,6 +503,7 @@ impl TransactionStore for BlockCache {
             self.update
                 .script_pubkeys_outpoints
                 .insert(outpoint.previous_outpoint, script_pubkey);
+            self.update.rune_balances.remove_output(&tx_out);
         }
 
         self.update
@@ -653,4 +664,22 @@ impl TransactionStore for BlockCache {
             .or_insert(vec![])
             .push(txid);
     }
+
+    fn add_rune_history(&mut self, rune_id: RuneId, height: u64, history: RuneHistory) {
+        self.update
+            .rune_history
+            .entry((rune_id, height))
+            .or_default()
+            .merge(&history);
+    }
+
+    fn add_address_transaction(&mut self, script_pubkey: ScriptBuf, txid: SerializedTxid) {
+        if self.settings.index_addresses {
+            self.update
+                .address_transactions
+                .entry(script_pubkey)
+                .or_insert(vec![])
+                .push(txid);
+        }
+    }
 }
@@ -6,8 +6,8 @@ use {
             Chain, Settings,
         },
         models::{
-            BatchDelete, BatchUpdate, BlockId, Inscription, RuneEntry, TransactionStateChange,
-            TransactionStateChangeInput,
+            BatchDelete, BatchUpdate, BlockId, Inscription, RuneEntry, RuneHistory,
+            TransactionStateChange, TransactionStateChangeInput,
         },
     },
     bitcoin::{consensus, ScriptBuf, Transaction},
@@ -173,13 +173,14 @@ impl TransactionStore for MempoolCache {
         tx_out: TxOut,
         script_pubkey: ScriptBuf,
     ) {
-        self.update.txouts.insert(outpoint, tx_out);
-
         if self.settings.index_addresses {
             self.update
                 .script_pubkeys_outpoints
                 .insert(outpoint, script_pubkey);
+            self.update.rune_balances.add_output(&tx_out);
         }
+
+        self.update.txouts.insert(outpoint, tx_out);
     }
 
     fn set_spent_tx_out(
@@ -189,6 +190,10 @@ impl TransactionStore for MempoolCache {
     ) -> Result<()> {
         match self.get_tx_out(&outpoint.previous_outpoint) {
             Ok(tx_out) => {
+                if self.settings.index_addresses {
+                    self.update.rune_balances.add_spend(&tx_out);
+                }
+
                 let mut tx_out = tx_out;
                 tx_out.spent = SpentStatus::Spent(spent.clone());
                 self.update
@@ -305,4 +310,18 @@ impl TransactionStore for MempoolCache {
             .or_insert(vec![])
             .push(txid);
     }
+
+    fn add_rune_history(&mut self, _rune_id: RuneId, _height: u64, _history: RuneHistory) {
+        // Rune history is per confirmed block; mempool transactions have none.
+    }
+
+    fn add_address_transaction(&mut self, script_pubkey: ScriptBuf, txid: SerializedTxid) {
+        if self.settings.index_addresses {
+            self.update
+                .address_transactions
+                .entry(script_pubkey)
+                .or_insert(vec![])
+                .push(txid);
+        }
+    }
 }
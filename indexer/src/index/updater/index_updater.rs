@@ -6,16 +6,16 @@ use {
     crate::{
         bitcoin_rpc::{RpcClientError, RpcClientPool, RpcClientPoolError},
         index::{
-            metrics::Metrics,
+            metrics::{Counter, Gauge, Metrics},
             store::Store,
             updater::{
                 cache::{BlockCache, BlockCacheSettings, MempoolCache, MempoolCacheSettings},
                 events::Events,
-                transaction::{TransactionParser, TransactionUpdater},
+                transaction::{TransactionParser, TransactionStore, TransactionUpdater},
             },
             Chain, Settings, StoreError,
         },
-        models::{BlockId, RuneEntry},
+        models::{BlockId, RuneEntry, TransactionStateChange},
     },
     bitcoin::{
         constants::SUBSIDY_HALVING_INTERVAL, hex::HexToArrayError, Block as BitcoinBlock,
@@ -41,7 +41,10 @@ use {
     },
     store_lock::StoreWithLock,
     thiserror::Error,
-    titan_types_core::{Block, Event, MempoolEntry, RuneId, SerializedTxid},
+    titan_types_core::{
+        Block, Event, MempoolEntry, ReplacementReason, RuneId, SerializedOutPoint, SerializedTxid,
+        SpentStatus, TransactionReplacement, TxOut,
+    },
     titan_types_core::{Rune, SpacedRune},
     tokio::sync::mpsc::{error::SendError, Sender},
     tracing::{debug, error, info, warn},
@@ -120,6 +123,10 @@ pub struct Updater {
 
     // monitoring
     latency: HistogramVec,
+    block_height: Gauge,
+    mempool_size: Gauge,
+    reorgs: Counter,
+    bg_writer_queue_depth: Gauge,
 }
 
 impl Updater {
@@ -146,6 +153,22 @@ impl Updater {
                 prometheus::HistogramOpts::new("indexer_latency", "Indexer latency"),
                 &["method"],
             ),
+            block_height: metrics.gauge(prometheus::Opts::new(
+                "indexer_block_height",
+                "Height of the last indexed block",
+            )),
+            mempool_size: metrics.gauge(prometheus::Opts::new(
+                "indexer_mempool_size",
+                "Number of transactions in the indexed mempool",
+            )),
+            reorgs: metrics.counter(prometheus::Opts::new(
+                "indexer_reorgs_total",
+                "Number of recoverable reorgs handled",
+            )),
+            bg_writer_queue_depth: metrics.gauge(prometheus::Opts::new(
+                "indexer_bg_writer_queue_depth",
+                "Number of batches waiting to be written by the background writer",
+            )),
         }
     }
 
@@ -234,6 +257,7 @@ impl Updater {
         let chain_info = rpc_client.get_blockchain_info()?;
 
         if self.is_already_at_tip(&chain_info)? {
+            self.block_height.set(chain_info.blocks as i64);
             self.mark_as_at_tip();
             return Ok(());
         }
@@ -253,7 +277,10 @@ impl Updater {
         let mut chain_info = bitcoin_block_client.get_blockchain_info()?;
 
         // Not at tip – proceed with full indexing workflow.
-        let mut cache = BlockCache::new(self.db.clone(), BlockCacheSettings::new(&self.settings))?;
+        let mut cache = BlockCache::new(
+            self.db.clone(),
+            BlockCacheSettings::new(&self.settings, self.bg_writer_queue_depth.clone()),
+        )?;
         let mut events = Events::new();
 
         let mut indexing_first_block = true;
@@ -306,6 +333,10 @@ impl Updater {
                         cache.flush_sync()?;
 
                         self.handle_reorg(height, depth)?;
+                        self.reorgs.inc();
+                        self.block_height
+                            .set((height - depth).saturating_sub(1) as i64);
+
                         if let Some(sender) = &self.sender {
                             if let Err(e) = sender.blocking_send(Event::Reorg { height, depth }) {
                                 error!("Failed to send reorg event: {:?}", e);
@@ -332,6 +363,7 @@ impl Updater {
                 });
 
                 cache.set_new_block(block);
+                self.block_height.set(cache.get_block_height_tip() as i64);
 
                 if cache.should_flush(commit_interval) {
                     info!("Flushing cache");
@@ -402,7 +434,9 @@ impl Updater {
         Ok(())
     }
 
-    pub fn index_mempool(&self) -> Result<()> {
+    /// Syncs the indexed mempool with the node. Returns whether any mempool
+    /// transaction was added, removed or updated.
+    pub fn index_mempool(&self) -> Result<bool> {
         let _timer = self
             .latency
             .with_label_values(&["index_mempool"])
@@ -428,6 +462,8 @@ impl Updater {
 
         drop(lock);
 
+        self.mempool_size.set(current_mempool.len() as i64);
+
         // Find new transactions to index
         let (new_txs, new_txs_with_mempool_entry): (
             Vec<SerializedTxid>,
@@ -456,12 +492,10 @@ impl Updater {
         let updated_txids =
             self.update_mempool_entries(&mut cache, &stored_mempool, &current_mempool);
 
-        self.send_mempool_events(
-            &mut events,
-            new_txs_with_mempool_entry,
-            removed_txs.clone(),
-            updated_txids,
-        )?;
+        let mempool_changed =
+            new_txs_len > 0 || !removed_txs.is_empty() || !updated_txids.is_empty();
+
+        self.send_mempool_events(&mut events, new_txs_with_mempool_entry, updated_txids)?;
 
         if new_txs_len > 0 {
             let tx_map = self.choose_mempool_transactions_to_index(&new_txs)?;
@@ -490,6 +524,11 @@ impl Updater {
 
         cache.flush()?;
 
+        if !removed_txs.is_empty() {
+            // Look for what replaced them while their inputs are still indexed.
+            self.mark_replaced(&removed_txs, &current_mempool, &mut events)?;
+        }
+
         if let Err(e) = events.send_events(&self.sender) {
             if !self.shutdown_flag.load(Ordering::SeqCst) {
                 error!("Failed to send events: {:?}", e);
@@ -521,7 +560,7 @@ impl Updater {
             .map_err(|_| UpdaterError::Mutex)?
             .clear();
 
-        Ok(())
+        Ok(mempool_changed)
     }
 
     fn update_mempool_entries(
@@ -549,19 +588,12 @@ impl Updater {
         &self,
         events: &mut Events,
         new_txids: Vec<(SerializedTxid, MempoolEntry)>,
-        removed_txids: Vec<SerializedTxid>,
         updated_txids: Vec<(SerializedTxid, MempoolEntry)>,
     ) -> Result<()> {
         if !new_txids.is_empty() {
             events.add_event(Event::MempoolTransactionsAdded { txids: new_txids });
         }
 
-        if !removed_txids.is_empty() {
-            events.add_event(Event::MempoolTransactionsReplaced {
-                txids: removed_txids,
-            });
-        }
-
         if !updated_txids.is_empty() {
             events.add_event(Event::MempoolEntriesUpdated {
                 txids: updated_txids,
@@ -571,6 +603,157 @@ impl Updater {
         Ok(())
     }
 
+    /// Stores why each of `removed_txids` left the mempool and queues the event
+    /// announcing it.
+    fn mark_replaced(
+        &self,
+        removed_txids: &[SerializedTxid],
+        current_mempool: &HashMap<SerializedTxid, GetMempoolEntryResult>,
+        events: &mut Events,
+    ) -> Result<()> {
+        let replacements = self.find_replacements(removed_txids, current_mempool)?;
+        if !replacements.is_empty() {
+            self.db.write().set_replacements(&replacements)?;
+        }
+
+        events.add_event(Event::MempoolTransactionsReplaced {
+            txids: removed_txids.to_vec(),
+            replacements,
+        });
+
+        Ok(())
+    }
+
+    /// Works out why each of `removed_txids` left the mempool. Transactions that were
+    /// mined are skipped. The rest were replaced by whatever now spends one of their
+    /// inputs, in the mempool or in a block, inherit the replacement of a removed
+    /// parent, or were otherwise evicted.
+    fn find_replacements(
+        &self,
+        removed_txids: &[SerializedTxid],
+        current_mempool: &HashMap<SerializedTxid, GetMempoolEntryResult>,
+    ) -> Result<Vec<TransactionReplacement>> {
+        let db = self.db.read();
+
+        let confirming_blocks = db.get_transaction_confirming_blocks(removed_txids)?;
+        let unmined: Vec<SerializedTxid> = removed_txids
+            .iter()
+            .filter(|txid| !matches!(confirming_blocks.get(*txid), Some(Some(_))))
+            .cloned()
+            .collect();
+
+        if unmined.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let unmined_set: HashSet<SerializedTxid> = unmined.iter().cloned().collect();
+        let state_changes = db.get_txs_state_changes(&unmined, true)?;
+        let outpoints: Vec<SerializedOutPoint> = state_changes
+            .values()
+            .flat_map(|state_change| state_change.inputs.iter())
+            .map(|input| input.previous_outpoint)
+            .collect();
+
+        // Spends recorded when the outputs were indexed, which is where block spends
+        // end up, and the same outputs with the latest mempool spends applied.
+        let tx_outs = db.get_tx_outs(&outpoints, None)?;
+        let mut mempool_tx_outs = db.get_tx_outs_with_mempool_spent_update(&outpoints, None)?;
+        // Unless addresses are indexed, mempool spends of confirmed outputs are only
+        // kept in the mempool copy of the output.
+        mempool_tx_outs.extend(db.get_tx_outs(&outpoints, Some(true))?);
+
+        let spender = |tx_outs: &HashMap<SerializedOutPoint, TxOut>,
+                       outpoint: &SerializedOutPoint,
+                       txid: &SerializedTxid| {
+            match tx_outs.get(outpoint).map(|tx_out| &tx_out.spent) {
+                Some(SpentStatus::Spent(spender)) if spender.txid != *txid => Some(spender.txid),
+                _ => None,
+            }
+        };
+
+        let mut replaced_by: HashMap<SerializedTxid, (ReplacementReason, Option<SerializedTxid>)> =
+            HashMap::default();
+        for txid in &unmined {
+            let Some(state_change) = state_changes.get(txid) else {
+                continue;
+            };
+
+            for input in &state_change.inputs {
+                let outpoint = &input.previous_outpoint;
+
+                if let Some(spender) = spender(&mempool_tx_outs, outpoint, txid)
+                    .filter(|spender| current_mempool.contains_key(spender))
+                {
+                    replaced_by.insert(*txid, (ReplacementReason::Rbf, Some(spender)));
+                    break;
+                }
+
+                if let Some(spender) = spender(&tx_outs, outpoint, txid) {
+                    replaced_by.insert(*txid, (ReplacementReason::BlockConflict, Some(spender)));
+                    break;
+                }
+
+                // Outputs are only pruned once spent in a block.
+                if !tx_outs.contains_key(outpoint)
+                    && !unmined_set.contains(&outpoint.to_serialized_txid())
+                {
+                    replaced_by.insert(*txid, (ReplacementReason::BlockConflict, None));
+                    break;
+                }
+            }
+        }
+
+        // Descendants go with their replaced parents.
+        loop {
+            let mut changed = false;
+            for txid in &unmined {
+                if replaced_by.contains_key(txid) {
+                    continue;
+                }
+
+                let inherited = state_changes.get(txid).and_then(|state_change| {
+                    state_change
+                        .inputs
+                        .iter()
+                        .find_map(|input| {
+                            replaced_by.get(&input.previous_outpoint.to_serialized_txid())
+                        })
+                        .cloned()
+                });
+
+                if let Some(inherited) = inherited {
+                    replaced_by.insert(*txid, inherited);
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Ok(unmined
+            .into_iter()
+            .map(|txid| {
+                let (reason, replaced_by) = replaced_by
+                    .remove(&txid)
+                    .unwrap_or((ReplacementReason::Evicted, None));
+
+                TransactionReplacement {
+                    txid,
+                    replaced_by,
+                    reason,
+                    timestamp,
+                }
+            })
+            .collect())
+    }
+
     fn choose_mempool_transactions_to_index(
         &self,
         new_txs: &Vec<SerializedTxid>,
@@ -750,6 +933,36 @@ impl Updater {
         Ok(())
     }
 
+    /// Parses `tx` against the current UTXO set, mempool included, without writing
+    /// anything. Also returns the outputs it spends that are known to the index.
+    pub fn decode_tx(
+        &self,
+        tx: &Transaction,
+    ) -> Result<(TransactionStateChange, HashMap<SerializedOutPoint, TxOut>)> {
+        let mut cache =
+            MempoolCache::new(self.db.clone(), MempoolCacheSettings::new(&self.settings))?;
+
+        let rpc_client = self.bitcoin_rpc_pool.get()?;
+        let mut transaction_parser = TransactionParser::new(
+            &rpc_client,
+            self.settings.chain,
+            cache.get_block_count(),
+            true,
+        )?;
+
+        let result = transaction_parser.parse(&mut cache, 0, tx)?;
+
+        let outpoints = tx
+            .input
+            .iter()
+            .map(|tx_in| tx_in.previous_output.into())
+            .collect::<Vec<SerializedOutPoint>>();
+
+        let prev_outputs = cache.get_tx_outs(&outpoints)?;
+
+        Ok((result, prev_outputs))
+    }
+
     fn index_tx(
         &self,
         txid: &SerializedTxid,
@@ -968,7 +1181,18 @@ impl Updater {
                     )?
                 };
 
-                sender.blocking_send(Event::TransactionsReplaced { txids: not_exists })?;
+                let replacements = {
+                    let db = self.db.read();
+                    db.get_replacements(&not_exists)?
+                };
+
+                sender.blocking_send(Event::TransactionsReplaced {
+                    replacements: not_exists
+                        .iter()
+                        .filter_map(|txid| replacements.get(txid).cloned())
+                        .collect(),
+                    txids: not_exists,
+                })?;
             }
 
             // Final added set: normal newly-added UNION
@@ -1030,3 +1254,207 @@ impl Updater {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            db::TempDb,
+            index::tests::{index, mempool_entry, settings, transaction},
+            models::BatchUpdate,
+        },
+        bitcoin::{hashes::Hash, Amount, BlockHash, OutPoint, ScriptBuf, Txid, Wtxid},
+        bitcoincore_rpc::json::GetMempoolEntryResultFees,
+        titan_types_core::{SpenderReference, TransactionStatus},
+        tokio::sync::mpsc,
+    };
+
+    fn mempool_entry_result() -> GetMempoolEntryResult {
+        GetMempoolEntryResult {
+            vsize: 100,
+            weight: Some(400),
+            time: 0,
+            height: 101,
+            descendant_count: 1,
+            descendant_size: 100,
+            ancestor_count: 1,
+            ancestor_size: 100,
+            wtxid: Txid::from_raw_hash(Wtxid::all_zeros().to_raw_hash()),
+            fees: GetMempoolEntryResultFees {
+                base: Amount::from_sat(1000),
+                modified: Amount::from_sat(1000),
+                ancestor: Amount::from_sat(1000),
+                descendant: Amount::from_sat(1000),
+            },
+            depends: vec![],
+            spent_by: vec![],
+            bip125_replaceable: true,
+            unbroadcast: None,
+        }
+    }
+
+    #[test]
+    fn marks_replaced_transactions() {
+        let db = TempDb::open();
+        let (sender, mut receiver) = mpsc::channel(16);
+        let updater = Updater::new(
+            db.shared(),
+            RpcClientPool::new(Arc::new(settings()), 1),
+            settings(),
+            &Metrics::new(),
+            Arc::new(AtomicBool::new(false)),
+            Some(sender),
+        );
+        let script_pubkey = ScriptBuf::from_bytes(vec![0x51]);
+        let unspent = TxOut {
+            runes: vec![],
+            risky_runes: vec![],
+            spent: SpentStatus::Unspent,
+            value: 546,
+            script_pubkey: script_pubkey.clone(),
+        };
+        let block = |height| BlockId {
+            hash: BlockHash::from_byte_array([height as u8; 32]),
+            height,
+        };
+
+        // A confirmed transaction funding the mempool ones.
+        let funding = transaction(&[OutPoint::null()], vec![script_pubkey.clone(); 4]);
+        let funding_txid = SerializedTxid::from(funding.compute_txid());
+        let funding_outpoint = |vout| OutPoint::new(funding.compute_txid(), vout);
+
+        let mut update = BatchUpdate::new(0, 101, 0);
+        update.transactions.insert(funding_txid, funding.clone());
+        update
+            .transaction_confirming_block
+            .insert(funding_txid, block(100));
+        for vout in 0..4 {
+            update
+                .txouts
+                .insert(funding_outpoint(vout).into(), unspent.clone());
+        }
+        db.batch_update(&update, false).unwrap();
+
+        let submit = |inputs: &[OutPoint], outputs: usize| {
+            let tx = transaction(inputs, vec![script_pubkey.clone(); outputs]);
+            let txid = SerializedTxid::from(tx.compute_txid());
+            updater
+                .index_new_submitted_tx(&txid, &tx, mempool_entry(100, 100, vec![]))
+                .unwrap();
+            (txid, OutPoint::new(tx.compute_txid(), 0))
+        };
+
+        let (replaced, replaced_outpoint) = submit(&[funding_outpoint(0)], 1);
+        let (conflicted, _) = submit(&[funding_outpoint(1)], 1);
+        let (child, _) = submit(&[replaced_outpoint], 1);
+        let (evicted, _) = submit(&[funding_outpoint(2)], 1);
+        let (mined, _) = submit(&[funding_outpoint(3)], 1);
+        // Spends the input of the first one again, so it replaces it and its child.
+        let (replacement, _) = submit(&[funding_outpoint(0)], 2);
+        while let Ok(Event::TransactionSubmitted { .. }) = receiver.try_recv() {}
+
+        // A block confirms one of them, and a conflict of another.
+        let block_spender = SerializedTxid::from([9; 32]);
+        let mut update = BatchUpdate::new(0, 102, 0);
+        update
+            .transaction_confirming_block
+            .insert(mined, block(101));
+        update.txouts.insert(
+            funding_outpoint(1).into(),
+            TxOut {
+                spent: SpentStatus::Spent(SpenderReference {
+                    txid: block_spender,
+                    vin: 0,
+                }),
+                ..unspent
+            },
+        );
+        db.batch_update(&update, false).unwrap();
+
+        let removed = vec![replaced, conflicted, child, evicted, mined];
+        let mut current_mempool = HashMap::default();
+        current_mempool.insert(replacement, mempool_entry_result());
+
+        let mut events = Events::new();
+        updater
+            .mark_replaced(&removed, &current_mempool, &mut events)
+            .unwrap();
+
+        // Mined transactions weren't replaced.
+        let expected = vec![
+            (replaced, Some(replacement), ReplacementReason::Rbf),
+            (
+                conflicted,
+                Some(block_spender),
+                ReplacementReason::BlockConflict,
+            ),
+            (child, Some(replacement), ReplacementReason::Rbf),
+            (evicted, None, ReplacementReason::Evicted),
+        ];
+        let summary = |replacements: &[TransactionReplacement]| {
+            replacements
+                .iter()
+                .map(|replacement| {
+                    (
+                        replacement.txid,
+                        replacement.replaced_by,
+                        replacement.reason,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let replacements = match events.take_all().as_slice() {
+            [Event::MempoolTransactionsReplaced {
+                txids,
+                replacements,
+            }] => {
+                assert_eq!(*txids, removed);
+                replacements.clone()
+            }
+            events => panic!("unexpected events: {events:?}"),
+        };
+        assert_eq!(summary(&replacements), expected);
+
+        // Once they're gone, their status and the transaction event tell what
+        // replaced them.
+        updater.remove_txs(&removed, true).unwrap();
+        updater
+            .transaction_update
+            .write()
+            .unwrap()
+            .update_mempool(TransactionChangeSet {
+                added: HashSet::default(),
+                removed: removed.iter().copied().collect(),
+            });
+        updater.notify_tx_updates(true).unwrap();
+
+        match receiver.try_recv().unwrap() {
+            Event::TransactionsReplaced {
+                txids,
+                replacements: event_replacements,
+            } => {
+                assert_eq!(
+                    txids.iter().copied().collect::<HashSet<_>>(),
+                    removed.iter().copied().collect::<HashSet<_>>()
+                );
+                assert_eq!(
+                    event_replacements.into_iter().collect::<HashSet<_>>(),
+                    replacements.iter().cloned().collect::<HashSet<_>>()
+                );
+            }
+            event => panic!("unexpected event: {event:?}"),
+        }
+
+        let index = index(&db);
+        assert_eq!(
+            index.get_transaction_status(&replaced).unwrap(),
+            TransactionStatus::replaced(replacements[0].clone())
+        );
+        assert_eq!(
+            index.get_transaction_status(&mined).unwrap(),
+            block(101).into_transaction_status()
+        );
+    }
+}
@@ -129,9 +129,13 @@ impl<'a> Rollback<'a> {
         }
 
         // Remove tx_outs
-        for (vout, _tx_out) in transaction.outputs.iter().enumerate() {
+        for (vout, tx_out) in transaction.outputs.iter().enumerate() {
             let outpoint = SerializedOutPoint::from_txid_vout(txid, vout as u32);
             self.cache.add_outpoint_to_delete(outpoint);
+
+            if self.settings.index_addresses {
+                self.cache.revert_output_balance(tx_out);
+            }
         }
 
         // Remove etched rune if any.
@@ -172,6 +176,25 @@ impl<'a> Rollback<'a> {
             self.update_burn_balance(rune_id, -(amount.n() as i128))?;
         }
 
+        // Remove the rune history of the block that confirmed it.
+        if !self.cache.mempool {
+            let rune_ids = transaction.rune_ids();
+            if !rune_ids.is_empty() {
+                match self.store.get_transaction_confirming_block(txid) {
+                    Ok(block_id) => {
+                        for rune_id in rune_ids {
+                            self.cache
+                                .add_rune_history_to_delete(rune_id, block_id.height);
+                        }
+                    }
+                    Err(e) if e.is_not_found() => {
+                        warn!("Confirming block of {} not found", txid);
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
         // Finally remove the transaction.
         self.cache.add_tx_to_delete(txid.clone());
 
@@ -185,6 +208,10 @@ impl<'a> Rollback<'a> {
     ) -> Result<()> {
         match self.cache.get_tx_out(&outpoint.previous_outpoint) {
             Ok(tx_out) => {
+                if self.settings.index_addresses && !matches!(tx_out.spent, SpentStatus::Unspent) {
+                    self.cache.revert_spend_balance(&tx_out);
+                }
+
                 let mut tx_out = tx_out;
                 tx_out.spent = spent;
                 self.cache.set_tx_out(outpoint.previous_outpoint, tx_out);
@@ -102,6 +102,10 @@ impl<'a> RollbackCache<'a> {
         self.update.delete_all_rune_transactions.push(rune_id);
     }
 
+    pub fn add_rune_history_to_delete(&mut self, rune_id: RuneId, height: u64) {
+        self.update.rune_history_to_delete.push((rune_id, height));
+    }
+
     pub fn get_outpoints_to_script_pubkey(
         &self,
         outpoints: &[SerializedOutPoint],
@@ -124,6 +128,20 @@ impl<'a> RollbackCache<'a> {
         self.update.prev_outpoints_to_delete.extend(outpoints);
     }
 
+    /// Takes the runes of a reverted output out of its address balance.
+    pub fn revert_output_balance(&mut self, tx_out: &TxOut) {
+        self.update.rune_balances.remove_output(tx_out);
+    }
+
+    /// Gives the runes of an output that is no longer spent back to its address balance.
+    pub fn revert_spend_balance(&mut self, tx_out: &TxOut) {
+        if self.mempool {
+            self.update.rune_balances.remove_spend(tx_out);
+        } else {
+            self.update.rune_balances.add_output(tx_out);
+        }
+    }
+
     pub fn flush(&mut self) -> Result<()> {
         info!("Flushing rollback cache: {}", self.update);
         self.db.batch_rollback(&self.update, self.mempool)?;
@@ -8,7 +8,8 @@ use titan_types_core::{
 use crate::{
     index::StoreError,
     models::{
-        BlockId, Inscription, RuneEntry, TransactionStateChange, TransactionStateChangeInput,
+        BlockId, Inscription, RuneEntry, RuneHistory, TransactionStateChange,
+        TransactionStateChangeInput,
     },
 };
 
@@ -44,4 +45,6 @@ pub trait TransactionStore {
         tx_state_changes: TransactionStateChange,
     );
     fn add_rune_transaction(&mut self, rune_id: RuneId, txid: SerializedTxid);
+    fn add_rune_history(&mut self, rune_id: RuneId, height: u64, history: RuneHistory);
+    fn add_address_transaction(&mut self, script_pubkey: ScriptBuf, txid: SerializedTxid);
 }
@@ -3,7 +3,7 @@ use {
     crate::{
         bitcoin_rpc::BitcoinCoreRpcResultExt,
         index::{Chain, StoreError},
-        models::{Lot, TransactionStateChange, TransactionStateChangeInput},
+        models::{artifact_kind, Lot, TransactionStateChange, TransactionStateChangeInput},
         util::IntoUsize,
     },
     bitcoin::{consensus::encode, OutPoint, Transaction},
@@ -73,8 +73,16 @@ impl<'client> TransactionParser<'client> {
             })
             .collect::<Result<Vec<_>>>()?;
 
+        let artifact = if self.should_index_runes {
+            Runestone::decipher(tx)
+        } else {
+            None
+        };
+
+        let artifact_kind = artifact.as_ref().map(artifact_kind);
+
         let (allocated, risky_allocated, minted, etched, burned) = if self.should_index_runes {
-            self.parse_runes(store, prev_outputs, tx_index, tx)?
+            self.parse_runes(store, prev_outputs, tx_index, tx, artifact)?
         } else {
             (
                 vec![HashMap::default(); tx.output.len()],
@@ -132,6 +140,7 @@ impl<'client> TransactionParser<'client> {
             etched,
             minted,
             is_coinbase: tx.is_coinbase(),
+            artifact: artifact_kind,
         };
 
         Ok(transaction_state_change)
@@ -143,6 +152,7 @@ impl<'client> TransactionParser<'client> {
         prev_outputs: HashMap<SerializedOutPoint, TxOut>,
         tx_index: u32,
         tx: &Transaction,
+        artifact: Option<Artifact>,
     ) -> Result<(
         Vec<HashMap<RuneId, Lot>>, // allocated runes per output
         Vec<HashMap<RuneId, Lot>>, // allocated risky runes per output
@@ -150,7 +160,6 @@ impl<'client> TransactionParser<'client> {
         Option<(RuneId, Rune)>,    // etched rune, if any
         HashMap<RuneId, Lot>,      // burned runes
     )> {
-        let artifact = Runestone::decipher(tx);
         let (mut unallocated, mut risky_unallocated) = self.unallocated(tx, prev_outputs)?;
 
         // Create per-output allocation maps
@@ -541,8 +550,7 @@ impl<'client> TransactionParser<'client> {
 
             if self.mempool {
                 for rune_amount in tx_out.risky_runes {
-                    *risky_unallocated.entry(rune_amount.id).or_default() +=
-                        rune_amount.amount;
+                    *risky_unallocated.entry(rune_amount.id).or_default() += rune_amount.amount;
                 }
             }
         }
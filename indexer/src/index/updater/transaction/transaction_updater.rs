@@ -2,13 +2,19 @@ use {
     super::TransactionStore,
     crate::{
         index::{inscription::index_rune_icon, Settings, StoreError},
-        models::{BlockId, RuneEntry, TransactionStateChange, TransactionStateChangeInput},
+        models::{
+            BlockId, RuneEntry, RuneHistory, TransactionStateChange, TransactionStateChangeInput,
+        },
     },
     bitcoin::Transaction,
     ordinals::{Artifact, Etching, Runestone},
+    rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet},
     thiserror::Error,
     titan_types_core::RuneId,
-    titan_types_core::{Event, SerializedOutPoint, SerializedTxid, SpenderReference, TxOut},
+    titan_types_core::{
+        ArtifactKind, CenotaphFlaw, Event, RuneAmount, SerializedOutPoint, SerializedTxid,
+        SpenderReference, TxOut,
+    },
     titan_types_core::{Rune, SpacedRune},
     tokio::sync::mpsc::error::SendError,
 };
@@ -32,12 +38,14 @@ type Result<T> = std::result::Result<T, TransactionUpdaterError>;
 #[derive(Debug)]
 pub struct TransactionUpdaterSettings {
     pub(super) index_bitcoin_transactions: bool,
+    pub(super) index_addresses: bool,
 }
 
 impl From<Settings> for TransactionUpdaterSettings {
     fn from(settings: Settings) -> Self {
         Self {
             index_bitcoin_transactions: settings.index_bitcoin_transactions,
+            index_addresses: settings.index_addresses,
         }
     }
 }
@@ -87,6 +95,16 @@ impl TransactionUpdater {
             )?;
         }
 
+        if let Some(ArtifactKind::Cenotaph { flaw }) = transaction_state_change.artifact {
+            self.cenotaph(
+                events,
+                block_id.as_ref().map(|id| id.height),
+                txid,
+                flaw,
+                transaction_state_change,
+            );
+        }
+
         // Add minted rune if any.
         if let Some(minted) = transaction_state_change.minted.as_ref() {
             self.mint_rune(
@@ -142,6 +160,29 @@ impl TransactionUpdater {
             store.add_rune_transaction(rune_id, txid);
         }
 
+        if let Some(block_id) = block_id.as_ref().filter(|_| !self.mempool) {
+            self.save_rune_history(store, block_time, block_id.height, transaction_state_change);
+        }
+
+        // Save address history, once per address spent from or paid to.
+        if self.settings.index_addresses {
+            let mut script_pubkeys = HashSet::default();
+            let spent = transaction_state_change
+                .inputs
+                .iter()
+                .filter_map(|tx_in| tx_in.script_pubkey.as_ref());
+            let received = transaction
+                .output
+                .iter()
+                .map(|tx_out| &tx_out.script_pubkey);
+
+            for script_pubkey in spent.chain(received) {
+                if !script_pubkey.is_op_return() && script_pubkeys.insert(script_pubkey) {
+                    store.add_address_transaction(script_pubkey.clone(), txid);
+                }
+            }
+        }
+
         if self.settings.index_bitcoin_transactions {
             store.set_transaction(txid, transaction.clone());
         }
@@ -153,6 +194,64 @@ impl TransactionUpdater {
         Ok(())
     }
 
+    fn save_rune_history(
+        &mut self,
+        store: &mut dyn TransactionStore,
+        block_time: u32,
+        height: u64,
+        transaction_state_change: &TransactionStateChange,
+    ) {
+        let mut history: HashMap<RuneId, RuneHistory> = HashMap::default();
+
+        if let Some(minted) = transaction_state_change.minted.as_ref() {
+            let entry = history.entry(minted.id).or_default();
+            entry.mints += 1;
+            entry.minted = entry.minted.saturating_add(minted.amount);
+        }
+
+        for (rune_id, amount) in transaction_state_change.burned.iter() {
+            let entry = history.entry(*rune_id).or_default();
+            entry.burns += 1;
+            entry.burned = entry.burned.saturating_add(amount.n());
+        }
+
+        let mut transferred: HashMap<RuneId, u128> = HashMap::default();
+        for output in transaction_state_change.outputs.iter() {
+            for rune in output.runes.iter().chain(output.risky_runes.iter()) {
+                let amount = transferred.entry(rune.id).or_default();
+                *amount = amount.saturating_add(rune.amount);
+            }
+        }
+
+        // Only what the inputs carried was moved. A rune can't be in the inputs of its
+        // own etching, so its outputs there are all premine, and a mint adds to the
+        // outputs without moving anything.
+        if let Some((id, _)) = transaction_state_change.etched {
+            transferred.remove(&id);
+        }
+
+        if let Some(minted) = transaction_state_change.minted.as_ref() {
+            if let Some(amount) = transferred.get_mut(&minted.id) {
+                *amount = amount.saturating_sub(minted.amount);
+            }
+        }
+
+        for (rune_id, amount) in transferred {
+            if amount == 0 {
+                continue;
+            }
+
+            let entry = history.entry(rune_id).or_default();
+            entry.transfers += 1;
+            entry.transferred = amount;
+        }
+
+        for (rune_id, mut entry) in history {
+            entry.block_time = block_time as u64;
+            store.add_rune_history(rune_id, height, entry);
+        }
+    }
+
     fn update_spendable_input(
         &mut self,
         store: &mut dyn TransactionStore,
@@ -194,6 +293,33 @@ impl TransactionUpdater {
         Ok(())
     }
 
+    fn cenotaph(
+        &mut self,
+        events: &mut dyn TransactionEventMgr,
+        height: Option<u64>,
+        txid: SerializedTxid,
+        flaw: Option<CenotaphFlaw>,
+        transaction_state_change: &TransactionStateChange,
+    ) {
+        let mut burned = transaction_state_change
+            .burned
+            .iter()
+            .map(|(rune_id, amount)| RuneAmount {
+                id: *rune_id,
+                amount: amount.n(),
+            })
+            .collect::<Vec<_>>();
+
+        burned.sort_by_key(|rune_amount| rune_amount.id);
+
+        events.add_event(Event::Cenotaph {
+            flaw,
+            location: height.into(),
+            burned,
+            txid,
+        });
+    }
+
     fn mint_rune(
         &mut self,
         store: &mut dyn TransactionStore,
@@ -1,10 +1,10 @@
+use auth::ApiKeys;
 use axum_server::Handle;
 use bitcoin_rpc::{validate_rpc_connection, RpcClientPool, RpcClientProvider};
-use clap::Parser;
 use db::RocksDB;
-use index::{Index, Settings};
-use options::Options;
-use server::{Server, ServerConfig};
+use index::{Index, Metrics, Settings};
+use options::{ApiKeyCommand, Command, Options};
+use server::{RateLimiter, Server, ServerConfig};
 use std::{io, panic, sync::Arc};
 use subscription::{
     shutdown_and_wait_subscription_tasks, spawn_subscription_tasks, SubscriptionSpawnResult,
@@ -17,7 +17,9 @@ use tokio::{
 use tracing::{error, info};
 
 mod api;
+mod auth;
 mod bitcoin_rpc;
+mod config;
 mod db;
 mod index;
 mod models;
@@ -38,6 +40,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 3. Prepare and validate configurations
     let settings = setup_settings(&options)?;
+
+    // Admin commands only need the database.
+    if let Some(command) = options.command.clone() {
+        return run_command(command, &settings);
+    }
+
     let server_config = setup_server_config(&options)?;
     validate_rpc(&settings)?;
 
@@ -46,18 +54,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     set_panic_hook(db_arc.clone());
 
     // 5. If subscriptions are enabled, spawn the dispatcher + cleanup tasks
-    let spawn_subscription_result =
-        spawn_subscription_tasks(db_arc.clone(), options.clone().into());
-
-    let (webhook_subscription_manager, event_sender) = match spawn_subscription_result.as_ref() {
-        Some(sub) => (
-            sub.webhook_spawn_result
-                .as_ref()
-                .map(|r| r.subscription_manager.clone()),
-            Some(sub.event_sender.clone()),
-        ),
-        None => (None, None),
-    };
+    let metrics = Metrics::new();
+    let api_keys = Arc::new(ApiKeys::new(db_arc.clone(), options.enable_api_keys));
+    let spawn_subscription_result = spawn_subscription_tasks(
+        db_arc.clone(),
+        options.clone().into(),
+        api_keys.clone(),
+        &metrics,
+    )?;
+
+    let (webhook_subscription_manager, event_subscription_manager, event_sender) =
+        match spawn_subscription_result.as_ref() {
+            Some(sub) => (
+                sub.webhook_spawn_result
+                    .as_ref()
+                    .map(|r| r.subscription_manager.clone()),
+                sub.tcp_spawn_result
+                    .as_ref()
+                    .map(|r| r.tcp_subscription_manager.clone()),
+                Some(sub.event_sender.clone()),
+            ),
+            None => (None, None, None),
+        };
 
     // 6. Create the index
     let bitcoin_rpc_pool = RpcClientPool::new(
@@ -69,6 +87,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         db_arc.clone(),
         bitcoin_rpc_pool.clone(),
         settings.clone(),
+        metrics.clone(),
         event_sender,
     ));
     index.validate_index()?;
@@ -83,11 +102,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 8. Start the HTTP server
     let handle = Handle::new();
     let server = Server;
+    let webhook_subscription_manager = webhook_subscription_manager
+        .unwrap_or_else(|| Arc::new(WebhookSubscriptionManager::new(db_arc.clone(), &metrics)));
+    let rate_limiter = server_config
+        .rate_limit
+        .clone()
+        .map(|config| Arc::new(RateLimiter::new(config, &metrics)));
     let http_server_jh = server.start(
         index.clone(),
-        webhook_subscription_manager
-            .unwrap_or(Arc::new(WebhookSubscriptionManager::new(db_arc.clone()))),
+        webhook_subscription_manager,
+        event_subscription_manager.filter(|_| options.enable_event_streams),
         bitcoin_rpc_pool.clone(),
+        api_keys,
+        rate_limiter,
         Arc::new(server_config),
         handle.clone(),
     )?;
@@ -109,9 +136,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-/// Parse CLI options
+/// Parse CLI options, layered on top of the config file and environment
 fn parse_options() -> Result<Options, Box<dyn std::error::Error>> {
-    let options = Options::parse();
+    let options = Options::load()?;
     Ok(options)
 }
 
@@ -126,6 +153,41 @@ fn setup_server_config(options: &Options) -> Result<ServerConfig, Box<dyn std::e
     Ok(config)
 }
 
+/// Run an admin command against the database and exit.
+fn run_command(command: Command, settings: &Settings) -> Result<(), Box<dyn std::error::Error>> {
+    let db_arc = open_rocks_db(settings)?;
+    let api_keys = ApiKeys::new(db_arc.clone(), true);
+
+    match command {
+        Command::ApiKey(ApiKeyCommand::Create { name, scopes }) => {
+            let (key, entry) = api_keys.create(name, scopes)?;
+            println!("id: {}", entry.id);
+            println!("key: {}", key);
+        }
+        Command::ApiKey(ApiKeyCommand::List) => {
+            for entry in api_keys.list()? {
+                let scopes = entry
+                    .scopes
+                    .iter()
+                    .map(|scope| scope.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    entry.id, entry.name, scopes, entry.created_at_epoch_secs
+                );
+            }
+        }
+        Command::ApiKey(ApiKeyCommand::Revoke { id }) => {
+            api_keys.revoke(&id)?;
+            println!("revoked {}", id);
+        }
+    }
+
+    db_arc.flush()?;
+    Ok(())
+}
+
 /// Validate the RPC connection using your `validate_rpc_connection`
 fn validate_rpc(settings: &Settings) -> Result<(), Box<dyn std::error::Error>> {
     validate_rpc_connection(settings.get_new_rpc_client()?, settings.chain)?;
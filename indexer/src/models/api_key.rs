@@ -0,0 +1,43 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    clap::ValueEnum,
+    std::fmt::{self, Display},
+    uuid::Uuid,
+};
+
+/// What an API key may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize, ValueEnum)]
+pub enum ApiKeyScope {
+    /// Every read-only endpoint, plus TCP, WebSocket and SSE event subscriptions.
+    Read,
+    /// `/tx/broadcast`.
+    Broadcast,
+    /// Managing webhook subscriptions under `/subscription`.
+    SubscriptionsAdmin,
+}
+
+impl Display for ApiKeyScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiKeyScope::Read => write!(f, "read"),
+            ApiKeyScope::Broadcast => write!(f, "broadcast"),
+            ApiKeyScope::SubscriptionsAdmin => write!(f, "subscriptions-admin"),
+        }
+    }
+}
+
+/// An API key, stored under the SHA-256 hash of the key itself so the key can't be
+/// read back from the database.
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct ApiKeyEntry {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub created_at_epoch_secs: u64,
+}
+
+impl ApiKeyEntry {
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
@@ -0,0 +1,28 @@
+use {
+    ordinals::{Artifact, Flaw},
+    titan_types_core::{ArtifactKind, CenotaphFlaw},
+};
+
+pub fn artifact_kind(artifact: &Artifact) -> ArtifactKind {
+    match artifact {
+        Artifact::Runestone(_) => ArtifactKind::Runestone,
+        Artifact::Cenotaph(cenotaph) => ArtifactKind::Cenotaph {
+            flaw: cenotaph.flaw.map(cenotaph_flaw),
+        },
+    }
+}
+
+pub fn cenotaph_flaw(flaw: Flaw) -> CenotaphFlaw {
+    match flaw {
+        Flaw::EdictOutput => CenotaphFlaw::EdictOutput,
+        Flaw::EdictRuneId => CenotaphFlaw::EdictRuneId,
+        Flaw::InvalidScript => CenotaphFlaw::InvalidScript,
+        Flaw::Opcode => CenotaphFlaw::Opcode,
+        Flaw::SupplyOverflow => CenotaphFlaw::SupplyOverflow,
+        Flaw::TrailingIntegers => CenotaphFlaw::TrailingIntegers,
+        Flaw::TruncatedField => CenotaphFlaw::TruncatedField,
+        Flaw::UnrecognizedEvenTag => CenotaphFlaw::UnrecognizedEvenTag,
+        Flaw::UnrecognizedFlag => CenotaphFlaw::UnrecognizedFlag,
+        Flaw::Varint => CenotaphFlaw::Varint,
+    }
+}
@@ -1,5 +1,5 @@
 use {
-    super::RuneEntry,
+    super::{RuneBalanceChanges, RuneEntry},
     bitcoin::ScriptBuf,
     rustc_hash::FxHashMap as HashMap,
     std::fmt::Display,
@@ -13,6 +13,7 @@ pub struct BatchRollback {
     pub rune_entry: HashMap<RuneId, RuneEntry>,
     pub txouts: HashMap<SerializedOutPoint, TxOut>,
     pub script_pubkey_entry: HashMap<ScriptBuf, (Vec<SerializedOutPoint>, Vec<SerializedOutPoint>)>,
+    pub rune_balances: RuneBalanceChanges,
 
     pub outpoints_to_delete: Vec<SerializedOutPoint>,
     pub prev_outpoints_to_delete: Vec<SerializedOutPoint>,
@@ -21,6 +22,7 @@ pub struct BatchRollback {
     pub rune_numbers_to_delete: Vec<u64>,
     pub inscriptions_to_delete: Vec<InscriptionId>,
     pub delete_all_rune_transactions: Vec<RuneId>,
+    pub rune_history_to_delete: Vec<(RuneId, u64)>,
     pub txs_to_delete: Vec<SerializedTxid>,
 }
 
@@ -31,6 +33,7 @@ impl BatchRollback {
             rune_entry: HashMap::default(),
             txouts: HashMap::default(),
             script_pubkey_entry: HashMap::default(),
+            rune_balances: RuneBalanceChanges::default(),
             outpoints_to_delete: Vec::new(),
             prev_outpoints_to_delete: Vec::new(),
             runes_to_delete: Vec::new(),
@@ -38,6 +41,7 @@ impl BatchRollback {
             rune_numbers_to_delete: Vec::new(),
             inscriptions_to_delete: Vec::new(),
             delete_all_rune_transactions: Vec::new(),
+            rune_history_to_delete: Vec::new(),
             txs_to_delete: Vec::new(),
         }
     }
@@ -48,14 +52,15 @@ impl Display for BatchRollback {
         write!(
             f,
             "BatchRollback: \
-             counts: [runes: {}, txouts: {}, script_pubkeys: {}]
+             counts: [runes: {}, txouts: {}, script_pubkeys: {}, rune_balances: {}]
              outpoints_to_delete: {}, prev_outpoints_to_delete: {}, runes_to_delete: {}, \
              runes_ids_to_delete: {}, rune_numbers_to_delete: {}, inscriptions_to_delete: {}, \
-             delete_all_rune_transactions: {}, txs_to_delete: {}
+             delete_all_rune_transactions: {}, rune_history_to_delete: {}, txs_to_delete: {}
              ",
             self.runes_count,
             self.txouts.len(),
             self.script_pubkey_entry.len(),
+            self.rune_balances.len(),
             self.outpoints_to_delete.len(),
             self.prev_outpoints_to_delete.len(),
             self.runes_to_delete.len(),
@@ -63,6 +68,7 @@ impl Display for BatchRollback {
             self.rune_numbers_to_delete.len(),
             self.inscriptions_to_delete.len(),
             self.delete_all_rune_transactions.len(),
+            self.rune_history_to_delete.len(),
             self.txs_to_delete.len()
         )
     }
@@ -1,5 +1,7 @@
 use {
-    super::{BlockId, Inscription, RuneEntry, TransactionStateChange},
+    super::{
+        BlockId, Inscription, RuneBalanceChanges, RuneEntry, RuneHistory, TransactionStateChange,
+    },
     bitcoin::{BlockHash, ScriptBuf, Transaction},
     rustc_hash::FxHashMap as HashMap,
     std::fmt::Display,
@@ -20,6 +22,9 @@ pub struct BatchUpdate {
     pub txouts: HashMap<SerializedOutPoint, TxOut>,
     pub tx_state_changes: HashMap<SerializedTxid, TransactionStateChange>,
     pub rune_transactions: HashMap<RuneId, Vec<SerializedTxid>>,
+    pub address_transactions: HashMap<ScriptBuf, Vec<SerializedTxid>>,
+    pub rune_balances: RuneBalanceChanges,
+    pub rune_history: HashMap<(RuneId, u64), RuneHistory>,
     pub runes: HashMap<RuneId, RuneEntry>,
     pub rune_ids: HashMap<u128, RuneId>,
     pub rune_numbers: HashMap<u64, RuneId>,
@@ -43,6 +48,9 @@ impl BatchUpdate {
             txouts: HashMap::default(),
             tx_state_changes: HashMap::default(),
             rune_transactions: HashMap::default(),
+            address_transactions: HashMap::default(),
+            rune_balances: RuneBalanceChanges::default(),
+            rune_history: HashMap::default(),
             runes: HashMap::default(),
             rune_ids: HashMap::default(),
             rune_numbers: HashMap::default(),
@@ -65,6 +73,9 @@ impl BatchUpdate {
             && self.txouts.is_empty()
             && self.tx_state_changes.is_empty()
             && self.rune_transactions.is_empty()
+            && self.address_transactions.is_empty()
+            && self.rune_balances.is_empty()
+            && self.rune_history.is_empty()
             && self.runes.is_empty()
             && self.rune_ids.is_empty()
             && self.rune_numbers.is_empty()
@@ -86,6 +97,9 @@ impl BatchUpdate {
         self.txouts.clear();
         self.tx_state_changes.clear();
         self.rune_transactions.clear();
+        self.address_transactions.clear();
+        self.rune_balances.clear();
+        self.rune_history.clear();
         self.runes.clear();
         self.rune_ids.clear();
         self.rune_numbers.clear();
@@ -103,10 +117,11 @@ impl Display for BatchUpdate {
             "BatchUpdate: \
              counts: [blocks: {}, runes: {}, purged_blocks: {}] \
              added: [blocks: {}, txouts: {}, tx_changes: {}, \
-             addresses: {} , address_outpoints: {}, \
+             addresses: {} , address_outpoints: {}, address_txs: {}, \
+             address_rune_balances: {}, \
              spent_outpoints_in_mempool: {}, \
              mempool_txs: {}, \
-             runes: txs {}/ runes {}/ ids {}, \
+             runes: txs {}/ runes {}/ ids {}/ history {}, \
              inscriptions: {}, \
              transactions: {}, \
              transaction_confirming_block: {}]",
@@ -118,11 +133,14 @@ impl Display for BatchUpdate {
             self.tx_state_changes.len(),
             self.script_pubkeys.len(),
             self.script_pubkeys_outpoints.len(),
+            self.address_transactions.len(),
+            self.rune_balances.len(),
             self.spent_outpoints_in_mempool.len(),
             self.mempool_txs.len(),
             self.rune_transactions.len(),
             self.runes.len(),
             self.rune_ids.len(),
+            self.rune_history.len(),
             self.inscriptions.len(),
             self.transactions.len(),
             self.transaction_confirming_block.len(),
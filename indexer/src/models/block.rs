@@ -12,11 +12,7 @@ pub struct BlockId {
 
 impl BlockId {
     pub fn into_transaction_status(self) -> TransactionStatus {
-        TransactionStatus {
-            confirmed: true,
-            block_height: Some(self.height),
-            block_hash: Some(self.hash),
-        }
+        TransactionStatus::confirmed(self.height, self.hash)
     }
 }
 
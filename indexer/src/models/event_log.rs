@@ -0,0 +1,32 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    std::io::{Error, ErrorKind, Read, Result, Write},
+    titan_types_core::Event,
+};
+
+/// An event kept in the event log, along with the chain height when it was emitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventLogEntry {
+    pub height: u64,
+    pub event: Event,
+}
+
+// `Event` holds types without a borsh encoding, so it is stored as JSON.
+impl BorshSerialize for EventLogEntry {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.height.serialize(writer)?;
+        let event = serde_json::to_vec(&self.event).map_err(|e| Error::new(ErrorKind::Other, e))?;
+        event.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for EventLogEntry {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let height = u64::deserialize_reader(reader)?;
+        let event = Vec::<u8>::deserialize_reader(reader)?;
+        let event =
+            serde_json::from_slice(&event).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        Ok(EventLogEntry { height, event })
+    }
+}
@@ -1,17 +1,38 @@
 pub use {
-    batch_delete::BatchDelete, batch_rollback::BatchRollback, batch_update::BatchUpdate,
-    block::block_id_to_transaction_status, block::BlockId, inscription::Inscription, lot::Lot,
-    media::Media, rune::RuneEntry, transaction_state_change::TransactionStateChange,
+    api_key::{ApiKeyEntry, ApiKeyScope},
+    artifact::{artifact_kind, cenotaph_flaw},
+    batch_delete::BatchDelete,
+    batch_rollback::BatchRollback,
+    batch_update::BatchUpdate,
+    block::block_id_to_transaction_status,
+    block::BlockId,
+    event_log::EventLogEntry,
+    inscription::Inscription,
+    lot::Lot,
+    media::Media,
+    rune::RuneEntry,
+    rune_balance::RuneBalance,
+    rune_balance::RuneBalanceChanges,
+    rune_history::RuneHistory,
+    transaction_state_change::TransactionStateChange,
     transaction_state_change::TransactionStateChangeInput,
+    transaction_state_change::TxAddressIndexRef,
     transaction_state_change::TxRuneIndexRef,
+    webhook_delivery::WebhookDeliveryEntry,
 };
 
+mod api_key;
+mod artifact;
 mod batch_delete;
 mod batch_rollback;
 mod batch_update;
 mod block;
+mod event_log;
 mod inscription;
 mod lot;
 mod media;
 mod rune;
+mod rune_balance;
+mod rune_history;
 mod transaction_state_change;
+mod webhook_delivery;
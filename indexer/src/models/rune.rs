@@ -117,7 +117,7 @@ impl RuneEntry {
             .or(absolute)
     }
 
-    pub fn to_rune_response(&self, id: RuneId, height: u64) -> RuneResponse {
+    pub fn to_rune_response(&self, id: RuneId, height: u64, holders: u64) -> RuneResponse {
         let mintable = match self.mintable(height) {
             Ok(_) => true,
             Err(_) => false,
@@ -155,6 +155,7 @@ impl RuneEntry {
             inscription_id: self.inscription_id.clone(),
             timestamp: self.timestamp,
             turbo: self.turbo,
+            holders,
         }
     }
 }
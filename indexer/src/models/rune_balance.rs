@@ -0,0 +1,121 @@
+use {
+    bitcoin::ScriptBuf,
+    borsh::{BorshDeserialize, BorshSerialize},
+    rustc_hash::FxHashMap as HashMap,
+    titan_types_core::{RuneId, TxOut},
+};
+
+/// Rune balance of a script pubkey.
+///
+/// Balances hold the runes received in outputs (`amount` and `risky`). Mempool
+/// balances also hold the runes held by outputs that mempool transactions spend
+/// (`spent`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct RuneBalance {
+    pub amount: u128,
+    pub risky: u128,
+    pub spent: u128,
+}
+
+impl RuneBalance {
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn apply(self, change: &RuneBalanceChange) -> Self {
+        Self {
+            amount: self
+                .amount
+                .saturating_add(change.added.amount)
+                .saturating_sub(change.removed.amount),
+            risky: self
+                .risky
+                .saturating_add(change.added.risky)
+                .saturating_sub(change.removed.risky),
+            spent: self
+                .spent
+                .saturating_add(change.added.spent)
+                .saturating_sub(change.removed.spent),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RuneBalanceChange {
+    pub added: RuneBalance,
+    pub removed: RuneBalance,
+}
+
+/// Balance changes per script pubkey and rune, applied on top of the stored balances
+/// when the batch is written.
+#[derive(Debug, Default, Clone)]
+pub struct RuneBalanceChanges(HashMap<(ScriptBuf, RuneId), RuneBalanceChange>);
+
+impl RuneBalanceChanges {
+    /// `tx_out` was created.
+    pub fn add_output(&mut self, tx_out: &TxOut) {
+        self.update(tx_out, false, false);
+    }
+
+    /// `tx_out` was reverted, or spent in a block.
+    pub fn remove_output(&mut self, tx_out: &TxOut) {
+        self.update(tx_out, true, false);
+    }
+
+    /// `tx_out` was spent by a mempool transaction.
+    pub fn add_spend(&mut self, tx_out: &TxOut) {
+        self.update(tx_out, false, true);
+    }
+
+    /// The mempool transaction spending `tx_out` was removed.
+    pub fn remove_spend(&mut self, tx_out: &TxOut) {
+        self.update(tx_out, true, true);
+    }
+
+    fn update(&mut self, tx_out: &TxOut, removed: bool, spent: bool) {
+        let runes = tx_out
+            .runes
+            .iter()
+            .map(|rune| (rune, false))
+            .chain(tx_out.risky_runes.iter().map(|rune| (rune, true)));
+
+        for (rune, risky) in runes {
+            let change = self
+                .0
+                .entry((tx_out.script_pubkey.clone(), rune.id))
+                .or_default();
+
+            let balance = if removed {
+                &mut change.removed
+            } else {
+                &mut change.added
+            };
+
+            let counter = if spent {
+                &mut balance.spent
+            } else if risky {
+                &mut balance.risky
+            } else {
+                &mut balance.amount
+            };
+
+            *counter = counter.saturating_add(rune.amount);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&(ScriptBuf, RuneId), &RuneBalanceChange)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
@@ -0,0 +1,25 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Rune activity confirmed in a single block.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct RuneHistory {
+    pub block_time: u64,
+    pub mints: u64,
+    pub minted: u128,
+    pub burns: u64,
+    pub burned: u128,
+    pub transfers: u64,
+    pub transferred: u128,
+}
+
+impl RuneHistory {
+    pub fn merge(&mut self, other: &RuneHistory) {
+        self.block_time = self.block_time.max(other.block_time);
+        self.mints = self.mints.saturating_add(other.mints);
+        self.minted = self.minted.saturating_add(other.minted);
+        self.burns = self.burns.saturating_add(other.burns);
+        self.burned = self.burned.saturating_add(other.burned);
+        self.transfers = self.transfers.saturating_add(other.transfers);
+        self.transferred = self.transferred.saturating_add(other.transferred);
+    }
+}
@@ -5,9 +5,9 @@ use {
     rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet},
     std::{
         fmt::Display,
-        io::{Read, Result, Write},
+        io::{Error, ErrorKind, Read, Result, Write},
     },
-    titan_types_core::{Rune, RuneAmount, RuneId, SerializedOutPoint, TxOut},
+    titan_types_core::{ArtifactKind, Rune, RuneAmount, RuneId, SerializedOutPoint, TxOut},
 };
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -56,6 +56,7 @@ pub struct TransactionStateChange {
     pub minted: Option<RuneAmount>,
     pub burned: HashMap<RuneId, Lot>,
     pub is_coinbase: bool,
+    pub artifact: Option<ArtifactKind>,
 }
 
 impl BorshSerialize for TransactionStateChange {
@@ -109,6 +110,9 @@ impl BorshSerialize for TransactionStateChange {
         // 6) is_coinbase: bool
         self.is_coinbase.serialize(writer)?;
 
+        // 7) artifact: Option<ArtifactKind>
+        self.artifact.serialize(writer)?;
+
         Ok(())
     }
 }
@@ -158,6 +162,24 @@ impl BorshDeserialize for TransactionStateChange {
         // 6) is_coinbase
         let is_coinbase = bool::deserialize_reader(reader)?;
 
+        // 7) artifact
+        //    State changes stored before the artifact was tracked end here.
+        let mut present = [0u8; 1];
+        let artifact = if reader.read(&mut present)? == 0 {
+            None
+        } else {
+            match present[0] {
+                0 => None,
+                1 => Some(ArtifactKind::deserialize_reader(reader)?),
+                tag => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("invalid artifact tag: {tag}"),
+                    ))
+                }
+            }
+        };
+
         Ok(TransactionStateChange {
             inputs,
             outputs,
@@ -165,6 +187,7 @@ impl BorshDeserialize for TransactionStateChange {
             minted,
             burned,
             is_coinbase,
+            artifact,
         })
     }
 }
@@ -173,8 +196,14 @@ impl Display for TransactionStateChange {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "TransactionStateChange {{ inputs: {:?}, outputs: {:?}, etched: {:?}, minted: {:?}, burned: {:?}, is_coinbase: {:?} }}",
-            self.inputs, self.outputs, self.etched, self.minted, self.burned, self.is_coinbase
+            "TransactionStateChange {{ inputs: {:?}, outputs: {:?}, etched: {:?}, minted: {:?}, burned: {:?}, is_coinbase: {:?}, artifact: {:?} }}",
+            self.inputs,
+            self.outputs,
+            self.etched,
+            self.minted,
+            self.burned,
+            self.is_coinbase,
+            self.artifact
         )
     }
 }
@@ -222,3 +251,42 @@ pub struct TxRuneIndexRef {
     pub rune_id: Vec<u8>,
     pub index: u64,
 }
+
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct TxAddressIndexRef {
+    pub script_pubkey: Vec<u8>,
+    pub index: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_artifact_when_stored() {
+        let state_change = TransactionStateChange {
+            inputs: vec![],
+            outputs: vec![],
+            etched: None,
+            minted: None,
+            burned: HashMap::default(),
+            is_coinbase: false,
+            artifact: Some(ArtifactKind::Runestone),
+        };
+
+        let stored = borsh::to_vec(&state_change).unwrap();
+        let read: TransactionStateChange = borsh::from_slice(&stored).unwrap();
+        assert_eq!(read.artifact, Some(ArtifactKind::Runestone));
+
+        // State changes stored before artifacts end before the tag.
+        let artifact_tag =
+            stored.len() - 1 - borsh::to_vec(&ArtifactKind::Runestone).unwrap().len();
+        let read: TransactionStateChange = borsh::from_slice(&stored[..artifact_tag]).unwrap();
+        assert_eq!(read.artifact, None);
+
+        let mut corrupt = stored[..=artifact_tag].to_vec();
+        corrupt[artifact_tag] = 2;
+        let error = borsh::from_slice::<TransactionStateChange>(&corrupt).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
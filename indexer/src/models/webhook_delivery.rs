@@ -0,0 +1,80 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    std::io::{Error, ErrorKind, Read, Result, Write},
+    titan_types_api::{DeliveryStatus, WebhookDelivery},
+    titan_types_core::Event,
+    uuid::Uuid,
+};
+
+/// An event in the outbox or the dead letters of a webhook subscription. The
+/// subscription and the sequence number make up its key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDeliveryEntry {
+    pub id: Uuid,
+    pub event: Event,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub created_at_epoch_secs: u64,
+    pub next_attempt_epoch_secs: u64,
+    pub last_error: Option<String>,
+}
+
+impl WebhookDeliveryEntry {
+    pub fn new(event: Event, now_secs: u64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            created_at_epoch_secs: now_secs,
+            next_attempt_epoch_secs: now_secs,
+            last_error: None,
+        }
+    }
+
+    pub fn into_delivery(self, seq: u64) -> WebhookDelivery {
+        WebhookDelivery {
+            id: self.id,
+            seq,
+            event: self.event,
+            status: self.status,
+            attempts: self.attempts,
+            created_at_epoch_secs: self.created_at_epoch_secs,
+            next_attempt_epoch_secs: self.next_attempt_epoch_secs,
+            last_error: self.last_error,
+        }
+    }
+}
+
+// Stored like `EventLogEntry`, with the event as JSON.
+impl BorshSerialize for WebhookDeliveryEntry {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.id.serialize(writer)?;
+        let event = serde_json::to_vec(&self.event).map_err(|e| Error::new(ErrorKind::Other, e))?;
+        event.serialize(writer)?;
+        self.status.serialize(writer)?;
+        self.attempts.serialize(writer)?;
+        self.created_at_epoch_secs.serialize(writer)?;
+        self.next_attempt_epoch_secs.serialize(writer)?;
+        self.last_error.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for WebhookDeliveryEntry {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let id = Uuid::deserialize_reader(reader)?;
+        let event = Vec::<u8>::deserialize_reader(reader)?;
+        let event =
+            serde_json::from_slice(&event).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        Ok(WebhookDeliveryEntry {
+            id,
+            event,
+            status: DeliveryStatus::deserialize_reader(reader)?,
+            attempts: u32::deserialize_reader(reader)?,
+            created_at_epoch_secs: u64::deserialize_reader(reader)?,
+            next_attempt_epoch_secs: u64::deserialize_reader(reader)?,
+            last_error: Option::<String>::deserialize_reader(reader)?,
+        })
+    }
+}
@@ -1,8 +1,10 @@
 use {
     crate::{
+        config::{ConfigError, ConfigFile},
         index::{Chain, Settings},
-        server::ServerConfig,
-        subscription::SubscriptionConfig,
+        models::ApiKeyScope,
+        server::{RateLimitConfig, RouteCost, ServerConfig},
+        subscription::{EventLogConfig, SubscriptionConfig},
     },
     bitcoincore_rpc::Auth,
     clap::{
@@ -11,12 +13,42 @@ use {
             styling::{AnsiColor, Effects},
             Styles,
         },
-        Parser,
+        parser::ValueSource,
+        ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand,
     },
-    std::path::PathBuf,
+    std::{net::ToSocketAddrs, path::PathBuf},
     tracing::warn,
+    uuid::Uuid,
 };
 
+/// Overwrite each listed option with the value from the config file, unless it was
+/// set through an environment variable or on the command line.
+macro_rules! merge_config_file {
+    ($options:expr, $file:expr, $matches:expr, [$($field:ident),* $(,)?], [$($optional:ident),* $(,)?]) => {
+        $(
+            if let Some(value) = $file.$field {
+                if !is_explicit($matches, stringify!($field)) {
+                    $options.$field = value;
+                }
+            }
+        )*
+        $(
+            if let Some(value) = $file.$optional {
+                if !is_explicit($matches, stringify!($optional)) {
+                    $options.$optional = Some(value);
+                }
+            }
+        )*
+    };
+}
+
+fn is_explicit(matches: &ArgMatches, id: &str) -> bool {
+    matches!(
+        matches.value_source(id),
+        Some(ValueSource::EnvVariable) | Some(ValueSource::CommandLine)
+    )
+}
+
 #[derive(Clone, Default, Debug, Parser)]
 #[command(
     name = "rune-indexer",
@@ -34,47 +66,58 @@ use {
 pub struct Options {
     #[arg(
         long,
+        env = "TITAN_BITCOIN_RPC_PASSWORD",
         help = "Authenticate to Bitcoin Core RPC with <BITCOIN_RPC_PASSWORD>."
     )]
     pub(super) bitcoin_rpc_password: Option<String>,
     #[arg(
         long,
+        env = "TITAN_BITCOIN_RPC_URL",
         help = "Connect to Bitcoin Core RPC at <BITCOIN_RPC_URL>.",
         default_value = "http://localhost:8332"
     )]
     pub(super) bitcoin_rpc_url: String,
     #[arg(
         long,
+        env = "TITAN_BITCOIN_RPC_USERNAME",
         help = "Authenticate to Bitcoin Core RPC as <BITCOIN_RPC_USERNAME>."
     )]
     pub(super) bitcoin_rpc_username: Option<String>,
     #[arg(
         long,
+        env = "TITAN_BITCOIN_RPC_LIMIT",
         help = "Max <N> requests in flight. [default: 12]",
         default_value = "12"
     )]
     pub(super) bitcoin_rpc_limit: u32,
     #[arg(
         long,
+        env = "TITAN_BITCOIN_RPC_POOL_SIZE",
         help = "Max number of RPC clients in pool. [default: 500]",
         default_value = "500"
     )]
     pub(super) bitcoin_rpc_pool_size: u32,
     #[arg(
         long = "chain",
+        env = "TITAN_CHAIN",
         value_enum,
         help = "Use <CHAIN>. [default: mainnet]",
         default_value = "mainnet"
     )]
     pub(super) chain: Chain,
-    #[arg(long, help = "Load configuration from <CONFIG>.")]
+    #[arg(long, help = "Load configuration from <CONFIG>.", env = "TITAN_CONFIG")]
     pub(super) config: Option<PathBuf>,
-    #[arg(long, help = "Load Bitcoin Core RPC cookie file from <COOKIE_FILE>.")]
+    #[arg(
+        long,
+        help = "Load Bitcoin Core RPC cookie file from <COOKIE_FILE>.",
+        env = "TITAN_COOKIE_FILE"
+    )]
     pub(super) cookie_file: Option<PathBuf>,
 
     /// Store index in <DATA_DIR>. [default: ./data]
     #[arg(
         long,
+        env = "TITAN_DATA_DIR",
         alias = "datadir",
         help = "Store index in <DATA_DIR>.",
         default_value = "./data"
@@ -84,6 +127,7 @@ pub struct Options {
     /// Do not index inscriptions (rune icons). [default: false]
     #[arg(
         long,
+        env = "TITAN_NO_INDEX_INSCRIPTIONS",
         short,
         alias = "noindex_inscriptions",
         help = "Do not index inscriptions (rune icons)."
@@ -93,6 +137,7 @@ pub struct Options {
     /// Index bitcoin transactions
     #[arg(
         long,
+        env = "TITAN_INDEX_BITCOIN_TRANSACTIONS",
         short,
         help = "Index bitcoin transactions. [default: true]",
         default_value = "true"
@@ -102,6 +147,7 @@ pub struct Options {
     /// Index spent outputs
     #[arg(
         long,
+        env = "TITAN_INDEX_SPENT_OUTPUTS",
         help = "Index spent outputs. [default: true]",
         default_value = "true"
     )]
@@ -110,6 +156,7 @@ pub struct Options {
     /// Index addresses. [default: false]
     #[arg(
         long,
+        env = "TITAN_INDEX_ADDRESSES",
         short = 'a',
         help = "Index addresses. [default: false]",
         default_value = "false"
@@ -119,6 +166,7 @@ pub struct Options {
     /// Commit interval in blocks. [default: 500]
     #[arg(
         long,
+        env = "TITAN_COMMIT_INTERVAL",
         help = "Commit interval in blocks. [default: 50]",
         default_value = "50"
     )]
@@ -126,25 +174,31 @@ pub struct Options {
 
     /// Enable zmq listener. This optimizes the mempool indexing process because
     /// we don't need to fetch transactions from the RPC.
-    #[arg(long, default_value = "false")]
+    #[arg(long, default_value = "false", env = "TITAN_ENABLE_ZMQ_LISTENER")]
     pub(super) enable_zmq_listener: bool,
 
     /// ZeroMQ endpoint for raw transactions from bitcoind
-    #[arg(long, default_value = "tcp://127.0.0.1:28332")]
+    #[arg(
+        long,
+        default_value = "tcp://127.0.0.1:28332",
+        env = "TITAN_ZMQ_ENDPOINT"
+    )]
     pub(super) zmq_endpoint: String,
 
     /// Listen address for the REST API server
-    #[arg(long, default_value = "0.0.0.0:3030")]
+    #[arg(long, default_value = "0.0.0.0:3030", env = "TITAN_HTTP_LISTEN")]
     pub(super) http_listen: String,
 
     #[arg(
         long,
+        env = "TITAN_CSP_ORIGIN",
         help = "Use <CSP_ORIGIN> in Content-Security-Policy header. Set this to the public-facing URL of your rune-indexer instance."
     )]
     pub(super) csp_origin: Option<String>,
 
     #[arg(
         long,
+        env = "TITAN_DECOMPRESS",
         help = "Decompress encoded content. Currently only supports brotli. Be careful using this on production instances. A decompressed inscription may be arbitrarily large, making decompression a DoS vector."
     )]
     pub(super) decompress: bool,
@@ -152,29 +206,244 @@ pub struct Options {
     /// Main loop interval in milliseconds. [default: 500]
     #[arg(
         long,
+        env = "TITAN_MAIN_LOOP_INTERVAL",
         default_value = "500",
         help = "Main loop interval in milliseconds. [default: 500]"
     )]
     pub(super) main_loop_interval: u64,
 
     /// Enable subscription service
-    #[arg(long, default_value = "false")]
+    #[arg(
+        long,
+        default_value = "false",
+        env = "TITAN_ENABLE_WEBHOOK_SUBSCRIPTIONS"
+    )]
     pub(super) enable_webhook_subscriptions: bool,
 
     /// Enable TCP subscription service
-    #[arg(long, default_value = "false")]
+    #[arg(long, default_value = "false", env = "TITAN_ENABLE_TCP_SUBSCRIPTIONS")]
     pub(super) enable_tcp_subscriptions: bool,
 
+    /// Serve event subscriptions over WebSocket (/events/ws) and SSE (/events/sse)
+    #[arg(long, default_value = "false", env = "TITAN_ENABLE_EVENT_STREAMS")]
+    pub(super) enable_event_streams: bool,
+
     /// Tcp address to listen to
-    #[arg(long, default_value = "127.0.0.1:8080")]
+    #[arg(long, default_value = "127.0.0.1:8080", env = "TITAN_TCP_ADDRESS")]
     pub(super) tcp_address: String,
 
     /// Enable file logging
-    #[arg(long, default_value = "false")]
+    #[arg(long, default_value = "false", env = "TITAN_ENABLE_FILE_LOGGING")]
     pub(super) enable_file_logging: bool,
+
+    /// Number of most recent events kept for subscribers to replay
+    #[arg(long, default_value = "100000", env = "TITAN_EVENT_LOG_MAX_EVENTS")]
+    pub(super) event_log_max_events: u64,
+
+    /// Also prune logged events emitted more than this many blocks below the tip
+    #[arg(long, env = "TITAN_EVENT_LOG_MAX_BLOCK_DEPTH")]
+    pub(super) event_log_max_block_depth: Option<u64>,
+
+    /// Require an API key, created with the `api-key` command, on HTTP requests and
+    /// event subscriptions
+    #[arg(long, default_value = "false", env = "TITAN_ENABLE_API_KEYS")]
+    pub(super) enable_api_keys: bool,
+
+    /// Rate limit the HTTP API to this many requests per second per API key, or per
+    /// client address for requests without one. Disabled when unset
+    #[arg(long, env = "TITAN_RATE_LIMIT_PER_SECOND")]
+    pub(super) rate_limit_per_second: Option<u32>,
+
+    /// Number of requests a client can burst above the rate limit
+    #[arg(long, default_value = "100", env = "TITAN_RATE_LIMIT_BURST")]
+    pub(super) rate_limit_burst: u32,
+
+    /// Cost of a route for the rate limit, as <ROUTE>=<COST>, e.g.
+    /// `/mempool/entries/all=50`. Expensive routes have a default cost, others cost 1
+    #[arg(
+        long = "rate-limit-route-cost",
+        value_delimiter = ',',
+        env = "TITAN_RATE_LIMIT_ROUTE_COSTS"
+    )]
+    pub(super) rate_limit_route_costs: Vec<RouteCost>,
+
+    /// Rate limit requests without an API key by the last address of
+    /// `X-Forwarded-For`, the one the proxy appends. Only enable this behind a
+    /// reverse proxy that sets it
+    #[arg(long, default_value = "false", env = "TITAN_RATE_LIMIT_FORWARDED_FOR")]
+    pub(super) rate_limit_forwarded_for: bool,
+
+    /// Serve an Esplora-compatible REST API under `/esplora`, for wallets and tools
+    /// that use an Esplora backend
+    #[arg(long, default_value = "false", env = "TITAN_ENABLE_ESPLORA")]
+    pub(super) enable_esplora: bool,
+
+    #[command(subcommand)]
+    pub(super) command: Option<Command>,
+}
+
+/// Admin commands. They open the database, so the indexer must not be running.
+#[derive(Clone, Debug, Subcommand)]
+pub(super) enum Command {
+    /// Manage API keys.
+    #[command(subcommand)]
+    ApiKey(ApiKeyCommand),
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub(super) enum ApiKeyCommand {
+    /// Create an API key and print it. The key can't be shown again.
+    Create {
+        /// Describes who the key is for.
+        #[arg(long)]
+        name: String,
+        /// Comma separated scopes the key grants.
+        #[arg(long, value_enum, value_delimiter = ',', required = true)]
+        scopes: Vec<ApiKeyScope>,
+    },
+    /// List the API keys.
+    List,
+    /// Revoke the API key with <ID>.
+    Revoke { id: Uuid },
 }
 
 impl Options {
+    /// Parse the command line and layer the configuration sources: values from the
+    /// `--config` file are overridden by environment variables, which are overridden
+    /// by command line flags. The merged result is validated before being returned.
+    pub fn load() -> Result<Self, ConfigError> {
+        let matches = Self::command().get_matches();
+        let mut options = Self::from_arg_matches(&matches)?;
+
+        if let Some(path) = options.config.clone() {
+            let file = ConfigFile::load(&path)?;
+            options.merge_config_file(file, &matches);
+        }
+
+        options.validate()?;
+
+        Ok(options)
+    }
+
+    fn merge_config_file(&mut self, file: ConfigFile, matches: &ArgMatches) {
+        merge_config_file!(
+            self,
+            file,
+            matches,
+            [
+                bitcoin_rpc_url,
+                bitcoin_rpc_limit,
+                bitcoin_rpc_pool_size,
+                chain,
+                data_dir,
+                no_index_inscriptions,
+                index_bitcoin_transactions,
+                index_spent_outputs,
+                index_addresses,
+                commit_interval,
+                enable_zmq_listener,
+                zmq_endpoint,
+                http_listen,
+                decompress,
+                main_loop_interval,
+                enable_webhook_subscriptions,
+                enable_tcp_subscriptions,
+                enable_event_streams,
+                tcp_address,
+                enable_file_logging,
+                event_log_max_events,
+                enable_api_keys,
+                rate_limit_burst,
+                rate_limit_route_costs,
+                rate_limit_forwarded_for,
+                enable_esplora,
+            ],
+            [
+                bitcoin_rpc_password,
+                bitcoin_rpc_username,
+                cookie_file,
+                csp_origin,
+                event_log_max_block_depth,
+                rate_limit_per_second,
+            ]
+        );
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.bitcoin_rpc_url.is_empty() {
+            return Err(ConfigError::Invalid(
+                "bitcoin_rpc_url must not be empty".to_string(),
+            ));
+        }
+
+        if self.bitcoin_rpc_limit == 0 {
+            return Err(ConfigError::Invalid(
+                "bitcoin_rpc_limit must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.bitcoin_rpc_pool_size == 0 {
+            return Err(ConfigError::Invalid(
+                "bitcoin_rpc_pool_size must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.bitcoin_rpc_password.is_some() && self.bitcoin_rpc_username.is_none() {
+            return Err(ConfigError::Invalid(
+                "bitcoin_rpc_password is set but bitcoin_rpc_username is not".to_string(),
+            ));
+        }
+
+        if let Some(cookie_file) = &self.cookie_file {
+            if !cookie_file.is_file() {
+                return Err(ConfigError::Invalid(format!(
+                    "cookie_file {} does not exist",
+                    cookie_file.display()
+                )));
+            }
+        }
+
+        if self.commit_interval == 0 {
+            return Err(ConfigError::Invalid(
+                "commit_interval must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.enable_zmq_listener && self.zmq_endpoint.is_empty() {
+            return Err(ConfigError::Invalid(
+                "zmq_endpoint must be set when enable_zmq_listener is enabled".to_string(),
+            ));
+        }
+
+        if self.rate_limit_per_second == Some(0) {
+            return Err(ConfigError::Invalid(
+                "rate_limit_per_second must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.rate_limit_burst == 0 {
+            return Err(ConfigError::Invalid(
+                "rate_limit_burst must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.http_listen.to_socket_addrs().is_err() {
+            return Err(ConfigError::Invalid(format!(
+                "http_listen `{}` is not a valid socket address",
+                self.http_listen
+            )));
+        }
+
+        if self.enable_tcp_subscriptions && self.tcp_address.to_socket_addrs().is_err() {
+            return Err(ConfigError::Invalid(format!(
+                "tcp_address `{}` is not a valid socket address",
+                self.tcp_address
+            )));
+        }
+
+        Ok(())
+    }
+
     pub fn get_bitcoin_rpc_auth(&self) -> Auth {
         let bitcoin_rpc_auth = if let Some(cookie_file) = self.cookie_file.as_ref() {
             Auth::CookieFile(cookie_file.clone())
@@ -227,6 +496,16 @@ impl From<Options> for ServerConfig {
 
             index_addresses: options.index_addresses,
             enable_webhook_subscriptions: options.enable_webhook_subscriptions,
+            enable_esplora: options.enable_esplora,
+
+            rate_limit: options.rate_limit_per_second.map(|per_second| {
+                RateLimitConfig::new(
+                    per_second,
+                    options.rate_limit_burst,
+                    options.rate_limit_route_costs,
+                    options.rate_limit_forwarded_for,
+                )
+            }),
         }
     }
 }
@@ -234,10 +513,16 @@ impl From<Options> for ServerConfig {
 impl From<Options> for SubscriptionConfig {
     fn from(options: Options) -> Self {
         Self {
+            chain: options.chain,
             enable_webhook_subscriptions: options.enable_webhook_subscriptions,
             enable_tcp_subscriptions: options.enable_tcp_subscriptions,
+            enable_event_streams: options.enable_event_streams,
             tcp_address: options.tcp_address,
             enable_file_logging: options.enable_file_logging,
+            event_log: EventLogConfig {
+                max_events: options.event_log_max_events,
+                max_block_depth: options.event_log_max_block_depth,
+            },
         }
     }
 }
@@ -0,0 +1,208 @@
+use {
+    super::error::ServerResult,
+    crate::{auth::ApiKeys, models::ApiKeyScope},
+    axum::{
+        extract::{Query, Request, State},
+        middleware::{self, Next},
+        Router,
+    },
+    http::{header, HeaderMap, HeaderName},
+    serde::Deserialize,
+    std::sync::Arc,
+    tokio::task,
+};
+
+pub(super) const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-api-key");
+
+/// The scope the routes behind [`require_api_key`] need.
+#[derive(Clone)]
+struct RequiredScope {
+    api_keys: Arc<ApiKeys>,
+    scope: ApiKeyScope,
+    /// Also take the key from the `api_key` query parameter, for clients like
+    /// `EventSource` that can't set headers.
+    from_query: bool,
+}
+
+#[derive(Deserialize)]
+struct ApiKeyQuery {
+    api_key: Option<String>,
+}
+
+/// Require an API key granting `scope` on every route of `routes`.
+pub(super) fn require_scope<S>(
+    routes: Router<S>,
+    api_keys: &Arc<ApiKeys>,
+    scope: ApiKeyScope,
+) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    routes.route_layer(middleware::from_fn_with_state(
+        RequiredScope {
+            api_keys: api_keys.clone(),
+            scope,
+            from_query: false,
+        },
+        require_api_key,
+    ))
+}
+
+/// Like [`require_scope`], but the key may also be sent as `?api_key=<key>`.
+pub(super) fn require_scope_or_query_key<S>(
+    routes: Router<S>,
+    api_keys: &Arc<ApiKeys>,
+    scope: ApiKeyScope,
+) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    routes.route_layer(middleware::from_fn_with_state(
+        RequiredScope {
+            api_keys: api_keys.clone(),
+            scope,
+            from_query: true,
+        },
+        require_api_key,
+    ))
+}
+
+/// Reject requests whose API key doesn't grant the scope of the route. The key's
+/// entry is added to the request extensions for the handlers behind it.
+async fn require_api_key(
+    State(required): State<RequiredScope>,
+    mut request: Request,
+    next: Next,
+) -> ServerResult {
+    let query_key = if required.from_query {
+        Query::<ApiKeyQuery>::try_from_uri(request.uri())
+            .ok()
+            .and_then(|Query(query)| query.api_key)
+    } else {
+        None
+    };
+
+    let key = api_key(request.headers()).or(query_key.as_deref());
+    if let Some(entry) = task::block_in_place(|| required.api_keys.authorize(key, required.scope))?
+    {
+        request.extensions_mut().insert(entry);
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// The key from either `Authorization: Bearer <key>` or `X-API-Key: <key>`. The
+/// scheme is case insensitive.
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim_start().split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+        .map(|(_, key)| key)
+        .or_else(|| {
+            headers
+                .get(&API_KEY_HEADER)
+                .and_then(|value| value.to_str().ok())
+        })
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::auth::MemoryStore,
+        axum::routing::get,
+        http::{HeaderValue, StatusCode},
+        tokio::net::TcpListener,
+    };
+
+    #[test]
+    fn reads_keys_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(api_key(&headers), None);
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer key"),
+        );
+        assert_eq!(api_key(&headers), Some("key"));
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("bearer key"),
+        );
+        assert_eq!(api_key(&headers), Some("key"));
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("BEARER  key"),
+        );
+        assert_eq!(api_key(&headers), Some("key"));
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic key"));
+        assert_eq!(api_key(&headers), None);
+
+        headers.insert(&API_KEY_HEADER, HeaderValue::from_static("other"));
+        assert_eq!(api_key(&headers), Some("other"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn requires_the_scope_of_each_route() {
+        let api_keys = Arc::new(ApiKeys::new(Arc::new(MemoryStore::default()), true));
+        let (read, _) = api_keys
+            .create("explorer".to_string(), vec![ApiKeyScope::Read])
+            .unwrap();
+
+        let app = Router::new()
+            .route("/", get(|| async {}))
+            .merge(require_scope(
+                Router::new().route("/tip", get(|| async {})),
+                &api_keys,
+                ApiKeyScope::Read,
+            ))
+            .merge(require_scope_or_query_key(
+                Router::new().route("/events/sse", get(|| async {})),
+                &api_keys,
+                ApiKeyScope::Read,
+            ))
+            .merge(require_scope(
+                Router::new().route("/subscriptions", get(|| async {})),
+                &api_keys,
+                ApiKeyScope::SubscriptionsAdmin,
+            ));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let status = |path: &str, key: Option<&str>| {
+            let mut request = client.get(format!("{base}{path}"));
+            if let Some(key) = key {
+                request = request.bearer_auth(key);
+            }
+            async move { request.send().await.unwrap().status() }
+        };
+
+        assert_eq!(status("/", None).await, StatusCode::OK);
+        assert_eq!(status("/tip", None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status("/tip", Some("titan_unknown")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(status("/tip", Some(&read)).await, StatusCode::OK);
+        assert_eq!(
+            status("/subscriptions", Some(&read)).await,
+            StatusCode::FORBIDDEN
+        );
+
+        // Only the routes that opt in take the key from the query string.
+        assert_eq!(
+            status(&format!("/events/sse?api_key={read}"), None).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&format!("/tip?api_key={read}"), None).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
@@ -1,12 +1,13 @@
 use {
     crate::{
         api::{content::ContentError, ApiError},
+        auth::AuthError,
         bitcoin_rpc::{RpcClientError, RpcClientPoolError},
         index::{IndexError, StoreError},
     },
     axum::response::{IntoResponse, Response},
     http::{header, HeaderValue, StatusCode},
-    std::fmt::Write,
+    std::{fmt::Write, time::Duration},
     tracing::error,
 };
 
@@ -29,6 +30,12 @@ pub(super) enum ServerError {
 
     #[error("not found: {0}")]
     NotFound(String),
+
+    #[error("auth error: {0}")]
+    AuthError(#[from] AuthError),
+
+    #[error("too many requests, retry after {0:?}")]
+    TooManyRequests(Duration),
 }
 
 pub(super) type ServerResult<T = Response> = Result<T, ServerError>;
@@ -44,6 +51,12 @@ impl IntoResponse for ServerError {
                 error!("rpc error: {error}");
                 (StatusCode::BAD_REQUEST, error.to_string()).into_response()
             }
+            Self::ApiError(
+                error @ (ApiError::HexError(_)
+                | ApiError::ConsensusError(_)
+                | ApiError::PsbtError(_)
+                | ApiError::TooManyUtxos(_)),
+            ) => (StatusCode::BAD_REQUEST, error.to_string()).into_response(),
             Self::ApiError(error) => {
                 error!("error serving request: {error}");
                 (
@@ -101,6 +114,36 @@ impl IntoResponse for ServerError {
                 )
                     .into_response()
             }
+            Self::AuthError(error @ (AuthError::Missing | AuthError::Invalid)) => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"))],
+                error.to_string(),
+            )
+                .into_response(),
+            Self::AuthError(error @ AuthError::MissingScope(_)) => {
+                (StatusCode::FORBIDDEN, error.to_string()).into_response()
+            }
+            Self::AuthError(AuthError::Store(error)) => {
+                error!("api key store error: {error}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    StatusCode::INTERNAL_SERVER_ERROR
+                        .canonical_reason()
+                        .unwrap_or_default(),
+                )
+                    .into_response()
+            }
+            Self::TooManyRequests(retry_after) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(
+                    header::RETRY_AFTER,
+                    (retry_after.as_secs_f64().ceil() as u64).max(1),
+                )],
+                StatusCode::TOO_MANY_REQUESTS
+                    .canonical_reason()
+                    .unwrap_or_default(),
+            )
+                .into_response(),
             Self::NotFound(message) => (
                 StatusCode::NOT_FOUND,
                 [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
@@ -0,0 +1,323 @@
+use {
+    super::{
+        error::{OptionExt, ServerResult},
+        Server, ServerConfig,
+    },
+    crate::{
+        api::{self, esplora},
+        bitcoin_rpc::RpcClientPool,
+        index::Index,
+    },
+    axum::{
+        body::Bytes,
+        extract::{Extension, Json, Path},
+        response::{IntoResponse, Response},
+        routing::{get, post},
+        Router,
+    },
+    bitcoin::{address::NetworkUnchecked, Address, BlockHash},
+    http::{header, StatusCode},
+    std::sync::Arc,
+    titan_types_core::SerializedTxid,
+    tokio::task,
+};
+
+/// Read-only routes of the Esplora REST API, served under `/esplora` with
+/// `--enable-esplora`.
+pub(super) fn router() -> Router<Arc<ServerConfig>> {
+    Router::new()
+        // Blocks
+        .route("/blocks/tip/height", get(tip_height))
+        .route("/blocks/tip/hash", get(tip_hash))
+        .route("/block-height/{height}", get(block_hash))
+        .route("/block/{hash}", get(block))
+        .route("/block/{hash}/header", get(block_header))
+        .route("/block/{hash}/status", get(block_status))
+        .route("/block/{hash}/txids", get(block_txids))
+        .route("/block/{hash}/txid/{index}", get(block_txid))
+        // Transactions
+        .route("/tx/{txid}", get(transaction))
+        .route("/tx/{txid}/status", get(transaction_status))
+        .route("/tx/{txid}/hex", get(transaction_hex))
+        .route("/tx/{txid}/raw", get(transaction_raw))
+        .route("/tx/{txid}/outspends", get(transaction_outspends))
+        .route("/tx/{txid}/outspend/{vout}", get(transaction_outspend))
+        // Addresses
+        .route("/address/{address}/utxo", get(address_utxos))
+        .route("/address/{address}/txs", get(address_transactions))
+        .route(
+            "/address/{address}/txs/mempool",
+            get(address_mempool_transactions),
+        )
+        .route(
+            "/address/{address}/txs/chain",
+            get(address_chain_transactions),
+        )
+        .route(
+            "/address/{address}/txs/chain/{last_seen}",
+            get(address_chain_transactions_after),
+        )
+        // Mempool and fees
+        .route("/mempool/txids", get(mempool_txids))
+        .route("/fee-estimates", get(fee_estimates))
+}
+
+/// `POST /tx`, kept apart from [`router`] as it needs the `broadcast` scope.
+pub(super) fn broadcast_router() -> Router<Arc<ServerConfig>> {
+    Router::new().route("/tx", post(broadcast_transaction))
+}
+
+fn text(body: String) -> Response {
+    (StatusCode::OK, [(header::CONTENT_TYPE, "text/plain")], body).into_response()
+}
+
+async fn tip_height(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+    task::block_in_place(|| Ok(text(esplora::tip_height(index)?.to_string())))
+}
+
+async fn tip_hash(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+    task::block_in_place(|| Ok(text(esplora::tip_hash(index)?.to_string())))
+}
+
+async fn block_hash(
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u64>,
+) -> ServerResult {
+    task::block_in_place(|| Ok(text(esplora::block_hash(index, height)?.to_string())))
+}
+
+async fn block(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(hash): Path<BlockHash>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        Ok(Json(esplora::block(index, bitcoin_rpc_pool.get()?, &hash)?).into_response())
+    })
+}
+
+async fn block_header(
+    Extension(index): Extension<Arc<Index>>,
+    Path(hash): Path<BlockHash>,
+) -> ServerResult {
+    task::block_in_place(|| Ok(text(esplora::block_header(index, &hash)?)))
+}
+
+async fn block_status(
+    Extension(index): Extension<Arc<Index>>,
+    Path(hash): Path<BlockHash>,
+) -> ServerResult {
+    task::block_in_place(|| Ok(Json(esplora::block_status(index, &hash)?).into_response()))
+}
+
+async fn block_txids(
+    Extension(index): Extension<Arc<Index>>,
+    Path(hash): Path<BlockHash>,
+) -> ServerResult {
+    task::block_in_place(|| Ok(Json(esplora::block_txids(index, &hash)?).into_response()))
+}
+
+async fn block_txid(
+    Extension(index): Extension<Arc<Index>>,
+    Path((hash, position)): Path<(BlockHash, usize)>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let txid = esplora::block_txids(index, &hash)?
+            .get(position)
+            .copied()
+            .ok_or_not_found(|| format!("transaction {position} of block {hash}"))?;
+
+        Ok(text(txid.to_string()))
+    })
+}
+
+async fn broadcast_transaction(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    hex: String,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let txid = api::broadcast_transaction(index, bitcoin_rpc_pool.get()?, hex.trim())?;
+        Ok(text(txid.to_string()))
+    })
+}
+
+async fn transaction(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<SerializedTxid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let network = config.chain.network();
+        Ok(Json(esplora::tx(index, bitcoin_rpc_pool.get()?, network, &txid)?).into_response())
+    })
+}
+
+async fn transaction_status(
+    Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<SerializedTxid>,
+) -> ServerResult {
+    task::block_in_place(|| Ok(Json(esplora::tx_status(index, &txid)?).into_response()))
+}
+
+async fn transaction_hex(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<SerializedTxid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        Ok(text(api::bitcoin_transaction_hex(
+            index,
+            bitcoin_rpc_pool.get()?,
+            &txid,
+        )?))
+    })
+}
+
+async fn transaction_raw(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<SerializedTxid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let raw_tx = api::bitcoin_transaction_raw(index, bitcoin_rpc_pool.get()?, &txid)?;
+
+        Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/octet-stream")],
+            Bytes::from(raw_tx),
+        )
+            .into_response())
+    })
+}
+
+async fn transaction_outspends(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(txid): Path<SerializedTxid>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        Ok(Json(esplora::tx_outspends(
+            index,
+            bitcoin_rpc_pool.get()?,
+            &txid,
+        )?)
+        .into_response())
+    })
+}
+
+async fn transaction_outspend(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path((txid, vout)): Path<(SerializedTxid, usize)>,
+) -> ServerResult {
+    task::block_in_place(|| {
+        let outspend = esplora::tx_outspends(index, bitcoin_rpc_pool.get()?, &txid)?
+            .into_iter()
+            .nth(vout)
+            .ok_or_not_found(|| format!("output {txid}:{vout}"))?;
+
+        Ok(Json(outspend).into_response())
+    })
+}
+
+async fn address_utxos(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+) -> ServerResult {
+    let address = Server::checked_address(&config, address)?;
+
+    task::block_in_place(|| Ok(Json(esplora::address_utxos(index, &address)?).into_response()))
+}
+
+async fn address_transactions(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+) -> ServerResult {
+    let address = Server::checked_address(&config, address)?;
+
+    task::block_in_place(|| {
+        let txs = esplora::address_txs(
+            index,
+            bitcoin_rpc_pool.get()?,
+            config.chain.network(),
+            &address,
+        )?;
+
+        Ok(Json(txs).into_response())
+    })
+}
+
+async fn address_mempool_transactions(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+) -> ServerResult {
+    let address = Server::checked_address(&config, address)?;
+
+    task::block_in_place(|| {
+        let txs = esplora::address_txs_mempool(
+            index,
+            bitcoin_rpc_pool.get()?,
+            config.chain.network(),
+            &address,
+        )?;
+
+        Ok(Json(txs).into_response())
+    })
+}
+
+async fn address_chain_transactions(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+) -> ServerResult {
+    let address = Server::checked_address(&config, address)?;
+
+    task::block_in_place(|| {
+        let txs = esplora::address_txs_chain(
+            index,
+            bitcoin_rpc_pool.get()?,
+            config.chain.network(),
+            &address,
+            None,
+        )?;
+
+        Ok(Json(txs).into_response())
+    })
+}
+
+async fn address_chain_transactions_after(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+    Path((address, last_seen)): Path<(Address<NetworkUnchecked>, SerializedTxid)>,
+) -> ServerResult {
+    let address = Server::checked_address(&config, address)?;
+
+    task::block_in_place(|| {
+        let txs = esplora::address_txs_chain(
+            index,
+            bitcoin_rpc_pool.get()?,
+            config.chain.network(),
+            &address,
+            Some(&last_seen),
+        )?;
+
+        Ok(Json(txs).into_response())
+    })
+}
+
+async fn mempool_txids(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+    task::block_in_place(|| Ok(Json(api::mempool_txids(index)?).into_response()))
+}
+
+async fn fee_estimates(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+    task::block_in_place(|| Ok(Json(esplora::fee_estimates(index)?).into_response()))
+}
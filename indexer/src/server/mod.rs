@@ -1,6 +1,13 @@
-pub use {server::Server, server_config::ServerConfig};
+pub use {
+    rate_limit::{RateLimitConfig, RateLimiter, RouteCost},
+    server::Server,
+    server_config::ServerConfig,
+};
 
+mod auth;
 mod deserialize_from_str;
 mod error;
+mod esplora;
+mod rate_limit;
 mod server;
 mod server_config;
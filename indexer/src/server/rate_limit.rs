@@ -0,0 +1,515 @@
+use {
+    super::error::{ServerError, ServerResult},
+    crate::{
+        index::{CounterVec, Metrics},
+        models::ApiKeyEntry,
+    },
+    axum::{
+        extract::{ConnectInfo, MatchedPath, Request, State},
+        middleware::Next,
+    },
+    http::StatusCode,
+    serde::Deserialize,
+    std::{
+        collections::HashMap,
+        net::{IpAddr, SocketAddr},
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    uuid::Uuid,
+};
+
+/// Cost of the routes that are much more expensive than a lookup, unless configured
+/// otherwise. Every other route costs 1.
+const DEFAULT_ROUTE_COSTS: &[(&str, u32)] = &[
+    ("/mempool/entries/all", 50),
+    ("/mempool/txids", 10),
+    ("/address/{address}", 10),
+    ("/address/{address}/txs", 10),
+    ("/address/{address}/utxos", 10),
+    ("/address/{address}/runes", 5),
+    ("/rune/{rune}/holders", 5),
+    ("/mempool/entries", 5),
+    ("/mempool/entries/ancestors", 5),
+    ("/mempool/tx/{txid}/package", 5),
+    ("/txs/statuses", 5),
+    ("/txs", 10),
+    ("/outputs", 5),
+    ("/runes/batch", 5),
+    ("/psbt/rune-transfer", 20),
+    ("/esplora/address/{address}/utxo", 20),
+    ("/esplora/address/{address}/txs", 10),
+    ("/esplora/address/{address}/txs/chain", 10),
+    ("/esplora/address/{address}/txs/chain/{last_seen}", 10),
+    ("/esplora/fee-estimates", 5),
+];
+
+/// How often buckets that have filled up again are dropped.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    /// Tokens added to every bucket per second.
+    pub per_second: u32,
+    /// Size of the buckets, i.e. the largest burst a client can make.
+    pub burst: u32,
+    /// Tokens taken by a request, by route. Routes that aren't listed take 1.
+    pub route_costs: HashMap<String, u32>,
+    /// Key clients without an API key by the last address of `X-Forwarded-For`, for
+    /// instances behind a reverse proxy.
+    pub forwarded_for: bool,
+}
+
+impl RateLimitConfig {
+    /// `route_costs` are added to, and override, the default route costs.
+    pub fn new(
+        per_second: u32,
+        burst: u32,
+        route_costs: Vec<RouteCost>,
+        forwarded_for: bool,
+    ) -> Self {
+        let route_costs = DEFAULT_ROUTE_COSTS
+            .iter()
+            .map(|(route, cost)| (route.to_string(), *cost))
+            .chain(route_costs.into_iter().map(|cost| (cost.route, cost.cost)))
+            .collect();
+
+        Self {
+            per_second,
+            burst,
+            route_costs,
+            forwarded_for,
+        }
+    }
+}
+
+/// A route and its cost, written `<ROUTE>=<COST>` with the route as it appears in
+/// the router, e.g. `/address/{address}/txs=20`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct RouteCost {
+    pub route: String,
+    pub cost: u32,
+}
+
+impl FromStr for RouteCost {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (route, cost) = s
+            .rsplit_once('=')
+            .ok_or_else(|| format!("expected <ROUTE>=<COST>, got `{s}`"))?;
+
+        if !route.starts_with('/') {
+            return Err(format!("route `{route}` must start with `/`"));
+        }
+
+        let cost = cost
+            .trim()
+            .parse()
+            .map_err(|e| format!("invalid cost `{cost}`: {e}"))?;
+
+        Ok(Self {
+            route: route.trim().to_string(),
+            cost,
+        })
+    }
+}
+
+impl TryFrom<String> for RouteCost {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ClientKey {
+    ApiKey(Uuid),
+    Ip(IpAddr),
+    /// Requests from an address that failed the API key check, kept apart from the
+    /// requests it makes without a key.
+    FailedAuth(IpAddr),
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+struct Buckets {
+    buckets: HashMap<ClientKey, Bucket>,
+    swept_at: Instant,
+}
+
+/// Token bucket rate limiter of the HTTP API, with one bucket per API key or, for
+/// requests without one, per client address. Failed API key checks have a bucket of
+/// their own per client address.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<Buckets>,
+    throttled: CounterVec,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, metrics: &Metrics) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                swept_at: Instant::now(),
+            }),
+            throttled: metrics.counter_vec(
+                prometheus::Opts::new(
+                    "http_throttled_requests_total",
+                    "Number of HTTP requests rejected by the rate limiter",
+                ),
+                &["route"],
+            ),
+        }
+    }
+
+    fn cost(&self, route: &str) -> u32 {
+        self.config.route_costs.get(route).copied().unwrap_or(1)
+    }
+
+    /// Take `cost` tokens from the bucket of `client`, or return how long it takes
+    /// until it holds enough of them.
+    fn acquire(&self, client: ClientKey, cost: u32, now: Instant) -> Result<(), Duration> {
+        // A request costing more than a full bucket would never get through.
+        let cost = f64::from(cost).min(f64::from(self.config.burst));
+        let per_second = f64::from(self.config.per_second);
+
+        self.with_bucket(client, now, |bucket| {
+            if bucket.tokens >= cost {
+                bucket.tokens -= cost;
+                Ok(())
+            } else {
+                Err(Duration::from_secs_f64((cost - bucket.tokens) / per_second))
+            }
+        })
+    }
+
+    /// Like [`Self::acquire`] with a cost of 1, but leaves the token in the bucket.
+    fn check(&self, client: ClientKey, now: Instant) -> Result<(), Duration> {
+        let per_second = f64::from(self.config.per_second);
+
+        self.with_bucket(client, now, |bucket| {
+            if bucket.tokens >= 1.0 {
+                Ok(())
+            } else {
+                Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+            }
+        })
+    }
+
+    /// Take a token from the bucket of `client`, if it has one left.
+    fn charge(&self, client: ClientKey, now: Instant) {
+        self.with_bucket(client, now, |bucket| {
+            bucket.tokens = (bucket.tokens - 1.0).max(0.0);
+        })
+    }
+
+    /// Run `f` on the bucket of `client`, refilled up to `now`.
+    fn with_bucket<T>(
+        &self,
+        client: ClientKey,
+        now: Instant,
+        f: impl FnOnce(&mut Bucket) -> T,
+    ) -> T {
+        let capacity = f64::from(self.config.burst);
+        let per_second = f64::from(self.config.per_second);
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if now.saturating_duration_since(buckets.swept_at) >= SWEEP_INTERVAL {
+            buckets.buckets.retain(|_, bucket| {
+                let elapsed = now.saturating_duration_since(bucket.updated_at);
+                bucket.tokens + elapsed.as_secs_f64() * per_second < capacity
+            });
+            buckets.swept_at = now;
+        }
+
+        let bucket = buckets.buckets.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * per_second).min(capacity);
+        bucket.updated_at = now;
+
+        f(bucket)
+    }
+
+    fn client_key(&self, request: &Request) -> Option<ClientKey> {
+        if let Some(entry) = request.extensions().get::<ApiKeyEntry>() {
+            return Some(ClientKey::ApiKey(entry.id));
+        }
+
+        self.client_address(request).map(ClientKey::Ip)
+    }
+
+    fn client_address(&self, request: &Request) -> Option<IpAddr> {
+        if self.config.forwarded_for {
+            let forwarded = request
+                .headers()
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok())
+                // Clients can send any leading entries; the proxy appends the last one.
+                .and_then(|value| value.rsplit(',').next())
+                .and_then(|address| address.trim().parse().ok());
+
+            if forwarded.is_some() {
+                return forwarded;
+            }
+        }
+
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip())
+    }
+
+    fn throttle(&self, route: &str, retry_after: Duration) -> ServerError {
+        self.throttled.with_label_values(&[route]).inc();
+        ServerError::TooManyRequests(retry_after)
+    }
+}
+
+fn route(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        // Keeps unknown paths from adding labels to the throttling metric.
+        .unwrap_or_else(|| "unmatched".to_string())
+}
+
+/// Label of the throttling metric for requests rejected before the API key check,
+/// where the route isn't matched yet.
+const FAILED_AUTH_ROUTE: &str = "auth";
+
+/// Answer `429 Too Many Requests` to client addresses that failed the API key check
+/// too often, before the check. Requests the check rejects take a token from the
+/// failed auth bucket of their address, so that keys can't be guessed faster than
+/// the rate limit.
+pub(super) async fn limit_failed_auth(
+    State(rate_limiter): State<Option<Arc<RateLimiter>>>,
+    request: Request,
+    next: Next,
+) -> ServerResult {
+    let Some(rate_limiter) = rate_limiter else {
+        return Ok(next.run(request).await);
+    };
+
+    let Some(client) = rate_limiter
+        .client_address(&request)
+        .map(ClientKey::FailedAuth)
+    else {
+        return Ok(next.run(request).await);
+    };
+
+    if let Err(retry_after) = rate_limiter.check(client, Instant::now()) {
+        return Err(rate_limiter.throttle(FAILED_AUTH_ROUTE, retry_after));
+    }
+
+    let response = next.run(request).await;
+    if matches!(
+        response.status(),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+    ) {
+        rate_limiter.charge(client, Instant::now());
+    }
+
+    Ok(response)
+}
+
+/// Answer `429 Too Many Requests` to clients that ran out of tokens. Runs after the
+/// API key check, so that clients with a key get their own bucket.
+pub(super) async fn limit_requests(
+    State(rate_limiter): State<Option<Arc<RateLimiter>>>,
+    request: Request,
+    next: Next,
+) -> ServerResult {
+    let Some(rate_limiter) = rate_limiter else {
+        return Ok(next.run(request).await);
+    };
+
+    if let Some(client) = rate_limiter.client_key(&request) {
+        let route = route(&request);
+        let cost = rate_limiter.cost(&route);
+        if let Err(retry_after) = rate_limiter.acquire(client, cost, Instant::now()) {
+            return Err(rate_limiter.throttle(&route, retry_after));
+        }
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            auth::{ApiKeys, MemoryStore},
+            models::ApiKeyScope,
+            server::auth,
+        },
+        axum::{middleware, routing::get, Router},
+        tokio::net::TcpListener,
+    };
+
+    #[test]
+    fn parses_route_costs() {
+        assert_eq!(
+            "/address/{address}/txs=20".parse::<RouteCost>().unwrap(),
+            RouteCost {
+                route: "/address/{address}/txs".to_string(),
+                cost: 20,
+            }
+        );
+        assert!("/tip".parse::<RouteCost>().is_err());
+        assert!("tip=2".parse::<RouteCost>().is_err());
+    }
+
+    #[test]
+    fn refills_buckets_over_time() {
+        let rate_limiter =
+            RateLimiter::new(RateLimitConfig::new(2, 10, vec![], false), &Metrics::new());
+        let client = ClientKey::Ip(IpAddr::from([127, 0, 0, 1]));
+        let start = Instant::now();
+
+        assert_eq!(rate_limiter.cost("/mempool/entries/all"), 50);
+        assert_eq!(rate_limiter.acquire(client, 50, start), Ok(()));
+        assert_eq!(
+            rate_limiter.acquire(client, 1, start),
+            Err(Duration::from_millis(500))
+        );
+        assert_eq!(
+            rate_limiter.acquire(client, 1, start + Duration::from_millis(500)),
+            Ok(())
+        );
+
+        // Other clients have their own bucket.
+        let other = ClientKey::Ip(IpAddr::from([127, 0, 0, 2]));
+        assert_eq!(rate_limiter.acquire(other, 10, start), Ok(()));
+
+        // Requests without a key don't use up the failed auth bucket of the address.
+        let failed_auth = ClientKey::FailedAuth(IpAddr::from([127, 0, 0, 1]));
+        assert_eq!(rate_limiter.check(failed_auth, start), Ok(()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn throttles_failed_auth_by_address() {
+        let api_keys = Arc::new(ApiKeys::new(Arc::new(MemoryStore::default()), true));
+        let (key, _) = api_keys
+            .create("explorer".to_string(), vec![ApiKeyScope::Read])
+            .unwrap();
+        let rate_limiter = Arc::new(RateLimiter::new(
+            RateLimitConfig::new(1, 2, vec![], false),
+            &Metrics::new(),
+        ));
+
+        let app = auth::require_scope(
+            Router::new().route("/tip", get(|| async {})),
+            &api_keys,
+            ApiKeyScope::Read,
+        )
+        .layer(middleware::from_fn_with_state(
+            Some(rate_limiter.clone()),
+            limit_failed_auth,
+        ));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/tip", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+        });
+
+        let client = reqwest::Client::new();
+        let status = |key: &str| {
+            let request = client.get(&url).bearer_auth(key);
+            async move { request.send().await.unwrap().status() }
+        };
+
+        assert_eq!(status("titan_unknown").await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status("titan_unknown").await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status("titan_unknown").await, StatusCode::TOO_MANY_REQUESTS);
+        // The address is throttled before its key is looked at.
+        assert_eq!(status(&key).await, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            rate_limiter
+                .throttled
+                .with_label_values(&[FAILED_AUTH_ROUTE])
+                .get(),
+            2
+        );
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(status(&key).await, StatusCode::OK);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn throttles_by_the_address_the_proxy_appends() {
+        let api_keys = Arc::new(ApiKeys::new(Arc::new(MemoryStore::default()), true));
+        let rate_limiter = Arc::new(RateLimiter::new(
+            RateLimitConfig::new(1, 2, vec![], true),
+            &Metrics::new(),
+        ));
+
+        let app = auth::require_scope(
+            Router::new().route("/tip", get(|| async {})),
+            &api_keys,
+            ApiKeyScope::Read,
+        )
+        .layer(middleware::from_fn_with_state(
+            Some(rate_limiter),
+            limit_failed_auth,
+        ));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/tip", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+        });
+
+        let client = reqwest::Client::new();
+        let status = |forwarded_for: String| {
+            let request = client
+                .get(&url)
+                .bearer_auth("titan_unknown")
+                .header("x-forwarded-for", forwarded_for);
+            async move { request.send().await.unwrap().status() }
+        };
+
+        // A new leading entry on every request doesn't get a new bucket.
+        for (spoofed, expected) in [
+            (1, StatusCode::UNAUTHORIZED),
+            (2, StatusCode::UNAUTHORIZED),
+            (3, StatusCode::TOO_MANY_REQUESTS),
+        ] {
+            assert_eq!(
+                status(format!("10.0.0.{spoofed}, 192.0.2.1")).await,
+                expected
+            );
+        }
+
+        // Another address the proxy saw has its own.
+        assert_eq!(
+            status("192.0.2.2".to_string()).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
@@ -1,28 +1,43 @@
 use {
     super::{
+        auth,
         deserialize_from_str::DeserializeFromStr,
         error::{OptionExt, ServerError, ServerResult},
-        ServerConfig,
+        esplora, rate_limit, RateLimiter, ServerConfig,
     },
     crate::{
         api::{self, content::AcceptEncoding},
+        auth::ApiKeys,
         bitcoin_rpc::RpcClientPool,
         index::Index,
-        subscription::WebhookSubscriptionManager,
+        models::ApiKeyScope,
+        subscription::{self, TcpSubscriptionManager, WebhookSubscriptionManager},
     },
     axum::{
         body::Bytes,
-        extract::{DefaultBodyLimit, Extension, FromRef, Json, Path, Query},
-        response::IntoResponse,
+        extract::{ws::WebSocketUpgrade, DefaultBodyLimit, Extension, FromRef, Json, Path, Query},
+        middleware,
+        response::{
+            sse::{KeepAlive, Sse},
+            IntoResponse,
+        },
         routing::{get, post},
         Router,
     },
     axum_server::Handle,
     bitcoin::{address::NetworkUnchecked, Address, BlockHash},
-    http::{header, StatusCode},
-    std::{io, net::ToSocketAddrs, sync::Arc},
-    titan_types_api::{query, Pagination, Subscription},
-    titan_types_core::{InscriptionId, SerializedOutPoint, SerializedTxid},
+    http::{header, HeaderMap, StatusCode},
+    std::{
+        io,
+        net::{SocketAddr, ToSocketAddrs},
+        sync::Arc,
+    },
+    titan_types_api::{
+        query, AddressFilter, Cursor, DeliveryQuery, EventStreamQuery, MempoolPackageQuery,
+        Pagination, RedeliverQuery, RuneFilter, RuneHistoryQuery, RuneTransferRequest,
+        Subscription, TcpSubscriptionRequest,
+    },
+    titan_types_core::{InscriptionId, RuneId, SerializedOutPoint, SerializedTxid},
     tokio::task,
     tower_http::{
         compression::CompressionLayer,
@@ -44,6 +59,16 @@ pub enum SpawnError {
 
 type SpawnResult<T> = std::result::Result<T, SpawnError>;
 
+/// Most items a request to `POST /outputs`, `POST /txs` or `POST /runes/batch` can ask
+/// for. Transactions are capped lower, as they may be fetched from the node one by one.
+const MAX_BATCH_OUTPUTS: usize = 1000;
+const MAX_BATCH_TRANSACTIONS: usize = 100;
+const MAX_BATCH_RUNES: usize = 1000;
+/// Most addresses and destinations a `POST /psbt/rune-transfer` can have. Every
+/// source address has a page of its outputs read for each rune sent.
+const MAX_RUNE_TRANSFER_SOURCES: usize = 20;
+const MAX_RUNE_TRANSFER_DESTINATIONS: usize = 100;
+
 pub struct Server;
 
 impl Server {
@@ -51,15 +76,35 @@ impl Server {
         &self,
         index: Arc<Index>,
         webhook_subscription_manager: Arc<WebhookSubscriptionManager>,
+        event_subscription_manager: Option<Arc<TcpSubscriptionManager>>,
         bitcoin_rpc_pool: RpcClientPool,
+        api_keys: Arc<ApiKeys>,
+        rate_limiter: Option<Arc<RateLimiter>>,
         config: Arc<ServerConfig>,
         handle: Handle,
     ) -> SpawnResult<task::JoinHandle<io::Result<()>>> {
-        let router = Router::new()
+        // Every route is in the group of the API key scope it needs. The rate limiter
+        // runs after the key check, so that clients with a key get their own bucket,
+        // and failed checks are throttled by client address before it.
+        let limit = |routes: Router<Arc<ServerConfig>>| {
+            routes.route_layer(middleware::from_fn_with_state(
+                rate_limiter.clone(),
+                rate_limit::limit_requests,
+            ))
+        };
+
+        let public = Router::new()
             // Health check
             .route("/", get(Self::health_check))
+            // WebSocket clients send the key with their subscription request, like TCP
+            // subscribers.
+            .route("/events/ws", get(Self::events_ws));
+
+        let mut read = Router::new()
             // Status
             .route("/status", get(Self::status))
+            // Metrics
+            .route("/metrics", get(Self::metrics))
             // Blocks
             .route("/tip", get(Self::tip))
             .route("/block/{query}", get(Self::block))
@@ -67,44 +112,113 @@ impl Server {
             .route("/block/{query}/txids", get(Self::block_txids))
             // Addresses
             .route("/address/{address}", get(Self::address))
+            .route("/address/{address}/txs", get(Self::address_transactions))
+            .route("/address/{address}/utxos", get(Self::address_utxos))
+            .route("/address/{address}/runes", get(Self::address_runes))
+            .route("/address/{address}/runes/{rune}", get(Self::address_rune))
             // Transactions
-            .route("/tx/broadcast", post(Self::broadcast_transaction))
+            .route("/tx/decode", post(Self::decode_transaction))
+            .route("/txs", post(Self::transactions))
             .route("/txs/statuses", post(Self::transaction_statuses))
             .route("/tx/{txid}", get(Self::transaction))
             .route("/tx/{txid}/raw", get(Self::transaction_raw))
             .route("/tx/{txid}/hex", get(Self::transaction_hex))
             .route("/tx/{txid}/status", get(Self::transaction_status))
+            .route("/tx/{txid}/outspends", get(Self::transaction_outspends))
             .route("/output/{outpoint}", get(Self::output))
+            .route("/outputs", post(Self::outputs))
+            // PSBTs
+            .route("/psbt/rune-transfer", post(Self::rune_transfer_psbt))
             // Inscriptions
             .route("/inscription/{inscription_id}", get(Self::inscription))
             // Runes
             .route("/runes", get(Self::runes))
+            .route("/runes/batch", post(Self::runes_batch))
             .route("/rune/{rune}", get(Self::rune))
+            .route("/rune/{rune}/history", get(Self::rune_history))
+            .route("/rune/{rune}/holders", get(Self::rune_holders))
             .route("/rune/{rune}/transactions", get(Self::rune_transactions))
             // Mempool
             .route("/mempool/txids", get(Self::mempool_txids))
+            .route("/mempool/blocks", get(Self::mempool_blocks))
+            .route("/mempool/fee-histogram", get(Self::mempool_fee_histogram))
+            // Fees
+            .route("/fees/recommended", get(Self::recommended_fees))
             // Mempool entries
             .route("/mempool/entry/{txid}", get(Self::mempool_tx))
             .route("/mempool/entries", post(Self::mempool_entries))
             .route("/mempool/entries/all", get(Self::mempool_all_entries))
+            .route("/mempool/tx/{txid}/package", get(Self::mempool_package))
             .route(
                 "/mempool/entries/ancestors",
                 post(Self::mempool_entries_with_ancestors),
-            )
-            // Subscriptions
+            );
+
+        // `EventSource` can't set headers, so it sends its key in the query string.
+        let event_stream = Router::new().route("/events/sse", get(Self::events_sse));
+
+        let mut broadcast = Router::new().route("/tx/broadcast", post(Self::broadcast_transaction));
+
+        let subscriptions_admin = Router::new()
             .route(
                 "/subscription/{id}",
                 get(Self::get_subscription).delete(Self::delete_subscription),
             )
+            .route(
+                "/subscription/{id}/rotate-secret",
+                post(Self::rotate_subscription_secret),
+            )
+            .route(
+                "/subscription/{id}/deliveries",
+                get(Self::subscription_deliveries),
+            )
+            .route(
+                "/subscription/{id}/redeliver",
+                post(Self::redeliver_subscription),
+            )
             .route("/subscription", post(Self::add_subscription))
-            .route("/subscriptions", get(Self::subscriptions))
+            .route("/subscriptions", get(Self::subscriptions));
+
+        if config.enable_esplora {
+            read = read.nest("/esplora", esplora::router());
+            broadcast = broadcast.nest("/esplora", esplora::broadcast_router());
+        }
+
+        let router = Router::new()
+            .merge(limit(public))
+            .merge(auth::require_scope(
+                limit(read),
+                &api_keys,
+                ApiKeyScope::Read,
+            ))
+            .merge(auth::require_scope_or_query_key(
+                limit(event_stream),
+                &api_keys,
+                ApiKeyScope::Read,
+            ))
+            .merge(auth::require_scope(
+                limit(broadcast),
+                &api_keys,
+                ApiKeyScope::Broadcast,
+            ))
+            .merge(auth::require_scope(
+                limit(subscriptions_admin),
+                &api_keys,
+                ApiKeyScope::SubscriptionsAdmin,
+            ))
+            .layer(middleware::from_fn_with_state(
+                rate_limiter,
+                rate_limit::limit_failed_auth,
+            ))
             .layer(Extension(index))
             .layer(Extension(webhook_subscription_manager))
+            .layer(Extension(event_subscription_manager))
             .layer(Extension(config.clone()))
             .layer(Extension(bitcoin_rpc_pool))
             .layer(
                 CorsLayer::new()
                     .allow_methods([http::Method::GET])
+                    .allow_headers([header::AUTHORIZATION, auth::API_KEY_HEADER])
                     .allow_origin(Any),
             )
             .layer(DefaultBodyLimit::disable())
@@ -133,7 +247,7 @@ impl Server {
         Ok(tokio::spawn(async move {
             axum_server::Server::bind(addr)
                 .handle(handle)
-                .serve(router.into_make_service())
+                .serve(router.into_make_service_with_connect_info::<SocketAddr>())
                 .await
         }))
     }
@@ -155,6 +269,17 @@ impl Server {
         task::block_in_place(|| Ok(Json(api::status(index)?).into_response()))
     }
 
+    async fn metrics(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+        task::block_in_place(|| {
+            Ok((
+                StatusCode::OK,
+                [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+                api::metrics(index)?,
+            )
+                .into_response())
+        })
+    }
+
     async fn block(
         Extension(index): Extension<Arc<Index>>,
         Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Block>>,
@@ -193,6 +318,13 @@ impl Server {
         })
     }
 
+    async fn decode_transaction(
+        Extension(index): Extension<Arc<Index>>,
+        hex: String,
+    ) -> ServerResult {
+        task::block_in_place(|| Ok(Json(api::decode_transaction(index, &hex)?).into_response()))
+    }
+
     async fn transaction(
         Extension(index): Extension<Arc<Index>>,
         Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
@@ -204,6 +336,19 @@ impl Server {
         })
     }
 
+    async fn transactions(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        Json(txids): Json<Vec<SerializedTxid>>,
+    ) -> ServerResult {
+        Self::checked_batch_size(txids.len(), MAX_BATCH_TRANSACTIONS)?;
+
+        task::block_in_place(|| {
+            let transactions = api::transactions(index, bitcoin_rpc_pool.get()?, &txids)?;
+            Ok(Json(transactions).into_response())
+        })
+    }
+
     async fn transaction_raw(
         Extension(index): Extension<Arc<Index>>,
         Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
@@ -245,6 +390,17 @@ impl Server {
         task::block_in_place(|| Ok(Json(api::transaction_status(index, &txid)?).into_response()))
     }
 
+    async fn transaction_outspends(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        Path(txid): Path<SerializedTxid>,
+    ) -> ServerResult {
+        task::block_in_place(|| {
+            let outspends = api::transaction_outspends(index, bitcoin_rpc_pool.get()?, &txid)?;
+            Ok(Json(outspends).into_response())
+        })
+    }
+
     async fn transaction_statuses(
         Extension(index): Extension<Arc<Index>>,
         Query(blockhash): Query<Option<BlockHash>>,
@@ -269,11 +425,80 @@ impl Server {
         task::block_in_place(|| Ok(Json(api::output(index, &outpoint)?).into_response()))
     }
 
+    async fn outputs(
+        Extension(index): Extension<Arc<Index>>,
+        Json(outpoints): Json<Vec<SerializedOutPoint>>,
+    ) -> ServerResult {
+        Self::checked_batch_size(outpoints.len(), MAX_BATCH_OUTPUTS)?;
+
+        task::block_in_place(|| Ok(Json(api::outputs(index, &outpoints)?).into_response()))
+    }
+
+    async fn rune_transfer_psbt(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Extension(bitcoin_rpc_pool): Extension<RpcClientPool>,
+        Json(request): Json<RuneTransferRequest>,
+    ) -> ServerResult {
+        if request.sources.is_empty() || request.destinations.is_empty() {
+            return Err(ServerError::BadRequest(
+                "sources and destinations can't be empty".to_string(),
+            ));
+        }
+
+        Self::checked_batch_size(request.sources.len(), MAX_RUNE_TRANSFER_SOURCES)?;
+        Self::checked_batch_size(request.destinations.len(), MAX_RUNE_TRANSFER_DESTINATIONS)?;
+
+        for source in &request.sources {
+            Self::checked_address(&config, source.clone())?;
+        }
+
+        // Nodes don't relay transactions paying less than 1 sat/vB.
+        if !request.fee_rate.is_finite() || request.fee_rate < 1.0 {
+            return Err(ServerError::BadRequest(
+                "fee_rate must be at least 1 sat/vB".to_string(),
+            ));
+        }
+
+        // An edict of 0 would send everything that's left of the rune.
+        if request
+            .destinations
+            .iter()
+            .any(|destination| destination.amount == 0)
+        {
+            return Err(ServerError::BadRequest(
+                "destination amounts must be positive".to_string(),
+            ));
+        }
+
+        task::block_in_place(|| {
+            Ok(Json(api::rune_transfer_psbt(
+                index,
+                bitcoin_rpc_pool.get()?,
+                config.chain.network(),
+                &request,
+            )?)
+            .into_response())
+        })
+    }
+
     async fn runes(
         Extension(index): Extension<Arc<Index>>,
         Query(pagination): Query<Pagination>,
+        Query(filter): Query<RuneFilter>,
     ) -> ServerResult {
-        task::block_in_place(|| Ok(Json(api::runes(index, pagination)?).into_response()))
+        Self::checked_cursor(&pagination, |cursor| cursor.index().is_some())?;
+
+        task::block_in_place(|| Ok(Json(api::runes(index, pagination, &filter)?).into_response()))
+    }
+
+    async fn runes_batch(
+        Extension(index): Extension<Arc<Index>>,
+        Json(rune_ids): Json<Vec<RuneId>>,
+    ) -> ServerResult {
+        Self::checked_batch_size(rune_ids.len(), MAX_BATCH_RUNES)?;
+
+        task::block_in_place(|| Ok(Json(api::runes_batch(index, &rune_ids)?).into_response()))
     }
 
     async fn rune(
@@ -283,11 +508,35 @@ impl Server {
         task::block_in_place(|| Ok(Json(api::rune(index, &rune)?).into_response()))
     }
 
+    async fn rune_history(
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+        Query(history_query): Query<RuneHistoryQuery>,
+    ) -> ServerResult {
+        task::block_in_place(|| {
+            Ok(Json(api::rune_history(index, &rune, &history_query)?).into_response())
+        })
+    }
+
+    async fn rune_holders(
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
+        Query(pagination): Query<Pagination>,
+    ) -> ServerResult {
+        Self::checked_cursor(&pagination, |cursor| cursor.holder().is_some())?;
+
+        task::block_in_place(|| {
+            Ok(Json(api::rune_holders(index, &rune, pagination)?).into_response())
+        })
+    }
+
     async fn rune_transactions(
         Extension(index): Extension<Arc<Index>>,
         Path(DeserializeFromStr(rune)): Path<DeserializeFromStr<query::Rune>>,
         Query(pagination): Query<Pagination>,
     ) -> ServerResult {
+        Self::checked_cursor(&pagination, |cursor| cursor.index().is_some())?;
+
         task::block_in_place(|| {
             Ok(Json(api::last_rune_transactions(index, &rune, Some(pagination))?).into_response())
         })
@@ -316,6 +565,37 @@ impl Server {
         task::block_in_place(|| Ok(Json(api::mempool_txids(index)?).into_response()))
     }
 
+    async fn mempool_package(
+        Extension(index): Extension<Arc<Index>>,
+        Path(txid): Path<SerializedTxid>,
+        Query(query): Query<MempoolPackageQuery>,
+    ) -> ServerResult {
+        if query
+            .fee_rate
+            .is_some_and(|fee_rate| !fee_rate.is_finite() || fee_rate <= 0.0)
+        {
+            return Err(ServerError::BadRequest(
+                "fee_rate must be a positive number".to_string(),
+            ));
+        }
+
+        task::block_in_place(|| {
+            Ok(Json(api::mempool_package(index, &txid, &query)?).into_response())
+        })
+    }
+
+    async fn mempool_blocks(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+        task::block_in_place(|| Ok(Json(api::mempool_blocks(index)?).into_response()))
+    }
+
+    async fn mempool_fee_histogram(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+        task::block_in_place(|| Ok(Json(api::mempool_fee_histogram(index)?).into_response()))
+    }
+
+    async fn recommended_fees(Extension(index): Extension<Arc<Index>>) -> ServerResult {
+        task::block_in_place(|| Ok(Json(api::recommended_fees(index)?).into_response()))
+    }
+
     async fn mempool_tx(
         Extension(index): Extension<Arc<Index>>,
         Path(txid): Path<SerializedTxid>,
@@ -343,11 +623,39 @@ impl Server {
         })
     }
 
-    async fn address(
-        Extension(index): Extension<Arc<Index>>,
-        Extension(config): Extension<Arc<ServerConfig>>,
-        Path(address): Path<Address<NetworkUnchecked>>,
-    ) -> ServerResult {
+    fn checked_batch_size(len: usize, max: usize) -> ServerResult<()> {
+        if len > max {
+            return Err(ServerError::BadRequest(format!(
+                "batch of {len} items exceeds the limit of {max}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Rejects cursors taken from a listing of another kind, which would otherwise
+    /// restart the listing from the start.
+    fn checked_cursor(
+        pagination: &Pagination,
+        belongs: impl Fn(&Cursor) -> bool,
+    ) -> ServerResult<()> {
+        if pagination
+            .cursor
+            .as_ref()
+            .is_some_and(|cursor| !belongs(cursor))
+        {
+            return Err(ServerError::BadRequest(
+                "cursor doesn't belong to this listing".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn checked_address(
+        config: &ServerConfig,
+        address: Address<NetworkUnchecked>,
+    ) -> ServerResult<Address> {
         if !config.index_addresses {
             return Err(ServerError::BadRequest(
                 "addresses are not indexed. Enable --index-addresses to index addresses"
@@ -355,13 +663,79 @@ impl Server {
             ));
         }
 
-        let address = address
+        address
             .require_network(config.chain.network())
-            .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+            .map_err(|err| ServerError::BadRequest(err.to_string()))
+    }
+
+    async fn address(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(address): Path<Address<NetworkUnchecked>>,
+    ) -> ServerResult {
+        let address = Self::checked_address(&config, address)?;
 
         task::block_in_place(|| Ok(Json(api::address(index, &address)?).into_response()))
     }
 
+    async fn address_transactions(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(address): Path<Address<NetworkUnchecked>>,
+        Query(pagination): Query<Pagination>,
+        Query(filter): Query<AddressFilter>,
+    ) -> ServerResult {
+        let address = Self::checked_address(&config, address)?;
+        Self::checked_cursor(&pagination, |cursor| cursor.index().is_some())?;
+
+        task::block_in_place(|| {
+            Ok(Json(api::address_transactions(
+                index, &address, pagination, &filter,
+            )?)
+            .into_response())
+        })
+    }
+
+    async fn address_utxos(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(address): Path<Address<NetworkUnchecked>>,
+        Query(pagination): Query<Pagination>,
+        Query(filter): Query<AddressFilter>,
+    ) -> ServerResult {
+        let address = Self::checked_address(&config, address)?;
+        Self::checked_cursor(&pagination, |cursor| cursor.outpoint().is_some())?;
+
+        task::block_in_place(|| {
+            Ok(Json(api::address_utxos(index, &address, pagination, &filter)?).into_response())
+        })
+    }
+
+    async fn address_runes(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(address): Path<Address<NetworkUnchecked>>,
+    ) -> ServerResult {
+        let address = Self::checked_address(&config, address)?;
+
+        task::block_in_place(|| Ok(Json(api::address_runes(index, &address)?).into_response()))
+    }
+
+    async fn address_rune(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path((address, DeserializeFromStr(rune))): Path<(
+            Address<NetworkUnchecked>,
+            DeserializeFromStr<query::Rune>,
+        )>,
+    ) -> ServerResult {
+        let address = Self::checked_address(&config, address)?;
+
+        task::block_in_place(
+            || Ok(Json(api::address_rune(index, &address, &rune)?).into_response()),
+        )
+    }
+
     async fn subscriptions(
         Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
         Extension(config): Extension<Arc<ServerConfig>>,
@@ -375,10 +749,51 @@ impl Server {
         task::block_in_place(|| Ok(Json(api::subscriptions(subscription_manager)?).into_response()))
     }
 
+    async fn events_ws(
+        Extension(manager): Extension<Option<Arc<TcpSubscriptionManager>>>,
+        ws: WebSocketUpgrade,
+    ) -> ServerResult {
+        let manager = manager
+            .ok_or_else(|| ServerError::BadRequest("event streams are not enabled".to_string()))?;
+
+        Ok(ws
+            .on_upgrade(move |socket| subscription::handle_websocket(socket, manager))
+            .into_response())
+    }
+
+    async fn events_sse(
+        Extension(manager): Extension<Option<Arc<TcpSubscriptionManager>>>,
+        Query(query): Query<EventStreamQuery>,
+        headers: HeaderMap,
+    ) -> ServerResult {
+        let manager = manager
+            .ok_or_else(|| ServerError::BadRequest("event streams are not enabled".to_string()))?;
+
+        let mut request = TcpSubscriptionRequest::try_from(query)
+            .map_err(|e| ServerError::BadRequest(e.to_string()))?;
+
+        // Browsers reconnect with the id of the last event they received.
+        let last_event_id = headers
+            .get("last-event-id")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        request.from_seq = request.from_seq.or(last_event_id.map(|seq| seq + 1));
+
+        manager
+            .validate(&mut request)
+            .map_err(|e| ServerError::BadRequest(e.to_string()))?;
+
+        let stream = subscription::sse_stream(manager, request).await;
+
+        Ok(Sse::new(stream)
+            .keep_alive(KeepAlive::default())
+            .into_response())
+    }
+
     async fn add_subscription(
         Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
         Extension(config): Extension<Arc<ServerConfig>>,
-        Json(subscription): Json<Subscription>,
+        Json(mut subscription): Json<Subscription>,
     ) -> ServerResult {
         if !config.enable_webhook_subscriptions {
             return Err(ServerError::BadRequest(
@@ -386,6 +801,12 @@ impl Server {
             ));
         }
 
+        if let Some(filter) = subscription.filter.as_mut() {
+            filter
+                .normalize_addresses(config.chain.network())
+                .map_err(|e| ServerError::BadRequest(e.to_string()))?;
+        }
+
         task::block_in_place(|| {
             Ok(Json(api::add_subscription(subscription_manager, subscription)?).into_response())
         })
@@ -422,6 +843,66 @@ impl Server {
             Ok(Json(api::get_subscription(subscription_manager, id)?).into_response())
         })
     }
+
+    async fn subscription_deliveries(
+        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(id): Path<Uuid>,
+        Query(query): Query<DeliveryQuery>,
+    ) -> ServerResult {
+        if !config.enable_webhook_subscriptions {
+            return Err(ServerError::BadRequest(
+                "subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        task::block_in_place(|| {
+            Ok(Json(api::subscription_deliveries(
+                subscription_manager,
+                id,
+                &query,
+            )?)
+            .into_response())
+        })
+    }
+
+    async fn redeliver_subscription(
+        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(id): Path<Uuid>,
+        Query(query): Query<RedeliverQuery>,
+    ) -> ServerResult {
+        if !config.enable_webhook_subscriptions {
+            return Err(ServerError::BadRequest(
+                "subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        task::block_in_place(|| {
+            Ok(Json(api::redeliver_subscription(
+                subscription_manager,
+                id,
+                query.seq,
+            )?)
+            .into_response())
+        })
+    }
+
+    async fn rotate_subscription_secret(
+        Extension(subscription_manager): Extension<Arc<WebhookSubscriptionManager>>,
+        Extension(config): Extension<Arc<ServerConfig>>,
+        Path(id): Path<Uuid>,
+    ) -> ServerResult {
+        if !config.enable_webhook_subscriptions {
+            return Err(ServerError::BadRequest(
+                "subscriptions are not enabled".to_string(),
+            ));
+        }
+
+        task::block_in_place(|| {
+            Ok(Json(api::rotate_subscription_secret(subscription_manager, id)?).into_response())
+        })
+    }
 }
 
 impl<S> axum::extract::FromRequestParts<S> for AcceptEncoding
@@ -1,4 +1,5 @@
 use {
+    super::RateLimitConfig,
     crate::{
         bitcoin_rpc::{RpcClientError, RpcClientProvider},
         index::Chain,
@@ -19,6 +20,9 @@ pub struct ServerConfig {
 
     pub(crate) index_addresses: bool,
     pub(crate) enable_webhook_subscriptions: bool,
+    pub(crate) enable_esplora: bool,
+
+    pub(crate) rate_limit: Option<RateLimitConfig>,
 }
 
 impl RpcClientProvider for ServerConfig {
@@ -1,5 +1,5 @@
 use {
-    super::tcp_subscription::TcpSubscriptionManager,
+    super::{event_log::EventLog, tcp_subscription::TcpSubscriptionManager},
     crate::subscription::WebhookSubscriptionManager,
     chrono::{DateTime, Utc},
     std::{fs::OpenOptions, io::Write, sync::Arc, time::SystemTime},
@@ -7,14 +7,16 @@ use {
     tokio::{
         select,
         sync::{mpsc, watch},
+        task,
     },
     tracing::{error, info},
 };
 
-/// Asynchronously receive events from `receiver` and process them.
-/// If a shutdown signal arrives, exit gracefully.
+/// Asynchronously receive events from `receiver`, append them to the event log and
+/// process them. If a shutdown signal arrives, exit gracefully.
 pub async fn event_dispatcher(
     mut receiver: mpsc::Receiver<Event>,
+    event_log: Arc<EventLog>,
     subscription_manager: Option<Arc<WebhookSubscriptionManager>>,
     tcp_subscription_manager: Option<Arc<TcpSubscriptionManager>>,
     mut shutdown_rx: watch::Receiver<()>,
@@ -26,22 +28,24 @@ pub async fn event_dispatcher(
             maybe_event = receiver.recv() => {
                 match maybe_event {
                     Some(event) => {
-                        // Process the event, e.g. dispatch to subscribed endpoints
+                        let event = task::block_in_place(|| event_log.append(event));
+
+                        // Queue the event for the subscribed webhook endpoints
                         if let Some(manager) = &subscription_manager {
-                            if let Err(e) = manager.broadcast(&event).await {
+                            if let Err(e) = task::block_in_place(|| manager.broadcast(&event.event)) {
                                 error!("Error processing event: {:?}", e);
                             }
                         }
 
                         if enable_file_logging {
                             if matches!(
-                                event,
+                                event.event,
                                 Event::TransactionsAdded { .. }
                                 | Event::TransactionsReplaced { .. }
                                 | Event::NewBlock { .. }
                                 | Event::Reorg { .. }
                             ) {
-                                append_to_file("events.log", &format!("{:?}", event)).unwrap();
+                                append_to_file("events.log", &format!("{:?}", event.event)).unwrap();
                             }
                         }
 
@@ -0,0 +1,247 @@
+use {
+    crate::models::EventLogEntry,
+    std::sync::{Arc, Mutex},
+    titan_types_core::{Event, SequencedEvent},
+    tracing::error,
+};
+
+mod store;
+
+pub use store::{Store, StoreError};
+
+/// Number of appended events between two count-based prunes. The log is also pruned
+/// on every new block.
+const PRUNE_INTERVAL: u64 = 1_000;
+
+#[derive(Debug, Clone)]
+pub struct EventLogConfig {
+    /// Number of most recent events kept in the log.
+    pub max_events: u64,
+    /// Events emitted more than this many blocks below the tip are pruned.
+    pub max_block_depth: Option<u64>,
+}
+
+#[derive(Debug)]
+struct EventLogState {
+    next_seq: u64,
+    /// Chain tip as seen by the emitted events.
+    height: u64,
+}
+
+/// Bounded, persisted log of every event the indexer emits. Sequence numbers survive
+/// restarts, so subscribers can resume across them.
+pub struct EventLog {
+    store: Arc<dyn Store>,
+    config: EventLogConfig,
+    state: Mutex<EventLogState>,
+}
+
+impl EventLog {
+    pub fn open(store: Arc<dyn Store>, config: EventLogConfig) -> Result<Self, StoreError> {
+        let next_seq = store.get_last_event_seq()?.map_or(0, |seq| seq + 1);
+        let height = store.get_block_count()?.saturating_sub(1);
+
+        let event_log = Self {
+            store,
+            config,
+            state: Mutex::new(EventLogState { next_seq, height }),
+        };
+        event_log.prune(next_seq, height)?;
+
+        Ok(event_log)
+    }
+
+    /// Assign the next sequence number to `event` and persist it. A failed write is
+    /// only logged: live subscribers still get the event, it just can't be replayed.
+    pub fn append(&self, event: Event) -> SequencedEvent {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        match &event {
+            Event::NewBlock { block_height, .. } => state.height = *block_height,
+            Event::Reorg { height, depth } => {
+                state.height = height.saturating_sub(*depth).saturating_sub(1)
+            }
+            _ => {}
+        }
+
+        let seq = state.next_seq;
+        state.next_seq += 1;
+
+        let entry = EventLogEntry {
+            height: state.height,
+            event,
+        };
+
+        if let Err(e) = self.store.append_event(seq, &entry) {
+            error!("Failed to append event {} to the event log: {:?}", seq, e);
+        }
+
+        if seq % PRUNE_INTERVAL == 0 || matches!(entry.event, Event::NewBlock { .. }) {
+            if let Err(e) = self.prune(state.next_seq, state.height) {
+                error!("Failed to prune the event log: {:?}", e);
+            }
+        }
+
+        SequencedEvent {
+            seq,
+            event: entry.event,
+        }
+    }
+
+    /// Returns up to `limit` logged events, starting at `from_seq` or at the oldest
+    /// logged event if `from_seq` has been pruned.
+    pub fn read(&self, from_seq: u64, limit: usize) -> Result<Vec<SequencedEvent>, StoreError> {
+        Ok(self
+            .store
+            .get_events(from_seq, limit)?
+            .into_iter()
+            .map(|(seq, entry)| SequencedEvent {
+                seq,
+                event: entry.event,
+            })
+            .collect())
+    }
+
+    fn prune(&self, next_seq: u64, height: u64) -> Result<(), StoreError> {
+        let mut keep_from = next_seq.saturating_sub(self.config.max_events);
+
+        if let Some(max_block_depth) = self.config.max_block_depth {
+            let min_height = height.saturating_sub(max_block_depth);
+            let first_kept = self
+                .store
+                .get_first_event_seq_from_height(min_height)?
+                .unwrap_or(next_seq);
+            keep_from = keep_from.max(first_kept);
+        }
+
+        match self.store.get_first_event_seq()? {
+            Some(first_seq) if first_seq < keep_from => self.store.delete_events_before(keep_from),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use {
+        super::*,
+        bitcoin::{hashes::Hash, BlockHash},
+        std::collections::BTreeMap,
+    };
+
+    #[derive(Default)]
+    pub(crate) struct MemoryStore {
+        events: Mutex<BTreeMap<u64, EventLogEntry>>,
+    }
+
+    impl Store for MemoryStore {
+        fn get_block_count(&self) -> Result<u64, StoreError> {
+            Ok(0)
+        }
+
+        fn get_first_event_seq(&self) -> Result<Option<u64>, StoreError> {
+            Ok(self.events.lock().unwrap().keys().next().copied())
+        }
+
+        fn get_last_event_seq(&self) -> Result<Option<u64>, StoreError> {
+            Ok(self.events.lock().unwrap().keys().next_back().copied())
+        }
+
+        fn get_first_event_seq_from_height(&self, height: u64) -> Result<Option<u64>, StoreError> {
+            Ok(self
+                .events
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(_, entry)| entry.height >= height)
+                .map(|(seq, _)| *seq))
+        }
+
+        fn append_event(&self, seq: u64, entry: &EventLogEntry) -> Result<(), StoreError> {
+            self.events.lock().unwrap().insert(seq, entry.clone());
+            Ok(())
+        }
+
+        fn get_events(
+            &self,
+            from_seq: u64,
+            limit: usize,
+        ) -> Result<Vec<(u64, EventLogEntry)>, StoreError> {
+            Ok(self
+                .events
+                .lock()
+                .unwrap()
+                .range(from_seq..)
+                .take(limit)
+                .map(|(seq, entry)| (*seq, entry.clone()))
+                .collect())
+        }
+
+        fn delete_events_before(&self, seq: u64) -> Result<(), StoreError> {
+            let mut events = self.events.lock().unwrap();
+            *events = events.split_off(&seq);
+            Ok(())
+        }
+    }
+
+    fn new_block(block_height: u64) -> Event {
+        Event::NewBlock {
+            block_hash: BlockHash::all_zeros(),
+            block_height,
+        }
+    }
+
+    fn transactions_added() -> Event {
+        Event::TransactionsAdded { txids: vec![] }
+    }
+
+    fn seqs(event_log: &EventLog, from_seq: u64) -> Vec<u64> {
+        event_log
+            .read(from_seq, usize::MAX)
+            .unwrap()
+            .into_iter()
+            .map(|event| event.seq)
+            .collect()
+    }
+
+    #[test]
+    fn resumes_sequence_numbers_after_restart() {
+        let store = Arc::new(MemoryStore::default());
+        let config = EventLogConfig {
+            max_events: 2,
+            max_block_depth: None,
+        };
+
+        let event_log = EventLog::open(store.clone(), config.clone()).unwrap();
+        assert_eq!(event_log.append(transactions_added()).seq, 0);
+        assert_eq!(event_log.append(transactions_added()).seq, 1);
+
+        let event_log = EventLog::open(store, config).unwrap();
+        assert_eq!(event_log.append(transactions_added()).seq, 2);
+        assert_eq!(seqs(&event_log, 1), vec![1, 2]);
+
+        // Pruning by count happens on the next block.
+        assert_eq!(event_log.append(new_block(1)).seq, 3);
+        assert_eq!(seqs(&event_log, 0), vec![2, 3]);
+    }
+
+    #[test]
+    fn prunes_by_block_depth() {
+        let event_log = EventLog::open(
+            Arc::new(MemoryStore::default()),
+            EventLogConfig {
+                max_events: 100,
+                max_block_depth: Some(1),
+            },
+        )
+        .unwrap();
+
+        for height in 1..=3 {
+            event_log.append(new_block(height));
+            event_log.append(transactions_added());
+        }
+
+        // Only the events of the tip and of the block below it are kept.
+        assert_eq!(seqs(&event_log, 0), vec![2, 3, 4, 5]);
+    }
+}
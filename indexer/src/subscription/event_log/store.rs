@@ -0,0 +1,63 @@
+use {
+    crate::{
+        db::{RocksDB, RocksDBError},
+        models::EventLogEntry,
+    },
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("db error {0}")]
+    DB(#[from] RocksDBError),
+}
+
+pub trait Store: Send + Sync {
+    fn get_block_count(&self) -> Result<u64, StoreError>;
+
+    // event log
+    fn get_first_event_seq(&self) -> Result<Option<u64>, StoreError>;
+    fn get_last_event_seq(&self) -> Result<Option<u64>, StoreError>;
+    fn get_first_event_seq_from_height(&self, height: u64) -> Result<Option<u64>, StoreError>;
+    fn append_event(&self, seq: u64, entry: &EventLogEntry) -> Result<(), StoreError>;
+    fn get_events(
+        &self,
+        from_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, EventLogEntry)>, StoreError>;
+    fn delete_events_before(&self, seq: u64) -> Result<(), StoreError>;
+}
+
+impl Store for RocksDB {
+    fn get_block_count(&self) -> Result<u64, StoreError> {
+        Ok(self.get_block_count()?)
+    }
+
+    fn get_first_event_seq(&self) -> Result<Option<u64>, StoreError> {
+        Ok(self.get_first_event_seq()?)
+    }
+
+    fn get_last_event_seq(&self) -> Result<Option<u64>, StoreError> {
+        Ok(self.get_last_event_seq()?)
+    }
+
+    fn get_first_event_seq_from_height(&self, height: u64) -> Result<Option<u64>, StoreError> {
+        Ok(self.get_first_event_seq_from_height(height)?)
+    }
+
+    fn append_event(&self, seq: u64, entry: &EventLogEntry) -> Result<(), StoreError> {
+        Ok(self.append_event(seq, entry)?)
+    }
+
+    fn get_events(
+        &self,
+        from_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, EventLogEntry)>, StoreError> {
+        Ok(self.get_events(from_seq, limit)?)
+    }
+
+    fn delete_events_before(&self, seq: u64) -> Result<(), StoreError> {
+        Ok(self.delete_events_before(seq)?)
+    }
+}
@@ -0,0 +1,365 @@
+use {
+    super::tcp_subscription::{TcpSubscriptionManager, Transport, HANDSHAKE_TIMEOUT_SECS},
+    axum::{
+        extract::ws::{Message, WebSocket},
+        response::sse::Event as SseEvent,
+    },
+    std::{sync::Arc, time::Duration},
+    titan_types_api::TcpSubscriptionRequest,
+    titan_types_core::SequencedEvent,
+    tokio::sync::mpsc,
+    tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt},
+    tracing::{debug, error, info},
+};
+
+/// Subscribe to the events `request` asks for and stream them as SSE messages whose id is the
+/// event's sequence number. The stream ends when the subscriber is evicted for
+/// falling behind.
+pub async fn sse_stream(
+    manager: Arc<TcpSubscriptionManager>,
+    request: TcpSubscriptionRequest,
+) -> impl Stream<Item = Result<SseEvent, axum::Error>> {
+    let mut subscription = manager.subscribe(request, Transport::Sse).await;
+    info!("Registered SSE subscription with id {}", subscription.id());
+
+    // Pump the subscription into a channel owned by the response, so that it ends
+    // (and is unregistered) as soon as the client goes away.
+    let (tx, rx) = mpsc::channel(1);
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                maybe_event = subscription.recv() => {
+                    let Some(event) = maybe_event else {
+                        break;
+                    };
+
+                    if tx.send(event).await.is_err() {
+                        break;
+                    }
+                }
+                _ = tx.closed() => break,
+            }
+        }
+
+        info!(
+            "Unregistered SSE subscription with id {}",
+            subscription.id()
+        );
+    });
+
+    ReceiverStream::new(rx).map(|event: SequencedEvent| {
+        SseEvent::default()
+            .id(event.seq.to_string())
+            .json_data(&event)
+    })
+}
+
+/// Handle a WebSocket connection. The client sends the same JSON request as TCP
+/// subscribers, then receives one text message per event. `PING` text messages are
+/// answered with `PONG`.
+pub async fn handle_websocket(mut socket: WebSocket, manager: Arc<TcpSubscriptionManager>) {
+    let mut request = match tokio::time::timeout(
+        Duration::from_secs(HANDSHAKE_TIMEOUT_SECS),
+        read_websocket_request(&mut socket),
+    )
+    .await
+    {
+        Ok(Ok(request)) => request,
+        Ok(Err(e)) => {
+            debug!("WebSocket subscription request failed: {}", e);
+            let _ = socket.send(Message::Text(e.into())).await;
+            return;
+        }
+        Err(_) => {
+            debug!("Timed out waiting for WebSocket subscription request");
+            return;
+        }
+    };
+
+    if let Err(e) = manager.authorize(&mut request) {
+        debug!("WebSocket subscription request rejected: {}", e);
+        let _ = socket.send(Message::Text(e.to_string().into())).await;
+        let _ = socket.send(Message::Close(None)).await;
+        return;
+    }
+
+    if let Err(e) = manager.validate(&mut request) {
+        debug!("WebSocket subscription request rejected: {}", e);
+        let _ = socket.send(Message::Text(e.to_string().into())).await;
+        let _ = socket.send(Message::Close(None)).await;
+        return;
+    }
+
+    info!("Received WebSocket subscription request: {:?}", request);
+
+    let mut subscription = manager.subscribe(request, Transport::WebSocket).await;
+    let sub_id = subscription.id();
+    info!("Registered WebSocket subscription with id {}", sub_id);
+
+    loop {
+        tokio::select! {
+            maybe_event = subscription.recv() => {
+                let Some(event) = maybe_event else {
+                    info!("Event channel closed for subscription {}", sub_id);
+                    break;
+                };
+
+                let json = match serde_json::to_string(&event) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        error!("Failed to serialize event: {:?}", e);
+                        continue;
+                    }
+                };
+
+                if socket.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => {
+                match message {
+                    Some(Ok(Message::Text(text))) if text.trim() == "PING" => {
+                        if socket.send(Message::Text("PONG".into())).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        info!("WebSocket client disconnected for subscription {}", sub_id);
+                        break;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        error!("Error reading from WebSocket connection: {:?}", e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+    drop(subscription);
+    info!("Unregistered WebSocket subscription with id {}", sub_id);
+}
+
+async fn read_websocket_request(socket: &mut WebSocket) -> Result<TcpSubscriptionRequest, String> {
+    loop {
+        match socket.recv().await {
+            Some(Ok(Message::Text(text))) => {
+                let trimmed = text.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+
+                if trimmed == "PING" {
+                    socket
+                        .send(Message::Text("PONG".into()))
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    continue;
+                }
+
+                return serde_json::from_str(trimmed)
+                    .map_err(|e| format!("invalid subscription request: {e}"));
+            }
+            Some(Ok(Message::Close(_))) | None => {
+                return Err("connection closed before sending subscription request".into());
+            }
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            index::Metrics,
+            subscription::tcp_subscription::tests::{manager, request, subscribers},
+        },
+        axum::{
+            extract::{ws::WebSocketUpgrade, State},
+            response::{sse::Sse, IntoResponse, Response},
+            routing::get,
+            Router,
+        },
+        bitcoin::{hashes::Hash, BlockHash},
+        futures_util::SinkExt,
+        titan_types_core::Event,
+        tokio::net::TcpListener,
+        tokio_tungstenite::tungstenite,
+    };
+
+    async fn websocket(
+        State(manager): State<Arc<TcpSubscriptionManager>>,
+        ws: WebSocketUpgrade,
+    ) -> Response {
+        ws.on_upgrade(move |socket| handle_websocket(socket, manager))
+    }
+
+    async fn sse(State(manager): State<Arc<TcpSubscriptionManager>>) -> Response {
+        Sse::new(sse_stream(manager, request()).await).into_response()
+    }
+
+    async fn serve(manager: Arc<TcpSubscriptionManager>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let app = Router::new()
+            .route("/events/ws", get(websocket))
+            .route("/events/sse", get(sse))
+            .with_state(manager);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        address
+    }
+
+    fn new_block(seq: u64) -> SequencedEvent {
+        SequencedEvent {
+            seq,
+            event: Event::NewBlock {
+                block_hash: BlockHash::all_zeros(),
+                block_height: seq,
+            },
+        }
+    }
+
+    fn transactions_added(seq: u64) -> SequencedEvent {
+        SequencedEvent {
+            seq,
+            event: Event::TransactionsAdded { txids: vec![] },
+        }
+    }
+
+    /// Wait for the subscribers of `transport` to be registered or unregistered.
+    async fn wait_for_subscribers(metrics: &Metrics, transport: &str, count: &str) {
+        for _ in 0..100 {
+            if subscribers(metrics, transport) == count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(subscribers(metrics, transport), count);
+    }
+
+    type WebSocketClient = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn next_text(socket: &mut WebSocketClient) -> String {
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(1), socket.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+
+            match message {
+                tungstenite::Message::Text(text) => return text.to_string(),
+                tungstenite::Message::Close(_) => panic!("closed by the server"),
+                _ => continue,
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn streams_events_over_websockets() {
+        let metrics = Metrics::new();
+        let manager = manager(&metrics);
+        let address = serve(manager.clone()).await;
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{address}/events/ws"))
+            .await
+            .unwrap();
+
+        // Pings are answered before and after the subscription request.
+        socket
+            .send(tungstenite::Message::text("PING"))
+            .await
+            .unwrap();
+        assert_eq!(next_text(&mut socket).await, "PONG");
+        socket
+            .send(tungstenite::Message::text(r#"{"subscribe":["NewBlock"]}"#))
+            .await
+            .unwrap();
+        wait_for_subscribers(&metrics, "websocket", "1").await;
+        socket
+            .send(tungstenite::Message::text("PING"))
+            .await
+            .unwrap();
+        assert_eq!(next_text(&mut socket).await, "PONG");
+
+        // Only the event types of the request are sent.
+        for event in [new_block(0), transactions_added(1), new_block(2)] {
+            manager.broadcast(&event).await;
+        }
+        for seq in [0, 2] {
+            let event: SequencedEvent =
+                serde_json::from_str(&next_text(&mut socket).await).unwrap();
+            assert_eq!(event, new_block(seq));
+        }
+
+        // Closing the socket unsubscribes.
+        socket.close(None).await.unwrap();
+        wait_for_subscribers(&metrics, "websocket", "0").await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_invalid_websocket_requests() {
+        let metrics = Metrics::new();
+        let manager = manager(&metrics);
+        let address = serve(manager).await;
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{address}/events/ws"))
+            .await
+            .unwrap();
+        socket
+            .send(tungstenite::Message::text(
+                r#"{"subscribe":["NotAnEvent"]}"#,
+            ))
+            .await
+            .unwrap();
+
+        assert!(next_text(&mut socket)
+            .await
+            .starts_with("invalid subscription request"));
+        assert_eq!(subscribers(&metrics, "websocket"), "");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn streams_events_over_sse() {
+        let metrics = Metrics::new();
+        let manager = manager(&metrics);
+        let address = serve(manager.clone()).await;
+
+        // The subscription is registered before the response starts.
+        let mut response = reqwest::get(format!("http://{address}/events/sse"))
+            .await
+            .unwrap();
+        assert_eq!(subscribers(&metrics, "sse"), "1");
+
+        for event in [transactions_added(0), new_block(1)] {
+            manager.broadcast(&event).await;
+        }
+
+        let chunk = tokio::time::timeout(Duration::from_secs(1), response.chunk())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let chunk = String::from_utf8(chunk.to_vec()).unwrap();
+        let data = chunk
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        assert!(chunk.lines().any(|line| line == "id: 1"));
+        assert_eq!(
+            serde_json::from_str::<SequencedEvent>(data).unwrap(),
+            new_block(1)
+        );
+
+        // Dropping the response unsubscribes.
+        drop(response);
+        wait_for_subscribers(&metrics, "sse", "0").await;
+    }
+}
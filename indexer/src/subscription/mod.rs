@@ -1,9 +1,14 @@
 mod dispatcher;
+mod event_log;
+mod event_stream;
 mod spawn;
 mod tcp_subscription;
 mod webhook;
 
+pub use event_log::EventLogConfig;
+pub use event_stream::{handle_websocket, sse_stream};
 pub use spawn::*;
+pub use tcp_subscription::TcpSubscriptionManager;
 pub use webhook::{
     StoreError as WebhookStoreError, SubscriptionManager as WebhookSubscriptionManager,
 };
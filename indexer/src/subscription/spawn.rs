@@ -1,7 +1,13 @@
 use {
-    super::{tcp_subscription::TcpSubscriptionManager, WebhookSubscriptionManager},
+    super::{
+        event_log::{EventLog, EventLogConfig, StoreError as EventLogStoreError},
+        tcp_subscription::TcpSubscriptionManager,
+        WebhookSubscriptionManager,
+    },
     crate::{
+        auth::ApiKeys,
         db::RocksDB,
+        index::{Chain, Metrics},
         subscription::{
             dispatcher::event_dispatcher, tcp_subscription::run_tcp_subscription_server,
             webhook::cleanup_inactive_subscriptions,
@@ -25,7 +31,8 @@ pub struct WebhookSubscriptionSpawnResult {
 }
 
 pub struct TcpSubscriptionSpawnResult {
-    pub tcp_server_handle: task::JoinHandle<()>,
+    /// `None` when only WebSocket and SSE subscriptions are enabled.
+    pub tcp_server_handle: Option<task::JoinHandle<()>>,
     pub tcp_subscription_manager: Arc<TcpSubscriptionManager>,
 }
 
@@ -38,41 +45,62 @@ pub struct SubscriptionSpawnResult {
 }
 
 pub struct SubscriptionConfig {
+    pub chain: Chain,
     pub enable_webhook_subscriptions: bool,
     pub enable_tcp_subscriptions: bool,
+    pub enable_event_streams: bool,
     pub tcp_address: String,
     pub enable_file_logging: bool,
+    pub event_log: EventLogConfig,
 }
 
 /// Spawns the subscription-related background tasks (dispatcher + cleanup).
 pub fn spawn_subscription_tasks(
     db: Arc<RocksDB>,
     config: SubscriptionConfig,
-) -> Option<SubscriptionSpawnResult> {
-    // If both webhook and TCP subscriptions are disabled, return None
-    if !config.enable_webhook_subscriptions && !config.enable_tcp_subscriptions {
-        return None;
+    api_keys: Arc<ApiKeys>,
+    metrics: &Metrics,
+) -> Result<Option<SubscriptionSpawnResult>, EventLogStoreError> {
+    // If every subscription transport is disabled, return None
+    if !config.enable_webhook_subscriptions
+        && !config.enable_tcp_subscriptions
+        && !config.enable_event_streams
+    {
+        return Ok(None);
     }
 
+    // Every dispatched event is numbered and logged so subscribers can replay it.
+    let event_log = Arc::new(EventLog::open(db.clone(), config.event_log)?);
+
     // Create a watch channel for shutdown signaling
     let (shutdown_tx, shutdown_rx) = watch::channel(());
 
-    // Create the TCP subscription manager if enabled
-    let tcp_spawn_result = if config.enable_tcp_subscriptions {
-        let tcp_subscription_manager = Arc::new(TcpSubscriptionManager::new());
-        let tcp_subscription_manager_clone = tcp_subscription_manager.clone();
-        let shutdown_rx_clone = shutdown_rx.clone();
-
-        let tcp_server_handle = tokio::spawn(async move {
-            if let Err(e) = run_tcp_subscription_server(
-                &config.tcp_address,
-                tcp_subscription_manager_clone,
-                shutdown_rx_clone,
-            )
-            .await
-            {
-                error!("TCP subscription server error: {:?}", e);
-            }
+    // Create the TCP subscription manager if enabled. WebSocket and SSE subscribers
+    // served by the HTTP server share it.
+    let tcp_spawn_result = if config.enable_tcp_subscriptions || config.enable_event_streams {
+        let tcp_subscription_manager = Arc::new(TcpSubscriptionManager::new(
+            event_log.clone(),
+            api_keys,
+            config.chain,
+            metrics,
+        ));
+
+        let tcp_server_handle = config.enable_tcp_subscriptions.then(|| {
+            let tcp_subscription_manager_clone = tcp_subscription_manager.clone();
+            let shutdown_rx_clone = shutdown_rx.clone();
+            let tcp_address = config.tcp_address.clone();
+
+            tokio::spawn(async move {
+                if let Err(e) = run_tcp_subscription_server(
+                    &tcp_address,
+                    tcp_subscription_manager_clone,
+                    shutdown_rx_clone,
+                )
+                .await
+                {
+                    error!("TCP subscription server error: {:?}", e);
+                }
+            })
         });
 
         Some(TcpSubscriptionSpawnResult {
@@ -85,14 +113,20 @@ pub fn spawn_subscription_tasks(
 
     // Create the webhook subscription manager if enabled
     let webhook_spawn_result = if config.enable_webhook_subscriptions {
-        let webhook_subscription_manager = Arc::new(WebhookSubscriptionManager::new(db.clone()));
+        let webhook_subscription_manager =
+            Arc::new(WebhookSubscriptionManager::new(db.clone(), metrics));
+
+        // Deliveries queued before a restart are picked up where they were left.
+        if let Err(e) = webhook_subscription_manager.resume_deliveries() {
+            error!("Failed to resume webhook deliveries: {:?}", e);
+        }
 
         let cleanup_rx = shutdown_rx.clone();
-        let cleanup_db = db.clone();
+        let cleanup_manager = webhook_subscription_manager.clone();
 
         let cleanup_handle = tokio::spawn(async move {
             cleanup_inactive_subscriptions(
-                cleanup_db,
+                cleanup_manager,
                 DEFAULT_CLEANUP_INTERVAL,
                 DEFAULT_CLEANUP_EXPIRY_SECS,
                 cleanup_rx,
@@ -125,6 +159,7 @@ pub fn spawn_subscription_tasks(
     let dispatcher_handle = tokio::spawn(async move {
         event_dispatcher(
             event_receiver,
+            event_log,
             webhook_manager_for_dispatcher,
             tcp_manager_for_dispatcher,
             dispatcher_rx,
@@ -135,13 +170,13 @@ pub fn spawn_subscription_tasks(
 
     info!("Spawned subscription tasks (dispatcher + cleanup).");
 
-    Some(SubscriptionSpawnResult {
+    Ok(Some(SubscriptionSpawnResult {
         event_sender,
         dispatcher_handle,
         webhook_spawn_result,
         tcp_spawn_result,
         shutdown_tx,
-    })
+    }))
 }
 
 pub async fn shutdown_and_wait_subscription_tasks(spawn_result: SubscriptionSpawnResult) {
@@ -164,6 +199,8 @@ pub async fn shutdown_and_wait_subscription_tasks(spawn_result: SubscriptionSpaw
     }
 
     if let Some(webhook_spawn_result) = webhook_spawn_result {
+        webhook_spawn_result.subscription_manager.stop_deliveries();
+
         if let Err(e) = webhook_spawn_result.cleanup_handle.await {
             error!("Webhook cleanup task join error: {:?}", e);
         } else {
@@ -171,8 +208,8 @@ pub async fn shutdown_and_wait_subscription_tasks(spawn_result: SubscriptionSpaw
         }
     }
 
-    if let Some(tcp_spawn_result) = tcp_spawn_result {
-        if let Err(e) = tcp_spawn_result.tcp_server_handle.await {
+    if let Some(tcp_server_handle) = tcp_spawn_result.and_then(|r| r.tcp_server_handle) {
+        if let Err(e) = tcp_server_handle.await {
             error!("TCP server task join error: {:?}", e);
         } else {
             info!("TCP server task ended cleanly.");
@@ -1,14 +1,21 @@
+use super::event_log::EventLog;
+use crate::auth::{ApiKeys, AuthError};
+use crate::index::{Chain, GaugeVec, Metrics};
+use crate::models::ApiKeyScope;
+use bitcoin::address::ParseError;
 use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
-use titan_types_api::TcpSubscriptionRequest;
-use titan_types_core::{Event, EventType};
+use titan_types_api::{EventFilter, TcpSubscriptionRequest};
+use titan_types_core::{EventType, SequencedEvent};
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::sync::mpsc::error::TrySendError;
 use tokio::{
     io::AsyncWriteExt,
     net::{TcpListener, TcpStream},
     sync::{mpsc, watch, RwLock},
+    task,
 };
 use tokio_stream::StreamExt;
 use tokio_util::codec::{FramedRead, LinesCodec};
@@ -16,52 +23,161 @@ use tracing::{debug, error, info};
 use uuid::Uuid;
 
 const MAX_LINE: usize = 8 * 1024;
-const HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+pub(super) const HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+/// Events buffered per subscriber before it's evicted as a slow consumer.
+const SUBSCRIBER_CHANNEL_SIZE: usize = 100;
+/// Events read from the event log at a time while replaying.
+const REPLAY_BATCH_SIZE: usize = 500;
+
+/// How a subscriber is connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Tcp,
+    WebSocket,
+    Sse,
+}
+
+impl Transport {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Transport::Tcp => "tcp",
+            Transport::WebSocket => "websocket",
+            Transport::Sse => "sse",
+        }
+    }
+}
 
-/// A subscription coming from a TCP client.
+/// A subscription coming from a TCP, WebSocket or SSE client.
 #[derive(Debug)]
 pub struct TcpSubscription {
     pub id: Uuid,
+    pub transport: Transport,
     /// The set of event types (as strings) the client wants.
     pub event_types: HashSet<EventType>,
+    /// Further narrows down the events of those types.
+    pub filter: Option<EventFilter>,
     /// Channel sender to deliver events to this client.
-    pub sender: mpsc::Sender<Event>,
+    pub sender: mpsc::Sender<SequencedEvent>,
 }
 
-/// Manages all active TCP subscriptions.
-#[derive(Default, Debug)]
+/// Manages all active TCP, WebSocket and SSE subscriptions.
 pub struct TcpSubscriptionManager {
     subscriptions: RwLock<HashMap<Uuid, TcpSubscription>>,
+    event_log: Arc<EventLog>,
+    api_keys: Arc<ApiKeys>,
+    chain: Chain,
+    subscribers: GaugeVec,
 }
 
 impl TcpSubscriptionManager {
-    pub fn new() -> Self {
+    pub fn new(
+        event_log: Arc<EventLog>,
+        api_keys: Arc<ApiKeys>,
+        chain: Chain,
+        metrics: &Metrics,
+    ) -> Self {
         Self {
             subscriptions: RwLock::new(HashMap::default()),
+            event_log,
+            api_keys,
+            chain,
+            subscribers: metrics.gauge_vec(
+                prometheus::Opts::new(
+                    "event_subscribers",
+                    "Number of connected event subscribers by transport",
+                ),
+                &["transport"],
+            ),
         }
     }
 
     /// Register a new TCP subscription.
     pub async fn register(&self, sub: TcpSubscription) {
-        self.subscriptions.write().await.insert(sub.id, sub);
+        let transport = sub.transport;
+        if self
+            .subscriptions
+            .write()
+            .await
+            .insert(sub.id, sub)
+            .is_none()
+        {
+            self.subscribers
+                .with_label_values(&[transport.as_str()])
+                .inc();
+        }
+    }
+
+    /// Check the API key sent with a TCP or WebSocket subscription request. The key is
+    /// taken out of the request so that it isn't logged.
+    pub fn authorize(&self, request: &mut TcpSubscriptionRequest) -> Result<(), AuthError> {
+        let api_key = request.api_key.take();
+        task::block_in_place(|| {
+            self.api_keys
+                .authorize(api_key.as_deref(), ApiKeyScope::Read)
+        })?;
+        Ok(())
+    }
+
+    /// Check the filter of a subscription request, rewriting its addresses the way
+    /// events spell them.
+    pub fn validate(&self, request: &mut TcpSubscriptionRequest) -> Result<(), ParseError> {
+        match request.filter.as_mut() {
+            Some(filter) => filter.normalize_addresses(self.chain.network()),
+            None => Ok(()),
+        }
+    }
+
+    /// Subscribe to the events `request` asks for. With `from_seq`, logged events
+    /// starting at that sequence number are delivered before live ones.
+    pub async fn subscribe(
+        self: &Arc<Self>,
+        request: TcpSubscriptionRequest,
+        transport: Transport,
+    ) -> EventSubscription {
+        let from_seq = request.from_seq;
+        let mut subscription = EventSubscription {
+            id: Uuid::new_v4(),
+            transport,
+            manager: self.clone(),
+            event_types: request.subscribe.into_iter().collect(),
+            filter: request.filter,
+            next_seq: from_seq.unwrap_or(0),
+            replaying: from_seq.is_some(),
+            backlog: VecDeque::new(),
+            receiver: None,
+        };
+
+        // Without a replay, events are only delivered from now on.
+        if !subscription.replaying {
+            subscription.register().await;
+        }
+
+        subscription
     }
 
     /// Unregister a subscription by its id.
     pub async fn unregister(&self, id: Uuid) {
-        self.subscriptions.write().await.remove(&id);
+        if let Some(sub) = self.subscriptions.write().await.remove(&id) {
+            self.subscribers
+                .with_label_values(&[sub.transport.as_str()])
+                .dec();
+        }
     }
 
     /// Broadcast an event to all subscriptions that have registered interest.
-    pub async fn broadcast(&self, event: &Event) {
-        // Assume you can derive a string event type from your event.
-        // For example, if you have a function or trait implementation:
-        let event_type: EventType = EventType::from(event.clone()); // adjust as needed
+    pub async fn broadcast(&self, event: &SequencedEvent) {
+        let event_type = EventType::from(event.event.clone());
 
         let subs = self.subscriptions.read().await;
         let mut failed_ids = Vec::new();
 
         for (id, sub) in subs.iter() {
-            if sub.event_types.contains(&event_type) {
+            if sub.event_types.contains(&event_type)
+                && sub
+                    .filter
+                    .as_ref()
+                    .map_or(true, |filter| filter.matches(&event.event))
+            {
                 // Non-blocking send to avoid stalling the dispatcher on slow clients.
                 match sub.sender.try_send(event.clone()) {
                     Ok(()) => {}
@@ -94,6 +210,112 @@ impl TcpSubscriptionManager {
     }
 }
 
+/// Events of a single subscriber: first the replayed part of the event log, if one
+/// was requested, then live events. The subscription is unregistered on drop.
+pub struct EventSubscription {
+    id: Uuid,
+    transport: Transport,
+    manager: Arc<TcpSubscriptionManager>,
+    event_types: HashSet<EventType>,
+    filter: Option<EventFilter>,
+    /// Sequence number of the next event to deliver.
+    next_seq: u64,
+    replaying: bool,
+    backlog: VecDeque<SequencedEvent>,
+    receiver: Option<mpsc::Receiver<SequencedEvent>>,
+}
+
+impl EventSubscription {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Returns the next event, or `None` once the subscription has ended, either
+    /// because it was evicted as a slow consumer or because the replay failed.
+    ///
+    /// Cancel safe, so it can be used in `tokio::select!`.
+    pub async fn recv(&mut self) -> Option<SequencedEvent> {
+        loop {
+            if let Some(event) = self.backlog.pop_front() {
+                return Some(event);
+            }
+
+            if self.replaying {
+                let batch = match task::block_in_place(|| {
+                    self.manager
+                        .event_log
+                        .read(self.next_seq, REPLAY_BATCH_SIZE)
+                }) {
+                    Ok(batch) => batch,
+                    Err(e) => {
+                        error!(
+                            "Failed to replay events for subscription {}: {:?}",
+                            self.id, e
+                        );
+                        return None;
+                    }
+                };
+
+                if let Some(last) = batch.last() {
+                    self.next_seq = last.seq + 1;
+                    let (event_types, filter) = (&self.event_types, &self.filter);
+                    self.backlog.extend(batch.into_iter().filter(|event| {
+                        event_types.contains(&EventType::from(event.event.clone()))
+                            && filter
+                                .as_ref()
+                                .map_or(true, |filter| filter.matches(&event.event))
+                    }));
+                } else if self.receiver.is_none() {
+                    // Caught up with the log. Go live, then replay once more to pick up
+                    // events logged before the registration took effect.
+                    self.register().await;
+                } else {
+                    self.replaying = false;
+                }
+
+                continue;
+            }
+
+            let event = self.receiver.as_mut()?.recv().await?;
+            // Already delivered by the replay.
+            if event.seq < self.next_seq {
+                continue;
+            }
+
+            self.next_seq = event.seq + 1;
+            return Some(event);
+        }
+    }
+
+    async fn register(&mut self) {
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_CHANNEL_SIZE);
+        self.manager
+            .register(TcpSubscription {
+                id: self.id,
+                transport: self.transport,
+                event_types: self.event_types.clone(),
+                filter: self.filter.clone(),
+                sender: tx,
+            })
+            .await;
+        self.receiver = Some(rx);
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        if self.receiver.is_none() {
+            return;
+        }
+
+        let id = self.id;
+        let manager = self.manager.clone();
+        tokio::spawn(async move {
+            manager.unregister(id).await;
+        });
+    }
+}
+
 /// Run the TCP subscription server on the given address.
 /// This server listens for incoming TCP connections and spawns a task
 /// to handle each connection.
@@ -141,32 +363,27 @@ async fn handle_tcp_connection(
     let mut lines = FramedRead::new(reader, LinesCodec::new_with_max_length(MAX_LINE));
 
     // Read subscription request with timeout.
-    let request: TcpSubscriptionRequest = tokio::time::timeout(
+    let mut request: TcpSubscriptionRequest = tokio::time::timeout(
         Duration::from_secs(HANDSHAKE_TIMEOUT_SECS),
         read_handshake_request(&mut lines, &mut writer),
     )
     .await??;
 
+    manager.authorize(&mut request)?;
+    manager.validate(&mut request)?;
+
     info!("Received TCP subscription request: {:?}", request);
 
-    let event_types: HashSet<EventType> = request.subscribe.into_iter().collect();
-
-    // Create an mpsc channel for delivering events to this connection.
-    let (tx, mut rx) = mpsc::channel::<Event>(100);
-    let sub = TcpSubscription {
-        id: Uuid::new_v4(),
-        event_types,
-        sender: tx,
-    };
-    let sub_id = sub.id;
-    manager.register(sub).await;
+    // Subscribe, replaying the event log first if the client asked for it.
+    let mut subscription = manager.subscribe(request, Transport::Tcp).await;
+    let sub_id = subscription.id();
     info!("Registered TCP subscription with id {}", sub_id);
 
     // Loop until the connection is closed.
     loop {
         tokio::select! {
-            // Send events received from the channel to the client.
-            maybe_event = rx.recv() => {
+            // Send events received from the subscription to the client.
+            maybe_event = subscription.recv() => {
                 match maybe_event {
                     Some(event) => {
                         let json_bytes = serde_json::to_vec(&event)?;
@@ -210,7 +427,7 @@ async fn handle_tcp_connection(
         }
     }
 
-    manager.unregister(sub_id).await;
+    drop(subscription);
     info!("Unregistered TCP subscription with id {}", sub_id);
     Ok(())
 }
@@ -287,7 +504,7 @@ async fn read_handshake_request(
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
     use tokio::net::{TcpListener, TcpStream};
@@ -559,4 +776,91 @@ mod tests {
         assert!(res.is_err());
         Ok(())
     }
+
+    pub(crate) fn manager(metrics: &Metrics) -> Arc<TcpSubscriptionManager> {
+        let event_log = EventLog::open(
+            Arc::new(crate::subscription::event_log::tests::MemoryStore::default()),
+            crate::subscription::event_log::EventLogConfig {
+                max_events: 100,
+                max_block_depth: None,
+            },
+        )
+        .unwrap();
+        let api_keys = ApiKeys::new(Arc::new(crate::auth::MemoryStore::default()), false);
+        Arc::new(TcpSubscriptionManager::new(
+            Arc::new(event_log),
+            Arc::new(api_keys),
+            Chain::Regtest,
+            metrics,
+        ))
+    }
+
+    pub(crate) fn request() -> TcpSubscriptionRequest {
+        TcpSubscriptionRequest {
+            subscribe: vec![EventType::NewBlock],
+            from_seq: None,
+            filter: None,
+            api_key: None,
+        }
+    }
+
+    pub(crate) fn subscribers(metrics: &Metrics, transport: &str) -> String {
+        let prefix = format!("event_subscribers{{transport=\"{}\"}} ", transport);
+        metrics
+            .encode()
+            .unwrap()
+            .lines()
+            .find_map(|line| line.strip_prefix(&prefix).map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn counts_subscribers_by_transport() {
+        let metrics = Metrics::new();
+        let manager = manager(&metrics);
+
+        let websocket = manager.subscribe(request(), Transport::WebSocket).await;
+        let sse = manager.subscribe(request(), Transport::Sse).await;
+        let other_sse = manager.subscribe(request(), Transport::Sse).await;
+        assert_eq!(subscribers(&metrics, "websocket"), "1");
+        assert_eq!(subscribers(&metrics, "sse"), "2");
+
+        // Dropped subscriptions are unregistered in the background.
+        drop(websocket);
+        drop(sse);
+        for _ in 0..100 {
+            if subscribers(&metrics, "sse") == "1" && subscribers(&metrics, "websocket") == "0" {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(subscribers(&metrics, "websocket"), "0");
+        assert_eq!(subscribers(&metrics, "sse"), "1");
+
+        drop(other_sse);
+    }
+
+    #[test]
+    fn validates_address_filters() {
+        let manager = manager(&Metrics::new());
+        let address = bitcoin::Address::p2wsh(bitcoin::Script::new(), bitcoin::Network::Regtest);
+        let with_addresses = |addresses: Vec<String>| TcpSubscriptionRequest {
+            filter: Some(EventFilter {
+                addresses,
+                ..Default::default()
+            }),
+            ..request()
+        };
+
+        let mut request = with_addresses(vec![address.to_string().to_uppercase()]);
+        manager.validate(&mut request).unwrap();
+        assert_eq!(request.filter.unwrap().addresses, vec![address.to_string()]);
+
+        let mainnet = bitcoin::Address::p2wsh(bitcoin::Script::new(), bitcoin::Network::Bitcoin);
+        for address in ["not an address".to_string(), mainnet.to_string()] {
+            assert!(manager
+                .validate(&mut with_addresses(vec![address]))
+                .is_err());
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use {
-    super::store::Store,
+    super::manager::SubscriptionManager,
     std::{sync::Arc, time::Duration},
     tokio::{select, sync::watch, time::sleep},
     tracing::{error, info},
@@ -8,7 +8,7 @@ use {
 /// Periodically delete subscriptions that haven't succeeded for `expiry_secs`.
 /// If a shutdown signal arrives, exit gracefully.
 pub async fn cleanup_inactive_subscriptions(
-    store: Arc<dyn Store>,
+    manager: Arc<SubscriptionManager>,
     interval: Duration,
     expiry_secs: u64,
     mut shutdown_rx: watch::Receiver<()>,
@@ -18,7 +18,7 @@ pub async fn cleanup_inactive_subscriptions(
     loop {
         select! {
             _ = sleep(interval) => {
-                if let Err(e) = do_cleanup(&store, expiry_secs).await {
+                if let Err(e) = do_cleanup(&manager, expiry_secs).await {
                     error!("cleanup error: {:?}", e);
                 }
             }
@@ -34,7 +34,7 @@ pub async fn cleanup_inactive_subscriptions(
 
 /// Do an actual iteration, removing stale subscriptions
 async fn do_cleanup(
-    store: &Arc<dyn Store>,
+    manager: &SubscriptionManager,
     expiry_secs: u64,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // 1) Get current epoch secs
@@ -44,12 +44,12 @@ async fn do_cleanup(
         .as_secs();
 
     // 2) Retrieve and filter
-    let subscriptions = store.get_subscriptions()?;
+    let subscriptions = manager.get_subscriptions()?;
     for sub in subscriptions {
         // If no last_success, or last_success is > 24 hours old, remove sub
         if now_secs - sub.last_success_epoch_secs > expiry_secs {
             // It's inactive, remove it
-            let _ = store.delete_subscription(&sub.id);
+            let _ = manager.delete_subscription(&sub.id);
         }
     }
 
@@ -1,32 +1,77 @@
 use {
     super::{
-        process_event,
+        outbox::Outbox,
+        process::{now_secs, process_event},
+        signature,
         store::{Store, StoreError},
     },
-    reqwest::Client,
+    crate::{
+        index::{Gauge, Metrics},
+        util::generate_secret,
+    },
     std::sync::Arc,
-    titan_types_api::Subscription,
+    titan_types_api::{DeliveryQuery, DeliveryStatus, Subscription, WebhookDelivery},
     titan_types_core::Event,
     uuid::Uuid,
 };
 
+const DEFAULT_DELIVERIES_LIMIT: usize = 50;
+const MAX_DELIVERIES_LIMIT: usize = 500;
+
 pub struct SubscriptionManager {
     store: Arc<dyn Store>,
-    client: Client,
+    outbox: Outbox,
+    subscriptions: Gauge,
 }
 
 impl SubscriptionManager {
-    pub fn new(store: Arc<dyn Store>) -> Self {
-        let client = Client::new();
-        Self { store, client }
+    pub fn new(store: Arc<dyn Store>, metrics: &Metrics) -> Self {
+        let subscriptions = metrics.gauge(prometheus::Opts::new(
+            "webhook_subscriptions",
+            "Number of registered webhook subscriptions",
+        ));
+
+        if let Ok(current) = store.get_subscriptions() {
+            subscriptions.set(current.len() as i64);
+        }
+
+        Self {
+            outbox: Outbox::new(store.clone()),
+            store,
+            subscriptions,
+        }
     }
 
-    pub fn add_subscription(&self, subscription: &Subscription) -> Result<(), StoreError> {
-        self.store.set_subscription(subscription)
+    /// Store `subscription` with a newly generated secret, replacing any secret it
+    /// came with, and return it.
+    pub fn add_subscription(
+        &self,
+        subscription: &Subscription,
+    ) -> Result<Subscription, StoreError> {
+        let subscription = Subscription {
+            secret: Some(generate_secret(signature::SECRET_PREFIX)),
+            ..subscription.clone()
+        };
+
+        if self.store.set_subscription(&subscription)? {
+            self.subscriptions.inc();
+        }
+
+        Ok(subscription)
+    }
+
+    /// Replace the secret of subscription `id` and return the updated subscription.
+    pub fn rotate_secret(&self, id: &Uuid) -> Result<Subscription, StoreError> {
+        self.store
+            .update_subscription_secret(id, generate_secret(signature::SECRET_PREFIX))
     }
 
     pub fn delete_subscription(&self, id: &Uuid) -> Result<(), StoreError> {
-        self.store.delete_subscription(id)
+        if self.store.delete_subscription(id)? {
+            self.subscriptions.dec();
+        }
+
+        Ok(())
     }
 
     pub fn get_subscriptions(&self) -> Result<Vec<Subscription>, StoreError> {
@@ -37,7 +82,147 @@ impl SubscriptionManager {
         self.store.get_subscription(id)
     }
 
-    pub async fn broadcast(&self, event: &Event) -> Result<(), Box<dyn std::error::Error>> {
-        process_event(&self.store, &self.client, event).await
+    /// Lists the pending deliveries and dead letters of subscription `id` in outbox order.
+    pub fn get_deliveries(
+        &self,
+        id: &Uuid,
+        query: &DeliveryQuery,
+    ) -> Result<Vec<WebhookDelivery>, StoreError> {
+        self.store.get_subscription(id)?;
+
+        let from_seq = query.from_seq.unwrap_or(0);
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_DELIVERIES_LIMIT)
+            .min(MAX_DELIVERIES_LIMIT);
+        let statuses = match query.status {
+            Some(status) => vec![status],
+            None => vec![DeliveryStatus::Pending, DeliveryStatus::DeadLetter],
+        };
+
+        let mut deliveries = Vec::new();
+        for status in statuses {
+            deliveries.extend(
+                self.store
+                    .get_deliveries(id, status, from_seq, limit)?
+                    .into_iter()
+                    .map(|(seq, entry)| entry.into_delivery(seq)),
+            );
+        }
+
+        deliveries.sort_by_key(|delivery| delivery.seq);
+        deliveries.truncate(limit);
+        Ok(deliveries)
+    }
+
+    /// Move the dead letters of subscription `id`, or only the one at `seq`, back to
+    /// its outbox with a fresh set of attempts.
+    pub fn redeliver(
+        &self,
+        id: &Uuid,
+        seq: Option<u64>,
+    ) -> Result<Vec<WebhookDelivery>, StoreError> {
+        self.store.get_subscription(id)?;
+
+        let dead_letters = match seq {
+            Some(seq) => {
+                let entry = self
+                    .store
+                    .get_delivery(id, DeliveryStatus::DeadLetter, seq)?
+                    .ok_or_else(|| {
+                        StoreError::NotFound(format!("Dead letter {} of subscription {}", seq, id))
+                    })?;
+                vec![(seq, entry)]
+            }
+            None => self
+                .store
+                .get_deliveries(id, DeliveryStatus::DeadLetter, 0, usize::MAX)?,
+        };
+
+        let now = now_secs();
+        let mut redelivered = Vec::with_capacity(dead_letters.len());
+        for (seq, mut entry) in dead_letters {
+            entry.status = DeliveryStatus::Pending;
+            entry.attempts = 0;
+            entry.next_attempt_epoch_secs = now;
+            self.store.set_delivery(id, seq, &entry)?;
+            redelivered.push(entry.into_delivery(seq));
+        }
+
+        self.outbox.notify(*id);
+        Ok(redelivered)
+    }
+
+    /// Queue `event` for the interested subscriptions and wake up their workers.
+    pub fn broadcast(&self, event: &Event) -> Result<(), StoreError> {
+        for id in process_event(&self.store, event)? {
+            self.outbox.notify(id);
+        }
+
+        Ok(())
+    }
+
+    /// Start delivering the outboxes left over by a previous run.
+    pub fn resume_deliveries(&self) -> Result<(), StoreError> {
+        for subscription in self.store.get_subscriptions()? {
+            self.outbox.notify(subscription.id);
+        }
+
+        Ok(())
+    }
+
+    pub fn stop_deliveries(&self) {
+        self.outbox.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::subscription::webhook::store::tests::MemoryStore,
+        titan_types_core::EventType,
+    };
+
+    fn subscription(id: Uuid) -> Subscription {
+        Subscription {
+            id,
+            endpoint: "http://127.0.0.1:1".to_string(),
+            event_types: vec![EventType::NewBlock],
+            last_success_epoch_secs: 0,
+            filter: None,
+            secret: None,
+            delivery_policy: None,
+        }
+    }
+
+    #[test]
+    fn subscriptions_gauge_follows_adds_and_deletes() {
+        let store = Arc::new(MemoryStore::default());
+        store
+            .set_subscription(&subscription(Uuid::new_v4()))
+            .unwrap();
+
+        let metrics = Metrics::new();
+        let manager = SubscriptionManager::new(store, &metrics);
+        assert!(metrics
+            .encode()
+            .unwrap()
+            .contains("\nwebhook_subscriptions 1\n"));
+
+        // Replacing or deleting a subscription twice counts once.
+        let id = Uuid::new_v4();
+        manager.add_subscription(&subscription(id)).unwrap();
+        manager.add_subscription(&subscription(id)).unwrap();
+        assert!(metrics
+            .encode()
+            .unwrap()
+            .contains("\nwebhook_subscriptions 2\n"));
+
+        manager.delete_subscription(&id).unwrap();
+        manager.delete_subscription(&id).unwrap();
+        assert!(metrics
+            .encode()
+            .unwrap()
+            .contains("\nwebhook_subscriptions 1\n"));
     }
 }
@@ -1,6 +1,8 @@
 mod cleanup;
 mod manager;
+mod outbox;
 mod process;
+mod signature;
 mod store;
 
 pub use cleanup::*;
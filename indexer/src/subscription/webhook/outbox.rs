@@ -0,0 +1,476 @@
+use {
+    super::{
+        process::{now_secs, send_delivery, SendEventError},
+        store::{Store, StoreError},
+    },
+    crate::models::WebhookDeliveryEntry,
+    reqwest::Client,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    titan_types_api::{DeliveryPolicy, DeliveryStatus, Subscription},
+    tokio::{
+        select,
+        sync::{watch, Notify},
+        task::JoinSet,
+        time::sleep,
+    },
+    tracing::{error, warn},
+    uuid::Uuid,
+};
+
+/// Attempts a delivery gets before it is dead-lettered.
+const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+/// Pending deliveries read from an outbox at once.
+const OUTBOX_BATCH_SIZE: usize = 100;
+/// How long a worker with nothing due sleeps if it isn't woken up by a new delivery.
+const IDLE_INTERVAL: Duration = Duration::from_secs(60);
+const STORE_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+type Workers = Arc<Mutex<HashMap<Uuid, Arc<Notify>>>>;
+
+/// Drains the outboxes of webhook subscriptions, with one worker task per
+/// subscription that follows the subscription's `DeliveryPolicy`.
+pub(super) struct Outbox {
+    store: Arc<dyn Store>,
+    client: Client,
+    workers: Workers,
+    shutdown_tx: watch::Sender<bool>,
+}
+
+impl Outbox {
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        let client = Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self {
+            store,
+            client,
+            workers: Arc::new(Mutex::new(HashMap::new())),
+            shutdown_tx: watch::channel(false).0,
+        }
+    }
+
+    /// Wake up the worker of `subscription_id`, starting it if needed.
+    pub fn notify(&self, subscription_id: Uuid) {
+        let mut workers = self.workers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(notify) = workers.get(&subscription_id) {
+            notify.notify_one();
+            return;
+        }
+
+        if *self.shutdown_tx.borrow() {
+            return;
+        }
+
+        let notify = Arc::new(Notify::new());
+        workers.insert(subscription_id, notify.clone());
+
+        tokio::spawn(run_worker(
+            self.store.clone(),
+            self.client.clone(),
+            subscription_id,
+            notify,
+            self.workers.clone(),
+            self.shutdown_tx.subscribe(),
+        ));
+    }
+
+    /// Stop every worker. Deliveries that are in flight are attempted again on restart.
+    pub fn shutdown(&self) {
+        self.shutdown_tx.send_replace(true);
+    }
+}
+
+async fn run_worker(
+    store: Arc<dyn Store>,
+    client: Client,
+    subscription_id: Uuid,
+    notify: Arc<Notify>,
+    workers: Workers,
+    mut shutdown_rx: watch::Receiver<bool>,
+) {
+    loop {
+        let wait = match deliver_due(&store, &client, &subscription_id).await {
+            Ok(Some(wait)) => wait,
+            // The subscription was deleted.
+            Ok(None) => break,
+            Err(e) => {
+                error!(
+                    "Failed to read the outbox of subscription {}: {:?}",
+                    subscription_id, e
+                );
+                STORE_RETRY_INTERVAL
+            }
+        };
+
+        select! {
+            _ = notify.notified() => {}
+            _ = sleep(wait) => {}
+            _ = shutdown_rx.changed() => break,
+        }
+    }
+
+    workers
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&subscription_id);
+}
+
+/// Send the deliveries of a subscription that are due and return how long to wait
+/// for the next ones, or `None` if the subscription doesn't exist anymore.
+async fn deliver_due(
+    store: &Arc<dyn Store>,
+    client: &Client,
+    subscription_id: &Uuid,
+) -> Result<Option<Duration>, StoreError> {
+    let subscription = match store.get_subscription(subscription_id) {
+        Ok(subscription) => Arc::new(subscription),
+        Err(StoreError::NotFound(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    // In order, only the head of the outbox can be sent.
+    let (batch_size, max_concurrency) = match subscription.delivery_policy.unwrap_or_default() {
+        DeliveryPolicy::Ordered => (1, 1),
+        DeliveryPolicy::Concurrent { max_concurrency } => {
+            (OUTBOX_BATCH_SIZE, (max_concurrency as usize).max(1))
+        }
+    };
+
+    let now = now_secs();
+    let (mut due, waiting): (Vec<_>, Vec<_>) = store
+        .get_deliveries(subscription_id, DeliveryStatus::Pending, 0, batch_size)?
+        .into_iter()
+        .partition(|(_, entry)| entry.next_attempt_epoch_secs <= now);
+    due.truncate(max_concurrency);
+
+    if due.is_empty() {
+        let wait = waiting
+            .iter()
+            .map(|(_, entry)| Duration::from_secs(entry.next_attempt_epoch_secs - now))
+            .min()
+            .unwrap_or(IDLE_INTERVAL);
+        return Ok(Some(wait));
+    }
+
+    let mut sends = JoinSet::new();
+    for (seq, entry) in due {
+        let store = store.clone();
+        let client = client.clone();
+        let subscription = subscription.clone();
+
+        sends.spawn(async move {
+            let result = send_delivery(&client, &subscription, &entry).await;
+            if let Err(e) = complete_delivery(&store, &subscription, seq, entry, result) {
+                error!(
+                    "Failed to update delivery {} of subscription {}: {:?}",
+                    seq, subscription.id, e
+                );
+            }
+        });
+    }
+    while sends.join_next().await.is_some() {}
+
+    Ok(Some(Duration::ZERO))
+}
+
+/// Drop a delivery that succeeded. Otherwise schedule its next attempt with
+/// exponential backoff, or move it to the dead letters once it ran out of attempts.
+fn complete_delivery(
+    store: &Arc<dyn Store>,
+    subscription: &Subscription,
+    seq: u64,
+    mut entry: WebhookDeliveryEntry,
+    result: Result<(), SendEventError>,
+) -> Result<(), StoreError> {
+    // The store doesn't write the deliveries of a subscription deleted in the
+    // meantime, so there is nothing left to update.
+    match update_delivery(store, subscription, seq, &mut entry, result) {
+        Err(StoreError::NotFound(_)) => Ok(()),
+        result => result,
+    }
+}
+
+fn update_delivery(
+    store: &Arc<dyn Store>,
+    subscription: &Subscription,
+    seq: u64,
+    entry: &mut WebhookDeliveryEntry,
+    result: Result<(), SendEventError>,
+) -> Result<(), StoreError> {
+    let now = now_secs();
+
+    let Err(e) = result else {
+        store.delete_delivery(&subscription.id, seq)?;
+        return store.update_subscription_last_success(&subscription.id, now);
+    };
+
+    entry.attempts += 1;
+    entry.last_error = Some(e.to_string());
+
+    if entry.attempts >= MAX_DELIVERY_ATTEMPTS {
+        warn!(
+            "Delivery {} of subscription {} failed {} times, moving it to the dead letters: {}",
+            seq, subscription.id, entry.attempts, e
+        );
+        entry.status = DeliveryStatus::DeadLetter;
+    } else {
+        entry.next_attempt_epoch_secs = now + 2u64.pow(entry.attempts);
+    }
+
+    store.set_delivery(&subscription.id, seq, entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::subscription::webhook::{signature::DELIVERY_HEADER, store::tests::MemoryStore},
+        axum::{extract::State, http::HeaderMap, routing::post, Router},
+        bitcoin::{hashes::Hash, BlockHash},
+        reqwest::StatusCode,
+        std::sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+        titan_types_core::{Event, EventType},
+        tokio::net::TcpListener,
+    };
+
+    /// Webhook endpoint that records the deliveries it receives.
+    #[derive(Default)]
+    struct Receiver {
+        fail: AtomicBool,
+        delay: Duration,
+        received: Mutex<Vec<Uuid>>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl Receiver {
+        fn received(&self) -> Vec<Uuid> {
+            self.received.lock().unwrap().clone()
+        }
+    }
+
+    async fn receive(State(receiver): State<Arc<Receiver>>, headers: HeaderMap) -> StatusCode {
+        let in_flight = receiver.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        receiver
+            .max_in_flight
+            .fetch_max(in_flight, Ordering::SeqCst);
+        sleep(receiver.delay).await;
+        receiver.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        let id = headers[DELIVERY_HEADER].to_str().unwrap().parse().unwrap();
+        receiver.received.lock().unwrap().push(id);
+
+        if receiver.fail.load(Ordering::SeqCst) {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            StatusCode::OK
+        }
+    }
+
+    /// Serves `receiver` on a local port and returns a subscription delivering to it.
+    async fn subscribe(
+        store: &Arc<dyn Store>,
+        receiver: Arc<Receiver>,
+        delivery_policy: Option<DeliveryPolicy>,
+    ) -> Uuid {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+        let app = Router::new().route("/", post(receive)).with_state(receiver);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let id = Uuid::new_v4();
+        store
+            .set_subscription(&Subscription {
+                id,
+                endpoint,
+                event_types: vec![EventType::NewBlock],
+                last_success_epoch_secs: 0,
+                filter: None,
+                secret: None,
+                delivery_policy,
+            })
+            .unwrap();
+        id
+    }
+
+    /// Queues a delivery and returns its id.
+    fn queue(store: &Arc<dyn Store>, subscription_id: &Uuid) -> Uuid {
+        let entry = WebhookDeliveryEntry::new(
+            Event::NewBlock {
+                block_hash: BlockHash::all_zeros(),
+                block_height: 1,
+            },
+            now_secs(),
+        );
+        store.queue_delivery(subscription_id, &entry).unwrap();
+        entry.id
+    }
+
+    fn deliveries(
+        store: &Arc<dyn Store>,
+        subscription_id: &Uuid,
+        status: DeliveryStatus,
+    ) -> Vec<WebhookDeliveryEntry> {
+        store
+            .get_deliveries(subscription_id, status, 0, usize::MAX)
+            .unwrap()
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    /// Skips the backoff of every pending delivery.
+    fn make_due(store: &Arc<dyn Store>, subscription_id: &Uuid) {
+        for (seq, mut entry) in store
+            .get_deliveries(subscription_id, DeliveryStatus::Pending, 0, usize::MAX)
+            .unwrap()
+        {
+            entry.next_attempt_epoch_secs = 0;
+            store.set_delivery(subscription_id, seq, &entry).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_with_backoff_until_dead_lettered() {
+        let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+        let client = Client::new();
+        let receiver = Arc::new(Receiver::default());
+        receiver.fail.store(true, Ordering::SeqCst);
+        let id = subscribe(&store, receiver.clone(), None).await;
+        queue(&store, &id);
+
+        for attempt in 1..MAX_DELIVERY_ATTEMPTS {
+            let before = now_secs();
+            assert_eq!(
+                deliver_due(&store, &client, &id).await.unwrap(),
+                Some(Duration::ZERO)
+            );
+
+            let entry = &deliveries(&store, &id, DeliveryStatus::Pending)[0];
+            let backoff = 2u64.pow(attempt);
+            assert_eq!(entry.attempts, attempt);
+            assert!(entry.last_error.is_some());
+            assert!(
+                (before + backoff..=now_secs() + backoff).contains(&entry.next_attempt_epoch_secs)
+            );
+
+            // Nothing is sent before the backoff is over.
+            let wait = deliver_due(&store, &client, &id).await.unwrap().unwrap();
+            assert!(wait <= Duration::from_secs(backoff));
+            assert!(wait >= Duration::from_secs(backoff - 1));
+            assert_eq!(receiver.received().len() as u32, attempt);
+
+            make_due(&store, &id);
+        }
+
+        deliver_due(&store, &client, &id).await.unwrap();
+        assert!(deliveries(&store, &id, DeliveryStatus::Pending).is_empty());
+
+        let dead_letters = deliveries(&store, &id, DeliveryStatus::DeadLetter);
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].attempts, MAX_DELIVERY_ATTEMPTS);
+        assert_eq!(receiver.received().len() as u32, MAX_DELIVERY_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn drops_delivered_events() {
+        let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+        let receiver = Arc::new(Receiver::default());
+        let id = subscribe(&store, receiver.clone(), None).await;
+        let delivery_id = queue(&store, &id);
+
+        deliver_due(&store, &Client::new(), &id).await.unwrap();
+
+        assert_eq!(receiver.received(), vec![delivery_id]);
+        assert!(deliveries(&store, &id, DeliveryStatus::Pending).is_empty());
+        assert!(store.get_subscription(&id).unwrap().last_success_epoch_secs > 0);
+    }
+
+    #[tokio::test]
+    async fn doesnt_resurrect_deliveries_of_deleted_subscriptions() {
+        let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+        let id = subscribe(&store, Arc::new(Receiver::default()), None).await;
+        queue(&store, &id);
+
+        let subscription = store.get_subscription(&id).unwrap();
+        let (seq, entry) = store
+            .get_deliveries(&id, DeliveryStatus::Pending, 0, 1)
+            .unwrap()
+            .remove(0);
+        store.delete_subscription(&id).unwrap();
+
+        let result = Err(SendEventError::Status(StatusCode::INTERNAL_SERVER_ERROR));
+        complete_delivery(&store, &subscription, seq, entry, result).unwrap();
+
+        assert!(deliveries(&store, &id, DeliveryStatus::Pending).is_empty());
+        assert_eq!(
+            deliver_due(&store, &Client::new(), &id).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn ordered_deliveries_wait_for_the_head() {
+        let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+        let client = Client::new();
+        let receiver = Arc::new(Receiver::default());
+        receiver.fail.store(true, Ordering::SeqCst);
+        let id = subscribe(&store, receiver.clone(), Some(DeliveryPolicy::Ordered)).await;
+        let head = queue(&store, &id);
+        let next = queue(&store, &id);
+
+        for _ in 0..MAX_DELIVERY_ATTEMPTS {
+            deliver_due(&store, &client, &id).await.unwrap();
+            make_due(&store, &id);
+        }
+
+        // Only the head was attempted, until it was dead-lettered.
+        assert_eq!(
+            receiver.received(),
+            vec![head; MAX_DELIVERY_ATTEMPTS as usize]
+        );
+        assert_eq!(
+            deliveries(&store, &id, DeliveryStatus::DeadLetter)[0].id,
+            head
+        );
+
+        receiver.fail.store(false, Ordering::SeqCst);
+        deliver_due(&store, &client, &id).await.unwrap();
+        assert_eq!(receiver.received().last(), Some(&next));
+        assert!(deliveries(&store, &id, DeliveryStatus::Pending).is_empty());
+    }
+
+    #[tokio::test]
+    async fn concurrent_deliveries_stay_under_max_concurrency() {
+        let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
+        let client = Client::new();
+        let receiver = Arc::new(Receiver {
+            delay: Duration::from_millis(50),
+            ..Default::default()
+        });
+        let id = subscribe(
+            &store,
+            receiver.clone(),
+            Some(DeliveryPolicy::Concurrent { max_concurrency: 2 }),
+        )
+        .await;
+        let mut queued = (0..5).map(|_| queue(&store, &id)).collect::<Vec<_>>();
+
+        while !deliveries(&store, &id, DeliveryStatus::Pending).is_empty() {
+            deliver_due(&store, &client, &id).await.unwrap();
+        }
+
+        let mut received = receiver.received();
+        received.sort();
+        queued.sort();
+        assert_eq!(received, queued);
+        assert_eq!(receiver.max_in_flight.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,99 +1,93 @@
 use {
-    super::store::Store,
-    reqwest::Client,
+    super::{
+        signature::{self, DELIVERY_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+        store::{Store, StoreError},
+    },
+    crate::models::WebhookDeliveryEntry,
+    reqwest::{header::CONTENT_TYPE, Client, StatusCode},
     std::{
         sync::Arc,
-        time::{Duration, SystemTime, UNIX_EPOCH},
+        time::{SystemTime, UNIX_EPOCH},
     },
     thiserror::Error,
+    titan_types_api::Subscription,
     titan_types_core::{Event, EventType},
-    tokio::time::sleep,
     tracing::error,
+    uuid::Uuid,
 };
 
 #[derive(Debug, Error)]
 pub enum SendEventError {
     #[error("reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
-    #[error("timeout after {attempts} retries")]
-    Timeout { attempts: usize },
+    #[error("serde error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("non-success HTTP status: {0}")]
+    Status(StatusCode),
+}
+
+pub(super) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
 }
 
-// Helper to send event to endpoint with retry logic
-async fn send_event_with_retry(
+/// Make one attempt at `delivery`. Every attempt carries the delivery's id, so
+/// receivers can drop duplicates, and is signed with a fresh timestamp.
+pub(super) async fn send_delivery(
     client: &Client,
-    endpoint: &str,
-    event: &Event,
-    max_retries: usize,
+    subscription: &Subscription,
+    delivery: &WebhookDeliveryEntry,
 ) -> Result<(), SendEventError> {
-    let mut attempt = 0;
-    loop {
-        let res = client.post(endpoint).json(event).send().await;
+    let body = serde_json::to_vec(&delivery.event)?;
+    let timestamp = now_secs();
 
-        match res {
-            Ok(response) if response.status().is_success() => {
-                // SUCCESS - stop retrying
-                return Ok(());
-            }
-            Ok(response) => {
-                error!("Non-success HTTP status: {}", response.status());
-            }
-            Err(e) => {
-                error!("HTTP request error: {}", e);
-            }
-        }
+    let mut request = client
+        .post(&subscription.endpoint)
+        .header(CONTENT_TYPE, "application/json")
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string());
 
-        attempt += 1;
-        if attempt >= max_retries {
-            error!("Max retries reached for endpoint: {}", endpoint);
-            return Err(SendEventError::Timeout {
-                attempts: max_retries,
-            });
-        }
+    // Subscriptions added before secrets existed stay unsigned until rotated.
+    if let Some(secret) = &subscription.secret {
+        request = request.header(SIGNATURE_HEADER, signature::sign(secret, timestamp, &body));
+    }
 
-        // Exponential backoff
-        let delay = Duration::from_secs(2u64.pow(attempt as u32));
-        sleep(delay).await;
+    let response = request.body(body).send().await?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(SendEventError::Status(response.status()))
     }
 }
 
-/// Process an event and send it to all interested webhook subscriptions
-pub async fn process_event(
-    store: &Arc<dyn Store>,
-    client: &Client,
-    event: &Event,
-) -> Result<(), Box<dyn std::error::Error>> {
+/// Queue an event in the outbox of every interested webhook subscription and return
+/// the ids of those subscriptions.
+pub fn process_event(store: &Arc<dyn Store>, event: &Event) -> Result<Vec<Uuid>, StoreError> {
     // Determine event type
     let event_type = EventType::from(event.clone());
+    let now = now_secs();
 
-    // Get all subscriptions from DB
-    if let Ok(subscriptions) = store.get_subscriptions() {
-        // Filter subscriptions interested in this event type
-        let interested: Vec<_> = subscriptions
-            .into_iter()
-            .filter(|sub| sub.event_types.contains(&event_type))
-            .collect();
-
-        // For each subscription, dispatch the event asynchronously
-        for sub in interested {
-            let client_clone = client.clone();
-            let endpoint = sub.endpoint.clone();
-            let event_clone = event.clone();
-            let store_clone = store.clone();
-            tokio::spawn(async move {
-                let result = send_event_with_retry(&client_clone, &endpoint, &event_clone, 5).await;
+    let mut queued = Vec::new();
+    for sub in store.get_subscriptions()? {
+        // Skip subscriptions that aren't interested in this event
+        if !sub.event_types.contains(&event_type)
+            || !sub
+                .filter
+                .as_ref()
+                .map_or(true, |filter| filter.matches(event))
+        {
+            continue;
+        }
 
-                if result.is_ok() {
-                    // Mark subscription as successful
-                    let now_secs = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs();
-                    let _ = store_clone.update_subscription_last_success(&sub.id, now_secs);
-                }
-            });
+        match store.queue_delivery(&sub.id, &WebhookDeliveryEntry::new(event.clone(), now)) {
+            Ok(_) => queued.push(sub.id),
+            // Deleted since it was listed.
+            Err(StoreError::NotFound(_)) => {}
+            Err(e) => error!("Failed to queue event for subscription {}: {:?}", sub.id, e),
         }
     }
 
-    Ok(())
+    Ok(queued)
 }
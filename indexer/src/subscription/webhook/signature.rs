@@ -0,0 +1,33 @@
+use bitcoin::hashes::{hmac, sha256, Hash, HashEngine};
+
+pub const DELIVERY_HEADER: &str = "x-titan-delivery";
+pub const TIMESTAMP_HEADER: &str = "x-titan-timestamp";
+pub const SIGNATURE_HEADER: &str = "x-titan-signature";
+
+/// Prefix of webhook secrets.
+pub const SECRET_PREFIX: &str = "whsec";
+
+/// Value of the signature header of a delivery: the HMAC-SHA256, keyed with the
+/// subscription's secret, of `{timestamp}.{body}`.
+pub fn sign(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(timestamp.to_string().as_bytes());
+    engine.input(b".");
+    engine.input(body);
+
+    let hmac = hmac::Hmac::<sha256::Hash>::from_engine(engine);
+    format!("sha256={}", hex::encode(hmac.to_byte_array()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        assert_eq!(
+            sign("secret", 1_700_000_000, br#"{"type":"NewBlock"}"#),
+            "sha256=3eb7a98ff10a893771589644df6df79885ff9bdf6b67cfe7d9a89a26ea0dc74f"
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use {
-    crate::db::{RocksDB, RocksDBError},
+    crate::{
+        db::{RocksDB, RocksDBError},
+        models::WebhookDeliveryEntry,
+    },
     thiserror::Error,
-    titan_types_api::Subscription,
+    titan_types_api::{DeliveryStatus, Subscription},
     uuid::Uuid,
 };
 
@@ -24,19 +27,55 @@ impl From<RocksDBError> for StoreError {
 
 pub trait Store: Send + Sync {
     // subscriptions
-    fn set_subscription(&self, sub: &Subscription) -> Result<(), StoreError>;
+    /// Returns whether the subscription is new.
+    fn set_subscription(&self, sub: &Subscription) -> Result<bool, StoreError>;
     fn update_subscription_last_success(
         &self,
         id: &Uuid,
         last_success: u64,
     ) -> Result<(), StoreError>;
+    fn update_subscription_secret(
+        &self,
+        id: &Uuid,
+        secret: String,
+    ) -> Result<Subscription, StoreError>;
     fn get_subscription(&self, id: &Uuid) -> Result<Subscription, StoreError>;
     fn get_subscriptions(&self) -> Result<Vec<Subscription>, StoreError>;
-    fn delete_subscription(&self, id: &Uuid) -> Result<(), StoreError>;
+    /// Returns whether the subscription existed.
+    fn delete_subscription(&self, id: &Uuid) -> Result<bool, StoreError>;
+
+    // deliveries
+    /// Queues a delivery under the next sequence number of the subscription and
+    /// returns it.
+    fn queue_delivery(
+        &self,
+        subscription_id: &Uuid,
+        entry: &WebhookDeliveryEntry,
+    ) -> Result<u64, StoreError>;
+    fn get_deliveries(
+        &self,
+        subscription_id: &Uuid,
+        status: DeliveryStatus,
+        from_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, WebhookDeliveryEntry)>, StoreError>;
+    fn get_delivery(
+        &self,
+        subscription_id: &Uuid,
+        status: DeliveryStatus,
+        seq: u64,
+    ) -> Result<Option<WebhookDeliveryEntry>, StoreError>;
+    fn set_delivery(
+        &self,
+        subscription_id: &Uuid,
+        seq: u64,
+        entry: &WebhookDeliveryEntry,
+    ) -> Result<(), StoreError>;
+    fn delete_delivery(&self, subscription_id: &Uuid, seq: u64) -> Result<(), StoreError>;
 }
 
 impl Store for RocksDB {
-    fn set_subscription(&self, sub: &Subscription) -> Result<(), StoreError> {
+    fn set_subscription(&self, sub: &Subscription) -> Result<bool, StoreError> {
         Ok(self.set_subscription(sub)?)
     }
 
@@ -48,6 +87,14 @@ impl Store for RocksDB {
         Ok(self.update_subscription_last_success(id, last_success)?)
     }
 
+    fn update_subscription_secret(
+        &self,
+        id: &Uuid,
+        secret: String,
+    ) -> Result<Subscription, StoreError> {
+        Ok(self.update_subscription_secret(id, secret)?)
+    }
+
     fn get_subscription(&self, id: &Uuid) -> Result<Subscription, StoreError> {
         Ok(self.get_subscription(id)?)
     }
@@ -56,7 +103,210 @@ impl Store for RocksDB {
         Ok(self.get_subscriptions()?)
     }
 
-    fn delete_subscription(&self, id: &Uuid) -> Result<(), StoreError> {
+    fn delete_subscription(&self, id: &Uuid) -> Result<bool, StoreError> {
         Ok(self.delete_subscription(id)?)
     }
+
+    fn queue_delivery(
+        &self,
+        subscription_id: &Uuid,
+        entry: &WebhookDeliveryEntry,
+    ) -> Result<u64, StoreError> {
+        Ok(self.queue_webhook_delivery(subscription_id, entry)?)
+    }
+
+    fn get_deliveries(
+        &self,
+        subscription_id: &Uuid,
+        status: DeliveryStatus,
+        from_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, WebhookDeliveryEntry)>, StoreError> {
+        Ok(self.get_webhook_deliveries(subscription_id, status, from_seq, limit)?)
+    }
+
+    fn get_delivery(
+        &self,
+        subscription_id: &Uuid,
+        status: DeliveryStatus,
+        seq: u64,
+    ) -> Result<Option<WebhookDeliveryEntry>, StoreError> {
+        Ok(self.get_webhook_delivery(subscription_id, status, seq)?)
+    }
+
+    fn set_delivery(
+        &self,
+        subscription_id: &Uuid,
+        seq: u64,
+        entry: &WebhookDeliveryEntry,
+    ) -> Result<(), StoreError> {
+        Ok(self.set_webhook_delivery(subscription_id, seq, entry)?)
+    }
+
+    fn delete_delivery(&self, subscription_id: &Uuid, seq: u64) -> Result<(), StoreError> {
+        Ok(self.delete_webhook_delivery(subscription_id, seq)?)
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use {
+        super::*,
+        std::{collections::BTreeMap, sync::Mutex},
+    };
+
+    #[derive(Default)]
+    struct State {
+        subscriptions: BTreeMap<Uuid, Subscription>,
+        deliveries: BTreeMap<(Uuid, u64), WebhookDeliveryEntry>,
+        next_seqs: BTreeMap<Uuid, u64>,
+    }
+
+    /// Keeps subscriptions and their deliveries in memory.
+    #[derive(Default)]
+    pub struct MemoryStore(Mutex<State>);
+
+    impl MemoryStore {
+        fn state(&self) -> std::sync::MutexGuard<'_, State> {
+            self.0.lock().unwrap()
+        }
+
+        fn not_found(id: &Uuid) -> StoreError {
+            StoreError::NotFound(format!("Subscription not found: {}", id))
+        }
+    }
+
+    impl Store for MemoryStore {
+        fn set_subscription(&self, sub: &Subscription) -> Result<bool, StoreError> {
+            Ok(self
+                .state()
+                .subscriptions
+                .insert(sub.id, sub.clone())
+                .is_none())
+        }
+
+        fn update_subscription_last_success(
+            &self,
+            id: &Uuid,
+            last_success: u64,
+        ) -> Result<(), StoreError> {
+            let mut state = self.state();
+            let sub = state
+                .subscriptions
+                .get_mut(id)
+                .ok_or_else(|| Self::not_found(id))?;
+            sub.last_success_epoch_secs = last_success;
+            Ok(())
+        }
+
+        fn update_subscription_secret(
+            &self,
+            id: &Uuid,
+            secret: String,
+        ) -> Result<Subscription, StoreError> {
+            let mut state = self.state();
+            let sub = state
+                .subscriptions
+                .get_mut(id)
+                .ok_or_else(|| Self::not_found(id))?;
+            sub.secret = Some(secret);
+            Ok(sub.clone())
+        }
+
+        fn get_subscription(&self, id: &Uuid) -> Result<Subscription, StoreError> {
+            self.state()
+                .subscriptions
+                .get(id)
+                .cloned()
+                .ok_or_else(|| Self::not_found(id))
+        }
+
+        fn get_subscriptions(&self) -> Result<Vec<Subscription>, StoreError> {
+            Ok(self.state().subscriptions.values().cloned().collect())
+        }
+
+        fn delete_subscription(&self, id: &Uuid) -> Result<bool, StoreError> {
+            let mut state = self.state();
+            state.deliveries.retain(|(sub_id, _), _| sub_id != id);
+            state.next_seqs.remove(id);
+            Ok(state.subscriptions.remove(id).is_some())
+        }
+
+        fn queue_delivery(
+            &self,
+            subscription_id: &Uuid,
+            entry: &WebhookDeliveryEntry,
+        ) -> Result<u64, StoreError> {
+            let mut state = self.state();
+            if !state.subscriptions.contains_key(subscription_id) {
+                return Err(Self::not_found(subscription_id));
+            }
+
+            let next_seq = state.next_seqs.entry(*subscription_id).or_default();
+            let seq = *next_seq;
+            *next_seq += 1;
+            state
+                .deliveries
+                .insert((*subscription_id, seq), entry.clone());
+            Ok(seq)
+        }
+
+        fn get_deliveries(
+            &self,
+            subscription_id: &Uuid,
+            status: DeliveryStatus,
+            from_seq: u64,
+            limit: usize,
+        ) -> Result<Vec<(u64, WebhookDeliveryEntry)>, StoreError> {
+            Ok(self
+                .state()
+                .deliveries
+                .range((*subscription_id, from_seq)..=(*subscription_id, u64::MAX))
+                .filter(|(_, entry)| entry.status == status)
+                .take(limit)
+                .map(|((_, seq), entry)| (*seq, entry.clone()))
+                .collect())
+        }
+
+        fn get_delivery(
+            &self,
+            subscription_id: &Uuid,
+            status: DeliveryStatus,
+            seq: u64,
+        ) -> Result<Option<WebhookDeliveryEntry>, StoreError> {
+            Ok(self
+                .state()
+                .deliveries
+                .get(&(*subscription_id, seq))
+                .filter(|entry| entry.status == status)
+                .cloned())
+        }
+
+        fn set_delivery(
+            &self,
+            subscription_id: &Uuid,
+            seq: u64,
+            entry: &WebhookDeliveryEntry,
+        ) -> Result<(), StoreError> {
+            let mut state = self.state();
+            if !state.subscriptions.contains_key(subscription_id) {
+                return Err(Self::not_found(subscription_id));
+            }
+
+            state
+                .deliveries
+                .insert((*subscription_id, seq), entry.clone());
+            Ok(())
+        }
+
+        fn delete_delivery(&self, subscription_id: &Uuid, seq: u64) -> Result<(), StoreError> {
+            let mut state = self.state();
+            if !state.subscriptions.contains_key(subscription_id) {
+                return Err(Self::not_found(subscription_id));
+            }
+
+            state.deliveries.remove(&(*subscription_id, seq));
+            Ok(())
+        }
+    }
 }
@@ -1,3 +1,4 @@
-pub use into_usize::IntoUsize;
+pub use {into_usize::IntoUsize, secret::generate_secret};
 
 mod into_usize;
+mod secret;
@@ -1,17 +1,36 @@
 import axios, { AxiosInstance, AxiosError, AxiosRequestConfig } from 'axios';
 import {
   AddressData,
+  AddressFilter,
+  AddressRuneBalance,
+  AddressTxOut,
   BlockTip,
+  DecodedTransaction,
+  DeliveryQuery,
+  FeeHistogramBucket,
+  MempoolBlock,
+  MempoolPackage,
+  MempoolPackageQuery,
   Pagination,
   PaginationResponse,
+  RecommendedFees,
+  RuneFilter,
+  RuneHistoryEntry,
+  RuneHistoryQuery,
+  RuneHolder,
   RuneResponse,
+  RuneTransferPsbt,
+  RuneTransferRequest,
   Subscription,
   Status,
   Transaction,
   TxOutEntry,
   TransactionStatus,
+  TxOutspend,
   Block,
   MempoolEntry,
+  OutPoint,
+  WebhookDelivery,
 } from './types';
 
 /**
@@ -22,11 +41,19 @@ export class TitanHttpClient {
   private http: AxiosInstance;
   private baseUrl: string;
 
-  constructor(baseUrl: string) {
+  /**
+   * @param baseUrl The indexer's URL, e.g. `http://localhost:3030`.
+   * @param options.apiKey Sent as a bearer token with every request, for indexers
+   *   running with API keys enabled.
+   */
+  constructor(baseUrl: string, options?: { apiKey?: string }) {
     // Remove any trailing slashes from the URL.
     this.baseUrl = baseUrl.replace(/\/+$/, '');
     this.http = axios.create({
       baseURL: this.baseUrl,
+      headers: options?.apiKey
+        ? { Authorization: `Bearer ${options.apiKey}` }
+        : undefined,
     });
   }
 
@@ -57,10 +84,60 @@ export class TitanHttpClient {
     return await this.getOrFail<AddressData>(`/address/${address}`);
   }
 
+  async getAddressTransactions(
+    address: string,
+    pagination?: Pagination,
+    filter?: AddressFilter,
+  ): Promise<PaginationResponse<string>> {
+    const params = { ...pagination, ...filter };
+    return await this.getOrFail<PaginationResponse<string>>(
+      `/address/${address}/txs`,
+      { params },
+    );
+  }
+
+  async getAddressUtxos(
+    address: string,
+    pagination?: Pagination,
+    filter?: AddressFilter,
+  ): Promise<PaginationResponse<AddressTxOut>> {
+    const params = { ...pagination, ...filter };
+    return await this.getOrFail<PaginationResponse<AddressTxOut>>(
+      `/address/${address}/utxos`,
+      { params },
+    );
+  }
+
+  async getAddressRunes(address: string): Promise<AddressRuneBalance[]> {
+    return await this.getOrFail<AddressRuneBalance[]>(
+      `/address/${address}/runes`,
+    );
+  }
+
+  async getAddressRune(
+    address: string,
+    rune: string,
+  ): Promise<AddressRuneBalance> {
+    return await this.getOrFail<AddressRuneBalance>(
+      `/address/${address}/runes/${rune}`,
+    );
+  }
+
   async getTransaction(txid: string): Promise<Transaction | undefined> {
     return await this.get<Transaction>(`/tx/${txid}`);
   }
 
+  // At most 100 txids per call.
+  async getTransactions(
+    txids: string[],
+  ): Promise<Map<string, Transaction | undefined>> {
+    const response = await this.http.post<
+      Record<string, Transaction | undefined>
+    >('/txs', txids);
+
+    return new Map(Object.entries(response.data));
+  }
+
   async getTransactionRaw(txid: string): Promise<Uint8Array | undefined> {
     // Request raw binary data using the arraybuffer responseType.
     const response = await this.get<ArrayBuffer>(`/tx/${txid}/raw`, {
@@ -84,6 +161,10 @@ export class TitanHttpClient {
     return await this.get<TransactionStatus>(`/tx/${txid}/status`);
   }
 
+  async getTransactionOutspends(txid: string): Promise<TxOutspend[]> {
+    return await this.getOrFail<TxOutspend[]>(`/tx/${txid}/outspends`);
+  }
+
   async sendTransaction(txHex: string): Promise<string> {
     try {
       const response = await this.http.post<string>('/tx/broadcast', txHex, {
@@ -106,10 +187,59 @@ export class TitanHttpClient {
     }
   }
 
+  async decodeTransaction(txHex: string): Promise<DecodedTransaction> {
+    try {
+      const response = await this.http.post<DecodedTransaction>(
+        '/tx/decode',
+        txHex,
+        {
+          headers: {
+            'Content-Type': 'text/plain',
+          },
+        },
+      );
+
+      return response.data;
+    } catch (err) {
+      if (err instanceof AxiosError) {
+        const axiosError = err as AxiosError;
+        if (axiosError.response?.data) {
+          const error = axiosError.response.data as any;
+          throw error;
+        }
+      }
+
+      throw err;
+    }
+  }
+
   async getOutput(txid: string, vout: number): Promise<TxOutEntry | undefined> {
     return await this.get<TxOutEntry>(`/output/${txid}:${vout}`);
   }
 
+  // At most 1000 outpoints per call. Unknown outputs are `null`, in the order asked for.
+  async getOutputs(outpoints: OutPoint[]): Promise<(TxOutEntry | null)[]> {
+    const response = await this.http.post<(TxOutEntry | null)[]>(
+      '/outputs',
+      outpoints,
+    );
+    return response.data;
+  }
+
+  /**
+   * Builds an unsigned PSBT sending runes from the indexed outputs of
+   * `request.sources`, with a preview of the runes each output gets.
+   */
+  async buildRuneTransferPsbt(
+    request: RuneTransferRequest,
+  ): Promise<RuneTransferPsbt> {
+    const response = await this.http.post<RuneTransferPsbt>(
+      '/psbt/rune-transfer',
+      request,
+    );
+    return response.data;
+  }
+
   async getInscription(
     inscriptionId: string,
   ): Promise<{ headers: any; data: Uint8Array }> {
@@ -128,8 +258,9 @@ export class TitanHttpClient {
 
   async getRunes(
     pagination?: Pagination,
+    filter?: RuneFilter,
   ): Promise<PaginationResponse<RuneResponse>> {
-    const params = pagination || {};
+    const params = { ...pagination, ...filter };
     return await this.getOrFail<PaginationResponse<RuneResponse>>('/runes', {
       params,
     });
@@ -139,6 +270,38 @@ export class TitanHttpClient {
     return await this.get<RuneResponse>(`/rune/${rune}`);
   }
 
+  // At most 1000 rune ids (`<block>:<tx>`) per call.
+  async getRunesByIds(
+    runeIds: string[],
+  ): Promise<Map<string, RuneResponse | undefined>> {
+    const response = await this.http.post<
+      Record<string, RuneResponse | undefined>
+    >('/runes/batch', runeIds);
+
+    return new Map(Object.entries(response.data));
+  }
+
+  async getRuneHistory(
+    rune: string,
+    query?: RuneHistoryQuery,
+  ): Promise<RuneHistoryEntry[]> {
+    const params = query || {};
+    return await this.getOrFail<RuneHistoryEntry[]>(`/rune/${rune}/history`, {
+      params,
+    });
+  }
+
+  async getRuneHolders(
+    rune: string,
+    pagination?: Pagination,
+  ): Promise<PaginationResponse<RuneHolder> | undefined> {
+    const params = pagination || {};
+    return await this.getOrFail<PaginationResponse<RuneHolder>>(
+      `/rune/${rune}/holders`,
+      { params },
+    );
+  }
+
   async getRuneTransactions(
     rune: string,
     pagination?: Pagination,
@@ -186,6 +349,28 @@ export class TitanHttpClient {
     return new Map(Object.entries(response.data));
   }
 
+  async getMempoolPackage(
+    txid: string,
+    query?: MempoolPackageQuery,
+  ): Promise<MempoolPackage> {
+    return await this.getOrFail<MempoolPackage>(
+      `/mempool/tx/${txid}/package`,
+      { params: query },
+    );
+  }
+
+  async getMempoolBlocks(): Promise<MempoolBlock[]> {
+    return await this.getOrFail<MempoolBlock[]>('/mempool/blocks');
+  }
+
+  async getMempoolFeeHistogram(): Promise<FeeHistogramBucket[]> {
+    return await this.getOrFail<FeeHistogramBucket[]>('/mempool/fee-histogram');
+  }
+
+  async getRecommendedFees(): Promise<RecommendedFees> {
+    return await this.getOrFail<RecommendedFees>('/fees/recommended');
+  }
+
   async getSubscription(id: string): Promise<Subscription | undefined> {
     return await this.get<Subscription>(`/subscription/${id}`);
   }
@@ -202,6 +387,45 @@ export class TitanHttpClient {
     return response.data;
   }
 
+  /**
+   * Replaces the secret used to sign the deliveries of a subscription. The returned
+   * subscription carries the new secret.
+   */
+  async rotateSubscriptionSecret(id: string): Promise<Subscription> {
+    const response = await this.http.post<Subscription>(
+      `/subscription/${id}/rotate-secret`,
+    );
+    return response.data;
+  }
+
+  /**
+   * Lists the pending deliveries and dead letters of a subscription.
+   */
+  async getSubscriptionDeliveries(
+    id: string,
+    query?: DeliveryQuery,
+  ): Promise<WebhookDelivery[]> {
+    return await this.getOrFail<WebhookDelivery[]>(
+      `/subscription/${id}/deliveries`,
+      { params: query },
+    );
+  }
+
+  /**
+   * Requeues the dead letters of a subscription, or only the one at `seq`.
+   */
+  async redeliverSubscription(
+    id: string,
+    seq?: number,
+  ): Promise<WebhookDelivery[]> {
+    const response = await this.http.post<WebhookDelivery[]>(
+      `/subscription/${id}/redeliver`,
+      undefined,
+      { params: { seq } },
+    );
+    return response.data;
+  }
+
   async deleteSubscription(id: string): Promise<void> {
     const response = await this.http.delete(`/subscription/${id}`);
     if (response.status < 200 || response.status >= 300) {
@@ -1,3 +1,5 @@
 export * from './http-client';
 export * from './tcp-client';
 export * from './types';
+export * from './webhook';
+export * from './ws-client';
@@ -1,7 +1,7 @@
 import * as net from 'net';
 import { EventEmitter } from 'events';
 import * as readline from 'readline';
-import { TcpSubscriptionRequest } from './types';
+import { SequencedTitanEvent, TcpSubscriptionRequest } from './types';
 
 /**
  * Options for the TCP client.
@@ -41,6 +41,8 @@ export class TitanTcpClient extends EventEmitter {
   private socket: net.Socket | null = null;
   private rl: readline.Interface | null = null;
   private subscriptionRequest: TcpSubscriptionRequest | null = null;
+  // Sequence number of the next event, so reconnections resume where they stopped.
+  private nextSeq: number | undefined;
   private shuttingDown = false;
   private connecting = false;
 
@@ -98,6 +100,7 @@ export class TitanTcpClient extends EventEmitter {
     if (this.socket || this.connecting) {
       // Replace the stored request then restart connection cleanly
       this.subscriptionRequest = subscriptionRequest;
+      this.nextSeq = subscriptionRequest.from_seq;
       // Forcefully close current socket to trigger reconnect logic
       if (this.socket) {
         this.socket.destroy(
@@ -112,6 +115,7 @@ export class TitanTcpClient extends EventEmitter {
     }
 
     this.subscriptionRequest = subscriptionRequest;
+    this.nextSeq = subscriptionRequest.from_seq;
     this.shuttingDown = false;
     this.connect();
   }
@@ -137,7 +141,10 @@ export class TitanTcpClient extends EventEmitter {
     // Create a new TCP connection.
     this.socket = net.createConnection(this.port, this.addr, () => {
       // On connection, send the subscription request as JSON (terminated by newline).
-      const reqJson = JSON.stringify(this.subscriptionRequest);
+      const reqJson = JSON.stringify({
+        ...this.subscriptionRequest,
+        from_seq: this.nextSeq,
+      });
       this.socket?.write(reqJson + '\n');
       // Enable TCP keepalive
       this.socket?.setKeepAlive(true, 30000);
@@ -175,7 +182,10 @@ export class TitanTcpClient extends EventEmitter {
           this.onPong();
           return;
         }
-        const event = JSON.parse(line);
+        const event: SequencedTitanEvent = JSON.parse(line);
+        if (typeof event.seq === 'number') {
+          this.nextSeq = event.seq + 1;
+        }
         this.emit('event', event);
         // Receiving a valid event also proves liveness
         this.onPong();
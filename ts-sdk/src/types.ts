@@ -32,10 +32,34 @@ export interface SpentStatus {
   vin?: SpenderReference;
 }
 
+export type ReplacementReason = 'rbf' | 'block_conflict' | 'evicted';
+
+/** A transaction that left the mempool without being mined. */
+export interface TransactionReplacement {
+  txid: string;
+  /** The transaction that spent the conflicting input, when known. */
+  replaced_by?: string;
+  reason: ReplacementReason;
+  /** Unix time, in seconds, when the indexer saw it leave the mempool. */
+  timestamp: number;
+}
+
 export interface TransactionStatus {
   confirmed: boolean;
   block_height?: number;
   block_hash?: string;
+  /** Set when the transaction was replaced or evicted from the mempool. */
+  replaced?: TransactionReplacement;
+}
+
+/** Spending status of a transaction output, from `/tx/{txid}/outspends`. */
+export interface TxOutspend {
+  vout: number;
+  spent: boolean;
+  /** Missing for spent outputs whose spender is no longer indexed. */
+  spender?: SpenderReference;
+  /** Confirmation status of the spending transaction. */
+  status?: TransactionStatus;
 }
 
 export interface OutPoint {
@@ -53,6 +77,27 @@ export interface AddressTxOut extends OutPoint {
   weight: number;
 }
 
+export interface AddressFilter {
+  /** Only confirmed (`false`) or only mempool (`true`) entries. Both when unset. */
+  mempool?: boolean;
+  /** Rune id or spaced rune name. */
+  rune?: string;
+}
+
+export interface AddressRuneBalance {
+  rune_id: string;
+  /** Held in confirmed outputs that are unspent on chain. */
+  confirmed: string;
+  /** Held in confirmed outputs whose allocation can still change. */
+  confirmed_risky: string;
+  /** Received in mempool outputs. */
+  pending: string;
+  /** Received in mempool outputs whose allocation can still change. */
+  risky: string;
+  /** Held in outputs spent by mempool transactions. */
+  pending_spent: string;
+}
+
 export interface AddressData {
   value: number;
   runes: RuneAmount[];
@@ -88,6 +133,26 @@ export interface TxIn {
   previous_output_data?: PreviousOutputData;
 }
 
+export type CenotaphFlaw =
+  | 'edict_output'
+  | 'edict_rune_id'
+  | 'invalid_script'
+  | 'opcode'
+  | 'supply_overflow'
+  | 'trailing_integers'
+  | 'truncated_field'
+  | 'unrecognized_even_tag'
+  | 'unrecognized_flag'
+  | 'varint';
+
+export type ArtifactKind =
+  | { type: 'runestone' }
+  | {
+      /** Malformed runestone, every rune in the inputs is burned. */
+      type: 'cenotaph';
+      flaw?: CenotaphFlaw;
+    };
+
 export interface Transaction {
   version: number;
   lock_time: number;
@@ -96,6 +161,8 @@ export interface Transaction {
   status: TransactionStatus;
   size: number;
   weight: number;
+  /** Runestone or cenotaph carried by the transaction, if any. */
+  artifact?: ArtifactKind;
 }
 
 export interface MintResponse {
@@ -125,6 +192,116 @@ export interface RuneResponse {
   inscription_id?: string;
   timestamp: number;
   turbo: boolean;
+  /** Number of addresses holding the rune in confirmed outputs. */
+  holders: number;
+}
+
+export type RuneSort = 'number' | 'supply' | 'mints' | 'activity';
+
+/** Names are matched without spacers and case-insensitively. */
+export interface RuneFilter {
+  /** Only runes whose name starts with this. */
+  prefix?: string;
+  /** Only runes whose name contains this. */
+  search?: string;
+  /** Only runes that can (`true`) or can't (`false`) be minted in the next block. */
+  mintable?: boolean;
+  turbo?: boolean;
+  /** Only runes etched at or after this height. */
+  etched_from?: number;
+  /** Only runes etched at or before this height. */
+  etched_to?: number;
+  symbol?: string;
+  /** Highest first. Defaults to `number`. */
+  sort?: RuneSort;
+}
+
+export interface RuneHistoryQuery {
+  /** First height, inclusive. */
+  from?: number;
+  /** Last height, inclusive. */
+  to?: number;
+  /** One entry per block (default) or per UTC day with activity. */
+  bucket?: 'block' | 'day';
+}
+
+export interface RuneHistoryEntry {
+  start_height: number;
+  end_height: number;
+  /** Block time for block buckets, start of the day for day buckets. */
+  timestamp: number;
+  mints: number;
+  minted: string;
+  burns: number;
+  burned: string;
+  /** Transactions that move the rune from their inputs to their outputs. */
+  transfers: number;
+  transferred: string;
+}
+
+export interface RuneHolder {
+  /** Missing when the script pubkey has no address form. */
+  address?: string;
+  script_pubkey: string;
+  amount: string;
+}
+
+export interface DecodedEdict {
+  /** `0:0` refers to the rune etched by the same transaction. */
+  id: string;
+  amount: string;
+  output: number;
+}
+
+export interface DecodedTerms {
+  amount: string;
+  cap: string;
+  height_start?: number;
+  height_end?: number;
+  offset_start?: number;
+  offset_end?: number;
+}
+
+export interface DecodedEtching {
+  /** Missing when the etching asks for a reserved name. */
+  rune?: string;
+  divisibility: number;
+  premine: string;
+  symbol?: string;
+  terms?: DecodedTerms;
+  turbo: boolean;
+}
+
+export type RuneArtifact =
+  | {
+      type: 'runestone';
+      edicts: DecodedEdict[];
+      etching?: DecodedEtching;
+      mint?: string;
+      pointer?: number;
+    }
+  | {
+      /** Malformed runestone, every rune in the inputs is burned. */
+      type: 'cenotaph';
+      flaw?: CenotaphFlaw;
+      etching?: string;
+      mint?: string;
+    };
+
+export interface DecodedInput {
+  previous_outpoint: string;
+  /** Missing when the output being spent isn't known to the index. */
+  tx_out?: TxOut;
+}
+
+export interface DecodedTransaction {
+  txid: string;
+  artifact?: RuneArtifact;
+  inputs: DecodedInput[];
+  /** Runes that depend on unconfirmed transactions, mints included, are in `risky_runes`. */
+  outputs: TxOut[];
+  minted?: RuneAmount;
+  burned: RuneAmount[];
 }
 
 export interface Subscription {
@@ -132,16 +309,84 @@ export interface Subscription {
   endpoint: string;
   event_types: TitanEventType[];
   last_success_epoch_secs: number;
+  filter?: EventFilter;
+  /**
+   * Key of the HMAC-SHA256 signature sent with every delivery. Generated by the
+   * server and only returned when the subscription is added or its secret rotated.
+   */
+  secret?: string;
+  /** Concurrent delivery (the default) or ordered delivery. */
+  delivery_policy?: DeliveryPolicy;
+}
+
+/**
+ * How the outbox of a webhook subscription is drained. `concurrent` sends up to
+ * `max_concurrency` due deliveries at once, so events can arrive out of order.
+ * `ordered` sends one at a time in the order the events were emitted, and a failing
+ * delivery holds back the ones after it until it succeeds or becomes a dead letter.
+ */
+export type DeliveryPolicy =
+  | { mode: 'concurrent'; max_concurrency: number }
+  | { mode: 'ordered' };
+
+/**
+ * An event queued for a webhook subscription. Deliveries that run out of attempts
+ * become dead letters, which are kept until redelivered.
+ */
+export interface WebhookDelivery {
+  /** Sent in the `x-titan-delivery` header of every attempt. */
+  id: string;
+  /** Position in the subscription's outbox, in the order the events were emitted. */
+  seq: number;
+  event: TitanEvent;
+  status: DeliveryStatus;
+  attempts: number;
+  created_at_epoch_secs: number;
+  next_attempt_epoch_secs: number;
+  last_error?: string;
+}
+
+export type DeliveryStatus = 'pending' | 'dead_letter';
+
+export interface DeliveryQuery {
+  status?: DeliveryStatus;
+  from_seq?: number;
+  limit?: number;
+}
+
+/**
+ * Narrows a subscription down within its event types. Every criterion that is set
+ * has to match, and each one only applies to the events that carry that kind of
+ * data: an address filter doesn't hold back `NewBlock`, for instance.
+ */
+export interface EventFilter {
+  /** Only `AddressModified` events of one of these addresses. */
+  addresses?: string[];
+  /** Only rune events, cenotaphs included, that involve one of these runes. */
+  rune_ids?: string[];
+  /** Only events about one of these transactions. */
+  txids?: string[];
+  /** Only rune events that move at least this amount. */
+  min_amount?: string;
+  /** Only confirmed (`false`) or only mempool (`true`) events. */
+  mempool?: boolean;
 }
 
+export type PaginationDirection = 'forward' | 'backward';
+
 export interface Pagination {
   skip?: number;
   limit?: number;
+  /** Opaque cursor from a previous `next_cursor` or `prev_cursor`. Overrides `skip`. */
+  cursor?: string;
+  direction?: PaginationDirection;
 }
 
 export interface PaginationResponse<T> {
   items: T[];
   offset: number;
+  next_cursor?: string;
+  prev_cursor?: string;
 }
 
 export enum TitanEventType {
@@ -149,6 +394,7 @@ export enum TitanEventType {
   RuneMinted = 'RuneMinted',
   RuneBurned = 'RuneBurned',
   RuneTransferred = 'RuneTransferred',
+  Cenotaph = 'Cenotaph',
   AddressModified = 'AddressModified',
   TransactionSubmitted = 'TransactionSubmitted',
   TransactionsAdded = 'TransactionsAdded',
@@ -165,6 +411,12 @@ export interface Location {
   block_height: number | null;
 }
 
+/**
+ * An event as delivered to TCP, WebSocket and SSE subscribers, numbered by its
+ * position in the indexer's event log.
+ */
+export type SequencedTitanEvent = TitanEvent & { seq: number };
+
 export type TitanEvent =
   | {
       type: TitanEventType.RuneEtched;
@@ -209,6 +461,16 @@ export type TitanEvent =
         txid: string;
       };
     }
+  | {
+      type: TitanEventType.Cenotaph;
+      data: {
+        flaw?: CenotaphFlaw;
+        location: Location;
+        /** Runes burned by the transaction, empty when its inputs held none. */
+        burned: RuneAmount[];
+        txid: string;
+      };
+    }
   | {
       type: TitanEventType.AddressModified;
       data: {
@@ -226,11 +488,11 @@ export type TitanEvent =
     }
   | {
       type: TitanEventType.MempoolTransactionsReplaced;
-      data: { txids: string[] };
+      data: { txids: string[]; replacements: TransactionReplacement[] };
     }
   | {
       type: TitanEventType.TransactionsReplaced;
-      data: { txids: string[] };
+      data: { txids: string[]; replacements: TransactionReplacement[] };
     }
   | {
       type: TitanEventType.MempoolEntriesUpdated;
@@ -258,6 +520,14 @@ export type TitanEvent =
  */
 export interface TcpSubscriptionRequest {
   subscribe: TitanEventType[];
+  /**
+   * Replay logged events starting at this sequence number before streaming new
+   * ones. If it has been pruned already, replay starts at the oldest logged event.
+   */
+  from_seq?: number;
+  filter?: EventFilter;
+  /** Required when the indexer runs with API keys enabled. Needs the `read` scope. */
+  api_key?: string;
 }
 
 export interface MempoolEntryFee {
@@ -277,3 +547,116 @@ export interface MempoolEntry {
   depends: string[];
   spentby: string[];
 }
+
+/** Fee rates in sat/vB for common confirmation targets. */
+export interface RecommendedFees {
+  /** Next block. */
+  fastest_fee: number;
+  /** Within three blocks. */
+  half_hour_fee: number;
+  /** Within six blocks. */
+  hour_fee: number;
+  economy_fee: number;
+  minimum_fee: number;
+}
+
+/** A block template projected from the mempool by ancestor fee rate. */
+export interface MempoolBlock {
+  block_vsize: number;
+  n_tx: number;
+  total_fees: number;
+  /** Median effective fee rate in sat/vB, weighted by vsize. */
+  median_fee: number;
+  /** Minimum, 10th, 25th, 50th, 75th, 90th percentile and maximum fee rates. */
+  fee_range: number[];
+}
+
+export interface MempoolPackageQuery {
+  /** Fee rate to reach, in sat/vB. Defaults to the recommended next block rate. */
+  fee_rate?: number;
+  /** Size of the CPFP child. Defaults to 111 vB. */
+  child_vsize?: number;
+}
+
+/** A mempool transaction with its unconfirmed cluster and bump fees. */
+export interface MempoolPackage {
+  txid: string;
+  vsize: number;
+  fee: number;
+  ancestors: string[];
+  /** Descendants a replacement would evict. */
+  descendants: string[];
+  ancestor_vsize: number;
+  ancestor_fees: number;
+  descendant_vsize: number;
+  descendant_fees: number;
+  effective_fee_rate: number;
+  /** Whether the transaction or an ancestor signals BIP125 replaceability. */
+  bip125_replaceable: boolean;
+  target_fee_rate: number;
+  cpfp: {
+    child_vsize: number;
+    child_fee: number;
+  };
+  /** Fees of a replacement of the same vsize. */
+  rbf: {
+    min_fee: number;
+    target_fee: number;
+  };
+}
+
+export interface FeeHistogramBucket {
+  /** Lower bound of the bucket, in sat/vB. */
+  fee_rate: number;
+  count: number;
+  vsize: number;
+}
+
+/** Body of `POST /psbt/rune-transfer`. */
+export interface RuneTransferRequest {
+  /** Addresses whose outputs can be spent, for the runes and for the fee. */
+  sources: string[];
+  destinations: RuneTransferDestination[];
+  /** Fee rate in sat/vB. */
+  fee_rate: number;
+  /** Receives the runes and bitcoin left over. */
+  change_address: string;
+  /** Also spend outputs of unconfirmed transactions. */
+  allow_pending?: boolean;
+  /** Also spend outputs whose rune allocation can still change. */
+  allow_risky?: boolean;
+}
+
+export interface RuneTransferDestination {
+  address: string;
+  /** Rune id or spaced rune name. */
+  rune: string;
+  amount: string;
+  /** Value of the output in sats. Defaults to the dust limit of the address. */
+  value?: number;
+}
+
+export interface RuneTransferInput extends OutPoint {
+  address: string;
+  value: number;
+  runes: RuneAmount[];
+}
+
+export interface RuneTransferOutput {
+  vout: number;
+  /** Missing for the runestone output. */
+  address?: string;
+  value: number;
+  runes: RuneAmount[];
+}
+
+/** An unsigned rune transfer and what it allocates to each output once signed. */
+export interface RuneTransferPsbt {
+  /** Base64 encoded PSBT. */
+  psbt: string;
+  fee: number;
+  /** Estimated size of the signed transaction. */
+  vsize: number;
+  inputs: RuneTransferInput[];
+  outputs: RuneTransferOutput[];
+}
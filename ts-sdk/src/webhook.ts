@@ -0,0 +1,40 @@
+import { createHmac, timingSafeEqual } from 'crypto';
+
+export const WEBHOOK_DELIVERY_HEADER = 'x-titan-delivery';
+export const WEBHOOK_TIMESTAMP_HEADER = 'x-titan-timestamp';
+export const WEBHOOK_SIGNATURE_HEADER = 'x-titan-signature';
+
+/**
+ * Checks the signature of a webhook delivery. `body` must be the raw request body,
+ * and `timestamp` and `signature` the values of the `x-titan-timestamp` and
+ * `x-titan-signature` headers.
+ *
+ * Deliveries older than `toleranceSecs` are rejected, so that a captured request
+ * can't be replayed later.
+ */
+export function verifyWebhookSignature(
+  secret: string,
+  body: string | Buffer,
+  timestamp: string,
+  signature: string,
+  toleranceSecs = 300,
+): boolean {
+  const seconds = Number(timestamp);
+  if (
+    !Number.isInteger(seconds) ||
+    Math.abs(Date.now() / 1000 - seconds) > toleranceSecs
+  ) {
+    return false;
+  }
+
+  const expected = Buffer.from(
+    'sha256=' +
+      createHmac('sha256', secret)
+        .update(`${timestamp}.`)
+        .update(body)
+        .digest('hex'),
+  );
+  const actual = Buffer.from(signature);
+
+  return expected.length === actual.length && timingSafeEqual(expected, actual);
+}
@@ -0,0 +1,242 @@
+import type { ConnectionStatus } from './tcp-client';
+import {
+  SequencedTitanEvent,
+  TcpSubscriptionRequest,
+  TitanEvent,
+} from './types';
+
+/**
+ * Options for the WebSocket client.
+ */
+export interface WsClientOptions {
+  autoReconnect?: boolean; // Should the client attempt to reconnect on disconnection?
+  heartbeatIntervalMs?: number; // Interval between client PINGs (ms)
+  baseDelayMs?: number; // Base delay for backoff (defaults to 1000ms)
+  maxDelayMs?: number; // Max delay cap for backoff (defaults to 60000ms)
+}
+
+type WsClientListeners = {
+  event: (event: TitanEvent) => void;
+  error: (error: Error) => void;
+  close: () => void;
+  status: (status: ConnectionStatus) => void;
+};
+
+/**
+ * A WebSocket client for subscribing to events from the Titan service through
+ * `/events/ws`. Unlike `TitanTcpClient` it only depends on the global `WebSocket`,
+ * so it works in browsers.
+ *
+ * Usage example:
+ *
+ *   const wsClient = new TitanWsClient('ws://localhost:3030/events/ws', { autoReconnect: true });
+ *   wsClient.on('event', (event) => console.log('Received event:', event));
+ *   wsClient.subscribe({ subscribe: ['RuneEtched', 'RuneMinted'] });
+ *
+ *   // To shut down:
+ *   wsClient.shutdown();
+ */
+export class TitanWsClient {
+  private socket: WebSocket | null = null;
+  private subscriptionRequest: TcpSubscriptionRequest | null = null;
+  // Sequence number of the next event, so reconnections resume where they stopped.
+  private nextSeq: number | undefined;
+  private shuttingDown = false;
+
+  private autoReconnect: boolean;
+  private heartbeatIntervalMs: number;
+  private baseDelayMs: number;
+  private maxDelayMs: number;
+
+  private heartbeatInterval: ReturnType<typeof setInterval> | null = null;
+  private reconnectTimer: ReturnType<typeof setTimeout> | null = null;
+  private reconnectAttempt = 0;
+
+  private status: ConnectionStatus = 'Disconnected';
+  private listeners: {
+    [K in keyof WsClientListeners]: Set<WsClientListeners[K]>;
+  } = {
+    event: new Set(),
+    error: new Set(),
+    close: new Set(),
+    status: new Set(),
+  };
+
+  /**
+   * @param url The indexer's WebSocket URL, e.g. `ws://localhost:3030/events/ws`.
+   * @param options Optional configuration for reconnection behavior.
+   */
+  constructor(
+    private url: string,
+    options?: WsClientOptions,
+  ) {
+    this.autoReconnect = options?.autoReconnect ?? false;
+    this.heartbeatIntervalMs = options?.heartbeatIntervalMs ?? 30000;
+    this.baseDelayMs = options?.baseDelayMs ?? 1000;
+    this.maxDelayMs = options?.maxDelayMs ?? 60000;
+  }
+
+  on<K extends keyof WsClientListeners>(
+    type: K,
+    listener: WsClientListeners[K],
+  ): this {
+    this.listeners[type].add(listener);
+    return this;
+  }
+
+  off<K extends keyof WsClientListeners>(
+    type: K,
+    listener: WsClientListeners[K],
+  ): this {
+    this.listeners[type].delete(listener);
+    return this;
+  }
+
+  /**
+   * Subscribes to the given event types. The request is stored and re-sent on
+   * reconnection. Calling it again replaces the current subscription.
+   */
+  subscribe(subscriptionRequest: TcpSubscriptionRequest): void {
+    this.subscriptionRequest = subscriptionRequest;
+    this.nextSeq = subscriptionRequest.from_seq;
+    this.shuttingDown = false;
+
+    if (this.socket) {
+      // The server takes one request per connection, so reconnect with the new one.
+      this.socket.close();
+      if (!this.autoReconnect) {
+        this.cleanup();
+        this.connect();
+      }
+      return;
+    }
+
+    this.connect();
+  }
+
+  /**
+   * Closes the connection and cancels any pending reconnection attempt.
+   */
+  shutdown(): void {
+    this.shuttingDown = true;
+    this.socket?.close();
+    this.cleanup();
+    this.updateStatus('Disconnected');
+  }
+
+  getStatus(): ConnectionStatus {
+    return this.status;
+  }
+
+  private connect(): void {
+    if (!this.subscriptionRequest) {
+      this.emit('error', new Error('No subscription request provided.'));
+      return;
+    }
+
+    this.updateStatus(
+      this.reconnectAttempt > 0 ? 'Reconnecting' : 'Connecting',
+    );
+
+    const socket = new WebSocket(this.url);
+    this.socket = socket;
+
+    socket.onopen = () => {
+      socket.send(
+        JSON.stringify({ ...this.subscriptionRequest, from_seq: this.nextSeq }),
+      );
+      this.reconnectAttempt = 0;
+      this.startHeartbeat();
+      this.updateStatus('Connected');
+    };
+
+    socket.onmessage = (message) => {
+      if (typeof message.data !== 'string' || message.data === 'PONG') {
+        return;
+      }
+
+      try {
+        const event: SequencedTitanEvent = JSON.parse(message.data);
+        if (typeof event.seq === 'number') {
+          this.nextSeq = event.seq + 1;
+        }
+        this.emit('event', event);
+      } catch (err) {
+        this.emit(
+          'error',
+          new Error(`Failed to parse event: ${message.data}`),
+        );
+      }
+    };
+
+    socket.onerror = () => {
+      this.emit('error', new Error('WebSocket error'));
+    };
+
+    socket.onclose = () => {
+      if (this.socket !== socket) {
+        return;
+      }
+
+      this.emit('close');
+      this.cleanup();
+
+      if (!this.shuttingDown && this.autoReconnect) {
+        this.scheduleReconnect();
+      } else {
+        this.updateStatus('Disconnected');
+      }
+    };
+  }
+
+  private cleanup(): void {
+    if (this.heartbeatInterval) {
+      clearInterval(this.heartbeatInterval);
+      this.heartbeatInterval = null;
+    }
+    if (this.reconnectTimer) {
+      clearTimeout(this.reconnectTimer);
+      this.reconnectTimer = null;
+    }
+    this.socket = null;
+  }
+
+  private startHeartbeat(): void {
+    this.heartbeatInterval = setInterval(() => {
+      if (this.socket?.readyState === WebSocket.OPEN) {
+        this.socket.send('PING');
+      }
+    }, this.heartbeatIntervalMs);
+  }
+
+  private scheduleReconnect(): void {
+    this.updateStatus('Reconnecting');
+    const exp = Math.min(30, this.reconnectAttempt); // prevent overflow
+    const delay = Math.min(
+      this.maxDelayMs,
+      this.baseDelayMs * Math.pow(2, exp),
+    );
+    this.reconnectAttempt += 1;
+    this.reconnectTimer = setTimeout(() => {
+      this.reconnectTimer = null;
+      this.connect();
+    }, delay);
+  }
+
+  private updateStatus(next: ConnectionStatus): void {
+    if (this.status === next) return;
+    this.status = next;
+    this.emit('status', next);
+  }
+
+  private emit<K extends keyof WsClientListeners>(
+    type: K,
+    ...args: Parameters<WsClientListeners[K]>
+  ): void {
+    for (const listener of this.listeners[type]) {
+      (listener as (...args: Parameters<WsClientListeners[K]>) => void)(
+        ...args,
+      );
+    }
+  }
+}
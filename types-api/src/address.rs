@@ -1,7 +1,10 @@
 use {
+    crate::query,
     bitcoin::Txid,
     serde::{Deserialize, Serialize},
-    titan_types_core::{RuneAmount, SerializedOutPoint, SpentStatus, TransactionStatus, TxOut},
+    titan_types_core::{
+        RuneAmount, RuneId, SerializedOutPoint, SpentStatus, TransactionStatus, TxOut,
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +14,41 @@ pub struct AddressData {
     pub outputs: Vec<AddressTxOut>,
 }
 
+/// Filters for the paginated address endpoints.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AddressFilter {
+    /// Only confirmed (`false`) or only mempool (`true`) entries. Both when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mempool: Option<bool>,
+    /// Only entries that involve this rune.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rune: Option<query::Rune>,
+}
+
+/// Rune balance of an address.
+///
+/// Once its mempool transactions confirm, the address holds
+/// `confirmed + confirmed_risky + pending + risky - pending_spent`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddressRuneBalance {
+    pub rune_id: RuneId,
+    /// Held in confirmed outputs that are unspent on chain.
+    #[serde(with = "titan_types_core::serde_str")]
+    pub confirmed: u128,
+    /// Held in confirmed outputs whose allocation can still change.
+    #[serde(with = "titan_types_core::serde_str")]
+    pub confirmed_risky: u128,
+    /// Received in mempool outputs.
+    #[serde(with = "titan_types_core::serde_str")]
+    pub pending: u128,
+    /// Received in mempool outputs whose allocation can still change.
+    #[serde(with = "titan_types_core::serde_str")]
+    pub risky: u128,
+    /// Held in outputs spent by mempool transactions.
+    #[serde(with = "titan_types_core::serde_str")]
+    pub pending_spent: u128,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddressTxOut {
     pub txid: Txid,
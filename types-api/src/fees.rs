@@ -0,0 +1,96 @@
+use {
+    serde::{Deserialize, Serialize},
+    titan_types_core::SerializedTxid,
+};
+
+/// Fee rates in sat/vB for common confirmation targets, derived from the
+/// projected mempool blocks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecommendedFees {
+    /// Next block.
+    pub fastest_fee: f64,
+    /// Within three blocks.
+    pub half_hour_fee: f64,
+    /// Within six blocks.
+    pub hour_fee: f64,
+    pub economy_fee: f64,
+    pub minimum_fee: f64,
+}
+
+/// A block template projected from the current mempool, ordered by ancestor
+/// fee rate. The last projected block holds every remaining transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MempoolBlock {
+    pub block_vsize: u64,
+    pub n_tx: u64,
+    pub total_fees: u64,
+    /// Median effective fee rate in sat/vB, weighted by vsize.
+    pub median_fee: f64,
+    /// Minimum, 10th, 25th, 50th, 75th, 90th percentile and maximum effective
+    /// fee rates in sat/vB.
+    pub fee_range: Vec<f64>,
+}
+
+/// Mempool transactions whose effective fee rate falls between `fee_rate`
+/// and the next bucket's rate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeHistogramBucket {
+    pub fee_rate: f64,
+    pub count: u64,
+    pub vsize: u64,
+}
+
+/// Target of the bump fees in a [`MempoolPackage`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MempoolPackageQuery {
+    /// Fee rate to reach, in sat/vB. Defaults to the recommended next block rate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_rate: Option<f64>,
+    /// Size of the CPFP child. Defaults to 111 vB, one P2TR input and output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_vsize: Option<u64>,
+}
+
+/// A mempool transaction with its unconfirmed ancestors and descendants, and
+/// the fees needed to bump it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MempoolPackage {
+    pub txid: SerializedTxid,
+    pub vsize: u64,
+    pub fee: u64,
+    /// Unconfirmed ancestors, which are mined before or with the transaction.
+    pub ancestors: Vec<SerializedTxid>,
+    /// Unconfirmed descendants, which a replacement would evict.
+    pub descendants: Vec<SerializedTxid>,
+    /// Size and fees of the transaction and its ancestors.
+    pub ancestor_vsize: u64,
+    pub ancestor_fees: u64,
+    /// Size and fees of the transaction and its descendants.
+    pub descendant_vsize: u64,
+    pub descendant_fees: u64,
+    /// Rate, in sat/vB, of the package the transaction is projected to be mined in.
+    pub effective_fee_rate: f64,
+    /// Whether the transaction or one of its ancestors signals replaceability.
+    /// Nodes running full RBF accept replacements either way.
+    pub bip125_replaceable: bool,
+    pub target_fee_rate: f64,
+    pub cpfp: CpfpFee,
+    pub rbf: RbfFee,
+}
+
+/// Fee of a child spending the transaction, to mine the package at the target rate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpfpFee {
+    pub child_vsize: u64,
+    pub child_fee: u64,
+}
+
+/// Fees of a replacement of the same vsize as the transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RbfFee {
+    /// Least fee accepted under BIP125: the fees of the transaction and its
+    /// descendants plus the incremental relay fee.
+    pub min_fee: u64,
+    /// Fee to reach the target rate, never below `min_fee`.
+    pub target_fee: u64,
+}
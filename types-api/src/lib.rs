@@ -1,14 +1,34 @@
 pub use {
-    address::{AddressData, AddressTxOut},
-    pagination::{Pagination, PaginationResponse},
-    rune::{MintResponse, RuneResponse},
+    address::{AddressData, AddressFilter, AddressRuneBalance, AddressTxOut},
+    fees::{
+        CpfpFee, FeeHistogramBucket, MempoolBlock, MempoolPackage, MempoolPackageQuery, RbfFee,
+        RecommendedFees,
+    },
+    pagination::{Cursor, CursorParseError, Pagination, PaginationDirection, PaginationResponse},
+    psbt::{
+        RuneTransferDestination, RuneTransferInput, RuneTransferOutput, RuneTransferPsbt,
+        RuneTransferRequest,
+    },
+    rune::{
+        HistoryBucket, MintResponse, RuneFilter, RuneHistoryEntry, RuneHistoryQuery, RuneHolder,
+        RuneResponse, RuneSort,
+    },
     stats::{BlockTip, Status},
-    subscription::{Subscription, TcpSubscriptionRequest},
+    subscription::{EventFilter, EventStreamQuery, Subscription, TcpSubscriptionRequest},
+    transaction::{
+        DecodedCenotaph, DecodedEdict, DecodedEtching, DecodedInput, DecodedRunestone,
+        DecodedTerms, DecodedTransaction, RuneArtifact, TxOutspend,
+    },
+    webhook::{DeliveryPolicy, DeliveryQuery, DeliveryStatus, RedeliverQuery, WebhookDelivery},
 };
 
 mod address;
+mod fees;
 mod pagination;
+mod psbt;
 pub mod query;
 mod rune;
 mod stats;
 mod subscription;
+mod transaction;
+mod webhook;
@@ -1,11 +1,25 @@
-use serde::{Deserialize, Deserializer, Serialize};
+use {
+    bitcoin::ScriptBuf,
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        fmt::{self, Display},
+        str::FromStr,
+    },
+    titan_types_core::SerializedOutPoint,
+};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pagination {
     #[serde(default = "default_skip")]
     pub skip: u64,
     #[serde(default = "default_limit", deserialize_with = "clamp_limit")]
     pub limit: u64,
+    /// Position to continue from, taken from a previous `next_cursor` or `prev_cursor`.
+    /// When set, `skip` is ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Cursor>,
+    #[serde(default)]
+    pub direction: PaginationDirection,
 }
 
 impl Default for Pagination {
@@ -13,6 +27,8 @@ impl Default for Pagination {
         Pagination {
             skip: 0,
             limit: default_limit(),
+            cursor: None,
+            direction: PaginationDirection::default(),
         }
     }
 }
@@ -40,6 +56,7 @@ impl Into<Pagination> for (u64, u64) {
         Pagination {
             skip: self.0,
             limit: self.1,
+            ..Default::default()
         }
     }
 }
@@ -54,4 +71,258 @@ impl Into<(u64, u64)> for Pagination {
 pub struct PaginationResponse<T> {
     pub items: Vec<T>,
     pub offset: u64,
+    /// Cursor to fetch the page after the last item, if there are more items.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<Cursor>,
+    /// Cursor to fetch the page before the first item, if there are more items.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<Cursor>,
+}
+
+/// Which side of the cursor to read. `Forward` continues in the order the endpoint
+/// lists its items (newest first), `Backward` returns the items right before the cursor.
+/// Items are always returned in listing order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaginationDirection {
+    #[default]
+    Forward,
+    Backward,
+}
+
+impl Display for PaginationDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaginationDirection::Forward => write!(f, "forward"),
+            PaginationDirection::Backward => write!(f, "backward"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CursorParseError {
+    #[error("invalid cursor")]
+    Invalid,
+}
+
+/// Opaque position in a paginated listing.
+///
+/// It points at an entry of the underlying index (e.g. the index of a rune transaction,
+/// the number of a rune, an address output or a rune holder), so it stays valid while
+/// new items are added.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cursor {
+    mempool: bool,
+    position: CursorPosition,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CursorPosition {
+    Index(u64),
+    OutPoint(SerializedOutPoint),
+    Holder(u128, ScriptBuf),
+}
+
+const CURSOR_MEMPOOL_FLAG: u8 = 0x01;
+const CURSOR_OUTPOINT_FLAG: u8 = 0x02;
+const CURSOR_HOLDER_FLAG: u8 = 0x04;
+
+impl Cursor {
+    pub fn new(mempool: bool, index: u64) -> Self {
+        Self {
+            mempool,
+            position: CursorPosition::Index(index),
+        }
+    }
+
+    pub fn from_outpoint(mempool: bool, outpoint: SerializedOutPoint) -> Self {
+        Self {
+            mempool,
+            position: CursorPosition::OutPoint(outpoint),
+        }
+    }
+
+    /// Cursor of a rune holder, made of its balance and script pubkey.
+    pub fn from_holder(amount: u128, script_pubkey: ScriptBuf) -> Self {
+        Self {
+            mempool: false,
+            position: CursorPosition::Holder(amount, script_pubkey),
+        }
+    }
+
+    pub fn mempool(&self) -> bool {
+        self.mempool
+    }
+
+    pub fn index(&self) -> Option<u64> {
+        match self.position {
+            CursorPosition::Index(index) => Some(index),
+            _ => None,
+        }
+    }
+
+    pub fn outpoint(&self) -> Option<SerializedOutPoint> {
+        match self.position {
+            CursorPosition::OutPoint(outpoint) => Some(outpoint),
+            _ => None,
+        }
+    }
+
+    pub fn holder(&self) -> Option<(u128, &ScriptBuf)> {
+        match &self.position {
+            CursorPosition::Holder(amount, script_pubkey) => Some((*amount, script_pubkey)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mempool = if self.mempool { CURSOR_MEMPOOL_FLAG } else { 0 };
+        match &self.position {
+            CursorPosition::Index(index) => write!(f, "{:02x}{:016x}", mempool, index),
+            CursorPosition::OutPoint(outpoint) => {
+                write!(f, "{:02x}", mempool | CURSOR_OUTPOINT_FLAG)?;
+                for byte in outpoint.as_ref() {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            CursorPosition::Holder(amount, script_pubkey) => {
+                write!(f, "{:02x}{:032x}", mempool | CURSOR_HOLDER_FLAG, amount)?;
+                for byte in script_pubkey.as_bytes() {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = CursorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() < 2 || !s.is_ascii() {
+            return Err(CursorParseError::Invalid);
+        }
+
+        let flags = u8::from_str_radix(&s[..2], 16).map_err(|_| CursorParseError::Invalid)?;
+        if flags & !(CURSOR_MEMPOOL_FLAG | CURSOR_OUTPOINT_FLAG | CURSOR_HOLDER_FLAG) != 0
+            || flags & CURSOR_OUTPOINT_FLAG != 0 && flags & CURSOR_HOLDER_FLAG != 0
+        {
+            return Err(CursorParseError::Invalid);
+        }
+
+        let mempool = flags & CURSOR_MEMPOOL_FLAG != 0;
+        let body = &s[2..];
+
+        let position = if flags & CURSOR_OUTPOINT_FLAG != 0 {
+            if body.len() != 72 {
+                return Err(CursorParseError::Invalid);
+            }
+
+            let mut bytes = [0u8; 36];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&body[i * 2..i * 2 + 2], 16)
+                    .map_err(|_| CursorParseError::Invalid)?;
+            }
+            CursorPosition::OutPoint(SerializedOutPoint::from(bytes))
+        } else if flags & CURSOR_HOLDER_FLAG != 0 {
+            if body.len() < 32 || body.len() % 2 != 0 {
+                return Err(CursorParseError::Invalid);
+            }
+
+            let amount =
+                u128::from_str_radix(&body[..32], 16).map_err(|_| CursorParseError::Invalid)?;
+            let script_pubkey = (32..body.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&body[i..i + 2], 16))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| CursorParseError::Invalid)?;
+            CursorPosition::Holder(amount, ScriptBuf::from_bytes(script_pubkey))
+        } else {
+            if body.len() != 16 {
+                return Err(CursorParseError::Invalid);
+            }
+
+            CursorPosition::Index(
+                u64::from_str_radix(body, 16).map_err(|_| CursorParseError::Invalid)?,
+            )
+        };
+
+        Ok(Self { mempool, position })
+    }
+}
+
+impl Serialize for Cursor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cursor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_roundtrip() {
+        let cursor = Cursor::new(true, 840_000);
+        let encoded = cursor.to_string();
+
+        assert_eq!(encoded.len(), 18);
+        assert_eq!(encoded.parse::<Cursor>().unwrap(), cursor);
+        assert!("02000000000000000a".parse::<Cursor>().is_err());
+        assert!("00zz".parse::<Cursor>().is_err());
+
+        let outpoint = SerializedOutPoint::new(&[7; 32], 3);
+        let cursor = Cursor::from_outpoint(false, outpoint);
+        let decoded = cursor.to_string().parse::<Cursor>().unwrap();
+
+        assert_eq!(decoded.outpoint(), Some(outpoint));
+        assert!(!decoded.mempool());
+
+        let script_pubkey = ScriptBuf::from_bytes(vec![0x51, 0x20, 0xab]);
+        let cursor = Cursor::from_holder(1_000, script_pubkey.clone());
+        let decoded = cursor.to_string().parse::<Cursor>().unwrap();
+
+        assert_eq!(decoded.holder(), Some((1_000, &script_pubkey)));
+        assert_eq!(decoded.index(), None);
+        assert_eq!(decoded.outpoint(), None);
+        assert!("04000000000000000000000000000003e85"
+            .parse::<Cursor>()
+            .is_err());
+        assert!("06000000000000000000000000000003e8"
+            .parse::<Cursor>()
+            .is_err());
+    }
+
+    #[test]
+    fn pagination_defaults_without_cursor() {
+        let pagination: Pagination = serde_json::from_str(r#"{"limit": 500}"#).unwrap();
+
+        assert_eq!(pagination.limit, 50);
+        assert!(pagination.cursor.is_none());
+        assert_eq!(pagination.direction, PaginationDirection::Forward);
+
+        let pagination: Pagination =
+            serde_json::from_str(r#"{"cursor": "000000000000000010", "direction": "backward"}"#)
+                .unwrap();
+
+        assert_eq!(pagination.cursor, Some(Cursor::new(false, 16)));
+        assert_eq!(pagination.direction, PaginationDirection::Backward);
+    }
 }
@@ -0,0 +1,66 @@
+use {
+    crate::query,
+    bitcoin::{address::NetworkUnchecked, Address, Txid},
+    serde::{Deserialize, Serialize},
+    titan_types_core::RuneAmount,
+};
+
+/// Body of `POST /psbt/rune-transfer`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuneTransferRequest {
+    /// Addresses whose outputs can be spent, for the runes and for the fee.
+    pub sources: Vec<Address<NetworkUnchecked>>,
+    pub destinations: Vec<RuneTransferDestination>,
+    /// Fee rate in sat/vB.
+    pub fee_rate: f64,
+    /// Receives the runes and bitcoin left over.
+    pub change_address: Address<NetworkUnchecked>,
+    /// Also spend outputs of unconfirmed transactions.
+    #[serde(default)]
+    pub allow_pending: bool,
+    /// Also spend outputs whose rune allocation can still change.
+    #[serde(default)]
+    pub allow_risky: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuneTransferDestination {
+    pub address: Address<NetworkUnchecked>,
+    pub rune: query::Rune,
+    #[serde(with = "titan_types_core::serde_str")]
+    pub amount: u128,
+    /// Value of the output in sats. Defaults to the dust limit of the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<u64>,
+}
+
+/// An unsigned rune transfer and what it allocates to each output once signed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneTransferPsbt {
+    /// Base64 encoded PSBT.
+    pub psbt: String,
+    pub fee: u64,
+    /// Estimated size of the signed transaction.
+    pub vsize: u64,
+    pub inputs: Vec<RuneTransferInput>,
+    pub outputs: Vec<RuneTransferOutput>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneTransferInput {
+    pub txid: Txid,
+    pub vout: u32,
+    pub address: Address<NetworkUnchecked>,
+    pub value: u64,
+    pub runes: Vec<RuneAmount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuneTransferOutput {
+    pub vout: u32,
+    /// Missing for the runestone output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address<NetworkUnchecked>>,
+    pub value: u64,
+    pub runes: Vec<RuneAmount>,
+}
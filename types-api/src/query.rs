@@ -1,5 +1,6 @@
 use {
     bitcoin::BlockHash,
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
     std::{
         fmt::{self, Display},
         str::FromStr,
@@ -96,3 +97,22 @@ impl Into<String> for Rune {
         }
     }
 }
+
+impl Serialize for Rune {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rune {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
@@ -1,5 +1,7 @@
 use {
+    bitcoin::ScriptBuf,
     serde::{Deserialize, Serialize},
+    std::fmt::{self, Display},
     titan_types_core::{InscriptionId, RuneId, SerializedTxid, SpacedRune},
 };
 
@@ -47,4 +49,133 @@ pub struct RuneResponse {
     pub inscription_id: Option<InscriptionId>,
     pub timestamp: u64,
     pub turbo: bool,
+    /// Number of addresses holding the rune in confirmed outputs. Only counted when
+    /// addresses are indexed.
+    #[serde(default)]
+    pub holders: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuneHolder {
+    /// `None` when the script pubkey has no address form.
+    pub address: Option<String>,
+    pub script_pubkey: ScriptBuf,
+    #[serde(with = "serde_str")]
+    pub amount: u128,
+}
+
+/// Filters and ordering of the rune listing. Names are matched without spacers and
+/// case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuneFilter {
+    /// Only runes whose name starts with this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Only runes whose name contains this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    /// Only runes that can (`true`) or can't (`false`) be minted in the next block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mintable: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turbo: Option<bool>,
+    /// Only runes etched at or after this height.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etched_from: Option<u64>,
+    /// Only runes etched at or before this height.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etched_to: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<char>,
+    #[serde(default)]
+    pub sort: RuneSort,
+}
+
+impl RuneFilter {
+    /// Whether the filter lists every rune in the default order.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Order of the rune listing. Runes are always listed from the highest value down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuneSort {
+    /// Rune number, newest etching first.
+    #[default]
+    Number,
+    /// Current supply.
+    Supply,
+    /// Number of mints.
+    Mints,
+    /// Height of the last confirmed transaction involving the rune.
+    Activity,
+}
+
+impl Display for RuneSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuneSort::Number => write!(f, "number"),
+            RuneSort::Supply => write!(f, "supply"),
+            RuneSort::Mints => write!(f, "mints"),
+            RuneSort::Activity => write!(f, "activity"),
+        }
+    }
+}
+
+/// Range and bucketing of the rune history.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuneHistoryQuery {
+    /// First height, inclusive. Defaults to the first block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// Last height, inclusive. Defaults to the tip.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+    #[serde(default)]
+    pub bucket: HistoryBucket,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryBucket {
+    /// One entry per block with activity.
+    #[default]
+    Block,
+    /// One entry per UTC day with activity, based on block times.
+    Day,
+}
+
+impl Display for HistoryBucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryBucket::Block => write!(f, "block"),
+            HistoryBucket::Day => write!(f, "day"),
+        }
+    }
+}
+
+/// Confirmed rune activity of a block, or of the blocks of a day.
+///
+/// Transfers count the transactions that move the rune from their inputs to their
+/// outputs, and `transferred` the amount they move. Mints and premines aren't
+/// transfers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuneHistoryEntry {
+    /// Height of the first block with activity in the bucket.
+    pub start_height: u64,
+    /// Height of the last block with activity in the bucket.
+    pub end_height: u64,
+    /// Block time for block buckets, start of the day for day buckets.
+    pub timestamp: u64,
+    pub mints: u64,
+    #[serde(with = "serde_str")]
+    pub minted: u128,
+    pub burns: u64,
+    #[serde(with = "serde_str")]
+    pub burned: u128,
+    pub transfers: u64,
+    #[serde(with = "serde_str")]
+    pub transferred: u128,
 }
@@ -1,22 +1,443 @@
 use {
+    crate::webhook::DeliveryPolicy,
+    bitcoin::{address::ParseError, Address, Network},
     borsh::{BorshDeserialize, BorshSerialize},
-    serde::{Deserialize, Serialize},
-    titan_types_core::EventType,
+    serde::{
+        de::{value::Error as ValueError, Error as _, IntoDeserializer},
+        Deserialize, Serialize,
+    },
+    std::{
+        io::{Error as IoError, ErrorKind, Read, Result as IoResult, Write},
+        str::FromStr,
+    },
+    titan_types_core::{Event, EventType, Location, RuneId, SerializedTxid},
     uuid::Uuid,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Subscription {
     pub id: Uuid,
     pub endpoint: String,
     pub event_types: Vec<EventType>,
     pub last_success_epoch_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<EventFilter>,
+    /// Key of the HMAC-SHA256 signature sent with every delivery. Generated by the
+    /// server when the subscription is added and only returned then and on rotation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Concurrent delivery (the default) or ordered delivery.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery_policy: Option<DeliveryPolicy>,
+}
+
+impl BorshSerialize for Subscription {
+    fn serialize<W: Write>(&self, writer: &mut W) -> IoResult<()> {
+        BorshSerialize::serialize(&self.id, writer)?;
+        BorshSerialize::serialize(&self.endpoint, writer)?;
+        BorshSerialize::serialize(&self.event_types, writer)?;
+        BorshSerialize::serialize(&self.last_success_epoch_secs, writer)?;
+        BorshSerialize::serialize(&self.filter, writer)?;
+        BorshSerialize::serialize(&self.secret, writer)?;
+        BorshSerialize::serialize(&self.delivery_policy, writer)
+    }
+}
+
+impl BorshDeserialize for Subscription {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> IoResult<Self> {
+        let id = Uuid::deserialize_reader(reader)?;
+        let endpoint = String::deserialize_reader(reader)?;
+        let event_types = Vec::<EventType>::deserialize_reader(reader)?;
+        let last_success_epoch_secs = u64::deserialize_reader(reader)?;
+
+        // Subscriptions stored before filters, secrets and delivery policies existed
+        // end early.
+        let filter = read_trailing_option(reader)?;
+        let secret = read_trailing_option(reader)?;
+        let delivery_policy = read_trailing_option(reader)?;
+
+        Ok(Self {
+            id,
+            endpoint,
+            event_types,
+            last_success_epoch_secs,
+            filter,
+            secret,
+            delivery_policy,
+        })
+    }
+}
+
+fn read_trailing_option<T: BorshDeserialize, R: Read>(reader: &mut R) -> IoResult<Option<T>> {
+    let mut present = [0u8; 1];
+    if reader.read(&mut present)? == 0 {
+        return Ok(None);
+    }
+
+    match present[0] {
+        0 => Ok(None),
+        1 => Ok(Some(T::deserialize_reader(reader)?)),
+        tag => Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!("invalid option tag: {tag}"),
+        )),
+    }
+}
+
+/// Narrows a subscription down within its event types. Every criterion that is set
+/// has to match, and each one only applies to the events that carry that kind of
+/// data: an address filter doesn't hold back `NewBlock`, for instance.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct EventFilter {
+    /// Only `AddressModified` events of one of these addresses.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<String>,
+    /// Only rune events, cenotaphs included, that involve one of these runes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rune_ids: Vec<RuneId>,
+    /// Only events about one of these transactions. Events that list several
+    /// transactions match if any of them does.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub txids: Vec<SerializedTxid>,
+    /// Only rune events that move at least this amount.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "option_serde_str"
+    )]
+    pub min_amount: Option<u128>,
+    /// Only confirmed (`false`) or only mempool (`true`) events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mempool: Option<bool>,
+}
+
+impl EventFilter {
+    /// Parse the address filters for `network` and rewrite them the way
+    /// `AddressModified` events spell them, so that any spelling of an address
+    /// matches. Fails on addresses that aren't valid on `network`.
+    pub fn normalize_addresses(&mut self, network: Network) -> Result<(), ParseError> {
+        for address in self.addresses.iter_mut() {
+            *address = address
+                .parse::<Address<_>>()?
+                .require_network(network)?
+                .to_string();
+        }
+
+        Ok(())
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        match event {
+            Event::RuneEtched {
+                location,
+                rune_id,
+                txid,
+            } => {
+                self.matches_location(location)
+                    && self.matches_rune(rune_id)
+                    && self.matches_txid(txid)
+            }
+            Event::RuneBurned {
+                amount,
+                location,
+                rune_id,
+                txid,
+            }
+            | Event::RuneMinted {
+                amount,
+                location,
+                rune_id,
+                txid,
+            }
+            | Event::RuneTransferred {
+                amount,
+                location,
+                rune_id,
+                txid,
+                ..
+            } => {
+                self.matches_location(location)
+                    && self.matches_rune(rune_id)
+                    && self.matches_amount(*amount)
+                    && self.matches_txid(txid)
+            }
+            Event::Cenotaph {
+                location,
+                burned,
+                txid,
+                ..
+            } => {
+                let matches_burned = (self.rune_ids.is_empty() && self.min_amount.is_none())
+                    || burned.iter().any(|burned| {
+                        self.matches_rune(&burned.id) && self.matches_amount(burned.amount)
+                    });
+
+                self.matches_location(location) && matches_burned && self.matches_txid(txid)
+            }
+            Event::AddressModified { address, location } => {
+                self.matches_location(location)
+                    && (self.addresses.is_empty() || self.addresses.contains(address))
+            }
+            Event::TransactionSubmitted { txid, .. } => self.matches_txid(txid),
+            Event::TransactionsAdded { txids }
+            | Event::TransactionsReplaced { txids, .. }
+            | Event::MempoolTransactionsReplaced { txids, .. } => {
+                self.matches_any_txid(txids.iter())
+            }
+            Event::MempoolTransactionsAdded { txids } | Event::MempoolEntriesUpdated { txids } => {
+                self.matches_any_txid(txids.iter().map(|(txid, _)| txid))
+            }
+            Event::NewBlock { .. } | Event::Reorg { .. } => true,
+        }
+    }
+
+    fn matches_location(&self, location: &Location) -> bool {
+        self.mempool
+            .map_or(true, |mempool| location.mempool == mempool)
+    }
+
+    fn matches_rune(&self, rune_id: &RuneId) -> bool {
+        self.rune_ids.is_empty() || self.rune_ids.contains(rune_id)
+    }
+
+    fn matches_amount(&self, amount: u128) -> bool {
+        self.min_amount
+            .map_or(true, |min_amount| amount >= min_amount)
+    }
+
+    fn matches_txid(&self, txid: &SerializedTxid) -> bool {
+        self.txids.is_empty() || self.txids.contains(txid)
+    }
+
+    fn matches_any_txid<'a>(&self, mut txids: impl Iterator<Item = &'a SerializedTxid>) -> bool {
+        self.txids.is_empty() || txids.any(|txid| self.txids.contains(txid))
+    }
+}
+
+mod option_serde_str {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<u128>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u128>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| value.parse().map_err(serde::de::Error::custom))
+            .transpose()
+    }
 }
 
 /// The expected subscription request from the TCP client.
 /// For example, the client should send:
 ///   {"subscribe": ["RuneEtched", "RuneMinted"]}
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TcpSubscriptionRequest {
     pub subscribe: Vec<EventType>,
+    /// Replay logged events starting at this sequence number before streaming new
+    /// ones. If it has been pruned already, replay starts at the oldest logged event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_seq: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<EventFilter>,
+    /// Required when the indexer runs with API keys enabled. The key needs the `read`
+    /// scope.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+/// Query string of `/events/sse`, which can't take a request body. Event types and
+/// the `EventFilter` lists are comma separated, e.g.
+/// `?subscribe=RuneTransferred&rune_ids=840000:1,840000:2&mempool=false`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventStreamQuery {
+    pub subscribe: String,
+    pub from_seq: Option<u64>,
+    pub addresses: Option<String>,
+    pub rune_ids: Option<String>,
+    pub txids: Option<String>,
+    pub min_amount: Option<String>,
+    pub mempool: Option<bool>,
+}
+
+impl TryFrom<EventStreamQuery> for TcpSubscriptionRequest {
+    type Error = ValueError;
+
+    fn try_from(query: EventStreamQuery) -> Result<Self, Self::Error> {
+        let subscribe = query
+            .subscribe
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| <EventType as Deserialize>::deserialize(name.into_deserializer()))
+            .collect::<Result<_, _>>()?;
+
+        let filter = EventFilter {
+            addresses: parse_list(query.addresses.as_deref())?,
+            rune_ids: parse_list(query.rune_ids.as_deref())?,
+            txids: parse_list(query.txids.as_deref())?,
+            min_amount: query
+                .min_amount
+                .map(|amount| amount.parse().map_err(ValueError::custom))
+                .transpose()?,
+            mempool: query.mempool,
+        };
+
+        Ok(Self {
+            subscribe,
+            from_seq: query.from_seq,
+            filter: (filter != EventFilter::default()).then_some(filter),
+            api_key: None,
+        })
+    }
+}
+
+fn parse_list<T>(list: Option<&str>) -> Result<Vec<T>, ValueError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    list.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse().map_err(ValueError::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, titan_types_core::RuneAmount};
+
+    fn transferred(rune_id: RuneId, amount: u128, mempool: bool) -> Event {
+        Event::RuneTransferred {
+            amount,
+            location: Location::from((!mempool).then_some(840_000)),
+            outpoint: "0000000000000000000000000000000000000000000000000000000000000000:0"
+                .parse()
+                .unwrap(),
+            rune_id,
+            txid: SerializedTxid::all_zeros(),
+        }
+    }
+
+    #[test]
+    fn filter_matches_only_the_events_carrying_its_criteria() {
+        let rune_id = RuneId::new(840_000, 1);
+        let other_rune_id = RuneId::new(840_000, 2);
+        let filter = EventFilter {
+            rune_ids: vec![rune_id],
+            min_amount: Some(100),
+            mempool: Some(false),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&transferred(rune_id, 100, false)));
+        assert!(!filter.matches(&transferred(rune_id, 99, false)));
+        assert!(!filter.matches(&transferred(rune_id, 100, true)));
+        assert!(!filter.matches(&transferred(other_rune_id, 100, false)));
+
+        assert!(filter.matches(&Event::Cenotaph {
+            flaw: None,
+            location: Location::block(840_000),
+            burned: vec![RuneAmount::from((rune_id, 500))],
+            txid: SerializedTxid::all_zeros(),
+        }));
+        assert!(filter.matches(&Event::Reorg {
+            height: 840_000,
+            depth: 1,
+        }));
+    }
+
+    #[test]
+    fn reads_subscriptions_stored_without_optional_fields() {
+        let subscription = Subscription {
+            id: Uuid::nil(),
+            endpoint: "http://localhost".to_string(),
+            event_types: vec![EventType::NewBlock],
+            last_success_epoch_secs: 1,
+            filter: None,
+            secret: None,
+            delivery_policy: None,
+        };
+
+        let mut stored = Vec::new();
+        BorshSerialize::serialize(&subscription.id, &mut stored).unwrap();
+        BorshSerialize::serialize(&subscription.endpoint, &mut stored).unwrap();
+        BorshSerialize::serialize(&subscription.event_types, &mut stored).unwrap();
+        BorshSerialize::serialize(&subscription.last_success_epoch_secs, &mut stored).unwrap();
+
+        let read: Subscription = borsh::from_slice(&stored).unwrap();
+        assert_eq!(read.filter, None);
+        assert_eq!(read.secret, None);
+        assert_eq!(read.delivery_policy, None);
+
+        let filtered = Subscription {
+            filter: Some(EventFilter {
+                addresses: vec!["bc1qexample".to_string()],
+                ..Default::default()
+            }),
+            secret: Some("secret".to_string()),
+            delivery_policy: Some(DeliveryPolicy::Ordered),
+            ..subscription.clone()
+        };
+        let stored = borsh::to_vec(&filtered).unwrap();
+        let read: Subscription = borsh::from_slice(&stored).unwrap();
+        assert_eq!(read.filter, filtered.filter);
+        assert_eq!(read.secret, filtered.secret);
+        assert_eq!(read.delivery_policy, filtered.delivery_policy);
+
+        // A corrupt option tag isn't mistaken for a missing field.
+        let mut stored = borsh::to_vec(&subscription).unwrap();
+        let filter_tag = stored.len() - 3;
+        stored[filter_tag] = 2;
+        let error = borsh::from_slice::<Subscription>(&stored).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn normalizes_address_filters() {
+        let mut filter = EventFilter {
+            addresses: vec![
+                "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4".to_string(),
+                "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_string(),
+            ],
+            ..Default::default()
+        };
+        filter.normalize_addresses(Network::Bitcoin).unwrap();
+        assert_eq!(
+            filter.addresses,
+            vec![
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+                "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_string(),
+            ]
+        );
+        assert!(filter.matches(&Event::AddressModified {
+            address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            location: Location::mempool(),
+        }));
+
+        let mut filter = EventFilter {
+            addresses: vec!["bc1qexample".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.normalize_addresses(Network::Bitcoin).is_err());
+
+        // Addresses of another network are rejected too.
+        let mut filter = EventFilter {
+            addresses: vec!["tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.normalize_addresses(Network::Bitcoin).is_err());
+    }
 }
@@ -0,0 +1,105 @@
+use {
+    serde::{Deserialize, Serialize},
+    titan_types_core::{
+        CenotaphFlaw, RuneAmount, RuneId, SerializedOutPoint, SerializedTxid, SpacedRune,
+        SpenderReference, TransactionStatus, TxOut,
+    },
+};
+
+mod serde_str {
+    pub use titan_types_core::serde_str::{deserialize, serialize};
+}
+
+/// Spending status of a transaction output, as listed by `/tx/{txid}/outspends`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxOutspend {
+    pub vout: u32,
+    /// Whether the output is spent, in a block or in the mempool.
+    pub spent: bool,
+    /// The spending input. `None` for spent outputs whose spender is no longer
+    /// indexed, which happens without `--index-spent-outputs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spender: Option<SpenderReference>,
+    /// Confirmation status of the spending transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<TransactionStatus>,
+}
+
+/// Rune effects of a transaction that hasn't been indexed, as if it were added to the
+/// mempool now. Returned by `POST /tx/decode`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedTransaction {
+    pub txid: SerializedTxid,
+    /// The runestone or cenotaph carried by the transaction, if any.
+    pub artifact: Option<RuneArtifact>,
+    pub inputs: Vec<DecodedInput>,
+    /// Outputs with the runes they would receive. Runes that depend on unconfirmed
+    /// transactions, mints included, are listed in `risky_runes`.
+    pub outputs: Vec<TxOut>,
+    pub minted: Option<RuneAmount>,
+    pub burned: Vec<RuneAmount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedInput {
+    pub previous_outpoint: SerializedOutPoint,
+    /// `None` when the output being spent isn't known to the index.
+    pub tx_out: Option<TxOut>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuneArtifact {
+    Runestone(DecodedRunestone),
+    Cenotaph(DecodedCenotaph),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedRunestone {
+    pub edicts: Vec<DecodedEdict>,
+    pub etching: Option<DecodedEtching>,
+    pub mint: Option<RuneId>,
+    pub pointer: Option<u32>,
+}
+
+/// A malformed runestone. Every rune in the inputs is burned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedCenotaph {
+    /// Why the runestone is invalid.
+    pub flaw: Option<CenotaphFlaw>,
+    pub etching: Option<SpacedRune>,
+    pub mint: Option<RuneId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedEdict {
+    /// `0:0` refers to the rune etched by the same transaction.
+    pub id: RuneId,
+    #[serde(with = "serde_str")]
+    pub amount: u128,
+    pub output: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedEtching {
+    /// `None` when the etching asks for a reserved name.
+    pub rune: Option<SpacedRune>,
+    pub divisibility: u8,
+    #[serde(with = "serde_str")]
+    pub premine: u128,
+    pub symbol: Option<char>,
+    pub terms: Option<DecodedTerms>,
+    pub turbo: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecodedTerms {
+    #[serde(with = "serde_str")]
+    pub amount: u128,
+    #[serde(with = "serde_str")]
+    pub cap: u128,
+    pub height_start: Option<u64>,
+    pub height_end: Option<u64>,
+    pub offset_start: Option<u64>,
+    pub offset_end: Option<u64>,
+}
@@ -0,0 +1,83 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+    std::fmt::{self, Display},
+    titan_types_core::Event,
+    uuid::Uuid,
+};
+
+/// An event queued for a webhook subscription, as listed by
+/// `/subscription/{id}/deliveries`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    /// Sent in the `X-Titan-Delivery` header of every attempt.
+    pub id: Uuid,
+    /// Position in the subscription's outbox, in the order the events were emitted.
+    pub seq: u64,
+    pub event: Event,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub created_at_epoch_secs: u64,
+    /// When the next attempt is due. Dead letters are only retried on redelivery.
+    pub next_attempt_epoch_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting in the outbox for its next attempt.
+    Pending,
+    /// Ran out of attempts. Kept until it is redelivered or the subscription is deleted.
+    DeadLetter,
+}
+
+impl Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeliveryStatus::Pending => write!(f, "pending"),
+            DeliveryStatus::DeadLetter => write!(f, "dead_letter"),
+        }
+    }
+}
+
+/// How the outbox of a webhook subscription is drained.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum DeliveryPolicy {
+    /// Up to `max_concurrency` due deliveries are sent at once, so events can arrive out
+    /// of order.
+    Concurrent { max_concurrency: u32 },
+    /// One delivery at a time, in the order the events were emitted. A failing delivery
+    /// holds back the ones after it until it succeeds or becomes a dead letter.
+    Ordered,
+}
+
+impl Default for DeliveryPolicy {
+    fn default() -> Self {
+        DeliveryPolicy::Concurrent {
+            max_concurrency: 10,
+        }
+    }
+}
+
+/// Query string of `/subscription/{id}/deliveries`. Lists both pending deliveries and
+/// dead letters unless `status` is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryQuery {
+    pub status: Option<DeliveryStatus>,
+    pub from_seq: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+/// Query string of `/subscription/{id}/redeliver`. Requeues every dead letter of the
+/// subscription unless `seq` picks one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedeliverQuery {
+    pub seq: Option<u64>,
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Rune protocol message carried by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub enum ArtifactKind {
+    Runestone,
+    /// A malformed runestone. Every rune in the transaction's inputs is burned.
+    Cenotaph {
+        flaw: Option<CenotaphFlaw>,
+    },
+}
+
+/// Why a runestone was turned into a cenotaph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub enum CenotaphFlaw {
+    EdictOutput,
+    EdictRuneId,
+    InvalidScript,
+    Opcode,
+    SupplyOverflow,
+    TrailingIntegers,
+    TruncatedField,
+    UnrecognizedEvenTag,
+    UnrecognizedFlag,
+    Varint,
+}
+
+impl fmt::Display for CenotaphFlaw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CenotaphFlaw::EdictOutput => {
+                write!(f, "edict output greater than transaction output count")
+            }
+            CenotaphFlaw::EdictRuneId => write!(f, "invalid rune ID in edict"),
+            CenotaphFlaw::InvalidScript => write!(f, "invalid script in OP_RETURN"),
+            CenotaphFlaw::Opcode => write!(f, "non-pushdata opcode in OP_RETURN"),
+            CenotaphFlaw::SupplyOverflow => write!(f, "supply overflows u128"),
+            CenotaphFlaw::TrailingIntegers => write!(f, "trailing integers in body"),
+            CenotaphFlaw::TruncatedField => write!(f, "field with missing value"),
+            CenotaphFlaw::UnrecognizedEvenTag => write!(f, "unrecognized even tag"),
+            CenotaphFlaw::UnrecognizedFlag => write!(f, "unrecognized field"),
+            CenotaphFlaw::Varint => write!(f, "invalid varint"),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_with_type_tag() {
+        let cenotaph = ArtifactKind::Cenotaph {
+            flaw: Some(CenotaphFlaw::UnrecognizedEvenTag),
+        };
+
+        let json = serde_json::to_string(&cenotaph).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"cenotaph","flaw":"unrecognized_even_tag"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ArtifactKind>(&json).unwrap(),
+            cenotaph
+        );
+
+        assert_eq!(
+            serde_json::to_string(&ArtifactKind::Runestone).unwrap(),
+            r#"{"type":"runestone"}"#
+        );
+    }
+}
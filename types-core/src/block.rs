@@ -72,6 +72,39 @@ impl BorshSerialize for Block {
 #[cfg(feature = "borsh")]
 impl BorshDeserialize for Block {
     fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        // 1) and 2) Deserialize `height` and `header`
+        let (height, header) = Self::deserialize_header_reader(reader)?;
+
+        // 3) Deserialize `tx_ids` (Vec<String>)
+        let tx_ids = Vec::<SerializedTxid>::deserialize_reader(reader)?;
+
+        // 3) Deserialize `etched_runes` manually:
+        //    - Read the length
+        //    - For each entry, read `block` (u64) then `tx` (u32)
+
+        let etched_len = u64::deserialize_reader(reader)?;
+        let mut etched_runes = Vec::with_capacity(etched_len as usize);
+
+        for _ in 0..etched_len {
+            let block = u64::deserialize_reader(reader)?;
+            let tx = u32::deserialize_reader(reader)?;
+            etched_runes.push(RuneId::new(block, tx));
+        }
+
+        Ok(Self {
+            height,
+            header,
+            tx_ids,
+            etched_runes,
+        })
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl Block {
+    /// Reads the height and header at the start of a serialized block, leaving its
+    /// txids and etched runes unread.
+    pub fn deserialize_header_reader<R: Read>(reader: &mut R) -> Result<(u64, Header)> {
         // 1) Deserialize `height`
         let height = u64::deserialize_reader(reader)?;
 
@@ -104,28 +137,7 @@ impl BorshDeserialize for Block {
             nonce,
         };
 
-        // 3) Deserialize `tx_ids` (Vec<String>)
-        let tx_ids = Vec::<SerializedTxid>::deserialize_reader(reader)?;
-
-        // 3) Deserialize `etched_runes` manually:
-        //    - Read the length
-        //    - For each entry, read `block` (u64) then `tx` (u32)
-
-        let etched_len = u64::deserialize_reader(reader)?;
-        let mut etched_runes = Vec::with_capacity(etched_len as usize);
-
-        for _ in 0..etched_len {
-            let block = u64::deserialize_reader(reader)?;
-            let tx = u32::deserialize_reader(reader)?;
-            etched_runes.push(RuneId::new(block, tx));
-        }
-
-        Ok(Self {
-            height,
-            header,
-            tx_ids,
-            etched_runes,
-        })
+        Ok((height, header))
     }
 }
 
@@ -249,6 +261,27 @@ mod tests {
         assert_eq!(deserialized.etched_runes.len(), 0);
     }
 
+    #[test]
+    fn test_header_deserialization_skips_the_rest() {
+        let block = Block {
+            height: 7,
+            header: create_test_header(),
+            tx_ids: create_test_txids(),
+            etched_runes: create_test_rune_ids(),
+        };
+
+        let serialized = borsh::to_vec(&block).expect("Failed to serialize block");
+        let mut reader = &serialized[..];
+
+        let (height, header) =
+            Block::deserialize_header_reader(&mut reader).expect("Failed to deserialize header");
+
+        assert_eq!(height, block.height);
+        assert_eq!(header, block.header);
+        // Height, version, both hashes, time, bits and nonce.
+        assert_eq!(serialized.len() - reader.len(), 8 + 4 + 32 + 32 + 4 + 4 + 4);
+    }
+
     #[test]
     fn test_block_with_single_rune_serialization() {
         let header = create_test_header();
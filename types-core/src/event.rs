@@ -1,6 +1,9 @@
 use {
     crate::rune_id::RuneId,
-    crate::{MempoolEntry, SerializedOutPoint, SerializedTxid},
+    crate::{
+        CenotaphFlaw, MempoolEntry, RuneAmount, SerializedOutPoint, SerializedTxid,
+        TransactionReplacement,
+    },
     bitcoin::BlockHash,
     std::fmt,
 };
@@ -18,6 +21,7 @@ pub enum EventType {
     RuneBurned,
     RuneMinted,
     RuneTransferred,
+    Cenotaph,
     AddressModified,
     TransactionSubmitted,
     TransactionsAdded,
@@ -36,6 +40,7 @@ impl From<Event> for EventType {
             Event::RuneBurned { .. } => EventType::RuneBurned,
             Event::RuneMinted { .. } => EventType::RuneMinted,
             Event::RuneTransferred { .. } => EventType::RuneTransferred,
+            Event::Cenotaph { .. } => EventType::Cenotaph,
             Event::AddressModified { .. } => EventType::AddressModified,
             Event::TransactionSubmitted { .. } => EventType::TransactionSubmitted,
             Event::TransactionsAdded { .. } => EventType::TransactionsAdded,
@@ -57,6 +62,7 @@ impl fmt::Display for EventType {
             EventType::RuneBurned => write!(f, "RuneBurned"),
             EventType::RuneMinted => write!(f, "RuneMinted"),
             EventType::RuneTransferred => write!(f, "RuneTransferred"),
+            EventType::Cenotaph => write!(f, "Cenotaph"),
             EventType::AddressModified => write!(f, "AddressModified"),
             EventType::TransactionSubmitted => write!(f, "TransactionSubmitted"),
             EventType::TransactionsAdded => write!(f, "TransactionsAdded"),
@@ -145,6 +151,14 @@ pub enum Event {
         rune_id: RuneId,
         txid: SerializedTxid,
     },
+    /// A transaction carried a malformed runestone. `burned` lists the runes it burned,
+    /// which is empty when its inputs held none.
+    Cenotaph {
+        flaw: Option<CenotaphFlaw>,
+        location: Location,
+        burned: Vec<RuneAmount>,
+        txid: SerializedTxid,
+    },
     AddressModified {
         address: String,
         location: Location,
@@ -158,12 +172,18 @@ pub enum Event {
     },
     TransactionsReplaced {
         txids: Vec<SerializedTxid>,
+        /// What replaced each of `txids` that left the mempool without being mined.
+        #[cfg_attr(feature = "serde", serde(default))]
+        replacements: Vec<TransactionReplacement>,
     },
     MempoolTransactionsAdded {
         txids: Vec<(SerializedTxid, MempoolEntry)>,
     },
     MempoolTransactionsReplaced {
         txids: Vec<SerializedTxid>,
+        /// What replaced each of `txids` that left the mempool without being mined.
+        #[cfg_attr(feature = "serde", serde(default))]
+        replacements: Vec<TransactionReplacement>,
     },
     MempoolEntriesUpdated {
         txids: Vec<(SerializedTxid, MempoolEntry)>,
@@ -177,3 +197,60 @@ pub enum Event {
         depth: u64,
     },
 }
+
+/// An event together with its position in the indexer's event log. Sequence numbers
+/// increase by one for every event the indexer emits, so a subscriber that
+/// reconnects can ask to resume from the one after the last it saw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SequencedEvent {
+    pub seq: u64,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub event: Event,
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequenced_event_flattens_event() {
+        let event = SequencedEvent {
+            seq: 42,
+            event: Event::Reorg {
+                height: 100,
+                depth: 2,
+            },
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            json,
+            r#"{"seq":42,"type":"Reorg","data":{"height":100,"depth":2}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<SequencedEvent>(&json).unwrap(),
+            event
+        );
+
+        // Consumers that only know about `Event` ignore the sequence number.
+        assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event.event);
+    }
+
+    #[test]
+    fn reads_replaced_events_without_replacements() {
+        // As logged before replacements were tracked.
+        let json = format!(
+            r#"{{"type":"TransactionsReplaced","data":{{"txids":["{}"]}}}}"#,
+            SerializedTxid::all_zeros()
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Event>(&json).unwrap(),
+            Event::TransactionsReplaced {
+                txids: vec![SerializedTxid::all_zeros()],
+                replacements: vec![],
+            }
+        );
+    }
+}
@@ -1,10 +1,12 @@
 pub use {
+    artifact::{ArtifactKind, CenotaphFlaw},
     block::Block,
-    event::{Event, EventType, Location},
+    event::{Event, EventType, Location, SequencedEvent},
     height::Height,
     inscription_id::InscriptionId,
     mempool_entry::{MempoolEntry, MempoolEntryFee},
     outpoint::SerializedOutPoint,
+    replacement::{ReplacementReason, TransactionReplacement},
     rune::Rune,
     rune_amount::RuneAmount,
     rune_id::RuneId,
@@ -15,12 +17,14 @@ pub use {
     txid::SerializedTxid,
 };
 
+mod artifact;
 mod block;
 mod event;
 mod height;
 mod inscription_id;
 mod mempool_entry;
 mod outpoint;
+mod replacement;
 mod rune;
 mod rune_amount;
 mod rune_id;
@@ -0,0 +1,38 @@
+use crate::SerializedTxid;
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Why a transaction left the mempool without being mined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub enum ReplacementReason {
+    /// A mempool transaction spending one of its inputs replaced it.
+    Rbf,
+    /// A block confirmed another transaction spending one of its inputs.
+    BlockConflict,
+    /// The node dropped it, e.g. because the mempool was full or it expired.
+    Evicted,
+}
+
+/// A transaction that was replaced, or dropped, while unconfirmed. Descendants of
+/// a replaced transaction are replaced along with it, for the same reason.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub struct TransactionReplacement {
+    pub txid: SerializedTxid,
+    /// The transaction that spent the conflicting input, when known.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub replaced_by: Option<SerializedTxid>,
+    pub reason: ReplacementReason,
+    /// Unix time, in seconds, when the indexer saw it leave the mempool.
+    pub timestamp: u64,
+}
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "borsh")]
 use std::io::{Read, Result, Write};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RuneAmount {
     pub id: RuneId,
@@ -17,7 +17,10 @@ pub struct RuneAmount {
 
 impl From<(RuneId, u128)> for RuneAmount {
     fn from((rune_id, amount): (RuneId, u128)) -> Self {
-        Self { id: rune_id, amount }
+        Self {
+            id: rune_id,
+            amount,
+        }
     }
 }
 
@@ -1,5 +1,5 @@
 use {
-    crate::{tx_in::TxIn, tx_out::SpentStatus, TxOut},
+    crate::{tx_in::TxIn, tx_out::SpentStatus, ArtifactKind, TransactionReplacement, TxOut},
     bitcoin::{constants::WITNESS_SCALE_FACTOR, BlockHash, Txid},
 };
 
@@ -14,6 +14,12 @@ pub struct TransactionStatus {
     pub block_height: Option<u64>,
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub block_hash: Option<BlockHash>,
+    /// Set for unconfirmed transactions that left the mempool without being mined.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub replaced: Option<TransactionReplacement>,
 }
 
 impl TransactionStatus {
@@ -22,6 +28,7 @@ impl TransactionStatus {
             confirmed: false,
             block_height: None,
             block_hash: None,
+            replaced: None,
         }
     }
 
@@ -30,6 +37,14 @@ impl TransactionStatus {
             confirmed: true,
             block_height: Some(block_height),
             block_hash: Some(block_hash),
+            replaced: None,
+        }
+    }
+
+    pub fn replaced(replacement: TransactionReplacement) -> Self {
+        Self {
+            replaced: Some(replacement),
+            ..Self::unconfirmed()
         }
     }
 }
@@ -45,6 +60,12 @@ pub struct Transaction {
     pub status: TransactionStatus,
     pub size: u64,
     pub weight: u64,
+    /// Runestone or cenotaph carried by the transaction, if any.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub artifact: Option<ArtifactKind>,
 }
 
 impl Transaction {
@@ -169,6 +190,7 @@ impl
                 })
                 .collect(),
             status,
+            artifact: None,
         }
     }
 }